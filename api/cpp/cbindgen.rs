@@ -436,7 +436,9 @@ fn gen_corelib(
         .with_config(string_config)
         .with_src(crate_dir.join("string.rs"))
         .with_src(crate_dir.join("slice.rs"))
-        .with_after_include("namespace slint { struct SharedString; }")
+        .with_after_include(
+            "namespace slint { struct SharedString; template<typename T> struct SharedVector; }",
+        )
         .generate()
         .context("Unable to generate bindings for slint_string_internal.h")?
         .write_to_file(include_dir.join("slint_string_internal.h"));
@@ -288,6 +288,17 @@ public:
         }
     }
 
+    /// Returns a std::optional that contains a 64-bit integer if the type of this Value is
+    /// Type::Long, otherwise an empty optional is returned.
+    std::optional<int64_t> to_long() const
+    {
+        if (auto *number = cbindgen_private::slint_interpreter_value_to_long(inner)) {
+            return *number;
+        } else {
+            return {};
+        }
+    }
+
     /// Returns a std::optional that contains a string if the type of this Value is
     /// Type::String, otherwise an empty optional is returned.
     std::optional<slint::SharedString> to_string() const
@@ -356,6 +367,9 @@ public:
     /// Constructs a new Value that holds the int \a value.
     /// Internally this is stored as a double and Value::type() will return Value::Type::Number.
     Value(int value) : Value(static_cast<double>(value)) { }
+    /// Constructs a new Value that holds the 64-bit integer \a value.
+    /// Value::type() will return Value::Type::Long.
+    Value(int64_t value) : inner(cbindgen_private::slint_interpreter_value_new_long(value)) { }
     /// Constructs a new Value that holds the string \a str.
     Value(const SharedString &str)
         : inner(cbindgen_private::slint_interpreter_value_new_string(&str))
@@ -17,6 +17,8 @@
 
 #include <vector>
 #include <chrono>
+#include <limits>
+#include <string>
 #include <span>
 #include <concepts>
 
@@ -91,6 +93,21 @@ inline void debug(const SharedString &str)
     cbindgen_private::slint_debug(&str);
 }
 
+/// Integer division of `long` values. Like in Rust and in the interpreter, a division by 0
+/// (or an overflowing division) results in 0.
+inline int64_t long_div(int64_t a, int64_t b)
+{
+    if (b == 0 || (b == -1 && a == std::numeric_limits<int64_t>::min())) {
+        return 0;
+    }
+    return a / b;
+}
+
+inline SharedString shared_string_from_long(int64_t n)
+{
+    return SharedString(std::to_string(n));
+}
+
 } // namespace private_api
 
 namespace cbindgen_private {
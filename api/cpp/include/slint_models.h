@@ -7,6 +7,7 @@
 
 #include <algorithm>
 #include <functional>
+#include <map>
 #include <memory>
 #include <optional>
 
@@ -288,6 +289,103 @@ public:
     }
 };
 
+namespace private_api {
+
+/// Returns a copy of all the rows of the model, tracking the row count and the rows
+template<typename M>
+auto model_rows_tracked(const std::shared_ptr<M> &model)
+{
+    std::vector<std::decay_t<decltype(*model->row_data(0))>> result;
+    if (model) {
+        model->track_row_count_changes();
+        auto count = model->row_count();
+        result.reserve(count);
+        for (size_t i = 0; i < count; ++i) {
+            if (auto v = model->row_data_tracked(i)) {
+                result.push_back(std::move(*v));
+            }
+        }
+    }
+    return result;
+}
+
+template<typename ModelData>
+std::shared_ptr<Model<ModelData>> make_vector_model(std::vector<ModelData> data)
+{
+    return std::make_shared<VectorModel<ModelData>>(std::move(data));
+}
+
+template<typename M, typename F>
+auto array_map(const std::shared_ptr<M> &model, F map_fn)
+{
+    std::vector<std::decay_t<decltype(map_fn(*model->row_data(0)))>> result;
+    for (const auto &v : model_rows_tracked(model)) {
+        result.push_back(map_fn(v));
+    }
+    return make_vector_model(std::move(result));
+}
+
+template<typename M, typename F>
+auto array_filter(const std::shared_ptr<M> &model, F filter_fn)
+{
+    auto rows = model_rows_tracked(model);
+    rows.erase(std::remove_if(rows.begin(), rows.end(), [&](const auto &v) { return !filter_fn(v); }),
+               rows.end());
+    return make_vector_model(std::move(rows));
+}
+
+template<typename M>
+auto array_slice(const std::shared_ptr<M> &model, int start, int end)
+{
+    auto rows = model_rows_tracked(model);
+    int len = int(rows.size());
+    auto clamp = [len](int i) { return i < 0 ? std::max(len + i, 0) : std::min(i, len); };
+    start = clamp(start);
+    end = std::max(clamp(end), start);
+    return make_vector_model(decltype(rows)(rows.begin() + start, rows.begin() + end));
+}
+
+template<typename M1, typename M2>
+auto array_concat(const std::shared_ptr<M1> &lhs, const std::shared_ptr<M2> &rhs)
+{
+    auto rows = model_rows_tracked(lhs);
+    for (auto &v : model_rows_tracked(rhs)) {
+        rows.push_back(std::move(v));
+    }
+    return make_vector_model(std::move(rows));
+}
+
+template<typename M, typename T>
+int array_index_of(const std::shared_ptr<M> &model, const T &value)
+{
+    auto rows = model_rows_tracked(model);
+    auto it = std::find(rows.begin(), rows.end(), value);
+    return it == rows.end() ? -1 : int(it - rows.begin());
+}
+
+/// Returns the value for the key in the map, or a default constructed value
+template<typename T>
+T map_get(const std::map<SharedString, T> &map, const SharedString &key)
+{
+    auto it = map.find(key);
+    return it == map.end() ? T {} : it->second;
+}
+
+/// Returns a model of the (key, value) entries of the map, sorted by key
+template<typename T>
+std::shared_ptr<Model<std::tuple<SharedString, T>>>
+map_entries(const std::map<SharedString, T> &map)
+{
+    std::vector<std::tuple<SharedString, T>> entries;
+    entries.reserve(map.size());
+    for (const auto &[key, value] : map) {
+        entries.emplace_back(key, value);
+    }
+    return make_vector_model(std::move(entries));
+}
+
+} // namespace private_api
+
 template<typename ModelData>
 class FilterModel;
 
@@ -150,6 +150,17 @@ pub extern "C" fn slint_string_to_float(string: &SharedString, value: &mut f32)
     }
 }
 
+#[no_mangle]
+pub extern "C" fn slint_string_to_long(string: &SharedString, value: &mut i64) -> bool {
+    match string.as_str().parse::<i64>() {
+        Ok(v) => {
+            *value = v;
+            true
+        }
+        Err(_) => false,
+    }
+}
+
 #[no_mangle]
 pub extern "C" fn slint_string_character_count(string: &SharedString) -> usize {
     unicode_segmentation::UnicodeSegmentation::graphemes(string.as_str(), true).count()
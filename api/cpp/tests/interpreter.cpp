@@ -43,6 +43,19 @@ SCENARIO("Value API")
         REQUIRE(*v2.to_number() == number);
     }
 
+    SECTION("Construct a long")
+    {
+        REQUIRE(!value.to_long().has_value());
+        const int64_t number = 9007199254740993;
+        value = Value(number);
+        REQUIRE(value.type() == Value::Type::Long);
+
+        auto long_opt = value.to_long();
+        REQUIRE(long_opt.has_value());
+        REQUIRE(long_opt.value() == number);
+        REQUIRE(!value.to_number().has_value());
+    }
+
     SECTION("Construct a bool")
     {
         REQUIRE(!value.to_bool().has_value());
@@ -131,6 +131,44 @@ test("get/set bool properties", (t) => {
     );
 });
 
+test("get/set optional properties", (t) => {
+    const compiler = new private_api.ComponentCompiler();
+    const definition = compiler.buildFromSource(
+        `
+  export struct Item {
+    label: string?,
+  }
+  export component App {
+    in-out property <int?> count;
+    in-out property <Item> item;
+    callback get-count() -> int?;
+    callback nothing();
+    get-count => { count }
+  }
+  `,
+        "",
+    );
+    t.not(definition.App, null);
+
+    const instance = definition.App!.create();
+    t.not(instance, null);
+
+    t.is(instance!.getProperty("count"), undefined);
+    instance!.setProperty("count", 42);
+    t.is(instance!.getProperty("count"), 42);
+    t.is(instance!.invoke("get-count", []), 42);
+    instance!.setProperty("count", null);
+    t.is(instance!.getProperty("count"), undefined);
+    t.is(instance!.invoke("get-count", []), undefined);
+
+    t.deepEqual(instance!.getProperty("item"), { label: undefined });
+    instance!.setProperty("item", { label: "hello" });
+    t.deepEqual(instance!.getProperty("item"), { label: "hello" });
+
+    // A callback that doesn't return anything still returns null
+    t.is(instance!.invoke("nothing", []), null);
+});
+
 test("set struct properties", (t) => {
     const compiler = new private_api.ComponentCompiler();
     const definition = compiler.buildFromSource(
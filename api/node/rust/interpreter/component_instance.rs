@@ -4,7 +4,7 @@
 use i_slint_compiler::langtype::Type;
 use i_slint_core::window::WindowInner;
 use napi::{Env, Error, JsFunction, JsUnknown, NapiRaw, NapiValue, Ref, Result};
-use slint_interpreter::{ComponentHandle, ComponentInstance, Value};
+use slint_interpreter::{ComponentHandle, ComponentInstance, SetCallbackError, Value};
 
 use crate::JsWindow;
 
@@ -41,7 +41,15 @@ pub fn get_property(&self, env: Env, name: String) -> Result<JsUnknown> {
             .inner
             .get_property(name.as_ref())
             .map_err(|e| Error::from_reason(e.to_string()))?;
-        super::value::to_js_unknown(&env, &value)
+        match self
+            .inner
+            .definition()
+            .properties_and_callbacks()
+            .find_map(|(prop_name, (ty, _))| (prop_name == name).then_some(ty))
+        {
+            Some(ty) => super::value::to_js_unknown_for_type(&env, &value, &ty),
+            None => super::value::to_js_unknown(&env, &value),
+        }
     }
 
     #[napi]
@@ -77,7 +85,16 @@ pub fn get_global_property(
             .inner
             .get_global_property(global_name.as_ref(), name.as_ref())
             .map_err(|e| Error::from_reason(e.to_string()))?;
-        super::value::to_js_unknown(&env, &value)
+        match self
+            .inner
+            .definition()
+            .global_properties_and_callbacks(global_name.as_str())
+            .and_then(|mut properties| {
+                properties.find_map(|(prop_name, (ty, _))| (prop_name == name).then_some(ty))
+            }) {
+            Some(ty) => super::value::to_js_unknown_for_type(&env, &value, &ty),
+            None => super::value::to_js_unknown(&env, &value),
+        }
     }
 
     #[napi]
@@ -137,6 +154,7 @@ pub fn set_callback(
             self.inner
                 .set_callback(callback_name.as_str(), {
                     let return_type = callback.return_type.clone();
+                    let arg_types = callback.args.clone();
                     let callback_name = callback_name.clone();
 
                     move |args| {
@@ -149,7 +167,8 @@ pub fn set_callback(
                             .call(
                                 None,
                                 args.iter()
-                                    .map(|v| super::value::to_js_unknown(&env, v).unwrap())
+                                    .zip(arg_types.iter())
+                                    .map(|(v, ty)| super::value::to_js_unknown_for_type(&env, v, ty).unwrap())
                                     .collect::<Vec<JsUnknown>>()
                                     .as_ref()
                             ) {
@@ -170,7 +189,7 @@ pub fn set_callback(
                         }
                     }
                 })
-                .map_err(|_| napi::Error::from_reason("Cannot set callback."))?;
+                .map_err(set_callback_error)?;
 
             return Ok(());
         }
@@ -205,6 +224,7 @@ pub fn set_global_callback(
             self.inner
                 .set_global_callback(global_name.as_str(), callback_name.as_str(), {
                     let return_type = callback.return_type.clone();
+                    let arg_types = callback.args.clone();
                     let global_name = global_name.clone();
                     let callback_name = callback_name.clone();
 
@@ -220,7 +240,8 @@ pub fn set_global_callback(
                             .call(
                                 None,
                                 args.iter()
-                                    .map(|v| super::value::to_js_unknown(&env, v).unwrap())
+                                    .zip(arg_types.iter())
+                                    .map(|(v, ty)| super::value::to_js_unknown_for_type(&env, v, ty).unwrap())
                                     .collect::<Vec<JsUnknown>>()
                                     .as_ref()
                             ) {
@@ -241,7 +262,7 @@ pub fn set_global_callback(
                         }
                     }
                 })
-                .map_err(|_| napi::Error::from_reason("Cannot set callback."))?;
+                .map_err(set_callback_error)?;
 
             return Ok(());
         }
@@ -294,10 +315,11 @@ pub fn invoke(
                 )
             })?;
 
-        let args = match ty {
-            Type::Callback(function) | Type::Function(function) => {
-                Self::invoke_args(env, &callback_name, callback_arguments, &function.args)?
-            }
+        let (args, return_type) = match ty {
+            Type::Callback(function) | Type::Function(function) => (
+                Self::invoke_args(env, &callback_name, callback_arguments, &function.args)?,
+                function.return_type.clone(),
+            ),
             _ => {
                 return Err(napi::Error::from_reason(
                     format!("{callback_name} is not a callback or a function").as_str(),
@@ -309,7 +331,7 @@ pub fn invoke(
             .inner
             .invoke(callback_name.as_str(), args.as_slice())
             .map_err(|_| napi::Error::from_reason("Cannot invoke callback."))?;
-        super::to_js_unknown(&env, &result)
+        super::to_js_unknown_for_type(&env, &result, &return_type)
     }
 
     #[napi]
@@ -336,10 +358,11 @@ pub fn invoke_global(
                 )
             })?;
 
-        let args = match ty {
-            Type::Callback(function) | Type::Function(function) => {
-                Self::invoke_args(env, &callback_name, callback_arguments, &function.args)?
-            }
+        let (args, return_type) = match ty {
+            Type::Callback(function) | Type::Function(function) => (
+                Self::invoke_args(env, &callback_name, callback_arguments, &function.args)?,
+                function.return_type.clone(),
+            ),
             _ => {
                 return Err(napi::Error::from_reason(
                     format!(
@@ -354,7 +377,7 @@ pub fn invoke_global(
             .inner
             .invoke_global(global_name.as_str(), callback_name.as_str(), args.as_slice())
             .map_err(|_| napi::Error::from_reason("Cannot invoke callback."))?;
-        super::to_js_unknown(&env, &result)
+        super::to_js_unknown_for_type(&env, &result, &return_type)
     }
 
     #[napi]
@@ -394,3 +417,12 @@ fn drop(&mut self) {
         self.reference.unref(self.env).unwrap();
     }
 }
+
+fn set_callback_error(error: SetCallbackError) -> napi::Error {
+    match error {
+        SetCallbackError::AsyncCallback => {
+            napi::Error::from_reason("Async callbacks are not supported in JavaScript.")
+        }
+        _ => napi::Error::from_reason("Cannot set callback."),
+    }
+}
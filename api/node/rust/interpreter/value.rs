@@ -30,11 +30,17 @@ pub enum JsValueType {
 impl From<slint_interpreter::ValueType> for JsValueType {
     fn from(value_type: slint_interpreter::ValueType) -> Self {
         match value_type {
-            slint_interpreter::ValueType::Number => JsValueType::Number,
+            // Longs are BigInts in JavaScript
+            slint_interpreter::ValueType::Number | slint_interpreter::ValueType::Long => {
+                JsValueType::Number
+            }
             slint_interpreter::ValueType::String => JsValueType::String,
             slint_interpreter::ValueType::Bool => JsValueType::Bool,
             slint_interpreter::ValueType::Model => JsValueType::Model,
-            slint_interpreter::ValueType::Struct => JsValueType::Struct,
+            // Maps are plain objects in JavaScript, like structs
+            slint_interpreter::ValueType::Struct | slint_interpreter::ValueType::Map => {
+                JsValueType::Struct
+            }
             slint_interpreter::ValueType::Brush => JsValueType::Brush,
             slint_interpreter::ValueType::Image => JsValueType::Image,
             _ => JsValueType::Void,
@@ -52,6 +58,7 @@ pub fn to_js_unknown(env: &Env, value: &Value) -> Result<JsUnknown> {
     match value {
         Value::Void => env.get_null().map(|v| v.into_unknown()),
         Value::Number(number) => env.create_double(*number).map(|v| v.into_unknown()),
+        Value::Long(number) => env.create_bigint_from_i64(*number)?.into_unknown(),
         Value::String(string) => env.create_string(string).map(|v| v.into_unknown()),
         Value::Bool(value) => env.get_boolean(*value).map(|v| v.into_unknown()),
         Value::Image(image) => Ok(SlintImageData::from(image.clone())
@@ -68,6 +75,13 @@ pub fn to_js_unknown(env: &Env, value: &Value) -> Result<JsUnknown> {
             }
             Ok(o.into_unknown())
         }
+        Value::Map(map) => {
+            let mut o = env.create_object()?;
+            for (key, value) in map.iter() {
+                o.set_property(env.create_string(key)?, to_js_unknown(env, value)?)?;
+            }
+            Ok(o.into_unknown())
+        }
         Value::Brush(brush) => {
             Ok(SlintBrush::from(brush.clone()).into_instance(*env)?.as_object(*env).into_unknown())
         }
@@ -84,8 +98,43 @@ pub fn to_js_unknown(env: &Env, value: &Value) -> Result<JsUnknown> {
     }
 }
 
+/// Like [`to_js_unknown`], but uses the type of the value to convert the missing values of
+/// optional types, including the ones in the fields of structs and in maps, to `undefined`.
+pub fn to_js_unknown_for_type(env: &Env, value: &Value, typ: &Type) -> Result<JsUnknown> {
+    match (typ, value) {
+        (Type::Optional(_), Value::Void) => env.get_undefined().map(|v| v.into_unknown()),
+        (Type::Optional(inner), value) => to_js_unknown_for_type(env, value, inner),
+        (Type::Struct(s), Value::Struct(struct_value)) => {
+            let mut o = env.create_object()?;
+            for (field_name, field_value) in struct_value.iter() {
+                let field_value = match s.fields.get(field_name) {
+                    Some(field_type) => to_js_unknown_for_type(env, field_value, field_type)?,
+                    None => to_js_unknown(env, field_value)?,
+                };
+                o.set_property(env.create_string(&field_name.replace('-', "_"))?, field_value)?;
+            }
+            Ok(o.into_unknown())
+        }
+        (Type::Map(value_type), Value::Map(map)) => {
+            let mut o = env.create_object()?;
+            for (key, value) in map.iter() {
+                o.set_property(
+                    env.create_string(key)?,
+                    to_js_unknown_for_type(env, value, value_type)?,
+                )?;
+            }
+            Ok(o.into_unknown())
+        }
+        _ => to_js_unknown(env, value),
+    }
+}
+
 pub fn to_value(env: &Env, unknown: JsUnknown, typ: &Type) -> Result<Value> {
     match typ {
+        Type::Optional(inner) => match unknown.get_type()? {
+            ValueType::Undefined | ValueType::Null => Ok(Value::Void),
+            _ => to_value(env, unknown, inner),
+        },
         Type::Float32
         | Type::Int32
         | Type::Duration
@@ -98,6 +147,21 @@ pub fn to_value(env: &Env, unknown: JsUnknown, typ: &Type) -> Result<Value> {
             let js_number: Result<JsNumber> = unknown.try_into();
             Ok(Value::Number(js_number?.get_double()?))
         }
+        Type::Int64 => match unknown.get_type()? {
+            ValueType::BigInt => {
+                let (number, lossless) = BigInt::from_unknown(unknown)?.get_i64();
+                if !lossless {
+                    return Err(napi::Error::from_reason(
+                        "Cannot convert BigInt to long, because it does not fit in 64 bits",
+                    ));
+                }
+                Ok(Value::Long(number))
+            }
+            _ => {
+                let js_number: JsNumber = unknown.try_into()?;
+                Ok(Value::Long(js_number.get_int64()?))
+            }
+        },
         Type::String => {
             let js_string: JsString = unknown.try_into()?;
             Ok(Value::String(js_string.into_utf8()?.as_str()?.into()))
@@ -234,6 +298,18 @@ fn try_convert_image<BufferType: AsRef<[u8]> + FromNapiValue>(
                     .collect::<Result<_, _>>()?,
             ))
         }
+        Type::Map(value_ty) => {
+            let js_object = unknown.coerce_to_object()?;
+            let keys = js_object.get_property_names()?;
+            let mut entries = vec![];
+            for i in 0..keys.get_array_length()? {
+                let key: JsString = keys.get_element(i)?;
+                let key = key.into_utf8()?.as_str()?.to_string();
+                let value: JsUnknown = js_object.get_named_property(&key)?;
+                entries.push((key, to_value(env, value, value_ty)?));
+            }
+            Ok(Value::Map(entries.into_iter().collect()))
+        }
         Type::Array(a) => {
             if unknown.is_array()? {
                 let array = Array::from_unknown(unknown)?;
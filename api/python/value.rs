@@ -34,8 +34,10 @@ fn into_py(self, py: Python<'_>) -> PyObject {
 impl ToPyObject for PyValueRef<'_> {
     fn to_object(&self, py: Python<'_>) -> PyObject {
         match &self.0 {
+            // This is also a null optional
             slint_interpreter::Value::Void => ().into_py(py),
             slint_interpreter::Value::Number(num) => num.into_py(py),
+            slint_interpreter::Value::Long(num) => num.into_py(py),
             slint_interpreter::Value::String(str) => str.into_py(py),
             slint_interpreter::Value::Bool(b) => b.into_py(py),
             slint_interpreter::Value::Image(image) => {
@@ -51,6 +53,14 @@ fn to_object(&self, py: Python<'_>) -> PyObject {
             slint_interpreter::Value::Brush(brush) => {
                 crate::brush::PyBrush::from(brush.clone()).into_py(py)
             }
+            slint_interpreter::Value::Map(map) => {
+                let dict = PyDict::new(py);
+                for (key, value) in map.iter() {
+                    // Setting an item with a string key in a new dict cannot fail
+                    dict.set_item(key.as_str(), PyValueRef(value).to_object(py)).unwrap();
+                }
+                dict.into_py(py)
+            }
             v @ _ => {
                 eprintln!("Python: conversion from slint to python needed for {v:#?} and not implemented yet");
                 ().into_py(py)
@@ -71,6 +81,14 @@ fn extract(ob: &PyAny) -> PyResult<Self> {
             .or_else(|_| {
                 ob.extract::<&'_ str>().map(|s| slint_interpreter::Value::String(s.into()))
             })
+            .or_else(|_| {
+                // Integers that a float can't represent exactly are kept as a long
+                ob.extract::<i64>()
+                    .ok()
+                    .filter(|num| num.unsigned_abs() > 1 << f64::MANTISSA_DIGITS)
+                    .map(slint_interpreter::Value::Long)
+                    .ok_or(())
+            })
             .or_else(|_| ob.extract::<f64>().map(|num| slint_interpreter::Value::Number(num)))
             .or_else(|_| {
                 ob.extract::<PyRef<'_, crate::image::PyImage>>()
@@ -40,6 +40,25 @@ fn main() {
     HelloWorld::new().run();
 }
 ```
+
+## Live reload
+
+When the `live-reload` feature of this crate and of the `slint` crate are enabled, the generated
+code doesn't contain the compiled `.slint` files. Instead, they are loaded at run-time with the
+interpreter and reloaded when they change, while the application keeps running with the same
+generated API. The state of the UI, such as the values of the properties, is kept across reloads.
+
+This is meant for development, so a feature of the application can enable both:
+
+```toml
+[features]
+live-reload = ["slint/live-reload", "slint-build/live-reload"]
+```
+
+Then run the application with `cargo run --features live-reload`. A change that modifies the API
+of the exported components, such as a new property, still requires a recompilation.
+The code of the `slint!` macro is reloaded the same way when the `live-reload` feature of the
+`slint` crate is enabled: it is loaded from the Rust file that invokes the macro.
 */
 #![doc(html_logo_url = "https://slint.dev/logo/slint-logo-square-light.svg")]
 #![warn(missing_docs)]
@@ -477,8 +496,13 @@ pub fn compile_with_output_path(
     let output_file =
         std::fs::File::create(&output_rust_file_path).map_err(CompileError::SaveError)?;
     let mut code_formatter = CodeFormatter::new(BufWriter::new(output_file));
+    #[cfg(not(feature = "live-reload"))]
     let generated = i_slint_compiler::generator::rust::generate(&doc, &loader.compiler_config)
         .map_err(|e| CompileError::CompileError(vec![e.to_string()]))?;
+    #[cfg(feature = "live-reload")]
+    let generated =
+        i_slint_compiler::generator::rust::live_reload::generate(&doc, &loader.compiler_config)
+            .map_err(|e| CompileError::CompileError(vec![e.to_string()]))?;
 
     let mut dependencies: Vec<std::path::PathBuf> = Vec::new();
 
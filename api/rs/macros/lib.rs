@@ -139,6 +139,11 @@ fn fill_token_vec(stream: impl Iterator<Item = TokenTree>, vec: &mut Vec<parser:
                                 prev_span = span;
                                 continue;
                             }
+                            if last.kind == SyntaxKind::Question && prev_spacing == Spacing::Joint {
+                                last.kind = SyntaxKind::QuestionDot;
+                                last.text = "?.".into();
+                                continue;
+                            }
                         }
                         SyntaxKind::Dot
                     }
@@ -182,7 +187,16 @@ fn fill_token_vec(stream: impl Iterator<Item = TokenTree>, vec: &mut Vec<parser:
                         SyntaxKind::RAngle
                     }
                     '#' => SyntaxKind::ColorLiteral,
-                    '?' => SyntaxKind::Question,
+                    '?' => {
+                        if let Some(last) = vec.last_mut() {
+                            if last.kind == SyntaxKind::Question && prev_spacing == Spacing::Joint {
+                                last.kind = SyntaxKind::QuestionQuestion;
+                                last.text = "??".into();
+                                continue;
+                            }
+                        }
+                        SyntaxKind::Question
+                    }
                     ',' => SyntaxKind::Comma,
                     '&' => {
                         // Since the '&' alone does not exist or cannot be part of any other token that &&
@@ -415,11 +429,28 @@ pub fn slint(stream: TokenStream) -> TokenStream {
         return diag.report_macro_diagnostic(&tokens);
     }
 
-    let mut result = generator::rust::generate(&root_component, &loader.compiler_config)
-        .unwrap_or_else(|e| {
-            let e_str = e.to_string();
-            quote!(compile_error!(#e_str))
-        });
+    #[cfg(not(feature = "live-reload"))]
+    let result = generator::rust::generate(&root_component, &loader.compiler_config);
+    #[cfg(feature = "live-reload")]
+    let result = {
+        // The code is loaded from the Rust file at run-time, so the paths can't be relative to
+        // the manifest directory anymore
+        let mut compiler_config = loader.compiler_config.clone();
+        if let Some(manifest_dir) = std::env::var_os("CARGO_MANIFEST_DIR") {
+            let manifest_dir = std::path::Path::new(&manifest_dir);
+            for path in compiler_config.include_paths.iter_mut() {
+                *path = manifest_dir.join(&*path);
+            }
+            for path in compiler_config.library_paths.values_mut() {
+                *path = manifest_dir.join(&*path);
+            }
+        }
+        generator::rust::live_reload::generate_for_rust_macro(&root_component, &compiler_config)
+    };
+    let mut result = result.unwrap_or_else(|e| {
+        let e_str = e.to_string();
+        quote!(compile_error!(#e_str))
+    });
 
     // Make sure to recompile if any of the external files changes
     let reload = diag
@@ -226,6 +226,7 @@ struct MyComponent { /*...*/ }
     FilterModel, MapModel, Model, ModelExt, ModelNotify, ModelPeer, ModelRc, ModelTracker,
     ReverseModel, SortModel, StandardListViewItem, TableColumn, VecModel,
 };
+pub use i_slint_core::sharedmap::SharedMap;
 pub use i_slint_core::sharedvector::SharedVector;
 pub use i_slint_core::timers::{Timer, TimerMode};
 pub use i_slint_core::translations::{select_bundled_translation, SelectBundledTranslationError};
@@ -190,6 +190,8 @@ pub mod re_exports {
     pub use i_slint_core::api::LogicalPosition;
     pub use i_slint_core::callbacks::Callback;
     pub use i_slint_core::date_time::*;
+    #[cfg(target_has_atomic = "ptr")]
+    pub use i_slint_core::future::AsyncCallbackRunner;
     pub use i_slint_core::graphics::*;
     pub use i_slint_core::input::{
         key_codes::Key, FocusEvent, InputEventResult, KeyEvent, KeyEventResult, KeyboardModifiers,
@@ -217,6 +219,9 @@ pub mod re_exports {
     pub use i_slint_core::string::shared_string_from_number;
     pub use i_slint_core::string::shared_string_from_number_fixed;
     pub use i_slint_core::string::shared_string_from_number_precision;
+    pub use i_slint_core::string::{
+        shared_string_index_of, shared_string_slice, shared_string_split,
+    };
     pub use i_slint_core::timers::{Timer, TimerMode};
     pub use i_slint_core::translations::{
         set_bundled_languages, translate_from_bundle, translate_from_bundle_with_plural,
@@ -224,13 +229,15 @@ pub mod re_exports {
     pub use i_slint_core::window::{
         InputMethodRequest, WindowAdapter, WindowAdapterRc, WindowInner,
     };
-    pub use i_slint_core::{Color, Coord, SharedString, SharedVector};
+    pub use i_slint_core::{Color, Coord, SharedMap, SharedString, SharedVector};
     pub use i_slint_core::{ItemTreeVTable_static, MenuVTable_static};
     pub use num_traits::float::Float;
     pub use num_traits::ops::euclid::Euclid;
     pub use once_cell::race::OnceBox;
     pub use once_cell::unsync::OnceCell;
     pub use pin_weak::rc::PinWeak;
+    #[cfg(feature = "live-reload")]
+    pub use slint_interpreter::live_reload;
     pub use unicode_segmentation::UnicodeSegmentation;
     pub use vtable::{self, *};
 }
@@ -65,6 +65,7 @@ export type KnownType =
     | "image"
     | "int"
     | "length"
+    | "long"
     | "percent"
     | "physical-length"
     | "Point"
@@ -137,6 +138,11 @@ export function getTypeInfo(typeName: KnownType): TypeInfo {
                 href: linkMap.length.href,
                 defaultValue: "0px",
             };
+        case "long":
+            return {
+                href: linkMap.long.href,
+                defaultValue: "0",
+            };
         case "percent":
             return {
                 href: linkMap.percent.href,
@@ -73,8 +73,8 @@ slint_init(slint_wasm_data).then((_) => {
     });
 
     connection.onDidChangeTextDocument(async (param) => {
-        await the_lsp.reload_document(
-            param.contentChanges[param.contentChanges.length - 1].text,
+        await the_lsp.apply_document_changes(
+            param.contentChanges,
             param.textDocument.uri,
             param.textDocument.version,
         );
@@ -68,6 +68,93 @@ pub fn lower_macro(
         }
         BuiltinMacroFunction::Rgb => rgb_macro(n, sub_expr.collect(), diag),
         BuiltinMacroFunction::Hsv => hsv_macro(n, sub_expr.collect(), diag),
+        BuiltinMacroFunction::ArrayIndexOf
+        | BuiltinMacroFunction::ArrayContains
+        | BuiltinMacroFunction::ArraySlice
+        | BuiltinMacroFunction::ArrayConcat => array_macro(mac, n, sub_expr.collect(), diag),
+        BuiltinMacroFunction::ArrayMap | BuiltinMacroFunction::ArrayFilter => {
+            unreachable!("map and filter are resolved with their lambda in the resolving pass")
+        }
+        BuiltinMacroFunction::MapHasKey => map_has_key_macro(n, sub_expr.collect(), diag),
+    }
+}
+
+/// The `has-key` member function of maps. The first argument is the map itself.
+fn map_has_key_macro(
+    node: &dyn Spanned,
+    args: Vec<(Expression, Option<NodeOrToken>)>,
+    diag: &mut BuildDiagnostics,
+) -> Expression {
+    if args.len() != 2 {
+        diag.push_error(
+            format!("`has-key` expects 1 argument, but {} are provided", args.len() - 1),
+            node,
+        );
+        return Expression::Invalid;
+    }
+    let mut args = args.into_iter();
+    let map = args.next().unwrap().0;
+    let (key, key_node) = args.next().unwrap();
+    Expression::MapHasKey {
+        map: map.into(),
+        key: key.maybe_convert_to(Type::String, &key_node, diag).into(),
+    }
+}
+
+/// The `index-of`, `contains`, `slice` and `concat` member functions of arrays.
+/// The first argument is the array itself.
+fn array_macro(
+    mac: BuiltinMacroFunction,
+    node: &dyn Spanned,
+    args: Vec<(Expression, Option<NodeOrToken>)>,
+    diag: &mut BuildDiagnostics,
+) -> Expression {
+    let (name, arg_count) = match mac {
+        BuiltinMacroFunction::ArrayIndexOf => ("index-of", 1),
+        BuiltinMacroFunction::ArrayContains => ("contains", 1),
+        BuiltinMacroFunction::ArraySlice => ("slice", 2),
+        BuiltinMacroFunction::ArrayConcat => ("concat", 1),
+        _ => unreachable!(),
+    };
+    if args.len() != arg_count + 1 {
+        diag.push_error(
+            format!(
+                "`{name}` expects {arg_count} argument{}, but {} are provided",
+                if arg_count > 1 { "s" } else { "" },
+                args.len() - 1
+            ),
+            node,
+        );
+        return Expression::Invalid;
+    }
+    let mut args = args.into_iter();
+    let array = args.next().unwrap().0;
+    let array_ty = array.ty();
+    let Type::Array(item_ty) = &array_ty else {
+        return Expression::Invalid;
+    };
+    let mut arg = |ty: &Type| {
+        let (e, n) = args.next().unwrap();
+        Box::new(e.maybe_convert_to(ty.clone(), &n, diag))
+    };
+    match mac {
+        BuiltinMacroFunction::ArrayIndexOf => {
+            Expression::ArrayIndexOf { value: arg(item_ty), array: array.into() }
+        }
+        BuiltinMacroFunction::ArrayContains => Expression::BinaryExpression {
+            lhs: Expression::ArrayIndexOf { value: arg(item_ty), array: array.into() }.into(),
+            rhs: Expression::NumberLiteral(-1., Unit::None).into(),
+            op: '!',
+        },
+        BuiltinMacroFunction::ArraySlice => Expression::ArraySlice {
+            start: arg(&Type::Int32),
+            end: arg(&Type::Int32),
+            array: array.into(),
+        },
+        BuiltinMacroFunction::ArrayConcat => {
+            Expression::ArrayConcat { rhs: arg(&array_ty), lhs: array.into() }
+        }
+        _ => unreachable!(),
     }
 }
 
@@ -319,10 +406,12 @@ fn to_debug_string(
             diag.push_error("Cannot debug this expression".into(), node);
             Expression::Invalid
         }
-        Type::Float32 | Type::Int32 => expr.maybe_convert_to(Type::String, node, diag),
+        Type::Float32 | Type::Int32 | Type::Int64 => {
+            expr.maybe_convert_to(Type::String, node, diag)
+        }
         Type::String => expr,
         // TODO
-        Type::Color | Type::Brush | Type::Image | Type::Easing | Type::Array(_) => {
+        Type::Color | Type::Brush | Type::Image | Type::Easing | Type::Array(_) | Type::Map(_) => {
             Expression::StringLiteral("<debug-of-this-type-not-yet-implemented>".into())
         }
         Type::Duration
@@ -349,6 +438,27 @@ fn to_debug_string(
             true_expr: Box::new(Expression::StringLiteral("true".into())),
             false_expr: Box::new(Expression::StringLiteral("false".into())),
         },
+        Type::Optional(inner) if **inner == Type::Void => Expression::StringLiteral("null".into()),
+        Type::Optional(_) => {
+            let local_object = format_smolstr!(
+                "debug_optional{}",
+                COUNTER.fetch_add(1, std::sync::atomic::Ordering::Relaxed)
+            );
+            let read =
+                || Expression::ReadLocalVariable { name: local_object.clone(), ty: ty.clone() };
+            let value = to_debug_string(Expression::OptionalUnwrap(Box::new(read())), node, diag);
+            Expression::CodeBlock(vec![
+                Expression::StoreLocalVariable {
+                    name: local_object.clone(),
+                    value: Box::new(expr),
+                },
+                Expression::Condition {
+                    condition: Box::new(Expression::OptionalHasValue(Box::new(read()))),
+                    true_expr: Box::new(value),
+                    false_expr: Box::new(Expression::StringLiteral("null".into())),
+                },
+            ])
+        }
         Type::Struct(s) => {
             let local_object = format_smolstr!(
                 "debug_struct{}",
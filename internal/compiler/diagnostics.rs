@@ -171,6 +171,7 @@ pub fn load_from_path(path: &Path) -> Result<String, Diagnostic> {
             span: Default::default(),
         },
         level: DiagnosticLevel::Error,
+        is_unnecessary: false,
     })?;
 
     if path.extension().is_some_and(|e| e == "rs") {
@@ -181,6 +182,7 @@ pub fn load_from_path(path: &Path) -> Result<String, Diagnostic> {
                 span: Default::default(),
             },
             level: DiagnosticLevel::Error,
+            is_unnecessary: false,
         });
     }
 
@@ -243,6 +245,8 @@ pub struct Diagnostic {
     message: String,
     span: SourceLocation,
     level: DiagnosticLevel,
+    /// The diagnostic is about code that is not used and could be removed
+    is_unnecessary: bool,
 }
 
 //NOTE! Diagnostic is re-exported in the public API of the interpreter
@@ -276,6 +280,22 @@ pub fn line_column(&self) -> (usize, usize) {
     pub fn source_file(&self) -> Option<&Path> {
         self.span.source_file().map(|sf| sf.path())
     }
+
+    /// The source file and span this diagnostic is attached to.
+    ///
+    /// Used by tools like the LSP that need to map the offset into another encoding.
+    #[doc(hidden)]
+    pub fn source_location(&self) -> &SourceLocation {
+        &self.span
+    }
+
+    /// Whether this diagnostic is about code that is not used, such as an unused import.
+    ///
+    /// Used by the LSP to let editors render that code as faded out.
+    #[doc(hidden)]
+    pub fn is_unnecessary(&self) -> bool {
+        self.is_unnecessary
+    }
 }
 
 impl std::fmt::Display for Diagnostic {
@@ -322,7 +342,7 @@ pub fn push_diagnostic_with_span(
             !message.as_str().ends_with('.'),
             "Error message should not end with a period: ({message:?})"
         );
-        self.inner.push(Diagnostic { message, span, level });
+        self.inner.push(Diagnostic { message, span, level, is_unnecessary: false });
     }
     pub fn push_error_with_span(&mut self, message: String, span: SourceLocation) {
         self.push_diagnostic_with_span(message, span, DiagnosticLevel::Error)
@@ -336,6 +356,13 @@ pub fn push_warning_with_span(&mut self, message: String, span: SourceLocation)
     pub fn push_warning(&mut self, message: String, source: &dyn Spanned) {
         self.push_warning_with_span(message, source.to_source_location());
     }
+    /// Push a warning about code that is not used, such as an unused import
+    pub fn push_unnecessary_code_warning(&mut self, message: String, source: &dyn Spanned) {
+        self.push_warning(message, source);
+        if let Some(d) = self.inner.last_mut() {
+            d.is_unnecessary = true;
+        }
+    }
     pub fn push_compiler_error(&mut self, error: Diagnostic) {
         self.inner.push(error);
     }
@@ -11,7 +11,7 @@
 use core::cell::RefCell;
 use smol_str::{format_smolstr, SmolStr};
 use std::cell::Cell;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::rc::{Rc, Weak};
 
 // FIXME remove the pub
@@ -61,12 +61,26 @@ pub enum BuiltinFunction {
     StringToFloat,
     /// the "42".is_float()
     StringIsFloat,
+    /// the "42".to_long()
+    StringToLong,
+    /// the "42".is_long()
+    StringIsLong,
     /// the "42".is_empty
     StringIsEmpty,
     /// the "42".length
     StringCharacterCount,
     StringToLowercase,
     StringToUppercase,
+    StringContains,
+    StringStartsWith,
+    StringEndsWith,
+    StringReplace,
+    StringTrim,
+    /// the "hello".slice(start, end), with indices in grapheme clusters
+    StringSlice,
+    StringSplit,
+    /// the "hello".index-of("l"), in grapheme clusters, or -1
+    StringIndexOf,
     ColorRgbaStruct,
     ColorHsvaStruct,
     ColorBrighter,
@@ -127,6 +141,19 @@ pub enum BuiltinMacroFunction {
     Hsv,
     /// transform `debug(a, b, c)` into debug `a + " " + b + " " + c`
     Debug,
+    /// `array.index-of(value)`: convert the value to the type of the items
+    ArrayIndexOf,
+    /// Transform `array.contains(value)` into `array.index-of(value) != -1`
+    ArrayContains,
+    ArraySlice,
+    /// `lhs.concat(rhs)`: convert `rhs` to the type of `lhs`
+    ArrayConcat,
+    /// `array.map(item => expression)`: the lambda is resolved with the item in scope
+    ArrayMap,
+    /// `array.filter(item => condition)`: the lambda is resolved with the item in scope
+    ArrayFilter,
+    /// `map.has-key(key)`: convert the key to a string
+    MapHasKey,
 }
 
 macro_rules! declare_builtin_function_types {
@@ -187,10 +214,20 @@ pub fn ty(&self, function: &BuiltinFunction) -> Rc<Function> {
     ItemFontMetrics: (Type::ElementReference) -> typeregister::font_metrics_type(),
     StringToFloat: (Type::String) -> Type::Float32,
     StringIsFloat: (Type::String) -> Type::Bool,
+    StringToLong: (Type::String) -> Type::Int64,
+    StringIsLong: (Type::String) -> Type::Bool,
     StringIsEmpty: (Type::String) -> Type::Bool,
     StringCharacterCount: (Type::String) -> Type::Int32,
     StringToLowercase: (Type::String) -> Type::String,
     StringToUppercase: (Type::String) -> Type::String,
+    StringContains: (Type::String, Type::String) -> Type::Bool,
+    StringStartsWith: (Type::String, Type::String) -> Type::Bool,
+    StringEndsWith: (Type::String, Type::String) -> Type::Bool,
+    StringReplace: (Type::String, Type::String, Type::String) -> Type::String,
+    StringTrim: (Type::String) -> Type::String,
+    StringSlice: (Type::String, Type::Int32, Type::Int32) -> Type::String,
+    StringSplit: (Type::String, Type::String) -> Type::Array(Rc::new(Type::String)),
+    StringIndexOf: (Type::String, Type::String) -> Type::Int32,
     ImplicitLayoutInfo(..): (Type::ElementReference) -> Type::Struct(typeregister::layout_info_type()),
     ColorRgbaStruct: (Type::Color) -> Type::Struct(Rc::new(Struct {
         fields: IntoIterator::into_iter([
@@ -309,10 +346,20 @@ fn is_const(&self) -> bool {
             BuiltinFunction::ItemFontMetrics => false, // depends also on Window's font properties
             BuiltinFunction::StringToFloat
             | BuiltinFunction::StringIsFloat
+            | BuiltinFunction::StringToLong
+            | BuiltinFunction::StringIsLong
             | BuiltinFunction::StringIsEmpty
             | BuiltinFunction::StringCharacterCount
             | BuiltinFunction::StringToLowercase
-            | BuiltinFunction::StringToUppercase => true,
+            | BuiltinFunction::StringToUppercase
+            | BuiltinFunction::StringContains
+            | BuiltinFunction::StringStartsWith
+            | BuiltinFunction::StringEndsWith
+            | BuiltinFunction::StringReplace
+            | BuiltinFunction::StringTrim
+            | BuiltinFunction::StringSlice
+            | BuiltinFunction::StringSplit
+            | BuiltinFunction::StringIndexOf => true,
             BuiltinFunction::ColorRgbaStruct
             | BuiltinFunction::ColorHsvaStruct
             | BuiltinFunction::ColorBrighter
@@ -387,10 +434,20 @@ pub fn is_pure(&self) -> bool {
             BuiltinFunction::ItemFontMetrics => true,
             BuiltinFunction::StringToFloat
             | BuiltinFunction::StringIsFloat
+            | BuiltinFunction::StringToLong
+            | BuiltinFunction::StringIsLong
             | BuiltinFunction::StringIsEmpty
             | BuiltinFunction::StringCharacterCount
             | BuiltinFunction::StringToLowercase
-            | BuiltinFunction::StringToUppercase => true,
+            | BuiltinFunction::StringToUppercase
+            | BuiltinFunction::StringContains
+            | BuiltinFunction::StringStartsWith
+            | BuiltinFunction::StringEndsWith
+            | BuiltinFunction::StringReplace
+            | BuiltinFunction::StringTrim
+            | BuiltinFunction::StringSlice
+            | BuiltinFunction::StringSplit
+            | BuiltinFunction::StringIndexOf => true,
             BuiltinFunction::ColorRgbaStruct
             | BuiltinFunction::ColorHsvaStruct
             | BuiltinFunction::ColorBrighter
@@ -567,6 +624,8 @@ pub enum Expression {
     NumberLiteral(f64, Unit),
     /// Bool
     BoolLiteral(bool),
+    /// The `null` literal. The type is a [`Type::Optional`]
+    NullLiteral(Type),
 
     /// Reference to the property
     PropertyReference(NamedReference),
@@ -624,6 +683,66 @@ pub enum Expression {
         index: Box<Expression>,
     },
 
+    /// `array.map(item => body)`: a new array with the value of `body` for each item of the array.
+    /// Within `body`, the item is read with a [`Self::ReadLocalVariable`] of the given `item` name.
+    ArrayMap {
+        array: Box<Expression>,
+        item: SmolStr,
+        body: Box<Expression>,
+    },
+
+    /// `array.filter(item => condition)`: a new array with the items for which `condition` is true.
+    /// Within `condition`, the item is read with a [`Self::ReadLocalVariable`] of the given `item` name.
+    ArrayFilter {
+        array: Box<Expression>,
+        item: SmolStr,
+        condition: Box<Expression>,
+    },
+
+    /// `array.slice(start, end)`: a new array with the items from `start` to `end` (excluded).
+    /// Negative indices count from the end of the array.
+    ArraySlice {
+        array: Box<Expression>,
+        start: Box<Expression>,
+        end: Box<Expression>,
+    },
+
+    /// `lhs.concat(rhs)`: a new array with the items of `lhs` followed by the items of `rhs`
+    ArrayConcat {
+        lhs: Box<Expression>,
+        rhs: Box<Expression>,
+    },
+
+    /// `array.index-of(value)`: the index of the first item equal to `value`, or -1
+    ArrayIndexOf {
+        array: Box<Expression>,
+        value: Box<Expression>,
+    },
+
+    /// `map[key]`: the value for the key, or the default value of the value type
+    MapIndex {
+        /// This expression should have [`Type::Map`] type
+        map: Box<Expression>,
+        key: Box<Expression>,
+    },
+
+    /// `map.has-key(key)`
+    MapHasKey {
+        map: Box<Expression>,
+        key: Box<Expression>,
+    },
+
+    /// The entries of a map, as an array of [`Type::map_entry_type`]. Used as the model of a `for`
+    MapEntries(Box<Expression>),
+
+    /// True if the expression of [`Type::Optional`] type is not null
+    OptionalHasValue(Box<Expression>),
+
+    /// The value of an expression of [`Type::Optional`] type.
+    /// Must only be evaluated if [`Self::OptionalHasValue`] is true.
+    /// (Converting a value to an optional is a [`Self::Cast`])
+    OptionalUnwrap(Box<Expression>),
+
     /// Cast an expression to the given type
     Cast {
         from: Box<Expression>,
@@ -682,6 +801,11 @@ pub enum Expression {
         ty: Rc<Struct>,
         values: HashMap<SmolStr, Expression>,
     },
+    /// A map literal. It is written like a struct literal that is converted to a [`Type::Map`]
+    Map {
+        value_ty: Type,
+        values: BTreeMap<SmolStr, Expression>,
+    },
 
     PathData(Path),
 
@@ -733,6 +857,7 @@ pub fn ty(&self) -> Type {
             Expression::StringLiteral(_) => Type::String,
             Expression::NumberLiteral(_, unit) => unit.ty(),
             Expression::BoolLiteral(_) => Type::Bool,
+            Expression::NullLiteral(ty) => ty.clone(),
             Expression::PropertyReference(nr) => nr.ty(),
             Expression::ElementReference(_) => Type::ElementReference,
             Expression::RepeaterIndexReference { .. } => Type::Int32,
@@ -752,6 +877,25 @@ pub fn ty(&self) -> Type {
                 Type::Array(ty) => (*ty).clone(),
                 _ => Type::Invalid,
             },
+            Expression::ArrayMap { body, .. } => Type::Array(Rc::new(body.ty())),
+            Expression::ArrayFilter { array, .. } => array.ty(),
+            Expression::ArraySlice { array, .. } => array.ty(),
+            Expression::ArrayConcat { lhs, .. } => lhs.ty(),
+            Expression::ArrayIndexOf { .. } => Type::Int32,
+            Expression::MapIndex { map, .. } => match map.ty() {
+                Type::Map(ty) => (*ty).clone(),
+                _ => Type::Invalid,
+            },
+            Expression::MapHasKey { .. } => Type::Bool,
+            Expression::MapEntries(map) => match map.ty() {
+                Type::Map(ty) => Type::Array(Type::map_entry_type(&ty).into()),
+                _ => Type::Invalid,
+            },
+            Expression::OptionalHasValue(_) => Type::Bool,
+            Expression::OptionalUnwrap(optional) => match optional.ty() {
+                Type::Optional(ty) => (*ty).clone(),
+                _ => Type::Invalid,
+            },
             Expression::Cast { to, .. } => to.clone(),
             Expression::CodeBlock(sub) => sub.last().map_or(Type::Void, |e| e.ty()),
             Expression::FunctionCall { function, .. } => match function.ty() {
@@ -783,6 +927,9 @@ pub fn ty(&self) -> Type {
                     } else {
                         Type::Invalid
                     }
+                } else if lhs.ty() == Type::Int64 && rhs.ty() == Type::Int64 {
+                    // Integer multiplication and division
+                    Type::Int64
                 } else {
                     debug_assert!(*op == '*' || *op == '/');
                     let unit_vec = |ty| {
@@ -828,6 +975,7 @@ pub fn ty(&self) -> Type {
             Expression::UnaryOp { sub, .. } => sub.ty(),
             Expression::Array { element_ty, .. } => Type::Array(Rc::new(element_ty.clone())),
             Expression::Struct { ty, .. } => ty.clone().into(),
+            Expression::Map { value_ty, .. } => Type::Map(value_ty.clone().into()),
             Expression::PathData { .. } => Type::PathData,
             Expression::StoreLocalVariable { .. } => Type::Void,
             Expression::ReadLocalVariable { ty, .. } => ty.clone(),
@@ -853,6 +1001,7 @@ pub fn visit(&self, mut visitor: impl FnMut(&Self)) {
             Expression::StringLiteral(_) => {}
             Expression::NumberLiteral(_, _) => {}
             Expression::BoolLiteral(_) => {}
+            Expression::NullLiteral(_) => {}
             Expression::PropertyReference { .. } => {}
             Expression::FunctionParameterReference { .. } => {}
             Expression::ElementReference(_) => {}
@@ -861,6 +1010,35 @@ pub fn visit(&self, mut visitor: impl FnMut(&Self)) {
                 visitor(array);
                 visitor(index);
             }
+            Expression::ArrayMap { array, body, .. } => {
+                visitor(array);
+                visitor(body);
+            }
+            Expression::ArrayFilter { array, condition, .. } => {
+                visitor(array);
+                visitor(condition);
+            }
+            Expression::ArraySlice { array, start, end } => {
+                visitor(array);
+                visitor(start);
+                visitor(end);
+            }
+            Expression::ArrayConcat { lhs, rhs } => {
+                visitor(lhs);
+                visitor(rhs);
+            }
+            Expression::ArrayIndexOf { array, value } => {
+                visitor(array);
+                visitor(value);
+            }
+            Expression::MapIndex { map, key } | Expression::MapHasKey { map, key } => {
+                visitor(map);
+                visitor(key);
+            }
+            Expression::MapEntries(map) => visitor(map),
+            Expression::OptionalHasValue(optional) | Expression::OptionalUnwrap(optional) => {
+                visitor(optional)
+            }
             Expression::RepeaterIndexReference { .. } => {}
             Expression::RepeaterModelReference { .. } => {}
             Expression::Cast { from, .. } => visitor(from),
@@ -895,6 +1073,11 @@ pub fn visit(&self, mut visitor: impl FnMut(&Self)) {
                     visitor(x);
                 }
             }
+            Expression::Map { values, .. } => {
+                for x in values.values() {
+                    visitor(x);
+                }
+            }
             Expression::PathData(data) => match data {
                 Path::Elements(elements) => {
                     for element in elements {
@@ -946,6 +1129,7 @@ pub fn visit_mut(&mut self, mut visitor: impl FnMut(&mut Self)) {
             Expression::StringLiteral(_) => {}
             Expression::NumberLiteral(_, _) => {}
             Expression::BoolLiteral(_) => {}
+            Expression::NullLiteral(_) => {}
             Expression::PropertyReference { .. } => {}
             Expression::FunctionParameterReference { .. } => {}
             Expression::ElementReference(_) => {}
@@ -954,6 +1138,35 @@ pub fn visit_mut(&mut self, mut visitor: impl FnMut(&mut Self)) {
                 visitor(array);
                 visitor(index);
             }
+            Expression::ArrayMap { array, body, .. } => {
+                visitor(array);
+                visitor(body);
+            }
+            Expression::ArrayFilter { array, condition, .. } => {
+                visitor(array);
+                visitor(condition);
+            }
+            Expression::ArraySlice { array, start, end } => {
+                visitor(array);
+                visitor(start);
+                visitor(end);
+            }
+            Expression::ArrayConcat { lhs, rhs } => {
+                visitor(lhs);
+                visitor(rhs);
+            }
+            Expression::ArrayIndexOf { array, value } => {
+                visitor(array);
+                visitor(value);
+            }
+            Expression::MapIndex { map, key } | Expression::MapHasKey { map, key } => {
+                visitor(map);
+                visitor(key);
+            }
+            Expression::MapEntries(map) => visitor(map),
+            Expression::OptionalHasValue(optional) | Expression::OptionalUnwrap(optional) => {
+                visitor(optional)
+            }
             Expression::RepeaterIndexReference { .. } => {}
             Expression::RepeaterModelReference { .. } => {}
             Expression::Cast { from, .. } => visitor(from),
@@ -988,6 +1201,11 @@ pub fn visit_mut(&mut self, mut visitor: impl FnMut(&mut Self)) {
                     visitor(x);
                 }
             }
+            Expression::Map { values, .. } => {
+                for x in values.values_mut() {
+                    visitor(x);
+                }
+            }
             Expression::PathData(data) => match data {
                 Path::Elements(elements) => {
                     for element in elements {
@@ -1054,6 +1272,7 @@ pub fn is_constant(&self) -> bool {
             Expression::StringLiteral(_) => true,
             Expression::NumberLiteral(_, _) => true,
             Expression::BoolLiteral(_) => true,
+            Expression::NullLiteral(_) => true,
             Expression::PropertyReference(nr) => nr.is_constant(),
             Expression::ElementReference(_) => false,
             Expression::RepeaterIndexReference { .. } => false,
@@ -1062,6 +1281,22 @@ pub fn is_constant(&self) -> bool {
             Expression::FunctionParameterReference { .. } => true,
             Expression::StructFieldAccess { base, .. } => base.is_constant(),
             Expression::ArrayIndex { array, index } => array.is_constant() && index.is_constant(),
+            Expression::ArrayMap { array, body, .. } => array.is_constant() && body.is_constant(),
+            Expression::ArrayFilter { array, condition, .. } => {
+                array.is_constant() && condition.is_constant()
+            }
+            Expression::ArraySlice { array, start, end } => {
+                array.is_constant() && start.is_constant() && end.is_constant()
+            }
+            Expression::ArrayConcat { lhs, rhs } => lhs.is_constant() && rhs.is_constant(),
+            Expression::ArrayIndexOf { array, value } => array.is_constant() && value.is_constant(),
+            Expression::MapIndex { map, key } | Expression::MapHasKey { map, key } => {
+                map.is_constant() && key.is_constant()
+            }
+            Expression::MapEntries(map) => map.is_constant(),
+            Expression::OptionalHasValue(optional) | Expression::OptionalUnwrap(optional) => {
+                optional.is_constant()
+            }
             Expression::Cast { from, .. } => from.is_constant(),
             // This is conservative: the return value is the last expression in the block, but
             // we kind of mean "pure" here too, so ensure the whole body is OK.
@@ -1086,6 +1321,7 @@ pub fn is_constant(&self) -> bool {
             //Expression::Array { values, .. } => values.iter().all(Expression::is_constant),
             Expression::Array { .. } => false,
             Expression::Struct { values, .. } => values.iter().all(|(_, v)| v.is_constant()),
+            Expression::Map { values, .. } => values.values().all(Expression::is_constant),
             Expression::PathData(data) => match data {
                 Path::Elements(elements) => elements
                     .iter()
@@ -1131,6 +1367,53 @@ pub fn maybe_convert_to(
             || ty == Type::Invalid
         {
             self
+        } else if let (Type::Optional(from), Type::Optional(to)) = (&ty, &target_type) {
+            if **from == Type::Void {
+                // The `null` literal
+                return match self {
+                    Expression::NullLiteral(_) => Expression::NullLiteral(target_type),
+                    _ => Expression::CodeBlock(vec![self, Expression::NullLiteral(target_type)]),
+                };
+            }
+            // Convert the value if there is one
+            static COUNT: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(0);
+            let var_name = format_smolstr!(
+                "tmpobj_optional_conv_{}",
+                COUNT.fetch_add(1, std::sync::atomic::Ordering::Relaxed)
+            );
+            let read = || Expression::ReadLocalVariable { name: var_name.clone(), ty: ty.clone() };
+            let value = Expression::OptionalUnwrap(Box::new(read())).maybe_convert_to(
+                (**to).clone(),
+                node,
+                diag,
+            );
+            Expression::CodeBlock(vec![
+                Expression::StoreLocalVariable { name: var_name.clone(), value: Box::new(self) },
+                Expression::Condition {
+                    condition: Box::new(Expression::OptionalHasValue(Box::new(read()))),
+                    true_expr: Box::new(Expression::Cast {
+                        from: Box::new(value),
+                        to: target_type.clone(),
+                    }),
+                    false_expr: Box::new(Expression::NullLiteral(target_type)),
+                },
+            ])
+        } else if let Type::Optional(inner) = &target_type {
+            // A value is converted to the type of the optional, and then wrapped in the optional
+            Expression::Cast {
+                from: Box::new(self.maybe_convert_to((**inner).clone(), node, diag)),
+                to: target_type,
+            }
+        } else if let (Type::Map(value_ty), Expression::Struct { .. }) = (&target_type, &self) {
+            // Struct literals are converted to map literals, with each value converted to the value type
+            let Expression::Struct { values, .. } = self else { unreachable!() };
+            Expression::Map {
+                value_ty: (**value_ty).clone(),
+                values: values
+                    .into_iter()
+                    .map(|(k, v)| (k, v.maybe_convert_to((**value_ty).clone(), node, diag)))
+                    .collect(),
+            }
         } else if ty.can_convert(&target_type) {
             let from = match (ty, &target_type) {
                 (Type::Brush, Type::Color) => match self {
@@ -1141,6 +1424,19 @@ pub fn maybe_convert_to(
                     }
                     _ => self,
                 },
+                (Type::Float32, Type::Int64) => match self {
+                    // Number literals are parsed as a f64
+                    Expression::NumberLiteral(value, Unit::None)
+                        if value.abs() >= 2f64.powi(53) =>
+                    {
+                        diag.push_warning(
+                            "This number is too large to be represented exactly as a long. Use to-long() on a string instead".into(),
+                            node,
+                        );
+                        self
+                    }
+                    _ => self,
+                },
                 (Type::Percent, Type::Float32) => Expression::BinaryExpression {
                     lhs: Box::new(self),
                     rhs: Box::new(Expression::NumberLiteral(0.01, Unit::None)),
@@ -1187,6 +1483,33 @@ pub fn maybe_convert_to(
                         Expression::Struct { values: new_values, ty: right.clone() },
                     ]);
                 }
+                (ref from_ty @ Type::Struct(ref left), Type::Map(value_ty)) => {
+                    static COUNT: std::sync::atomic::AtomicUsize =
+                        std::sync::atomic::AtomicUsize::new(0);
+                    let var_name = format_smolstr!(
+                        "tmpobj_map_conv_{}",
+                        COUNT.fetch_add(1, std::sync::atomic::Ordering::Relaxed)
+                    );
+                    let values = left
+                        .fields
+                        .keys()
+                        .map(|key| {
+                            let value = Expression::StructFieldAccess {
+                                base: Box::new(Expression::ReadLocalVariable {
+                                    name: var_name.clone(),
+                                    ty: from_ty.clone(),
+                                }),
+                                name: key.clone(),
+                            };
+                            (key.clone(), value.maybe_convert_to((**value_ty).clone(), node, diag))
+                        })
+                        .collect();
+                    return Expression::CodeBlock(vec![
+                        Expression::StoreLocalVariable { name: var_name, value: Box::new(self) },
+                        Expression::Map { value_ty: (**value_ty).clone(), values },
+                    ]);
+                }
+                (Type::Map(_), Type::Model) => Expression::MapEntries(Box::new(self)),
                 (left, right) => match (left.as_unit_product(), right.as_unit_product()) {
                     (Some(left), Some(right)) => {
                         if let Some(conversion_powers) =
@@ -1251,6 +1574,21 @@ pub fn maybe_convert_to(
                 },
                 _ => unreachable!(),
             }
+        } else if matches!(
+            (&ty, &target_type, &self),
+            (Type::Array(_), Type::Array(_), Expression::ArrayMap { .. })
+        ) {
+            // Also special case `map` so that its body is converted to the item type
+            match (self, target_type) {
+                (Expression::ArrayMap { array, item, body }, Type::Array(target_type)) => {
+                    Expression::ArrayMap {
+                        array,
+                        item,
+                        body: Box::new(body.maybe_convert_to((*target_type).clone(), node, diag)),
+                    }
+                }
+                _ => unreachable!(),
+            }
         } else if let (Type::Struct(struct_type), Expression::Struct { values, .. }) =
             (&target_type, &self)
         {
@@ -1272,7 +1610,11 @@ pub fn maybe_convert_to(
         } else {
             let mut message = format!("Cannot convert {ty} to {target_type}");
             // Explicit error message for unit conversion
-            if let Some(from_unit) = ty.default_unit() {
+            if let Type::Optional(inner) = &ty {
+                if inner.can_convert(&target_type) {
+                    message = format!("{message}. Use `??` to provide a value for null");
+                }
+            } else if let Some(from_unit) = ty.default_unit() {
                 if matches!(&target_type, Type::Int32 | Type::Float32 | Type::String) {
                     message =
                         format!("{message}. Divide by 1{from_unit} to convert to a plain number");
@@ -1308,7 +1650,7 @@ pub fn default_value_for_type(ty: &Type) -> Expression {
             Type::Void => Expression::CodeBlock(vec![]),
             Type::Float32 => Expression::NumberLiteral(0., Unit::None),
             Type::String => Expression::StringLiteral(SmolStr::default()),
-            Type::Int32 | Type::Color | Type::UnitProduct(_) => Expression::Cast {
+            Type::Int32 | Type::Int64 | Type::Color | Type::UnitProduct(_) => Expression::Cast {
                 from: Box::new(Expression::NumberLiteral(0., Unit::None)),
                 to: ty.clone(),
             },
@@ -1337,6 +1679,10 @@ pub fn default_value_for_type(ty: &Type) -> Expression {
                     .map(|(k, v)| (k.clone(), Expression::default_value_for_type(v)))
                     .collect(),
             },
+            Type::Map(value_ty) => {
+                Expression::Map { value_ty: (**value_ty).clone(), values: Default::default() }
+            }
+            Type::Optional(_) => Expression::NullLiteral(ty.clone()),
             Type::Easing => Expression::EasingCurve(EasingCurve::default()),
             Type::Brush => Expression::Cast {
                 from: Box::new(Expression::default_value_for_type(&Type::Color)),
@@ -1594,6 +1940,7 @@ pub fn pretty_print(f: &mut dyn std::fmt::Write, expression: &Expression) -> std
         Expression::StringLiteral(s) => write!(f, "{s:?}"),
         Expression::NumberLiteral(vl, unit) => write!(f, "{vl}{unit}"),
         Expression::BoolLiteral(b) => write!(f, "{b:?}"),
+        Expression::NullLiteral(_) => write!(f, "null"),
         Expression::PropertyReference(a) => write!(f, "{a:?}"),
         Expression::ElementReference(a) => write!(f, "{a:?}"),
         Expression::RepeaterIndexReference { element } => {
@@ -1619,6 +1966,65 @@ pub fn pretty_print(f: &mut dyn std::fmt::Write, expression: &Expression) -> std
             pretty_print(f, index)?;
             write!(f, "]")
         }
+        Expression::ArrayMap { array, item, body } => {
+            pretty_print(f, array)?;
+            write!(f, ".map({item} => ")?;
+            pretty_print(f, body)?;
+            write!(f, ")")
+        }
+        Expression::ArrayFilter { array, item, condition } => {
+            pretty_print(f, array)?;
+            write!(f, ".filter({item} => ")?;
+            pretty_print(f, condition)?;
+            write!(f, ")")
+        }
+        Expression::ArraySlice { array, start, end } => {
+            pretty_print(f, array)?;
+            write!(f, ".slice(")?;
+            pretty_print(f, start)?;
+            write!(f, ", ")?;
+            pretty_print(f, end)?;
+            write!(f, ")")
+        }
+        Expression::ArrayConcat { lhs, rhs } => {
+            pretty_print(f, lhs)?;
+            write!(f, ".concat(")?;
+            pretty_print(f, rhs)?;
+            write!(f, ")")
+        }
+        Expression::ArrayIndexOf { array, value } => {
+            pretty_print(f, array)?;
+            write!(f, ".index-of(")?;
+            pretty_print(f, value)?;
+            write!(f, ")")
+        }
+        Expression::MapIndex { map, key } => {
+            pretty_print(f, map)?;
+            write!(f, "[")?;
+            pretty_print(f, key)?;
+            write!(f, "]")
+        }
+        Expression::MapHasKey { map, key } => {
+            pretty_print(f, map)?;
+            write!(f, ".has-key(")?;
+            pretty_print(f, key)?;
+            write!(f, ")")
+        }
+        Expression::MapEntries(map) => {
+            write!(f, "entries(")?;
+            pretty_print(f, map)?;
+            write!(f, ")")
+        }
+        Expression::OptionalHasValue(optional) => {
+            write!(f, "has-value(")?;
+            pretty_print(f, optional)?;
+            write!(f, ")")
+        }
+        Expression::OptionalUnwrap(optional) => {
+            write!(f, "unwrap(")?;
+            pretty_print(f, optional)?;
+            write!(f, ")")
+        }
         Expression::Cast { from, to } => {
             write!(f, "(")?;
             pretty_print(f, from)?;
@@ -1690,6 +2096,15 @@ pub fn pretty_print(f: &mut dyn std::fmt::Write, expression: &Expression) -> std
             }
             write!(f, " }}")
         }
+        Expression::Map { value_ty: _, values } => {
+            write!(f, "[ ")?;
+            for (key, e) in values {
+                write!(f, "{key:?}: ")?;
+                pretty_print(f, e)?;
+                write!(f, ", ")?;
+            }
+            write!(f, " ]")
+        }
         Expression::PathData(data) => write!(f, "{data:?}"),
         Expression::EasingCurve(e) => write!(f, "{e:?}"),
         Expression::LinearGradient { angle, stops } => {
@@ -207,7 +207,7 @@ fn item_sub_tree_size(e: &ElementRc) -> usize {
 
     // Number of repeaters in this sub component
     fn repeater_count_in_sub_component(e: &ElementRc) -> usize {
-        let mut count = if e.borrow().repeated.is_some() { 0 } else { 1 };
+        let mut count = if e.borrow().repeated.is_some() { 1 } else { 0 };
         for i in &e.borrow().children {
             count += repeater_count_in_sub_component(i);
         }
@@ -364,6 +364,7 @@ fn visit_item<T: ItemTreeBuilder>(
                 parent_index,
                 component_state,
             );
+            *container_count += 1;
         } else if item.borrow().repeated.is_some() {
             builder.push_repeated_item(item, *repeater_count, parent_index, component_state);
             *repeater_count += 1;
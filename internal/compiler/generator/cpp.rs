@@ -505,6 +505,7 @@ fn cpp_type(&self) -> Option<SmolStr> {
             Type::Void => Some("void".into()),
             Type::Float32 => Some("float".into()),
             Type::Int32 => Some("int".into()),
+            Type::Int64 => Some("int64_t".into()),
             Type::String => Some("slint::SharedString".into()),
             Type::Color => Some("slint::Color".into()),
             Type::Duration => Some("std::int64_t".into()),
@@ -531,6 +532,10 @@ fn cpp_type(&self) -> Option<SmolStr> {
             Type::Array(i) => {
                 Some(format_smolstr!("std::shared_ptr<slint::Model<{}>>", i.cpp_type()?))
             }
+            Type::Map(i) => {
+                Some(format_smolstr!("std::map<slint::SharedString, {}>", i.cpp_type()?))
+            }
+            Type::Optional(i) => Some(format_smolstr!("std::optional<{}>", i.cpp_type()?)),
             Type::Image => Some("slint::Image".into()),
             Type::Enumeration(enumeration) => {
                 if enumeration.node.is_some() {
@@ -684,6 +689,8 @@ pub fn generate(
 
     file.includes.push("<array>".into());
     file.includes.push("<limits>".into());
+    file.includes.push("<map>".into());
+    file.includes.push("<optional>".into());
     file.includes.push("<slint.h>".into());
 
     for (path, er) in doc.embedded_file_resources.borrow().iter() {
@@ -709,6 +716,20 @@ pub fn generate(
 
     let llr = llr::lower_to_item_tree::lower_to_item_tree(doc, compiler_config)?;
 
+    // The handler of an async callback returns a future, which the C++ API doesn't support
+    if let Some(p) = llr
+        .public_components
+        .iter()
+        .flat_map(|c| &c.public_properties)
+        .chain(llr.globals.iter().filter(|g| g.exported).flat_map(|g| &g.public_properties))
+        .find(|p| p.is_async)
+    {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::Other,
+            format!("Async callback '{}' is not supported by the C++ generator", p.name),
+        ));
+    }
+
     #[cfg(feature = "bundle-translations")]
     if let Some(translations) = &llr.translations {
         generate_translation(translations, &llr, &mut file.resources);
@@ -3047,6 +3068,10 @@ fn compile_expression(expr: &llr::Expression, ctx: &EvaluationContext) -> String
             }
         }
         Expression::BoolLiteral(b) => b.to_string(),
+        Expression::NullLiteral(ty) => match ty {
+            Type::Optional(inner) if **inner == Type::Void => "std::nullopt".into(),
+            _ => format!("{}()", ty.cpp_type().unwrap()),
+        },
         Expression::PropertyReference(nr) => {
             let access = access_member(nr, ctx);
             format!(r#"{access}.get()"#)
@@ -3094,12 +3119,75 @@ fn compile_expression(expr: &llr::Expression, ctx: &EvaluationContext) -> String
                 compile_expression(array, ctx), compile_expression(index, ctx)
             )
         },
+        Expression::ArrayMap { array, item, body } => {
+            format!(
+                "slint::private_api::array_map({}, [&](const auto &{}) -> {} {{ return {}; }})",
+                compile_expression(array, ctx),
+                ident(item),
+                body.ty(ctx).cpp_type().unwrap(),
+                compile_expression(body, ctx)
+            )
+        },
+        Expression::ArrayFilter { array, item, condition } => {
+            format!(
+                "slint::private_api::array_filter({}, [&](const auto &{}) -> bool {{ return {}; }})",
+                compile_expression(array, ctx),
+                ident(item),
+                compile_expression(condition, ctx)
+            )
+        },
+        Expression::ArraySlice { array, start, end } => {
+            format!(
+                "slint::private_api::array_slice({}, {}, {})",
+                compile_expression(array, ctx), compile_expression(start, ctx), compile_expression(end, ctx)
+            )
+        },
+        Expression::ArrayConcat { lhs, rhs } => {
+            format!(
+                "slint::private_api::array_concat({}, {})",
+                compile_expression(lhs, ctx), compile_expression(rhs, ctx)
+            )
+        },
+        Expression::ArrayIndexOf { array, value } => {
+            format!(
+                "slint::private_api::array_index_of({}, {})",
+                compile_expression(array, ctx), compile_expression(value, ctx)
+            )
+        },
+        Expression::MapIndex { map, key } => {
+            format!(
+                "slint::private_api::map_get({}, {})",
+                compile_expression(map, ctx), compile_expression(key, ctx)
+            )
+        },
+        Expression::MapHasKey { map, key } => {
+            format!("{}.contains({})", compile_expression(map, ctx), compile_expression(key, ctx))
+        },
+        Expression::MapEntries(map) => {
+            format!("slint::private_api::map_entries({})", compile_expression(map, ctx))
+        },
+        Expression::OptionalHasValue(optional) => {
+            format!("({}).has_value()", compile_expression(optional, ctx))
+        },
+        Expression::OptionalUnwrap(optional) => {
+            let Type::Optional(inner) = optional.ty(ctx) else { unreachable!() };
+            format!("({}).value_or({}{{}})", compile_expression(optional, ctx), inner.cpp_type().unwrap())
+        },
         Expression::Cast { from, to } => {
             let f = compile_expression(from, ctx);
             match (from.ty(ctx), to) {
-                (Type::Float32, Type::Int32) => {
+                (Type::Float32 | Type::Int64, Type::Int32) => {
                     format!("static_cast<int>({f})")
                 }
+                (Type::Float32 | Type::Int32, Type::Int64) => {
+                    format!("static_cast<int64_t>({f})")
+                }
+                (Type::Int64, Type::Float32) => {
+                    format!("static_cast<double>({f})")
+                }
+                (Type::Int64, Type::String) => {
+                    format!("slint::private_api::shared_string_from_long({f})")
+                }
                 (from, Type::String) if from.as_unit_product().is_some() => {
                     format!("slint::SharedString::from_number({f})")
                 }
@@ -3116,6 +3204,9 @@ fn compile_expression(expr: &llr::Expression, ctx: &EvaluationContext) -> String
                 (Type::Brush, Type::Color) => {
                     format!("{f}.color()")
                 }
+                (_, Type::Optional(_)) => {
+                    format!("{}({f})", to.cpp_type().unwrap())
+                }
                 (Type::Struct (_), Type::Struct(s)) if s.name.is_some() => {
                     format!(
                         "[&](const auto &o){{ {struct_name} s; {fields} return s; }}({obj})",
@@ -3251,7 +3342,21 @@ fn compile_expression(expr: &llr::Expression, ctx: &EvaluationContext) -> String
             if lhs_ty.as_unit_product().is_some() && (*op == '=' || *op == '!') {
                 let op = if *op == '=' { "<" } else { ">=" };
                 format!("(std::abs(float({lhs_str} - {rhs_str})) {op} std::numeric_limits<float>::epsilon())")
-            }  else {
+            } else if lhs_ty == Type::Int64 && *op == '/' {
+                format!("slint::private_api::long_div({lhs_str}, {rhs_str})")
+            } else if lhs_ty == Type::Int64 {
+                let mut buffer = [0; 3];
+                format!(
+                    "({lhs_str} {op} {rhs_str})",
+                    op = match op {
+                        '=' => "==",
+                        '!' => "!=",
+                        '≤' => "<=",
+                        '≥' => ">=",
+                        _ => op.encode_utf8(&mut buffer),
+                    },
+                )
+            } else {
                 let mut buffer = [0; 3];
                 format!(
                     "({lhs_str} {op} {rhs_str})",
@@ -3324,6 +3429,16 @@ fn compile_expression(expr: &llr::Expression, ctx: &EvaluationContext) -> String
                 )
             }
         }
+        Expression::Map { value_ty, values } => {
+            let ty = value_ty.cpp_type().unwrap();
+            format!(
+                "std::map<slint::SharedString, {ty}>{{ {} }}",
+                values.iter().map(|(k, e)| format!(
+                    "{{ slint::SharedString(u8\"{}\"), {ty}({}) }}",
+                    escape_string(k), compile_expression(e, ctx)
+                )).join(", ")
+            )
+        }
         Expression::Struct { ty, values } => {
             if ty.name.is_none()  {
                 let mut elem = ty.fields.iter().map(|(k, t)| {
@@ -3582,6 +3697,12 @@ fn compile_builtin_function_call(
             ctx.generator_state.conditional_includes.cstdlib.set(true);
             format!("[](const auto &a){{ float res = 0; slint::cbindgen_private::slint_string_to_float(&a, &res); return res; }}({})", a.next().unwrap())
         }
+        BuiltinFunction::StringIsLong => {
+            format!("[](const auto &a){{ int64_t res = 0; return slint::cbindgen_private::slint_string_to_long(&a, &res); }}({})", a.next().unwrap())
+        }
+        BuiltinFunction::StringToLong => {
+            format!("[](const auto &a){{ int64_t res = 0; slint::cbindgen_private::slint_string_to_long(&a, &res); return res; }}({})", a.next().unwrap())
+        }
         BuiltinFunction::StringIsEmpty => {
             format!("{}.empty()", a.next().unwrap())
         }
@@ -3594,6 +3715,30 @@ fn compile_builtin_function_call(
         BuiltinFunction::StringToUppercase => {
             format!("{}.to_uppercase()", a.next().unwrap())
         }
+        BuiltinFunction::StringContains => {
+            format!("[](const auto &a, const auto &b){{ return slint::cbindgen_private::slint_shared_string_contains(&a, &b); }}({}, {})", a.next().unwrap(), a.next().unwrap())
+        }
+        BuiltinFunction::StringStartsWith => {
+            format!("[](const auto &a, const auto &b){{ return slint::cbindgen_private::slint_shared_string_starts_with(&a, &b); }}({}, {})", a.next().unwrap(), a.next().unwrap())
+        }
+        BuiltinFunction::StringEndsWith => {
+            format!("[](const auto &a, const auto &b){{ return slint::cbindgen_private::slint_shared_string_ends_with(&a, &b); }}({}, {})", a.next().unwrap(), a.next().unwrap())
+        }
+        BuiltinFunction::StringReplace => {
+            format!("[](const auto &a, const auto &b, const auto &c){{ slint::SharedString out; slint::cbindgen_private::slint_shared_string_replace(&out, &a, &b, &c); return out; }}({}, {}, {})", a.next().unwrap(), a.next().unwrap(), a.next().unwrap())
+        }
+        BuiltinFunction::StringTrim => {
+            format!("[](const auto &a){{ slint::SharedString out; slint::cbindgen_private::slint_shared_string_trim(&out, &a); return out; }}({})", a.next().unwrap())
+        }
+        BuiltinFunction::StringSlice => {
+            format!("[](const auto &a, int32_t start, int32_t end){{ slint::SharedString out; slint::cbindgen_private::slint_shared_string_slice(&out, &a, start, end); return out; }}({}, {}, {})", a.next().unwrap(), a.next().unwrap(), a.next().unwrap())
+        }
+        BuiltinFunction::StringSplit => {
+            format!("[](const auto &a, const auto &b){{ slint::SharedVector<slint::SharedString> out; slint::cbindgen_private::slint_shared_string_split(&out, &a, &b); return std::make_shared<slint::VectorModel<slint::SharedString>>(std::vector<slint::SharedString>(out.begin(), out.end())); }}({}, {})", a.next().unwrap(), a.next().unwrap())
+        }
+        BuiltinFunction::StringIndexOf => {
+            format!("[](const auto &a, const auto &b){{ return slint::cbindgen_private::slint_shared_string_index_of(&a, &b); }}({}, {})", a.next().unwrap(), a.next().unwrap())
+        }
         BuiltinFunction::ColorRgbaStruct => {
             format!("{}.to_argb_uint()", a.next().unwrap())
         }
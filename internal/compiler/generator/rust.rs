@@ -25,11 +25,13 @@
 use lyon_path::geom::euclid::approxeq::ApproxEq;
 use proc_macro2::{Ident, TokenStream};
 use quote::{format_ident, quote};
-use smol_str::SmolStr;
+use smol_str::{format_smolstr, SmolStr};
 use std::collections::{BTreeMap, BTreeSet};
 use std::num::NonZeroUsize;
 use std::str::FromStr;
 
+pub mod live_reload;
+
 #[derive(Clone)]
 struct RustGeneratorContext {
     /// Path to the SharedGlobals structure that contains the global and the WindowAdaptor
@@ -80,6 +82,7 @@ fn rust_primitive_type(ty: &Type) -> Option<proc_macro2::TokenStream> {
     match ty {
         Type::Void => Some(quote!(())),
         Type::Int32 => Some(quote!(i32)),
+        Type::Int64 => Some(quote!(i64)),
         Type::Float32 => Some(quote!(f32)),
         Type::String => Some(quote!(sp::SharedString)),
         Type::Color => Some(quote!(sp::Color)),
@@ -106,6 +109,14 @@ fn rust_primitive_type(ty: &Type) -> Option<proc_macro2::TokenStream> {
             let inner = rust_primitive_type(o)?;
             Some(quote!(sp::ModelRc<#inner>))
         }
+        Type::Map(o) => {
+            let inner = rust_primitive_type(o)?;
+            Some(quote!(sp::SharedMap<#inner>))
+        }
+        Type::Optional(o) => {
+            let inner = rust_primitive_type(o)?;
+            Some(quote!(::core::option::Option<#inner>))
+        }
         Type::Enumeration(e) => {
             let i = ident(&e.name);
             if e.node.is_some() {
@@ -271,6 +282,11 @@ fn generate_public_component(
         &llr.public_properties,
         &llr.private_properties,
         quote!(sp::VRc::as_pin_ref(&self.0)),
+        quote!(sp::VRc::downgrade(&self.0)),
+        quote!(
+            let sp::Some(self_rc) = self_weak.upgrade() else { return };
+            let _self = sp::VRc::as_pin_ref(&self_rc);
+        ),
         &ctx,
     );
 
@@ -560,10 +576,15 @@ fn handle_property_init(
 }
 
 /// Public API for Global and root component
+///
+/// `self_weak` creates a weak reference from `self`, and `self_upgrade` initializes `_self` from
+/// the `self_weak` weak reference, or returns if it was destroyed.
 fn public_api(
     public_properties: &llr::PublicProperties,
     private_properties: &llr::PrivateProperties,
     self_init: TokenStream,
+    self_weak: TokenStream,
+    self_upgrade: TokenStream,
     ctx: &EvaluationContext,
 ) -> TokenStream {
     let mut property_and_callback_accessors: Vec<TokenStream> = vec![];
@@ -571,7 +592,59 @@ fn public_api(
         let prop_ident = ident(&p.name);
         let prop = access_member(&p.prop, ctx).unwrap();
 
-        if let Type::Callback(callback) = &p.ty {
+        if let (Type::Callback(callback), true) = (&p.ty, p.is_async) {
+            let callback_args =
+                callback.args.iter().map(|a| rust_primitive_type(a).unwrap()).collect::<Vec<_>>();
+            let args_name =
+                (0..callback.args.len()).map(|i| format_ident!("arg_{}", i)).collect::<Vec<_>>();
+            let caller_ident = format_ident!("invoke_{}", prop_ident);
+            property_and_callback_accessors.push(quote!(
+                #[allow(dead_code)]
+                pub fn #caller_ident(&self, #(#args_name : #callback_args,)*) {
+                    let _self = #self_init;
+                    #prop.call(&(#(#args_name,)*))
+                }
+            ));
+
+            let find_property = |suffix: &str| {
+                let name = format_smolstr!("{}-{suffix}", p.name);
+                public_properties.iter().find(|p| p.name == name)
+            };
+            let set_pending = find_property("pending")
+                .map(|pending| property_set_value_tokens(&pending.prop, quote!(pending), ctx));
+            let (return_type, set_result) = match find_property("result") {
+                Some(result) => (
+                    rust_primitive_type(&result.ty).unwrap(),
+                    Some(property_set_value_tokens(&result.prop, quote!(value), ctx)),
+                ),
+                None => (quote!(()), None),
+            };
+            let set_result = set_result
+                .map(|set_result| quote!(if let sp::Some(value) = result { #set_result }));
+            let on_ident = format_ident!("on_{}", prop_ident);
+            let args_index = (0..callback_args.len()).map(proc_macro2::Literal::usize_unsuffixed);
+            property_and_callback_accessors.push(quote!(
+                #[allow(dead_code)]
+                pub fn #on_ident<F: ::core::future::Future<Output = #return_type> + 'static>(
+                    &self,
+                    mut f: impl FnMut(#(#callback_args),*) -> F + 'static,
+                ) {
+                    let _self = #self_init;
+                    let self_weak = #self_weak;
+                    let runner = sp::AsyncCallbackRunner::default();
+                    #[allow(unused)]
+                    #prop.set_handler(move |args| {
+                        let self_weak = self_weak.clone();
+                        runner.spawn(f(#(args.#args_index.clone()),*), move |result| {
+                            #self_upgrade
+                            let pending = result.is_none();
+                            #set_result
+                            #set_pending
+                        })
+                    })
+                }
+            ));
+        } else if let Type::Callback(callback) = &p.ty {
             let callback_args =
                 callback.args.iter().map(|a| rust_primitive_type(a).unwrap()).collect::<Vec<_>>();
             let return_type = rust_primitive_type(&callback.return_type).unwrap();
@@ -1440,6 +1513,12 @@ fn generate_global(
             &global.public_properties,
             &global.private_properties,
             quote!(self.0.as_ref()),
+            quote!(_self.globals.get().unwrap().clone()),
+            quote!(
+                let sp::Some(globals) = self_weak.upgrade() else { return };
+                let self_rc = globals.#global_id.clone();
+                let _self = self_rc.as_ref();
+            ),
             &ctx,
         );
         let aliases = global.aliases.iter().map(|name| ident(name));
@@ -2186,12 +2265,26 @@ fn compile_expression(expr: &Expression, ctx: &EvaluationContext) -> TokenStream
         Expression::NumberLiteral(n) if n.is_finite() => quote!(#n),
         Expression::NumberLiteral(_) => quote!(0.),
         Expression::BoolLiteral(b) => quote!(#b),
+        Expression::NullLiteral(ty) => {
+            let Type::Optional(inner) = ty else { unreachable!() };
+            let inner = rust_primitive_type(inner).unwrap();
+            quote!(::core::option::Option::<#inner>::None)
+        }
         Expression::Cast { from, to } => {
             let f = compile_expression(from, ctx);
             match (from.ty(ctx), to) {
-                (Type::Float32, Type::Int32) => {
+                (Type::Float32 | Type::Int64, Type::Int32) => {
                     quote!((#f as i32))
                 }
+                (Type::Float32 | Type::Int32, Type::Int64) => {
+                    quote!((#f as i64))
+                }
+                (Type::Int64, Type::Float32) => {
+                    quote!((#f as f64))
+                }
+                (Type::Int64, Type::String) => {
+                    quote!(sp::format!("{}", #f))
+                }
                 (from, Type::String) if from.as_unit_product().is_some() => {
                     quote!(sp::shared_string_from_number((#f) as f64))
                 }
@@ -2254,6 +2347,15 @@ fn compile_expression(expr: &Expression, ctx: &EvaluationContext) -> TokenStream
                 (Type::String, Type::PathData) => {
                     quote!(sp::PathData::Commands(#f))
                 }
+                (_, Type::Optional(inner)) => {
+                    if inner.as_unit_product().is_some() {
+                        // Numbers might be f64 literals
+                        let t = rust_primitive_type(inner).unwrap();
+                        quote!(::core::option::Option::Some((#f) as #t))
+                    } else {
+                        quote!(::core::option::Option::Some(#f))
+                    }
+                }
                 (_, Type::Void) => {
                     quote!(#f;)
                 }
@@ -2329,6 +2431,100 @@ fn compile_expression(expr: &Expression, ctx: &EvaluationContext) -> TokenStream
                 x.row_data_tracked(index).unwrap_or_default()
             }})
         }
+        Expression::ArrayMap { array, item, body } => {
+            let array_e = compile_expression(array, ctx);
+            let item = ident(item);
+            let body_ty = rust_primitive_type(&body.ty(ctx)).unwrap();
+            let body_e = compile_expression(body, ctx);
+            quote!(match &#array_e { x => {
+                x.model_tracker().track_row_count_changes();
+                sp::ModelRc::new(sp::VecModel::<#body_ty>::from(
+                    (0..x.row_count())
+                        .filter_map(|i| x.row_data_tracked(i))
+                        .map(|#item| -> #body_ty { (#body_e) as _ })
+                        .collect::<sp::Vec<_>>()
+                ))
+            }})
+        }
+        Expression::ArrayFilter { array, item, condition } => {
+            let array_e = compile_expression(array, ctx);
+            let item = ident(item);
+            let condition_e = compile_expression(condition, ctx);
+            quote!(match &#array_e { x => {
+                x.model_tracker().track_row_count_changes();
+                sp::ModelRc::new(sp::VecModel::from(
+                    (0..x.row_count())
+                        .filter_map(|i| x.row_data_tracked(i))
+                        .filter(|#item| #condition_e)
+                        .collect::<sp::Vec<_>>()
+                ))
+            }})
+        }
+        Expression::ArraySlice { array, start, end } => {
+            let array_e = compile_expression(array, ctx);
+            let start_e = compile_expression(start, ctx);
+            let end_e = compile_expression(end, ctx);
+            quote!(match &#array_e { x => {
+                x.model_tracker().track_row_count_changes();
+                let len = x.row_count() as i32;
+                let clamp = |i: i32| (if i < 0 { (len + i).max(0) } else { i.min(len) }) as usize;
+                let start = clamp((#start_e) as i32);
+                let end = clamp((#end_e) as i32).max(start);
+                sp::ModelRc::new(sp::VecModel::from(
+                    (start..end).filter_map(|i| x.row_data_tracked(i)).collect::<sp::Vec<_>>()
+                ))
+            }})
+        }
+        Expression::ArrayConcat { lhs, rhs } => {
+            let lhs_e = compile_expression(lhs, ctx);
+            let rhs_e = compile_expression(rhs, ctx);
+            quote!(match (&#lhs_e, &#rhs_e) { (x, y) => {
+                x.model_tracker().track_row_count_changes();
+                y.model_tracker().track_row_count_changes();
+                sp::ModelRc::new(sp::VecModel::from(
+                    (0..x.row_count())
+                        .filter_map(|i| x.row_data_tracked(i))
+                        .chain((0..y.row_count()).filter_map(|i| y.row_data_tracked(i)))
+                        .collect::<sp::Vec<_>>()
+                ))
+            }})
+        }
+        Expression::ArrayIndexOf { array, value } => {
+            let array_e = compile_expression(array, ctx);
+            let value_ty = rust_primitive_type(&value.ty(ctx)).unwrap();
+            let value_e = compile_expression(value, ctx);
+            quote!(match (&#array_e, (#value_e) as #value_ty) { (x, value) => {
+                x.model_tracker().track_row_count_changes();
+                (0..x.row_count())
+                    .position(|i| x.row_data_tracked(i).is_some_and(|v| v == value))
+                    .map_or(-1, |i| i as i32)
+            }})
+        }
+        Expression::MapIndex { map, key } => {
+            let map_e = compile_expression(map, ctx);
+            let key_e = compile_expression(key, ctx);
+            quote!((#map_e).get(&(#key_e)).cloned().unwrap_or_default())
+        }
+        Expression::MapHasKey { map, key } => {
+            let map_e = compile_expression(map, ctx);
+            let key_e = compile_expression(key, ctx);
+            quote!((#map_e).contains_key(&(#key_e)))
+        }
+        Expression::MapEntries(map) => {
+            let map_e = compile_expression(map, ctx);
+            // The entries are anonymous structs, so tuples of the key and the value
+            quote!(sp::ModelRc::new(sp::VecModel::from(
+                (#map_e).iter().map(|(k, v)| (k.clone(), v.clone())).collect::<sp::Vec<_>>()
+            )))
+        }
+        Expression::OptionalHasValue(optional) => {
+            let optional = compile_expression(optional, ctx);
+            quote!((#optional).is_some())
+        }
+        Expression::OptionalUnwrap(optional) => {
+            let optional = compile_expression(optional, ctx);
+            quote!((#optional).unwrap_or_default())
+        }
         Expression::CodeBlock(sub) => {
             let map = sub.iter().map(|e| compile_expression(e, ctx));
             quote!({ #(#map);* })
@@ -2376,7 +2572,18 @@ fn compile_expression(expr: &Expression, ctx: &EvaluationContext) -> TokenStream
             let lhs = compile_expression(lhs, ctx);
             let rhs = compile_expression(rhs, ctx);
 
-            if lhs_ty.as_unit_product().is_some() && (*op == '=' || *op == '!') {
+            if lhs_ty == Type::Int64
+                && crate::expression_tree::operator_class(*op) == OperatorClass::ArithmeticOp
+            {
+                // Integer arithmetic that doesn't panic on overflow, and where a division by 0 is 0
+                match op {
+                    '+' => quote!((#lhs as i64).wrapping_add(#rhs as i64)),
+                    '-' => quote!((#lhs as i64).wrapping_sub(#rhs as i64)),
+                    '*' => quote!((#lhs as i64).wrapping_mul(#rhs as i64)),
+                    '/' => quote!((#lhs as i64).checked_div(#rhs as i64).unwrap_or(0)),
+                    _ => unreachable!(),
+                }
+            } else if lhs_ty.as_unit_product().is_some() && (*op == '=' || *op == '!') {
                 let maybe_negate = if *op == '!' { quote!(!) } else { quote!() };
                 quote!(#maybe_negate sp::ApproxEq::<f64>::approx_eq(&(#lhs as f64), &(#rhs as f64)))
             } else {
@@ -2483,6 +2690,18 @@ fn compile_expression(expr: &Expression, ctx: &EvaluationContext) -> TokenStream
                 quote!(sp::Slice::from_slice(&[#(#val),*]))
             }
         }
+        Expression::Map { value_ty, values } => {
+            let rust_value_ty = rust_primitive_type(value_ty).unwrap();
+            if values.is_empty() {
+                quote!(sp::SharedMap::<#rust_value_ty>::default())
+            } else {
+                let keys = values.keys().map(|k| k.as_str());
+                let val = values.values().map(|e| compile_expression(e, ctx));
+                quote!(sp::SharedMap::<#rust_value_ty>::from([
+                    #((sp::SharedString::from(#keys), (#val) as _)),*
+                ]))
+            }
+        }
         Expression::Struct { ty, values } => {
             let elem = ty.fields.keys().map(|k| values.get(k).map(|e| compile_expression(e, ctx)));
             if let Some(name) = &ty.name {
@@ -3015,12 +3234,45 @@ fn compile_builtin_function_call(
             quote!(#(#a)*.as_str().parse::<f64>().unwrap_or_default())
         }
         BuiltinFunction::StringIsFloat => quote!(#(#a)*.as_str().parse::<f64>().is_ok()),
+        BuiltinFunction::StringToLong => {
+            quote!(#(#a)*.as_str().parse::<i64>().unwrap_or_default())
+        }
+        BuiltinFunction::StringIsLong => quote!(#(#a)*.as_str().parse::<i64>().is_ok()),
         BuiltinFunction::StringIsEmpty => quote!(#(#a)*.is_empty()),
         BuiltinFunction::StringCharacterCount => {
             quote!( sp::UnicodeSegmentation::graphemes(#(#a)*.as_str(), true).count() as i32 )
         }
         BuiltinFunction::StringToLowercase => quote!(sp::SharedString::from(#(#a)*.to_lowercase())),
         BuiltinFunction::StringToUppercase => quote!(sp::SharedString::from(#(#a)*.to_uppercase())),
+        BuiltinFunction::StringContains => {
+            let (s, needle) = (a.next().unwrap(), a.next().unwrap());
+            quote!(#s.contains(#needle.as_str()))
+        }
+        BuiltinFunction::StringStartsWith => {
+            let (s, prefix) = (a.next().unwrap(), a.next().unwrap());
+            quote!(#s.starts_with(#prefix.as_str()))
+        }
+        BuiltinFunction::StringEndsWith => {
+            let (s, suffix) = (a.next().unwrap(), a.next().unwrap());
+            quote!(#s.ends_with(#suffix.as_str()))
+        }
+        BuiltinFunction::StringReplace => {
+            let (s, from, to) = (a.next().unwrap(), a.next().unwrap(), a.next().unwrap());
+            quote!(sp::SharedString::from(#s.replace(#from.as_str(), #to.as_str())))
+        }
+        BuiltinFunction::StringTrim => quote!(sp::SharedString::from(#(#a)*.trim())),
+        BuiltinFunction::StringSlice => {
+            let (s, start, end) = (a.next().unwrap(), a.next().unwrap(), a.next().unwrap());
+            quote!(sp::shared_string_slice(#s.as_str(), #start as i32, #end as i32))
+        }
+        BuiltinFunction::StringSplit => {
+            let (s, separator) = (a.next().unwrap(), a.next().unwrap());
+            quote!(sp::ModelRc::new(sp::SharedVectorModel::from(sp::shared_string_split(#s.as_str(), #separator.as_str()))))
+        }
+        BuiltinFunction::StringIndexOf => {
+            let (s, needle) = (a.next().unwrap(), a.next().unwrap());
+            quote!(sp::shared_string_index_of(#s.as_str(), #needle.as_str()))
+        }
         BuiltinFunction::ColorRgbaStruct => quote!( #(#a)*.to_argb_u8()),
         BuiltinFunction::ColorHsvaStruct => quote!( #(#a)*.to_hsva()),
         BuiltinFunction::ColorBrighter => {
@@ -0,0 +1,579 @@
+// Copyright © SixtyFPS GmbH <info@slint.dev>
+// SPDX-License-Identifier: GPL-3.0-only OR LicenseRef-Slint-Royalty-free-2.0 OR LicenseRef-Slint-Software-3.0
+
+/*! Rust code generator for the live reload mode
+
+The generated code has the same API as the one of the [`super::generate`], but the components
+are not compiled to Rust: they hold a `LiveReloadingComponent` from the interpreter, that loads
+the `.slint` files at run-time and reloads them when they change.
+For the `slint!` macro, the code is loaded from the Rust file that invokes the macro.
+The properties, callbacks and functions are accessed by name, and their values are converted
+from and to `slint_interpreter::Value`.
+*/
+
+use super::{
+    generate_enum, generate_named_exports, generate_struct, ident, rust_primitive_type,
+    struct_name_to_tokens,
+};
+use crate::langtype::{Enumeration, Struct, Type};
+use crate::llr;
+use crate::object_tree::Document;
+use crate::CompilerConfiguration;
+use proc_macro2::{Literal, TokenStream};
+use quote::{format_ident, quote};
+use smol_str::format_smolstr;
+use std::path::{Path, PathBuf};
+
+/// Where the generated code loads the `.slint` code from
+enum Source {
+    /// The main `.slint` file
+    File(PathBuf),
+    /// The Rust file and line where the `slint!` macro is invoked, which the generated code
+    /// gets with `file!()` and `line!()`
+    RustMacro,
+}
+
+/// Generate the rust code for the given component, in live reload mode.
+pub fn generate(
+    doc: &Document,
+    compiler_config: &CompilerConfiguration,
+) -> std::io::Result<TokenStream> {
+    let Some(main_file) = doc.node.as_ref().map(|node| node.source_file.path()) else {
+        return Ok(Default::default());
+    };
+    generate_impl(doc, compiler_config, Source::File(std::path::absolute(main_file)?))
+}
+
+/// Generate the rust code for the component of a `slint!` macro, in live reload mode.
+pub fn generate_for_rust_macro(
+    doc: &Document,
+    compiler_config: &CompilerConfiguration,
+) -> std::io::Result<TokenStream> {
+    generate_impl(doc, compiler_config, Source::RustMacro)
+}
+
+fn generate_impl(
+    doc: &Document,
+    compiler_config: &CompilerConfiguration,
+    source: Source,
+) -> std::io::Result<TokenStream> {
+    let (structs_and_enums_ids, structs_and_enum_def): (Vec<_>, Vec<_>) = doc
+        .used_types
+        .borrow()
+        .structs_and_enums
+        .iter()
+        .filter_map(|ty| match ty {
+            Type::Struct(s) => match s.as_ref() {
+                Struct { fields, name: Some(name), node: Some(_), rust_attributes } => {
+                    let definition = generate_struct(name, fields, rust_attributes);
+                    let conversion = generate_struct_conversion(s);
+                    Some((ident(name), quote!(#definition #conversion)))
+                }
+                _ => None,
+            },
+            Type::Enumeration(en) => {
+                let definition = generate_enum(en);
+                let conversion = generate_enum_conversion(en);
+                Some((ident(&en.name), quote!(#definition #conversion)))
+            }
+            _ => None,
+        })
+        .unzip();
+
+    let llr = crate::llr::lower_to_item_tree::lower_to_item_tree(doc, compiler_config)?;
+
+    if llr.public_components.is_empty() {
+        return Ok(Default::default());
+    }
+
+    let compiler_init = compiler_init(compiler_config)?;
+
+    let public_components =
+        llr.public_components.iter().map(|p| generate_public_component(p, &source, &compiler_init));
+
+    let globals = llr
+        .globals
+        .iter()
+        .filter(|glob| glob.exported && glob.must_generate())
+        .map(|glob| generate_global(glob, &llr));
+
+    let version_check = format_ident!(
+        "VersionCheck_{}_{}_{}",
+        env!("CARGO_PKG_VERSION_MAJOR"),
+        env!("CARGO_PKG_VERSION_MINOR"),
+        env!("CARGO_PKG_VERSION_PATCH"),
+    );
+
+    let globals_ids = llr.globals.iter().filter(|glob| glob.exported).flat_map(|glob| {
+        std::iter::once(ident(&glob.name)).chain(glob.aliases.iter().map(|x| ident(x)))
+    });
+    let compo_ids = llr.public_components.iter().map(|c| ident(&c.name));
+    let named_exports = generate_named_exports(doc);
+    let generated_mod = doc
+        .last_exported_component()
+        .map(|c| format_ident!("slint_generated{}", ident(&c.id)))
+        .unwrap_or_else(|| format_ident!("slint_generated"));
+
+    Ok(quote! {
+        #[allow(non_snake_case, non_camel_case_types)]
+        #[allow(unused_braces, unused_parens)]
+        #[allow(clippy::all, clippy::pedantic, clippy::nursery)]
+        mod #generated_mod {
+            use slint::private_unstable_api::re_exports as sp;
+            #(#structs_and_enum_def)*
+            #(#globals)*
+            #(#public_components)*
+            const _THE_SAME_VERSION_MUST_BE_USED_FOR_THE_COMPILER_AND_THE_RUNTIME : slint::#version_check = slint::#version_check;
+        }
+        #[allow(unused_imports)]
+        pub use #generated_mod::{#(#compo_ids,)* #(#structs_and_enums_ids,)* #(#globals_ids,)* #(#named_exports,)*};
+        #[allow(unused_imports)]
+        pub use slint::{ComponentHandle as _, Global as _, ModelExt as _};
+    })
+}
+
+/// The code that creates the interpreter's compiler with the same configuration as the one
+/// used to generate the code. The paths are made absolute, as the application may not be
+/// started from the directory of the build.
+fn compiler_init(compiler_config: &CompilerConfiguration) -> std::io::Result<TokenStream> {
+    let absolute =
+        |path: &Path| std::path::absolute(path).map(|path| path.to_string_lossy().into_owned());
+    let include_paths = compiler_config
+        .include_paths
+        .iter()
+        .map(|path| absolute(path))
+        .collect::<std::io::Result<Vec<_>>>()?;
+    let (library_names, library_paths): (Vec<_>, Vec<_>) = compiler_config
+        .library_paths
+        .iter()
+        .map(|(name, path)| Ok((name.clone(), absolute(path)?)))
+        .collect::<std::io::Result<Vec<_>>>()?
+        .into_iter()
+        .unzip();
+    let style =
+        compiler_config.style.as_ref().map(|style| quote!(compiler.set_style(#style.into());));
+    let translation_domain = compiler_config
+        .translation_domain
+        .as_ref()
+        .map(|domain| quote!(compiler.set_translation_domain(#domain.into());));
+    Ok(quote! {
+        let mut compiler = sp::live_reload::Compiler::default();
+        compiler.set_include_paths(sp::vec![#(#include_paths.into()),*]);
+        compiler.set_library_paths([#((#library_names.into(), #library_paths.into())),*].into_iter().collect());
+        #style
+        #translation_domain
+    })
+}
+
+fn generate_public_component(
+    llr: &llr::PublicComponent,
+    source: &Source,
+    compiler_init: &TokenStream,
+) -> TokenStream {
+    let public_component_id = ident(&llr.name);
+    let component_name = llr.name.as_str();
+    let create = match source {
+        Source::File(main_file) => {
+            let main_file = main_file.to_string_lossy();
+            quote!(sp::live_reload::LiveReloadingComponent::new(compiler, #main_file.into(), #component_name.into()))
+        }
+        Source::RustMacro => quote!(sp::live_reload::LiveReloadingComponent::new_from_rust_macro(
+            compiler,
+            ::core::env!("CARGO_MANIFEST_DIR"),
+            ::core::file!(),
+            ::core::line!(),
+            #component_name.into(),
+        )),
+    };
+    let property_and_callback_accessors =
+        public_api(&llr.public_properties, &llr.private_properties, "");
+
+    quote!(
+        pub struct #public_component_id(sp::VRc<sp::ItemTreeVTable, sp::live_reload::LiveReloadingComponent>);
+
+        impl #public_component_id {
+            pub fn new() -> core::result::Result<Self, slint::PlatformError> {
+                #compiler_init
+                let inner = #create?;
+                core::result::Result::Ok(Self(inner))
+            }
+
+            #property_and_callback_accessors
+        }
+
+        impl ::core::convert::From<#public_component_id> for sp::VRc<sp::ItemTreeVTable, sp::live_reload::LiveReloadingComponent> {
+            fn from(value: #public_component_id) -> Self {
+                value.0
+            }
+        }
+
+        impl slint::ComponentHandle for #public_component_id {
+            type Inner = sp::live_reload::LiveReloadingComponent;
+            fn as_weak(&self) -> slint::Weak<Self> {
+                slint::Weak::new(&self.0)
+            }
+
+            fn clone_strong(&self) -> Self {
+                Self(self.0.clone())
+            }
+
+            fn from_inner(inner: sp::VRc<sp::ItemTreeVTable, sp::live_reload::LiveReloadingComponent>) -> Self {
+                Self(inner)
+            }
+
+            fn run(&self) -> core::result::Result<(), slint::PlatformError> {
+                self.show()?;
+                slint::run_event_loop()?;
+                self.hide()?;
+                core::result::Result::Ok(())
+            }
+
+            fn show(&self) -> core::result::Result<(), slint::PlatformError> {
+                sp::live_reload::LiveReloadingComponent::show(&self.0)
+            }
+
+            fn hide(&self) -> core::result::Result<(), slint::PlatformError> {
+                self.0.hide()
+            }
+
+            fn window(&self) -> &slint::Window {
+                self.0.window()
+            }
+
+            fn global<'a, T: slint::Global<'a, Self>>(&'a self) -> T {
+                T::get(&self)
+            }
+        }
+    )
+}
+
+fn generate_global(global: &llr::GlobalComponent, root: &llr::CompilationUnit) -> TokenStream {
+    let public_component_id = ident(&global.name);
+    let property_and_callback_accessors =
+        public_api(&global.public_properties, &global.private_properties, &global.name);
+    let aliases = global.aliases.iter().map(|name| ident(name));
+    let getters = root.public_components.iter().map(|c| {
+        let root_component_id = ident(&c.name);
+        quote! {
+            impl<'a> slint::Global<'a, #root_component_id> for #public_component_id<'a> {
+                fn get(component: &'a #root_component_id) -> Self {
+                    Self(&component.0)
+                }
+            }
+        }
+    });
+
+    quote!(
+        #[allow(unused)]
+        pub struct #public_component_id<'a>(&'a sp::VRc<sp::ItemTreeVTable, sp::live_reload::LiveReloadingComponent>);
+
+        impl<'a> #public_component_id<'a> {
+            #property_and_callback_accessors
+        }
+        #(pub type #aliases<'a> = #public_component_id<'a>;)*
+        #(#getters)*
+    )
+}
+
+/// Public API for Global and root component. `global` is the name of the global, or empty
+/// for the component.
+fn public_api(
+    public_properties: &llr::PublicProperties,
+    private_properties: &llr::PrivateProperties,
+    global: &str,
+) -> TokenStream {
+    let mut property_and_callback_accessors: Vec<TokenStream> = vec![];
+    for p in public_properties {
+        let prop_ident = ident(&p.name);
+        let prop_name = p.name.as_str();
+
+        if let Type::Callback(callback) = &p.ty {
+            let callback_args =
+                callback.args.iter().map(|a| rust_primitive_type(a).unwrap()).collect::<Vec<_>>();
+            let return_type = rust_primitive_type(&callback.return_type).unwrap();
+            let args_name =
+                (0..callback.args.len()).map(|i| format_ident!("arg_{}", i)).collect::<Vec<_>>();
+            let args_value =
+                callback.args.iter().zip(&args_name).map(|(ty, arg)| to_value(ty, quote!(#arg)));
+            let return_value = from_value(&callback.return_type, quote!(value));
+            let caller_ident = format_ident!("invoke_{}", prop_ident);
+            property_and_callback_accessors.push(quote!(
+                #[allow(dead_code)]
+                pub fn #caller_ident(&self, #(#args_name : #callback_args,)*) -> #return_type {
+                    let value = self.0.invoke(#global, #prop_name, &[#(#args_value,)*]);
+                    #return_value
+                }
+            ));
+            let on_ident = format_ident!("on_{}", prop_ident);
+            let args_from_value = callback.args.iter().enumerate().map(|(i, ty)| {
+                let i = Literal::usize_unsuffixed(i);
+                from_value(ty, quote!(args.get(#i).cloned().unwrap_or_default()))
+            });
+            if p.is_async {
+                let result_name = format_smolstr!("{}-result", p.name);
+                let result_type = public_properties
+                    .iter()
+                    .find(|p| p.name == result_name)
+                    .map_or(Type::Void, |p| p.ty.clone());
+                let return_type = rust_primitive_type(&result_type).unwrap();
+                let return_to_value = to_value(&result_type, quote!(value));
+                property_and_callback_accessors.push(quote!(
+                    #[allow(dead_code)]
+                    pub fn #on_ident<F: ::core::future::Future<Output = #return_type> + 'static>(
+                        &self,
+                        f: impl FnMut(#(#callback_args),*) -> F + 'static,
+                    ) {
+                        let f = ::core::cell::RefCell::new(f);
+                        #[allow(unused)]
+                        self.0.set_async_callback(#global, #prop_name, sp::Rc::new(
+                            move |args: &[sp::live_reload::Value]| -> ::core::pin::Pin<sp::Box<dyn ::core::future::Future<Output = sp::live_reload::Value>>> {
+                                let future = (f.borrow_mut())(#(#args_from_value),*);
+                                sp::Box::pin(async move {
+                                    let value = future.await;
+                                    #return_to_value
+                                })
+                            }
+                        ))
+                    }
+                ));
+                continue;
+            }
+            let return_to_value = to_value(&callback.return_type, quote!(value));
+            property_and_callback_accessors.push(quote!(
+                #[allow(dead_code)]
+                pub fn #on_ident(&self, f: impl FnMut(#(#callback_args),*) -> #return_type + 'static) {
+                    let f = ::core::cell::RefCell::new(f);
+                    #[allow(unused)]
+                    self.0.set_callback(#global, #prop_name, sp::Rc::new(move |args: &[sp::live_reload::Value]| {
+                        #[allow(unused)]
+                        let value = (f.borrow_mut())(#(#args_from_value),*);
+                        #return_to_value
+                    }))
+                }
+            ));
+        } else if let Type::Function(function) = &p.ty {
+            let callback_args =
+                function.args.iter().map(|a| rust_primitive_type(a).unwrap()).collect::<Vec<_>>();
+            let return_type = rust_primitive_type(&function.return_type).unwrap();
+            let args_name =
+                (0..function.args.len()).map(|i| format_ident!("arg_{}", i)).collect::<Vec<_>>();
+            let args_value =
+                function.args.iter().zip(&args_name).map(|(ty, arg)| to_value(ty, quote!(#arg)));
+            let return_value = from_value(&function.return_type, quote!(value));
+            let caller_ident = format_ident!("invoke_{}", prop_ident);
+            property_and_callback_accessors.push(quote!(
+                #[allow(dead_code)]
+                pub fn #caller_ident(&self, #(#args_name : #callback_args,)*) -> #return_type {
+                    let value = self.0.invoke(#global, #prop_name, &[#(#args_value,)*]);
+                    #return_value
+                }
+            ));
+        } else {
+            let rust_property_type = rust_primitive_type(&p.ty).unwrap();
+            let getter_ident = format_ident!("get_{}", prop_ident);
+            let value = from_value(&p.ty, quote!(value));
+            property_and_callback_accessors.push(quote!(
+                #[allow(dead_code)]
+                pub fn #getter_ident(&self) -> #rust_property_type {
+                    let value = self.0.get_property(#global, #prop_name);
+                    #value
+                }
+            ));
+
+            let setter_ident = format_ident!("set_{}", prop_ident);
+            if !p.read_only {
+                let value = to_value(&p.ty, quote!(value));
+                property_and_callback_accessors.push(quote!(
+                    #[allow(dead_code)]
+                    pub fn #setter_ident(&self, value: #rust_property_type) {
+                        self.0.set_property(#global, #prop_name, #value)
+                    }
+                ));
+            } else {
+                property_and_callback_accessors.push(quote!(
+                    #[allow(dead_code)] fn #setter_ident(&self, _read_only_property : ()) { }
+                ));
+            }
+        }
+    }
+
+    for (name, ty) in private_properties {
+        let prop_ident = ident(name);
+        if let Type::Function { .. } = ty {
+            let caller_ident = format_ident!("invoke_{}", prop_ident);
+            property_and_callback_accessors.push(
+                quote!( #[allow(dead_code)] fn #caller_ident(&self, _private_function: ()) {} ),
+            );
+        } else {
+            let getter_ident = format_ident!("get_{}", prop_ident);
+            let setter_ident = format_ident!("set_{}", prop_ident);
+            property_and_callback_accessors.push(quote!(
+                #[allow(dead_code)] fn #getter_ident(&self, _private_property: ()) {}
+                #[allow(dead_code)] fn #setter_ident(&self, _private_property: ()) {}
+            ));
+        }
+    }
+
+    quote!(#(#property_and_callback_accessors)*)
+}
+
+/// The expression that converts `value`, of the Rust type of `ty`, to a `Value`.
+/// Anonymous structs are tuples in Rust, which can't implement the conversion from and to `Value`,
+/// so they are converted field by field. Models are wrapped in a `ConvertedModel` that converts
+/// their rows in both directions, so that the rows written by the `.slint` code reach the Rust model.
+/// Maps are converted value by value. A null optional is a `Value::Void`.
+fn to_value(ty: &Type, value: TokenStream) -> TokenStream {
+    match ty {
+        Type::Struct(s) if s.name.is_none() => {
+            let fields = s.fields.iter().enumerate().map(|(i, (name, ty))| {
+                let i = Literal::usize_unsuffixed(i);
+                let name = name.as_str();
+                let field = to_value(ty, quote!(value.#i));
+                quote!(struct_.set_field(#name.into(), #field);)
+            });
+            quote!({
+                #[allow(unused)]
+                let value = #value;
+                let mut struct_ = sp::live_reload::Struct::default();
+                #(#fields)*
+                sp::live_reload::Value::Struct(struct_)
+            })
+        }
+        Type::Array(inner) => {
+            let to_item = to_value(inner, quote!(value));
+            let from_item = from_value(inner, quote!(value));
+            quote!(sp::live_reload::Value::Model(sp::live_reload::ConvertedModel::convert(#value, |value| #to_item, |value| #from_item)))
+        }
+        Type::Map(inner) => {
+            let item = to_value(inner, quote!(value.clone()));
+            quote!(sp::live_reload::Value::Map(#value.iter().map(|(key, value)| (key.clone(), #item)).collect()))
+        }
+        Type::Optional(inner) => {
+            let item = to_value(inner, quote!(value));
+            quote!(match #value {
+                ::core::option::Option::Some(value) => #item,
+                ::core::option::Option::None => sp::live_reload::Value::Void,
+            })
+        }
+        _ => quote!(sp::live_reload::Value::from(#value)),
+    }
+}
+
+/// The expression that converts `value`, a `Value`, to the Rust type of `ty`, or to its default
+/// if the type of the property changed in the `.slint` file
+fn from_value(ty: &Type, value: TokenStream) -> TokenStream {
+    match ty {
+        Type::Struct(s) if s.name.is_none() => {
+            let fields = s.fields.iter().map(|(name, ty)| {
+                let name = name.as_str();
+                from_value(ty, quote!(struct_.get_field(#name).cloned().unwrap_or_default()))
+            });
+            quote!(match #value {
+                sp::live_reload::Value::Struct(struct_) => (#(#fields,)*),
+                _ => ::core::default::Default::default(),
+            })
+        }
+        Type::Array(inner) => {
+            let from_item = from_value(inner, quote!(value));
+            let to_item = to_value(inner, quote!(value));
+            quote!(match #value {
+                sp::live_reload::Value::Model(model) => sp::live_reload::ConvertedModel::convert(model, |value| #from_item, |value| #to_item),
+                _ => ::core::default::Default::default(),
+            })
+        }
+        Type::Map(inner) => {
+            let item = from_value(inner, quote!(value.clone()));
+            quote!(match #value {
+                sp::live_reload::Value::Map(map) => map.iter().map(|(key, value)| (key.clone(), #item)).collect(),
+                _ => ::core::default::Default::default(),
+            })
+        }
+        Type::Optional(inner) => {
+            let item = from_value(inner, quote!(value));
+            quote!(match #value {
+                sp::live_reload::Value::Void => ::core::option::Option::None,
+                value => ::core::option::Option::Some(#item),
+            })
+        }
+        _ => {
+            let rust_type = rust_primitive_type(ty).unwrap();
+            quote!(<#rust_type as ::core::convert::TryFrom<sp::live_reload::Value>>::try_from(#value).unwrap_or_default())
+        }
+    }
+}
+
+fn generate_struct_conversion(s: &Struct) -> TokenStream {
+    let struct_id = struct_name_to_tokens(s.name.as_ref().unwrap());
+    let names = s.fields.keys().map(|name| name.as_str()).collect::<Vec<_>>();
+    let to_values = s.fields.iter().map(|(name, ty)| {
+        let field = ident(name);
+        to_value(ty, quote!(value.#field))
+    });
+    let from_values = s.fields.iter().map(|(name, ty)| {
+        let field = ident(name);
+        let name = name.as_str();
+        let value = from_value(ty, quote!(value.clone()));
+        quote!(#field: match struct_.get_field(#name) {
+            ::core::option::Option::Some(value) => #value,
+            ::core::option::Option::None => return ::core::result::Result::Err(()),
+        })
+    });
+    quote! {
+        impl ::core::convert::From<#struct_id> for sp::live_reload::Value {
+            #[allow(unused)]
+            fn from(value: #struct_id) -> Self {
+                let mut struct_ = sp::live_reload::Struct::default();
+                #(struct_.set_field(#names.into(), #to_values);)*
+                sp::live_reload::Value::Struct(struct_)
+            }
+        }
+        impl ::core::convert::TryFrom<sp::live_reload::Value> for #struct_id {
+            type Error = ();
+            #[allow(unused)]
+            fn try_from(value: sp::live_reload::Value) -> ::core::result::Result<Self, ()> {
+                match value {
+                    sp::live_reload::Value::Struct(struct_) => ::core::result::Result::Ok(Self { #(#from_values,)* }),
+                    _ => ::core::result::Result::Err(()),
+                }
+            }
+        }
+    }
+}
+
+fn generate_enum_conversion(en: &std::rc::Rc<Enumeration>) -> TokenStream {
+    let enum_id = ident(&en.name);
+    let enum_name = en.name.as_str();
+    let value_ids = (0..en.values.len())
+        .map(|value| {
+            ident(
+                &crate::langtype::EnumerationValue { value, enumeration: en.clone() }
+                    .to_pascal_case(),
+            )
+        })
+        .collect::<Vec<_>>();
+    let value_names = en.values.iter().map(|v| v.as_str()).collect::<Vec<_>>();
+    quote! {
+        impl ::core::convert::From<#enum_id> for sp::live_reload::Value {
+            fn from(value: #enum_id) -> Self {
+                let value = match value {
+                    #(#enum_id::#value_ids => #value_names,)*
+                };
+                sp::live_reload::Value::EnumerationValue(#enum_name.into(), value.into())
+            }
+        }
+        impl ::core::convert::TryFrom<sp::live_reload::Value> for #enum_id {
+            type Error = ();
+            fn try_from(value: sp::live_reload::Value) -> ::core::result::Result<Self, ()> {
+                match value {
+                    sp::live_reload::Value::EnumerationValue(enumeration, value) if enumeration == #enum_name => {
+                        match value.as_str() {
+                            #(#value_names => ::core::result::Result::Ok(#enum_id::#value_ids),)*
+                            _ => ::core::result::Result::Err(()),
+                        }
+                    }
+                    _ => ::core::result::Result::Err(()),
+                }
+            }
+        }
+    }
+}
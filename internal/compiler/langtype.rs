@@ -35,6 +35,8 @@ pub enum Type {
     // Other property types:
     Float32,
     Int32,
+    /// A 64-bit integer (`long`), with integer arithmetic
+    Int64,
     String,
     Color,
     Duration,
@@ -52,6 +54,11 @@ pub enum Type {
     Brush,
     /// This is usually a model
     Array(Rc<Type>),
+    /// A map from string keys to values of the given type
+    Map(Rc<Type>),
+    /// A value of the given type, or `null`.
+    /// The type of the `null` literal itself is `Optional(Void)`
+    Optional(Rc<Type>),
     Struct(Rc<Struct>),
     Enumeration(Rc<Enumeration>),
 
@@ -82,6 +89,7 @@ fn eq(&self, other: &Self) -> bool {
             Type::ComponentFactory => matches!(other, Type::ComponentFactory),
             Type::Float32 => matches!(other, Type::Float32),
             Type::Int32 => matches!(other, Type::Int32),
+            Type::Int64 => matches!(other, Type::Int64),
             Type::String => matches!(other, Type::String),
             Type::Color => matches!(other, Type::Color),
             Type::Duration => matches!(other, Type::Duration),
@@ -97,6 +105,8 @@ fn eq(&self, other: &Self) -> bool {
             Type::Easing => matches!(other, Type::Easing),
             Type::Brush => matches!(other, Type::Brush),
             Type::Array(a) => matches!(other, Type::Array(b) if a == b),
+            Type::Map(a) => matches!(other, Type::Map(b) if a == b),
+            Type::Optional(a) => matches!(other, Type::Optional(b) if a == b),
             Type::Struct(lhs) => {
                 matches!(other, Type::Struct(rhs) if lhs.fields == rhs.fields && lhs.name == rhs.name)
             }
@@ -143,6 +153,7 @@ fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
             }
             Type::Float32 => write!(f, "float"),
             Type::Int32 => write!(f, "int"),
+            Type::Int64 => write!(f, "long"),
             Type::String => write!(f, "string"),
             Type::Duration => write!(f, "duration"),
             Type::Angle => write!(f, "angle"),
@@ -155,6 +166,8 @@ fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
             Type::Bool => write!(f, "bool"),
             Type::Model => write!(f, "model"),
             Type::Array(t) => write!(f, "[{t}]"),
+            Type::Map(t) => write!(f, "[string: {t}]"),
+            Type::Optional(t) => write!(f, "{t}?"),
             Type::Struct(t) => write!(f, "{t}"),
             Type::PathData => write!(f, "pathdata"),
             Type::Easing => write!(f, "easing"),
@@ -191,10 +204,14 @@ fn from(value: Rc<Struct>) -> Self {
 impl Type {
     /// valid type for properties
     pub fn is_property_type(&self) -> bool {
+        if let Self::Optional(inner) = self {
+            return inner.is_property_type();
+        }
         matches!(
             self,
             Self::Float32
                 | Self::Int32
+                | Self::Int64
                 | Self::String
                 | Self::Color
                 | Self::ComponentFactory
@@ -211,6 +228,7 @@ pub fn is_property_type(&self) -> bool {
                 | Self::ElementReference
                 | Self::Struct { .. }
                 | Self::Array(_)
+                | Self::Map(_)
                 | Self::Brush
                 | Self::InferredProperty
         )
@@ -256,6 +274,11 @@ pub fn can_convert(&self, other: &Self) -> bool {
             | (Type::Float32, Type::String)
             | (Type::Int32, Type::Float32)
             | (Type::Int32, Type::String)
+            | (Type::Int64, Type::Float32)
+            | (Type::Int64, Type::Int32)
+            | (Type::Int64, Type::String)
+            | (Type::Float32, Type::Int64)
+            | (Type::Int32, Type::Int64)
             | (Type::Float32, Type::Model)
             | (Type::Int32, Type::Model)
             | (Type::PhysicalLength, Type::LogicalLength)
@@ -268,7 +291,14 @@ pub fn can_convert(&self, other: &Self) -> bool {
             | (Type::Brush, Type::Color)
             | (Type::Color, Type::Brush) => true,
             (Type::Array(a), Type::Model) if a.is_property_type() => true,
+            (Type::Map(a), Type::Model) if a.is_property_type() => true,
             (Type::Struct(a), Type::Struct(b)) => can_convert_struct(&a.fields, &b.fields),
+            (Type::Struct(a), Type::Map(v)) if a.name.is_none() => {
+                a.fields.values().all(|t| t.can_convert(v))
+            }
+            // `null`, or the conversion of the value if there is one
+            (Type::Optional(a), Type::Optional(b)) => **a == Type::Void || a.can_convert(b),
+            (a, Type::Optional(b)) => a.can_convert(b),
             (Type::UnitProduct(u), o) => match o.as_unit_product() {
                 Some(o) => unit_product_length_conversion(u.as_slice(), o.as_slice()).is_some(),
                 None => false,
@@ -300,6 +330,7 @@ pub fn default_unit(&self) -> Option<Unit> {
             Type::Function { .. } => None,
             Type::Float32 => None,
             Type::Int32 => None,
+            Type::Int64 => None,
             Type::String => None,
             Type::Color => None,
             Type::Image => None,
@@ -309,6 +340,8 @@ pub fn default_unit(&self) -> Option<Unit> {
             Type::Easing => None,
             Type::Brush => None,
             Type::Array(_) => None,
+            Type::Map(_) => None,
+            Type::Optional(_) => None,
             Type::Struct { .. } => None,
             Type::Enumeration(_) => None,
             Type::UnitProduct(_) => None,
@@ -326,6 +359,20 @@ pub fn as_unit_product(&self) -> Option<Vec<(Unit, i8)>> {
             _ => self.default_unit().map(|u| vec![(u, 1)]),
         }
     }
+
+    /// The type of the items when iterating over a map with the given value type in a `for`:
+    /// an anonymous struct with a `key` and a `value` field
+    pub fn map_entry_type(value_ty: &Type) -> Type {
+        Type::Struct(Rc::new(Struct {
+            fields: BTreeMap::from([
+                (SmolStr::new_static("key"), Type::String),
+                (SmolStr::new_static("value"), value_ty.clone()),
+            ]),
+            name: None,
+            node: None,
+            rust_attributes: None,
+        }))
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -148,6 +148,10 @@ pub struct CompilerConfiguration {
     /// Generate debug information for elements (ids, type names)
     pub debug_info: bool,
 
+    /// Emit warnings for unused imports, exported components that are never imported,
+    /// unused private properties and states that can never be active
+    pub warn_unused_code: bool,
+
     pub components_to_generate: ComponentSelection,
 
     #[cfg(feature = "software-renderer")]
@@ -200,6 +204,8 @@ pub fn new(output_format: OutputFormat) -> Self {
 
         let debug_info = std::env::var_os("SLINT_EMIT_DEBUG_INFO").is_some();
 
+        let warn_unused_code = std::env::var_os("SLINT_WARN_UNUSED_CODE").is_some();
+
         let cpp_namespace = match output_format {
             #[cfg(feature = "cpp")]
             OutputFormat::Cpp(config) => match config.namespace {
@@ -226,6 +232,7 @@ pub fn new(output_format: OutputFormat) -> Self {
             translation_domain: None,
             cpp_namespace,
             debug_info,
+            warn_unused_code,
             components_to_generate: ComponentSelection::ExportedWindows,
             #[cfg(feature = "software-renderer")]
             font_cache: Default::default(),
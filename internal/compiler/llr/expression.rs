@@ -21,6 +21,8 @@ pub enum Expression {
     NumberLiteral(f64),
     /// Bool
     BoolLiteral(bool),
+    /// `null`, of the given [`Type::Optional`]
+    NullLiteral(Type),
 
     /// Reference to a property (which can also be a callback) or an element (property name is empty then).
     PropertyReference(PropertyReference),
@@ -58,6 +60,61 @@ pub enum Expression {
         index: Box<Expression>,
     },
 
+    /// `array.map(item => body)`: the `body` reads the current item from the local variable `item`
+    ArrayMap {
+        array: Box<Expression>,
+        item: SmolStr,
+        body: Box<Expression>,
+    },
+
+    /// `array.filter(item => condition)`: the `condition` reads the current item from the local variable `item`
+    ArrayFilter {
+        array: Box<Expression>,
+        item: SmolStr,
+        condition: Box<Expression>,
+    },
+
+    /// `array.slice(start, end)`
+    ArraySlice {
+        array: Box<Expression>,
+        start: Box<Expression>,
+        end: Box<Expression>,
+    },
+
+    /// `lhs.concat(rhs)`
+    ArrayConcat {
+        lhs: Box<Expression>,
+        rhs: Box<Expression>,
+    },
+
+    /// `array.index-of(value)`
+    ArrayIndexOf {
+        array: Box<Expression>,
+        value: Box<Expression>,
+    },
+
+    /// `map[key]`: the value for the key, or the default value
+    MapIndex {
+        /// This expression should have [`Type::Map`] type
+        map: Box<Expression>,
+        key: Box<Expression>,
+    },
+
+    /// `map.has-key(key)`
+    MapHasKey {
+        map: Box<Expression>,
+        key: Box<Expression>,
+    },
+
+    /// The entries of the map as an array of [`Type::map_entry_type`], sorted by key
+    MapEntries(Box<Expression>),
+
+    /// True if the optional is not null
+    OptionalHasValue(Box<Expression>),
+
+    /// The value of the optional, which must not be null
+    OptionalUnwrap(Box<Expression>),
+
     /// Cast an expression to the given type
     Cast {
         from: Box<Expression>,
@@ -141,6 +198,10 @@ pub enum Expression {
         ty: Rc<crate::langtype::Struct>,
         values: BTreeMap<SmolStr, Expression>,
     },
+    Map {
+        value_ty: Type,
+        values: BTreeMap<SmolStr, Expression>,
+    },
 
     EasingCurve(crate::expression_tree::EasingCurve),
 
@@ -225,7 +286,7 @@ pub fn default_value_for_type(ty: &Type) -> Option<Self> {
             | Type::UnitProduct(_) => Expression::NumberLiteral(0.),
             Type::Percent => Expression::NumberLiteral(1.),
             Type::String => Expression::StringLiteral(SmolStr::default()),
-            Type::Color => {
+            Type::Int64 | Type::Color => {
                 Expression::Cast { from: Box::new(Expression::NumberLiteral(0.)), to: ty.clone() }
             }
             Type::Image => Expression::ImageReference {
@@ -248,6 +309,10 @@ pub fn default_value_for_type(ty: &Type) -> Option<Self> {
                     .map(|(k, v)| Some((k.clone(), Expression::default_value_for_type(v)?)))
                     .collect::<Option<_>>()?,
             },
+            Type::Map(value_ty) => {
+                Expression::Map { value_ty: (**value_ty).clone(), values: Default::default() }
+            }
+            Type::Optional(_) => Expression::NullLiteral(ty.clone()),
             Type::Easing => Expression::EasingCurve(crate::expression_tree::EasingCurve::default()),
             Type::Brush => Expression::Cast {
                 from: Box::new(Expression::default_value_for_type(&Type::Color)?),
@@ -265,6 +330,7 @@ pub fn ty(&self, ctx: &dyn TypeResolutionContext) -> Type {
             Self::StringLiteral(_) => Type::String,
             Self::NumberLiteral(_) => Type::Float32,
             Self::BoolLiteral(_) => Type::Bool,
+            Self::NullLiteral(ty) => ty.clone(),
             Self::PropertyReference(prop) => ctx.property_ty(prop).clone(),
             Self::FunctionParameterReference { index } => ctx.arg_type(*index).clone(),
             Self::StoreLocalVariable { .. } => Type::Void,
@@ -277,6 +343,24 @@ pub fn ty(&self, ctx: &dyn TypeResolutionContext) -> Type {
                 Type::Array(ty) => (*ty).clone(),
                 _ => unreachable!(),
             },
+            Self::ArrayMap { body, .. } => Type::Array(body.ty(ctx).into()),
+            Self::ArrayFilter { array, .. } | Self::ArraySlice { array, .. } => array.ty(ctx),
+            Self::ArrayConcat { lhs, .. } => lhs.ty(ctx),
+            Self::ArrayIndexOf { .. } => Type::Int32,
+            Self::MapIndex { map, .. } => match map.ty(ctx) {
+                Type::Map(ty) => (*ty).clone(),
+                _ => unreachable!(),
+            },
+            Self::MapHasKey { .. } => Type::Bool,
+            Self::MapEntries(map) => match map.ty(ctx) {
+                Type::Map(ty) => Type::Array(Type::map_entry_type(&ty).into()),
+                _ => unreachable!(),
+            },
+            Self::OptionalHasValue(_) => Type::Bool,
+            Self::OptionalUnwrap(optional) => match optional.ty(ctx) {
+                Type::Optional(ty) => (*ty).clone(),
+                _ => unreachable!(),
+            },
             Self::Cast { to, .. } => to.clone(),
             Self::CodeBlock(sub) => sub.last().map_or(Type::Void, |e| e.ty(ctx)),
             Self::BuiltinFunctionCall { function, .. } => function.ty().return_type.clone(),
@@ -304,6 +388,7 @@ pub fn ty(&self, ctx: &dyn TypeResolutionContext) -> Type {
             Self::Condition { false_expr, .. } => false_expr.ty(ctx),
             Self::Array { element_ty, .. } => Type::Array(element_ty.clone().into()),
             Self::Struct { ty, .. } => ty.clone().into(),
+            Self::Map { value_ty, .. } => Type::Map(value_ty.clone().into()),
             Self::EasingCurve(_) => Type::Easing,
             Self::LinearGradient { .. } => Type::Brush,
             Self::RadialGradient { .. } => Type::Brush,
@@ -326,6 +411,7 @@ macro_rules! visit_impl {
             Expression::StringLiteral(_) => {}
             Expression::NumberLiteral(_) => {}
             Expression::BoolLiteral(_) => {}
+            Expression::NullLiteral(_) => {}
             Expression::PropertyReference(_) => {}
             Expression::FunctionParameterReference { .. } => {}
             Expression::StoreLocalVariable { value, .. } => $visitor(value),
@@ -335,6 +421,35 @@ macro_rules! visit_impl {
                 $visitor(array);
                 $visitor(index);
             }
+            Expression::ArrayMap { array, item: _, body } => {
+                $visitor(array);
+                $visitor(body);
+            }
+            Expression::ArrayFilter { array, item: _, condition } => {
+                $visitor(array);
+                $visitor(condition);
+            }
+            Expression::ArraySlice { array, start, end } => {
+                $visitor(array);
+                $visitor(start);
+                $visitor(end);
+            }
+            Expression::ArrayConcat { lhs, rhs } => {
+                $visitor(lhs);
+                $visitor(rhs);
+            }
+            Expression::ArrayIndexOf { array, value } => {
+                $visitor(array);
+                $visitor(value);
+            }
+            Expression::MapIndex { map, key } | Expression::MapHasKey { map, key } => {
+                $visitor(map);
+                $visitor(key);
+            }
+            Expression::MapEntries(map) => $visitor(map),
+            Expression::OptionalHasValue(optional) | Expression::OptionalUnwrap(optional) => {
+                $visitor(optional)
+            }
             Expression::Cast { from, .. } => $visitor(from),
             Expression::CodeBlock(b) => b.$iter().for_each($visitor),
             Expression::BuiltinFunctionCall { arguments, .. }
@@ -364,7 +479,9 @@ macro_rules! visit_impl {
                 $visitor(false_expr);
             }
             Expression::Array { values, .. } => values.$iter().for_each($visitor),
-            Expression::Struct { values, .. } => values.$values().for_each($visitor),
+            Expression::Struct { values, .. } | Expression::Map { values, .. } => {
+                values.$values().for_each($visitor)
+            }
             Expression::EasingCurve(_) => {}
             Expression::LinearGradient { angle, stops } => {
                 $visitor(angle);
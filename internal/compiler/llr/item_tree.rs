@@ -476,6 +476,8 @@ pub struct PublicProperty {
     pub ty: Type,
     pub prop: PropertyReference,
     pub read_only: bool,
+    /// For callbacks declared as `async`
+    pub is_async: bool,
 }
 pub type PublicProperties = Vec<PublicProperty>;
 pub type PrivateProperties = Vec<(SmolStr, Type)>;
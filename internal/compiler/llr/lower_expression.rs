@@ -76,6 +76,7 @@ pub fn lower_expression(
             llr_Expression::NumberLiteral(unit.normalize(*n))
         }
         tree_Expression::BoolLiteral(b) => llr_Expression::BoolLiteral(*b),
+        tree_Expression::NullLiteral(ty) => llr_Expression::NullLiteral(ty.clone()),
         tree_Expression::PropertyReference(nr) => {
             llr_Expression::PropertyReference(ctx.map_property_reference(nr))
         }
@@ -124,6 +125,46 @@ pub fn lower_expression(
             array: Box::new(lower_expression(array, ctx)),
             index: Box::new(lower_expression(index, ctx)),
         },
+        tree_Expression::ArrayMap { array, item, body } => llr_Expression::ArrayMap {
+            array: Box::new(lower_expression(array, ctx)),
+            item: item.clone(),
+            body: Box::new(lower_expression(body, ctx)),
+        },
+        tree_Expression::ArrayFilter { array, item, condition } => llr_Expression::ArrayFilter {
+            array: Box::new(lower_expression(array, ctx)),
+            item: item.clone(),
+            condition: Box::new(lower_expression(condition, ctx)),
+        },
+        tree_Expression::ArraySlice { array, start, end } => llr_Expression::ArraySlice {
+            array: Box::new(lower_expression(array, ctx)),
+            start: Box::new(lower_expression(start, ctx)),
+            end: Box::new(lower_expression(end, ctx)),
+        },
+        tree_Expression::ArrayConcat { lhs, rhs } => llr_Expression::ArrayConcat {
+            lhs: Box::new(lower_expression(lhs, ctx)),
+            rhs: Box::new(lower_expression(rhs, ctx)),
+        },
+        tree_Expression::ArrayIndexOf { array, value } => llr_Expression::ArrayIndexOf {
+            array: Box::new(lower_expression(array, ctx)),
+            value: Box::new(lower_expression(value, ctx)),
+        },
+        tree_Expression::MapIndex { map, key } => llr_Expression::MapIndex {
+            map: Box::new(lower_expression(map, ctx)),
+            key: Box::new(lower_expression(key, ctx)),
+        },
+        tree_Expression::MapHasKey { map, key } => llr_Expression::MapHasKey {
+            map: Box::new(lower_expression(map, ctx)),
+            key: Box::new(lower_expression(key, ctx)),
+        },
+        tree_Expression::MapEntries(map) => {
+            llr_Expression::MapEntries(Box::new(lower_expression(map, ctx)))
+        }
+        tree_Expression::OptionalHasValue(optional) => {
+            llr_Expression::OptionalHasValue(Box::new(lower_expression(optional, ctx)))
+        }
+        tree_Expression::OptionalUnwrap(optional) => {
+            llr_Expression::OptionalUnwrap(Box::new(lower_expression(optional, ctx)))
+        }
         tree_Expression::Cast { from, to } => {
             llr_Expression::Cast { from: Box::new(lower_expression(from, ctx)), to: to.clone() }
         }
@@ -208,6 +249,10 @@ pub fn lower_expression(
                 .map(|(s, e)| (s.clone(), lower_expression(e, ctx)))
                 .collect::<_>(),
         },
+        tree_Expression::Map { value_ty, values } => llr_Expression::Map {
+            value_ty: value_ty.clone(),
+            values: values.iter().map(|(k, e)| (k.clone(), lower_expression(e, ctx))).collect(),
+        },
         tree_Expression::PathData(data) => compile_path(data, ctx),
         tree_Expression::EasingCurve(x) => llr_Expression::EasingCurve(x.clone()),
         tree_Expression::LinearGradient { angle, stops } => llr_Expression::LinearGradient {
@@ -230,7 +230,13 @@ fn component_id(component: &Rc<Component>) -> SmolStr {
     } else if component.id.is_empty() {
         format_smolstr!("Component_{}", component.root_element.borrow().id)
     } else {
-        format_smolstr!("{}_{}", component.id, component.root_element.borrow().id)
+        // The id of an instance of a generic component contains its type arguments, like `Picker<int>`
+        let id: SmolStr = component
+            .id
+            .chars()
+            .map(|c| if c.is_alphanumeric() || c == '-' || c == '_' { c } else { '_' })
+            .collect();
+        format_smolstr!("{}_{}", id, component.root_element.borrow().id)
     }
 }
 
@@ -983,6 +989,7 @@ fn public_properties(
                 ty: c.property_type.clone(),
                 prop: property_reference,
                 read_only: c.visibility == PropertyVisibility::Output,
+                is_async: c.is_async,
             }
         })
         .collect()
@@ -24,12 +24,23 @@ fn expression_cost(exp: &Expression, ctx: &EvaluationContext) -> isize {
         Expression::StringLiteral(_) => ALLOC_COST,
         Expression::NumberLiteral(_) => 0,
         Expression::BoolLiteral(_) => 0,
+        Expression::NullLiteral(_) => 0,
         Expression::PropertyReference(_) => PROPERTY_ACCESS_COST,
         Expression::FunctionParameterReference { .. } => return isize::MAX,
         Expression::StoreLocalVariable { .. } => 0,
         Expression::ReadLocalVariable { .. } => 1,
         Expression::StructFieldAccess { .. } => 1,
         Expression::ArrayIndex { .. } => ARRAY_INDEX_COST,
+        // These create a new model, which needs to keep its identity like an array literal
+        Expression::ArrayMap { .. }
+        | Expression::ArrayFilter { .. }
+        | Expression::ArraySlice { .. }
+        | Expression::ArrayConcat { .. } => return isize::MAX,
+        Expression::ArrayIndexOf { .. } => ALLOC_COST,
+        Expression::MapIndex { .. } | Expression::MapHasKey { .. } => ARRAY_INDEX_COST,
+        // Creates a new model, like an array literal
+        Expression::MapEntries(_) => return isize::MAX,
+        Expression::OptionalHasValue(_) | Expression::OptionalUnwrap(_) => 1,
         Expression::Cast { .. } => 0,
         Expression::CodeBlock(_) => 0,
         Expression::BuiltinFunctionCall { function, .. } => builtin_function_cost(function),
@@ -59,6 +70,7 @@ fn expression_cost(exp: &Expression, ctx: &EvaluationContext) -> isize {
         // (cf #5249)  (otherwise it would be `ALLOC_COST`)
         Expression::Array { .. } => return isize::MAX,
         Expression::Struct { .. } => 1,
+        Expression::Map { .. } => ALLOC_COST,
         Expression::EasingCurve(_) => 1,
         Expression::LinearGradient { .. } => ALLOC_COST,
         Expression::RadialGradient { .. } => ALLOC_COST,
@@ -113,10 +125,20 @@ fn builtin_function_cost(function: &BuiltinFunction) -> isize {
         BuiltinFunction::ItemFontMetrics => PROPERTY_ACCESS_COST,
         BuiltinFunction::StringToFloat => 50,
         BuiltinFunction::StringIsFloat => 50,
+        BuiltinFunction::StringToLong => 50,
+        BuiltinFunction::StringIsLong => 50,
         BuiltinFunction::StringIsEmpty => 50,
         BuiltinFunction::StringCharacterCount => 50,
         BuiltinFunction::StringToLowercase => ALLOC_COST,
         BuiltinFunction::StringToUppercase => ALLOC_COST,
+        BuiltinFunction::StringContains => 50,
+        BuiltinFunction::StringStartsWith => 50,
+        BuiltinFunction::StringEndsWith => 50,
+        BuiltinFunction::StringReplace => ALLOC_COST,
+        BuiltinFunction::StringTrim => ALLOC_COST,
+        BuiltinFunction::StringSlice => ALLOC_COST,
+        BuiltinFunction::StringSplit => ALLOC_COST,
+        BuiltinFunction::StringIndexOf => 50,
         BuiltinFunction::ColorRgbaStruct => 50,
         BuiltinFunction::ColorHsvaStruct => 50,
         BuiltinFunction::ColorBrighter => 50,
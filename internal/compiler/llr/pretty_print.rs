@@ -251,6 +251,7 @@ fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> Result {
             Expression::StringLiteral(x) => write!(f, "{x:?}"),
             Expression::NumberLiteral(x) => write!(f, "{x:?}"),
             Expression::BoolLiteral(x) => write!(f, "{x:?}"),
+            Expression::NullLiteral(_) => write!(f, "null"),
             Expression::PropertyReference(x) => write!(f, "{}", DisplayPropertyRef(x, ctx)),
             Expression::FunctionParameterReference { index } => write!(f, "arg_{index}"),
             Expression::StoreLocalVariable { name, value } => {
@@ -259,6 +260,24 @@ fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> Result {
             Expression::ReadLocalVariable { name, .. } => write!(f, "{name}"),
             Expression::StructFieldAccess { base, name } => write!(f, "{}.{}", e(base), name),
             Expression::ArrayIndex { array, index } => write!(f, "{}[{}]", e(array), e(index)),
+            Expression::ArrayMap { array, item, body } => {
+                write!(f, "{}.map({} => {})", e(array), item, e(body))
+            }
+            Expression::ArrayFilter { array, item, condition } => {
+                write!(f, "{}.filter({} => {})", e(array), item, e(condition))
+            }
+            Expression::ArraySlice { array, start, end } => {
+                write!(f, "{}.slice({}, {})", e(array), e(start), e(end))
+            }
+            Expression::ArrayConcat { lhs, rhs } => write!(f, "{}.concat({})", e(lhs), e(rhs)),
+            Expression::ArrayIndexOf { array, value } => {
+                write!(f, "{}.index-of({})", e(array), e(value))
+            }
+            Expression::MapIndex { map, key } => write!(f, "{}[{}]", e(map), e(key)),
+            Expression::MapHasKey { map, key } => write!(f, "{}.has-key({})", e(map), e(key)),
+            Expression::MapEntries(map) => write!(f, "entries({})", e(map)),
+            Expression::OptionalHasValue(optional) => write!(f, "has-value({})", e(optional)),
+            Expression::OptionalUnwrap(optional) => write!(f, "unwrap({})", e(optional)),
             Expression::Cast { from, to } => write!(f, "{} /*as {:?}*/", e(from), to),
             Expression::CodeBlock(v) => {
                 write!(f, "{{ {} }}", v.iter().map(e).join("; "))
@@ -316,6 +335,11 @@ fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> Result {
                 "{{ {} }}",
                 values.iter().map(|(k, v)| format!("{}: {}", k, e(v))).join(", ")
             ),
+            Expression::Map { values, .. } => write!(
+                f,
+                "[{}]",
+                values.iter().map(|(k, v)| format!("{:?}: {}", k, e(v))).join(", ")
+            ),
             Expression::EasingCurve(x) => write!(f, "{x:?}"),
             Expression::LinearGradient { angle, stops } => write!(
                 f,
@@ -37,6 +37,10 @@ pub struct LookupCtx<'a> {
     /// The name of the arguments of the callback or function
     pub arguments: Vec<SmolStr>,
 
+    /// The local variables in scope, such as the item in `array.map(item => ...)`, with the
+    /// expression that reads them. (the last element is looked up first)
+    pub local_variables: Vec<(SmolStr, Expression)>,
+
     /// The type register in which to look for Globals
     pub type_register: &'a TypeRegister,
 
@@ -57,6 +61,7 @@ pub fn empty_context(type_register: &'a TypeRegister, diag: &'a mut BuildDiagnos
             component_scope: Default::default(),
             diag,
             arguments: Default::default(),
+            local_variables: Default::default(),
             type_register,
             type_loader: None,
             current_token: None,
@@ -238,6 +243,17 @@ fn for_each_entry<R>(
     }
 }
 
+struct LocalVariableLookup;
+impl LookupObject for LocalVariableLookup {
+    fn for_each_entry<R>(
+        &self,
+        ctx: &LookupCtx,
+        f: &mut impl FnMut(&SmolStr, LookupResult) -> Option<R>,
+    ) -> Option<R> {
+        ctx.local_variables.iter().rev().find_map(|(name, e)| f(name, e.clone().into()))
+    }
+}
+
 struct SpecialIdLookup;
 impl LookupObject for SpecialIdLookup {
     fn for_each_entry<R>(
@@ -262,6 +278,7 @@ fn for_each_entry<R>(
             })
             .or_else(|| f("true", Expression::BoolLiteral(true)))
             .or_else(|| f("false", Expression::BoolLiteral(false)))
+            .or_else(|| f("null", Expression::NullLiteral(Type::Optional(Type::Void.into()))))
         // "root" is just a normal id
     }
 }
@@ -826,16 +843,22 @@ fn for_each_entry<R>(
 
 pub fn global_lookup() -> impl LookupObject {
     (
-        ArgumentsLookup,
+        LocalVariableLookup,
         (
-            SpecialIdLookup,
+            ArgumentsLookup,
             (
-                IdLookup,
+                SpecialIdLookup,
                 (
-                    InScopeLookup,
+                    IdLookup,
                     (
-                        LookupType,
-                        (BuiltinNamespaceLookup, (ReturnTypeSpecificLookup, BuiltinFunctionLookup)),
+                        InScopeLookup,
+                        (
+                            LookupType,
+                            (
+                                BuiltinNamespaceLookup,
+                                (ReturnTypeSpecificLookup, BuiltinFunctionLookup),
+                            ),
+                        ),
                     ),
                 ),
             ),
@@ -871,6 +894,7 @@ fn for_each_entry<R>(
                 Type::Brush | Type::Color => ColorExpression(self).for_each_entry(ctx, f),
                 Type::Image => ImageExpression(self).for_each_entry(ctx, f),
                 Type::Array(_) => ArrayExpression(self).for_each_entry(ctx, f),
+                Type::Map(_) => MapExpression(self).for_each_entry(ctx, f),
                 Type::Float32 | Type::Int32 | Type::Percent => {
                     NumberExpression(self).for_each_entry(ctx, f)
                 }
@@ -896,6 +920,7 @@ fn lookup(&self, ctx: &LookupCtx, name: &SmolStr) -> Option<LookupResult> {
                 Type::Brush | Type::Color => ColorExpression(self).lookup(ctx, name),
                 Type::Image => ImageExpression(self).lookup(ctx, name),
                 Type::Array(_) => ArrayExpression(self).lookup(ctx, name),
+                Type::Map(_) => MapExpression(self).lookup(ctx, name),
                 Type::Float32 | Type::Int32 | Type::Percent => {
                     NumberExpression(self).lookup(ctx, name)
                 }
@@ -933,10 +958,20 @@ fn for_each_entry<R>(
         let mut f = |s, res| f(&SmolStr::new_static(s), res);
         None.or_else(|| f("is-float", member_function(BuiltinFunction::StringIsFloat)))
             .or_else(|| f("to-float", member_function(BuiltinFunction::StringToFloat)))
+            .or_else(|| f("is-long", member_function(BuiltinFunction::StringIsLong)))
+            .or_else(|| f("to-long", member_function(BuiltinFunction::StringToLong)))
             .or_else(|| f("is-empty", function_call(BuiltinFunction::StringIsEmpty)))
             .or_else(|| f("character-count", function_call(BuiltinFunction::StringCharacterCount)))
             .or_else(|| f("to-lowercase", member_function(BuiltinFunction::StringToLowercase)))
             .or_else(|| f("to-uppercase", member_function(BuiltinFunction::StringToUppercase)))
+            .or_else(|| f("contains", member_function(BuiltinFunction::StringContains)))
+            .or_else(|| f("starts-with", member_function(BuiltinFunction::StringStartsWith)))
+            .or_else(|| f("ends-with", member_function(BuiltinFunction::StringEndsWith)))
+            .or_else(|| f("replace", member_function(BuiltinFunction::StringReplace)))
+            .or_else(|| f("trim", member_function(BuiltinFunction::StringTrim)))
+            .or_else(|| f("slice", member_function(BuiltinFunction::StringSlice)))
+            .or_else(|| f("split", member_function(BuiltinFunction::StringSplit)))
+            .or_else(|| f("index-of", member_function(BuiltinFunction::StringIndexOf)))
     }
 }
 struct ColorExpression<'a>(&'a Expression);
@@ -1018,16 +1053,46 @@ fn for_each_entry<R>(
         ctx: &LookupCtx,
         f: &mut impl FnMut(&SmolStr, LookupResult) -> Option<R>,
     ) -> Option<R> {
-        let member_function = |f: BuiltinFunction| {
+        let function_call = |f: BuiltinFunction| {
             LookupResult::from(Expression::FunctionCall {
                 function: Callable::Builtin(f),
                 source_location: ctx.current_token.as_ref().map(|t| t.to_source_location()),
                 arguments: vec![self.0.clone()],
             })
         };
-        None.or_else(|| {
-            f(&SmolStr::new_static("length"), member_function(BuiltinFunction::ArrayLength))
-        })
+        let member_macro = |m: BuiltinMacroFunction| {
+            LookupResult::Callable(LookupResultCallable::MemberFunction {
+                base: self.0.clone(),
+                base_node: ctx.current_token.clone(), // Note that this is not the base_node, but the function's node
+                member: LookupResultCallable::Macro(m).into(),
+            })
+        };
+        let mut f = |s, res| f(&SmolStr::new_static(s), res);
+        None.or_else(|| f("length", function_call(BuiltinFunction::ArrayLength)))
+            .or_else(|| f("index-of", member_macro(BuiltinMacroFunction::ArrayIndexOf)))
+            .or_else(|| f("contains", member_macro(BuiltinMacroFunction::ArrayContains)))
+            .or_else(|| f("slice", member_macro(BuiltinMacroFunction::ArraySlice)))
+            .or_else(|| f("concat", member_macro(BuiltinMacroFunction::ArrayConcat)))
+            .or_else(|| f("map", member_macro(BuiltinMacroFunction::ArrayMap)))
+            .or_else(|| f("filter", member_macro(BuiltinMacroFunction::ArrayFilter)))
+    }
+}
+
+struct MapExpression<'a>(&'a Expression);
+impl LookupObject for MapExpression<'_> {
+    fn for_each_entry<R>(
+        &self,
+        ctx: &LookupCtx,
+        f: &mut impl FnMut(&SmolStr, LookupResult) -> Option<R>,
+    ) -> Option<R> {
+        let member_macro = |m: BuiltinMacroFunction| {
+            LookupResult::Callable(LookupResultCallable::MemberFunction {
+                base: self.0.clone(),
+                base_node: ctx.current_token.clone(), // Note that this is not the base_node, but the function's node
+                member: LookupResultCallable::Macro(m).into(),
+            })
+        };
+        f(&SmolStr::new_static("has-key"), member_macro(BuiltinMacroFunction::MapHasKey))
     }
 }
 
@@ -20,7 +20,7 @@
 use crate::parser::{syntax_nodes, SyntaxKind, SyntaxNode};
 use crate::typeloader::{ImportKind, ImportedTypes};
 use crate::typeregister::TypeRegister;
-use itertools::Either;
+use itertools::{Either, Itertools};
 use smol_str::{format_smolstr, SmolStr, ToSmolStr};
 use std::cell::{Cell, OnceCell, RefCell};
 use std::collections::btree_map::Entry;
@@ -87,10 +87,19 @@ pub fn from_node(
             |n: syntax_nodes::Component,
              diag: &mut BuildDiagnostics,
              local_registry: &mut TypeRegister| {
+                if let Some(parameters) = n.GenericParameters() {
+                    let generic = GenericComponent::from_node(n, parameters, diag, local_registry);
+                    if !local_registry.add_generic_component(generic.clone()) {
+                        diag.push_warning(format!("Component '{}' is replacing a previously defined component with the same name", generic.name), &generic.node.DeclaredIdentifier());
+                    }
+                    return;
+                }
                 let compo = Component::from_node(n, diag, local_registry);
                 if !local_registry.add(compo.clone()) {
                     diag.push_warning(format!("Component '{}' is replacing a previously defined component with the same name", compo.id), &syntax_nodes::Component::from(compo.node.clone().unwrap()).DeclaredIdentifier());
                 }
+                // The instances of generic components must come before the component using them
+                inner_components.extend(local_registry.take_generic_instances());
                 inner_components.push(compo);
             };
         let process_struct = |n: syntax_nodes::StructDeclaration,
@@ -403,6 +412,11 @@ pub struct Component {
     /// The list of properties (name and type) declared as private in the component.
     /// This is used to issue better error in the generated code if the property is used.
     pub private_properties: RefCell<Vec<(SmolStr, Type)>>,
+
+    /// For an instance of a generic component: the type register in which its type parameters
+    /// are bound to the type arguments. Its expressions are resolved with this register instead
+    /// of the one of the document.
+    pub generic_type_register: Option<Rc<TypeRegister>>,
 }
 
 impl Component {
@@ -410,12 +424,23 @@ pub fn from_node(
         node: syntax_nodes::Component,
         diag: &mut BuildDiagnostics,
         tr: &TypeRegister,
+    ) -> Rc<Self> {
+        let id = parser::identifier_text(&node.DeclaredIdentifier()).unwrap_or_default();
+        Self::from_node_with_id(node, id, None, diag, tr)
+    }
+
+    fn from_node_with_id(
+        node: syntax_nodes::Component,
+        id: SmolStr,
+        generic_type_register: Option<Rc<TypeRegister>>,
+        diag: &mut BuildDiagnostics,
+        tr: &TypeRegister,
     ) -> Rc<Self> {
         let mut child_insertion_point = None;
         let is_legacy_syntax = node.child_token(SyntaxKind::ColonEqual).is_some();
         let c = Component {
             node: Some(node.clone().into()),
-            id: parser::identifier_text(&node.DeclaredIdentifier()).unwrap_or_default(),
+            id,
             root_element: Element::from_node(
                 node.Element(),
                 "root".into(),
@@ -430,6 +455,7 @@ pub fn from_node(
                 tr,
             ),
             child_insertion_point: RefCell::new(child_insertion_point),
+            generic_type_register,
             ..Default::default()
         };
         let c = Rc::new(c);
@@ -480,6 +506,136 @@ pub fn repeater_count(&self) -> u32 {
     }
 }
 
+/// A component declared with type parameters, such as `component Picker<T> { in property <[T]> items; }`.
+///
+/// It is not a component by itself: each use with different type arguments, such as
+/// `Picker<int> { ... }`, instantiates a [`Component`] in which the type parameters are bound
+/// to the type arguments.
+#[derive(Debug)]
+pub struct GenericComponent {
+    pub name: SmolStr,
+    pub node: syntax_nodes::Component,
+    pub parameters: Vec<SmolStr>,
+    /// The types and components declared before this component, in which its content is looked up
+    registry: Rc<RefCell<TypeRegister>>,
+    /// The components instantiated so far, with their type arguments
+    instances: RefCell<Vec<(Vec<Type>, Rc<Component>)>>,
+    /// The instances (and the instances of the generic components they use) that were not yet
+    /// added to the `inner_components` of the document using them
+    new_instances: RefCell<Vec<Rc<Component>>>,
+}
+
+impl GenericComponent {
+    pub fn from_node(
+        node: syntax_nodes::Component,
+        parameters: syntax_nodes::GenericParameters,
+        diag: &mut BuildDiagnostics,
+        tr: &TypeRegister,
+    ) -> Rc<Self> {
+        let mut parameter_names: Vec<SmolStr> = vec![];
+        for parameter in parameters.DeclaredIdentifier() {
+            let Some(name) = parser::identifier_text(&parameter) else { continue };
+            if parameter_names.contains(&name) {
+                diag.push_error(format!("Duplicated type parameter '{name}'"), &parameter);
+            } else {
+                parameter_names.push(name);
+            }
+        }
+        Rc::new(Self {
+            name: parser::identifier_text(&node.DeclaredIdentifier()).unwrap_or_default(),
+            node,
+            parameters: parameter_names,
+            registry: Rc::new(RefCell::new(tr.shallow_copy())),
+            instances: Default::default(),
+            new_instances: Default::default(),
+        })
+    }
+
+    /// Returns the component for the type arguments of an element using this generic component.
+    /// The component is only instantiated once for the same type arguments.
+    pub fn instantiate(
+        &self,
+        arguments: Option<syntax_nodes::GenericArguments>,
+        diag: &mut BuildDiagnostics,
+        tr: &TypeRegister,
+    ) -> Result<ElementType, String> {
+        let Some(arguments) = arguments else {
+            return Err(format!(
+                "'{0}' is a generic component and must be used with type arguments: {0}<{1}>",
+                self.name,
+                self.parameters.join(", ")
+            ));
+        };
+        let argument_nodes = arguments.Type().collect::<Vec<_>>();
+        if argument_nodes.len() != self.parameters.len() {
+            diag.push_error(
+                format!(
+                    "Wrong number of type arguments for '{}<{}>': expected {}, got {}",
+                    self.name,
+                    self.parameters.join(", "),
+                    self.parameters.len(),
+                    argument_nodes.len()
+                ),
+                &arguments,
+            );
+            return Ok(ElementType::Error);
+        }
+        let mut types = Vec::with_capacity(argument_nodes.len());
+        for node in argument_nodes {
+            let ty = type_from_node(node.clone(), diag, tr);
+            if ty == Type::Invalid {
+                // The error was already reported
+                return Ok(ElementType::Error);
+            }
+            if !ty.is_property_type() {
+                diag.push_error(format!("'{ty}' cannot be used as a type argument"), &node);
+                return Ok(ElementType::Error);
+            }
+            types.push(ty);
+        }
+
+        if let Some((_, c)) = self.instances.borrow().iter().find(|(t, _)| *t == types) {
+            return Ok(ElementType::Component(c.clone()));
+        }
+
+        let mut registry = TypeRegister::new(&self.registry);
+        for (parameter, ty) in self.parameters.iter().zip(&types) {
+            registry.insert_type_with_name(ty.clone(), parameter.clone());
+        }
+        let registry = Rc::new(registry);
+        let id = format_smolstr!("{}<{}>", self.name, types.iter().join(", "));
+        let component = Component::from_node_with_id(
+            self.node.clone(),
+            id,
+            Some(registry.clone()),
+            diag,
+            &registry,
+        );
+        let mut new_instances = self.new_instances.borrow_mut();
+        new_instances.extend(registry.take_generic_instances());
+        new_instances.push(component.clone());
+        self.instances.borrow_mut().push((types, component.clone()));
+        Ok(ElementType::Component(component))
+    }
+
+    /// Takes the instances that were not yet added to a document
+    pub fn take_new_instances(&self) -> Vec<Rc<Component>> {
+        self.new_instances.take()
+    }
+
+    /// A copy that doesn't share the instances, which refer to components of the original tree
+    pub(crate) fn snapshot(&self) -> Rc<Self> {
+        Rc::new(Self {
+            name: self.name.clone(),
+            node: self.node.clone(),
+            parameters: self.parameters.clone(),
+            registry: self.registry.clone(),
+            instances: Default::default(),
+            new_instances: Default::default(),
+        })
+    }
+}
+
 #[derive(Copy, Clone, Debug, Eq, PartialEq, Default)]
 pub enum PropertyVisibility {
     #[default]
@@ -523,6 +679,9 @@ pub struct PropertyDeclaration {
     pub visibility: PropertyVisibility,
     /// For function or callback: whether it is declared as `pure` (None for private function for which this has to be deduced)
     pub pure: Option<bool>,
+    /// For callback: whether it is declared as `async`. The handler set from native code then returns a future,
+    /// and its result is stored in the `<name>-result` property, while `<name>-pending` is true.
+    pub is_async: bool,
 }
 
 impl PropertyDeclaration {
@@ -936,7 +1095,20 @@ pub fn from_node(
         let base_type = if let Some(base_node) = node.QualifiedName() {
             let base = QualifiedTypeName::from_node(base_node.clone());
             let base_string = base.to_smolstr();
-            match parent_type.lookup_type_for_child_element(&base_string, tr) {
+            let lookup_result = if let Some(generic) = tr.lookup_generic_component(&base_string) {
+                generic.instantiate(node.GenericArguments(), diag, tr)
+            } else if let Some(arguments) = node.GenericArguments() {
+                diag.push_error(
+                    format!(
+                        "'{base_string}' is not a generic component and has no type parameters"
+                    ),
+                    &arguments,
+                );
+                parent_type.lookup_type_for_child_element(&base_string, tr)
+            } else {
+                parent_type.lookup_type_for_child_element(&base_string, tr)
+            };
+            match lookup_result {
                 Ok(ElementType::Component(c)) if c.is_global() => {
                     diag.push_error(
                         "Cannot create an instance of a global component".into(),
@@ -1136,6 +1308,8 @@ pub fn from_node(
             let pure = Some(
                 sig_decl.child_token(SyntaxKind::Identifier).is_some_and(|t| t.text() == "pure"),
             );
+            let is_async =
+                sig_decl.child_token(SyntaxKind::Identifier).is_some_and(|t| t.text() == "async");
 
             let PropertyLookupResult {
                 resolved_name: existing_name,
@@ -1168,6 +1342,12 @@ pub fn from_node(
             }
 
             if let Some(csn) = sig_decl.TwoWayBinding() {
+                if is_async {
+                    diag.push_error(
+                        "An async callback cannot be an alias".into(),
+                        &sig_decl.DeclaredIdentifier(),
+                    );
+                }
                 r.bindings
                     .insert(name.clone(), BindingExpression::new_uncompiled(csn.into()).into());
                 r.property_declarations.insert(
@@ -1199,6 +1379,40 @@ pub fn from_node(
                         .unwrap_or_default()
                 })
                 .collect();
+            let return_type = if is_async {
+                // The handler returns a future: the callback itself doesn't return anything, and
+                // the result is reported through properties
+                let mut result_properties = vec![(format_smolstr!("{name}-pending"), Type::Bool)];
+                if return_type != Type::Void {
+                    result_properties.push((format_smolstr!("{name}-result"), return_type));
+                }
+                for (prop_name, property_type) in result_properties {
+                    if !matches!(r.lookup_property(&prop_name).property_type, Type::Invalid) {
+                        diag.push_error(
+                            format!("Cannot declare async callback '{name}' when a property '{prop_name}' exists"),
+                            &sig_decl.DeclaredIdentifier(),
+                        );
+                        continue;
+                    }
+                    r.property_analysis
+                        .borrow_mut()
+                        .entry(prop_name.clone())
+                        .or_default()
+                        .is_set_externally = true;
+                    r.property_declarations.insert(
+                        prop_name,
+                        PropertyDeclaration {
+                            property_type,
+                            node: Some(sig_decl.clone().into()),
+                            visibility: PropertyVisibility::Output,
+                            ..Default::default()
+                        },
+                    );
+                }
+                Type::Void
+            } else {
+                return_type
+            };
             r.property_declarations.insert(
                 name,
                 PropertyDeclaration {
@@ -1210,6 +1424,7 @@ pub fn from_node(
                     node: Some(sig_decl.into()),
                     visibility: PropertyVisibility::InOut,
                     pure,
+                    is_async,
                     ..Default::default()
                 },
             );
@@ -1938,6 +2153,30 @@ pub fn type_from_node(
         type_struct_from_node(object_node, diag, tr, None, None)
     } else if let Some(array_node) = node.ArrayType() {
         Type::Array(Rc::new(type_from_node(array_node.Type(), diag, tr)))
+    } else if let Some(map_node) = node.MapType() {
+        let mut types = map_node.Type();
+        let key_type =
+            types.next().map(|key_node| (type_from_node(key_node.clone(), diag, tr), key_node));
+        if let Some((key_type, key_node)) = key_type {
+            if !matches!(key_type, Type::String | Type::Invalid) {
+                diag.push_error(
+                    format!("The keys of a map must be strings, not '{key_type}'"),
+                    &key_node,
+                );
+            }
+        }
+        let value_type =
+            types.next().map_or(Type::Invalid, |value_node| type_from_node(value_node, diag, tr));
+        Type::Map(Rc::new(value_type))
+    } else if let Some(optional_node) = node.OptionalType() {
+        match type_from_node(optional_node.Type(), diag, tr) {
+            Type::Invalid => Type::Invalid,
+            Type::Optional(_) => {
+                diag.push_error("Optional types cannot be nested".into(), &optional_node);
+                Type::Invalid
+            }
+            inner => Type::Optional(Rc::new(inner)),
+        }
     } else {
         assert!(diag.has_errors());
         Type::Invalid
@@ -2510,6 +2749,8 @@ pub fn from_export_specifier(
 pub struct Exports {
     #[deref]
     components_or_types: Vec<(ExportedName, Either<Rc<Component>, Type>)>,
+    /// The exported components with type parameters, which are only instantiated where they are used
+    generic_components: Vec<(ExportedName, Rc<GenericComponent>)>,
 }
 
 impl Exports {
@@ -2521,7 +2762,12 @@ pub fn from_node(
     ) -> Self {
         let resolve_export_to_inner_component_or_import =
             |internal_name: &str, internal_name_node: &dyn Spanned, diag: &mut BuildDiagnostics| {
-                if let Ok(ElementType::Component(c)) = type_registry.lookup_element(internal_name) {
+                if type_registry.lookup_generic_component(internal_name).is_some() {
+                    // Collected separately in `generic_components`
+                    None
+                } else if let Ok(ElementType::Component(c)) =
+                    type_registry.lookup_element(internal_name)
+                {
                     Some(Either::Left(c))
                 } else if let ty @ Type::Struct { .. } | ty @ Type::Enumeration(_) =
                     type_registry.lookup(internal_name)
@@ -2612,6 +2858,23 @@ pub fn from_node(
                 }),
         );
 
+        let generic_components = doc
+            .ExportsList()
+            .filter(|exports| exports.ExportModule().is_none())
+            .flat_map(|exports| exports.ExportSpecifier())
+            .map(|export_specifier| ExportedName::from_export_specifier(&export_specifier))
+            .chain(doc.ExportsList().flat_map(|exports| exports.Component()).filter_map(
+                |component| {
+                    let name = parser::identifier_text(&component.DeclaredIdentifier())?;
+                    let name_ident = component.DeclaredIdentifier().into();
+                    Some((name.clone(), ExportedName { name, name_ident }))
+                },
+            ))
+            .filter_map(|(internal_name, exported_name)| {
+                Some((exported_name, type_registry.lookup_generic_component(&internal_name)?))
+            })
+            .collect();
+
         let mut sorted_deduped_exports = Vec::with_capacity(sorted_exports_with_duplicates.len());
         let mut it = sorted_exports_with_duplicates.into_iter().peekable();
         while let Some((exported_name, compo_or_type)) = it.next() {
@@ -2659,7 +2922,7 @@ pub fn from_node(
                 )
             }
         }
-        Self { components_or_types: sorted_deduped_exports }
+        Self { components_or_types: sorted_deduped_exports, generic_components }
     }
 
     pub fn add_reexports(
@@ -2692,6 +2955,13 @@ pub fn find(&self, name: &str) -> Option<Either<Rc<Component>, Type>> {
             .map(|index| self.components_or_types[index].1.clone())
     }
 
+    pub fn find_generic_component(&self, name: &str) -> Option<Rc<GenericComponent>> {
+        self.generic_components
+            .iter()
+            .find(|(exported_name, _)| exported_name.as_str() == name)
+            .map(|(_, generic)| generic.clone())
+    }
+
     pub fn retain(
         &mut self,
         func: impl FnMut(&mut (ExportedName, Either<Rc<Component>, Type>)) -> bool,
@@ -2716,7 +2986,13 @@ pub(crate) fn snapshot(&self, snapshotter: &mut crate::typeloader::Snapshotter)
             })
             .collect();
 
-        Self { components_or_types }
+        let generic_components = self
+            .generic_components
+            .iter()
+            .map(|(en, generic)| (en.clone(), generic.snapshot()))
+            .collect();
+
+        Self { components_or_types, generic_components }
     }
 }
 
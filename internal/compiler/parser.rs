@@ -322,6 +322,8 @@ fn source_file(&self) -> Option<&SourceFile> {
         Semicolon -> ";",
         Bang -> "!",
         Dot -> ".",
+        QuestionQuestion -> "??",
+        QuestionDot -> "?.",
         Question -> "?",
         Dollar -> "$",
         At -> "@",
@@ -332,10 +334,14 @@ fn source_file(&self) -> Option<&SourceFile> {
     {
         Document -> [ *Component, *ExportsList, *ImportSpecifier, *StructDeclaration, *EnumDeclaration ],
         /// `DeclaredIdentifier := Element { ... }`
-        Component -> [ DeclaredIdentifier, Element ],
+        Component -> [ DeclaredIdentifier, ?GenericParameters, Element ],
+        /// `<T, U>` after the name of a generic component. Each DeclaredIdentifier is a type parameter
+        GenericParameters -> [ *DeclaredIdentifier ],
+        /// `<int, string>` after the base type of an element instantiating a generic component
+        GenericArguments -> [ *Type ],
         /// `id := Element { ... }`
         SubElement -> [ Element ],
-        Element -> [ ?QualifiedName, *PropertyDeclaration, *Binding, *CallbackConnection,
+        Element -> [ ?QualifiedName, ?GenericArguments, *PropertyDeclaration, *Binding, *CallbackConnection,
                      *CallbackDeclaration, *ConditionalElement, *Function, *SubElement,
                      *RepeatedElement, *PropertyAnimation, *PropertyChangedCallback,
                      *TwoWayBinding, *States, *Transitions, ?ChildrenPlaceholder ],
@@ -373,7 +379,7 @@ fn source_file(&self) -> Option<&SourceFile> {
         Expression-> [ ?Expression, ?FunctionCallExpression, ?IndexExpression, ?SelfAssignment,
                        ?ConditionalExpression, ?QualifiedName, ?BinaryExpression, ?Array, ?ObjectLiteral,
                        ?UnaryOpExpression, ?CodeBlock, ?StringTemplate, ?AtImageUrl, ?AtGradient, ?AtTr,
-                       ?MemberAccess ],
+                       ?MemberAccess, ?OptionalMemberAccess, ?Lambda, ?MatchExpression ],
         /// Concatenate the Expressions to make a string (usually expended from a template string)
         StringTemplate -> [*Expression],
         /// `@image-url("foo.png")`
@@ -400,6 +406,19 @@ fn source_file(&self) -> Option<&SourceFile> {
         UnaryOpExpression -> [Expression],
         /// `(foo).bar`, where `foo` is the base expression, and `bar` is a Identifier.
         MemberAccess -> [Expression],
+        /// `(foo)?.bar`, where `foo` is the base expression, and `bar` is a Identifier.
+        OptionalMemberAccess -> [Expression],
+        /// `item => expression`, as argument of `array.map(...)` or `array.filter(...)`
+        Lambda -> [DeclaredIdentifier, Expression],
+        /// `match value { pattern => expression, ... }`. The Expression is the matched value.
+        MatchExpression -> [ Expression, *MatchArm ],
+        /// `pattern | pattern => expression` inside a MatchExpression
+        MatchArm -> [ *MatchPattern, Expression ],
+        /// `_`, an enum value, a string or number literal (stored as token, with an optional minus),
+        /// or `{ field: pattern, ... }`
+        MatchPattern -> [ ?QualifiedName, *MatchFieldPattern ],
+        /// `field: pattern` inside a MatchPattern. The field name is an Identifier token
+        MatchFieldPattern -> [ MatchPattern ],
         /// `[ ... ]`
         Array -> [ *Expression ],
         /// `{ foo: bar }`
@@ -433,13 +452,17 @@ fn source_file(&self) -> Option<&SourceFile> {
         ExternalName -> [],
         InternalName -> [],
         /// The representation of a type
-        Type -> [ ?QualifiedName, ?ObjectType, ?ArrayType ],
+        Type -> [ ?QualifiedName, ?ObjectType, ?ArrayType, ?MapType, ?OptionalType ],
         /// `{foo: string, bar: string} `
         ObjectType ->[ *ObjectTypeMember ],
         /// `foo: type` inside an ObjectType
         ObjectTypeMember -> [ Type ],
         /// `[ type ]`
         ArrayType -> [ Type ],
+        /// `[ string: type ]` (the key type, then the value type)
+        MapType -> [ *Type ],
+        /// `type?`
+        OptionalType -> [ Type ],
         /// `struct Foo { ... }`
         StructDeclaration -> [DeclaredIdentifier, ObjectType, ?AtRustAttr],
         /// `enum Foo { bli, bla, blu }`
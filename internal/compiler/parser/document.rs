@@ -3,7 +3,9 @@
 
 use super::element::{parse_element, parse_element_content};
 use super::prelude::*;
-use super::r#type::{parse_enum_declaration, parse_rustattr, parse_struct_declaration};
+use super::r#type::{
+    parse_enum_declaration, parse_generic_parameters, parse_rustattr, parse_struct_declaration,
+};
 
 #[cfg_attr(test, parser_test)]
 /// ```test,Document
@@ -99,6 +101,8 @@ pub fn parse_document(p: &mut impl Parser) -> bool {
 /// global Struct { property<int> xx; }
 /// component C { property<int> xx; }
 /// component C inherits D { }
+/// component C<T> { property<[T]> xx; }
+/// component C<T, U> inherits D<U> { }
 /// ```
 pub fn parse_component(p: &mut impl Parser) -> bool {
     let simple_component = p.nth(1).kind() == SyntaxKind::ColonEqual;
@@ -118,6 +122,9 @@ pub fn parse_component(p: &mut impl Parser) -> bool {
         drop(p.start_node(SyntaxKind::Element));
         return false;
     }
+    if is_new_component && p.peek().kind() == SyntaxKind::LAngle {
+        parse_generic_parameters(&mut *p);
+    }
     if is_global {
         if p.peek().kind() == SyntaxKind::ColonEqual {
             p.warning("':=' to declare a global is deprecated. Remove the ':='");
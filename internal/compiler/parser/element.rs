@@ -6,7 +6,7 @@
 use super::document::parse_qualified_name;
 use super::expressions::parse_expression;
 use super::prelude::*;
-use super::r#type::parse_type;
+use super::r#type::{parse_generic_arguments, parse_type};
 use super::statements::parse_statement;
 
 #[cfg_attr(test, parser_test)]
@@ -14,6 +14,7 @@
 /// Item { }
 /// Item { property: value; SubElement { } }
 /// Item { if true: Rectangle {} }
+/// Item<int> { }
 /// ```
 pub fn parse_element(p: &mut impl Parser) -> bool {
     let mut p = p.start_node(SyntaxKind::Element);
@@ -27,6 +28,10 @@ pub fn parse_element(p: &mut impl Parser) -> bool {
         };
     }
 
+    if p.peek().kind() == SyntaxKind::LAngle {
+        parse_generic_arguments(&mut *p);
+    }
+
     if !p.expect(SyntaxKind::LBrace) {
         return false;
     }
@@ -40,6 +45,7 @@ pub fn parse_element(p: &mut impl Parser) -> bool {
 /// ```test
 /// property1: value; property2: value;
 /// sub := Sub { }
+/// Sub<[string]> { }
 /// for xx in model: Sub {}
 /// if condition : Sub {}
 /// clicked => {}
@@ -63,6 +69,9 @@ pub fn parse_element_content(p: &mut impl Parser) {
                 SyntaxKind::ColonEqual | SyntaxKind::LBrace => {
                     had_parse_error |= !parse_sub_element(&mut *p)
                 }
+                SyntaxKind::LAngle if p.peek().as_str() != "property" => {
+                    had_parse_error |= !parse_sub_element(&mut *p)
+                }
                 SyntaxKind::FatArrow | SyntaxKind::LParent if p.peek().as_str() != "if" => {
                     parse_callback_connection(&mut *p)
                 }
@@ -72,7 +81,8 @@ pub fn parse_element_content(p: &mut impl Parser) {
                 }
                 SyntaxKind::Identifier
                     if p.peek().as_str() == "callback"
-                        || (p.peek().as_str() == "pure" && p.nth(1).as_str() == "callback") =>
+                        || (matches!(p.peek().as_str(), "pure" | "async")
+                            && p.nth(1).as_str() == "callback") =>
                 {
                     parse_callback_declaration(&mut *p);
                 }
@@ -153,6 +163,7 @@ pub fn parse_element_content(p: &mut impl Parser) {
 /// Bar {}
 /// foo := Bar {}
 /// Bar { x : y ; }
+/// foo := Bar<int> {}
 /// ```
 /// Must consume at least one token
 fn parse_sub_element(p: &mut impl Parser) -> bool {
@@ -321,11 +332,12 @@ fn parse_two_way_binding(p: &mut impl Parser) {
 /// callback with_return(a, b) -> int;
 /// callback with_return2({a: string}) -> { a: string };
 /// callback foobar <=> elem.foobar;
+/// async callback validate(string) -> bool;
 /// ```
 /// Must consume at least one token
 fn parse_callback_declaration(p: &mut impl Parser) {
     let mut p = p.start_node(SyntaxKind::CallbackDeclaration);
-    if p.peek().as_str() == "pure" {
+    if matches!(p.peek().as_str(), "pure" | "async") {
         p.consume();
     }
     debug_assert_eq!(p.peek().as_str(), "callback");
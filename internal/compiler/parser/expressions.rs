@@ -30,6 +30,13 @@
 /// array[index]
 /// {object:42}
 /// "foo".bar.something().something.xx({a: 1.foo}.a)
+/// foo.map(item => item.bar)
+/// foo?.bar ?? baz
+/// foo ?? bar ? a ?? b : null
+/// match foo { a => 1, _ => 2 } + 1
+/// match + 1
+/// match(foo) + match.bar
+/// [match(foo), match]
 /// ```
 pub fn parse_expression(p: &mut impl Parser) -> bool {
     p.peek(); // consume the whitespace so they aren't part of the Expression node
@@ -41,6 +48,8 @@ pub fn parse_expression(p: &mut impl Parser) -> bool {
 enum OperatorPrecedence {
     /// ` ?: `
     Default,
+    /// `??`
+    NullCoalescing,
     /// `||`, `&&`
     Logical,
     /// `==` `!=` `>=` `<=` `<` `>`
@@ -56,6 +65,16 @@ fn parse_expression_helper(p: &mut impl Parser, precedence: OperatorPrecedence)
     let mut p = p.start_node(SyntaxKind::Expression);
     let checkpoint = p.checkpoint();
     match p.nth(0).kind() {
+        SyntaxKind::Identifier
+            if precedence == OperatorPrecedence::Default
+                && p.nth(1).kind() == SyntaxKind::FatArrow =>
+        {
+            parse_lambda(&mut *p);
+            return true;
+        }
+        SyntaxKind::Identifier if p.nth(0).as_str() == "match" && is_match_expression(&mut *p) => {
+            parse_match_expression(&mut *p);
+        }
         SyntaxKind::Identifier => {
             parse_qualified_name(&mut *p);
         }
@@ -101,6 +120,16 @@ fn parse_expression_helper(p: &mut impl Parser, precedence: OperatorPrecedence)
                     return false;
                 }
             }
+            SyntaxKind::QuestionDot => {
+                {
+                    let _ = p.start_node_at(checkpoint.clone(), SyntaxKind::Expression);
+                }
+                let mut p = p.start_node_at(checkpoint.clone(), SyntaxKind::OptionalMemberAccess);
+                p.consume(); // '?.'
+                if !p.expect(SyntaxKind::Identifier) {
+                    return false;
+                }
+            }
             SyntaxKind::LParent => {
                 {
                     let _ = p.start_node_at(checkpoint.clone(), SyntaxKind::Expression);
@@ -201,6 +230,19 @@ fn parse_expression_helper(p: &mut impl Parser, precedence: OperatorPrecedence)
         parse_expression_helper(&mut *p, OperatorPrecedence::Logical);
     }
 
+    if precedence >= OperatorPrecedence::NullCoalescing {
+        return true;
+    }
+
+    while p.nth(0).kind() == SyntaxKind::QuestionQuestion {
+        {
+            let _ = p.start_node_at(checkpoint.clone(), SyntaxKind::Expression);
+        }
+        let mut p = p.start_node_at(checkpoint.clone(), SyntaxKind::BinaryExpression);
+        p.consume();
+        parse_expression_helper(&mut *p, OperatorPrecedence::NullCoalescing);
+    }
+
     if p.nth(0).kind() == SyntaxKind::Question {
         {
             let _ = p.start_node_at(checkpoint.clone(), SyntaxKind::Expression);
@@ -243,6 +285,109 @@ fn parse_at_keyword(p: &mut impl Parser) {
     }
 }
 
+/// `match` is only a keyword if it is followed by the matched value and a `{`.
+/// Otherwise, it is the name of a property, a function or a callback.
+fn is_match_expression(p: &mut impl Parser) -> bool {
+    let mut depth = 0usize;
+    for n in 1.. {
+        match p.nth(n).kind() {
+            SyntaxKind::LBrace if depth == 0 => return n > 1,
+            SyntaxKind::LParent | SyntaxKind::LBracket | SyntaxKind::LBrace => depth += 1,
+            SyntaxKind::RParent | SyntaxKind::RBracket | SyntaxKind::RBrace if depth > 0 => {
+                depth -= 1
+            }
+            SyntaxKind::RParent
+            | SyntaxKind::RBracket
+            | SyntaxKind::RBrace
+            | SyntaxKind::Comma
+            | SyntaxKind::Semicolon
+            | SyntaxKind::Eof => return false,
+            _ => {}
+        }
+    }
+    false
+}
+
+#[cfg_attr(test, parser_test)]
+/// ```test,MatchExpression
+/// match foo { a => 1 }
+/// match foo.bar { Enum.a | b => "x", _ => "y", }
+/// match (foo) { "a" | "b" => 1, -1.5 => 2, 42px => 3, _ => 4 }
+/// match foo { { a: 1, b: { c: x } } => 1, { a: _ } => match bar { _ => 2 } }
+/// ```
+fn parse_match_expression(p: &mut impl Parser) {
+    let mut p = p.start_node(SyntaxKind::MatchExpression);
+    debug_assert_eq!(p.peek().as_str(), "match");
+    p.consume(); // "match"
+    parse_expression(&mut *p);
+    p.expect(SyntaxKind::LBrace);
+    while !matches!(p.nth(0).kind(), SyntaxKind::RBrace | SyntaxKind::Eof) {
+        {
+            let mut p = p.start_node(SyntaxKind::MatchArm);
+            parse_match_pattern(&mut *p);
+            while p.test(SyntaxKind::Pipe) {
+                parse_match_pattern(&mut *p);
+            }
+            p.expect(SyntaxKind::FatArrow);
+            parse_expression(&mut *p);
+        }
+        if !p.test(SyntaxKind::Comma) {
+            break;
+        }
+    }
+    p.expect(SyntaxKind::RBrace);
+}
+
+#[cfg_attr(test, parser_test)]
+/// ```test,MatchPattern
+/// _
+/// foo
+/// Enum.value
+/// "foo"
+/// -42
+/// 1.5px
+/// {}
+/// { a: 1, b: Enum.value, c: { d: "x" }, }
+/// ```
+fn parse_match_pattern(p: &mut impl Parser) {
+    let mut p = p.start_node(SyntaxKind::MatchPattern);
+    match p.nth(0).kind() {
+        SyntaxKind::Identifier => {
+            parse_qualified_name(&mut *p);
+        }
+        SyntaxKind::StringLiteral => {
+            if p.nth(0).as_str().ends_with('{') {
+                p.error("String templates cannot be used as pattern");
+            }
+            p.consume();
+        }
+        SyntaxKind::NumberLiteral => p.consume(),
+        SyntaxKind::Minus => {
+            p.consume();
+            p.expect(SyntaxKind::NumberLiteral);
+        }
+        SyntaxKind::LBrace => {
+            p.consume();
+            while p.nth(0).kind() != SyntaxKind::RBrace {
+                {
+                    let mut p = p.start_node(SyntaxKind::MatchFieldPattern);
+                    p.expect(SyntaxKind::Identifier);
+                    p.expect(SyntaxKind::Colon);
+                    parse_match_pattern(&mut *p);
+                }
+                if !p.test(SyntaxKind::Comma) {
+                    break;
+                }
+            }
+            p.expect(SyntaxKind::RBrace);
+        }
+        _ => {
+            p.error("Expected a pattern: '_', an enum value, a string or number literal, or a struct pattern");
+            p.consume();
+        }
+    }
+}
+
 #[cfg_attr(test, parser_test)]
 /// ```test,Array
 /// [ a, b, c , d]
@@ -305,6 +450,21 @@ fn parse_function_arguments(p: &mut impl Parser) {
     p.expect(SyntaxKind::RParent);
 }
 
+#[cfg_attr(test, parser_test)]
+/// ```test,Lambda
+/// item => item.foo
+/// x => x > 42 && x < 50
+/// ```
+fn parse_lambda(p: &mut impl Parser) {
+    let mut p = p.start_node(SyntaxKind::Lambda);
+    {
+        let mut p = p.start_node(SyntaxKind::DeclaredIdentifier);
+        p.expect(SyntaxKind::Identifier);
+    }
+    p.expect(SyntaxKind::FatArrow);
+    parse_expression(&mut *p);
+}
+
 #[cfg_attr(test, parser_test)]
 /// ```test,StringTemplate
 /// "foo\{bar}"
@@ -11,18 +11,83 @@
 /// string
 /// [ int ]
 /// {a: string, b: int}
+/// [string: int]
+/// [string: {a: string, b: [string: [int]]}]
+/// int?
+/// [{a: string?}?]?
 /// ```
 pub fn parse_type(p: &mut impl Parser) {
-    let mut p = p.start_node(SyntaxKind::Type);
-    match p.nth(0).kind() {
-        SyntaxKind::LBrace => parse_type_object(&mut *p),
-        SyntaxKind::LBracket => parse_type_array(&mut *p),
-        _ => {
-            parse_qualified_name(&mut *p);
+    // Consume the leading whitespace before taking the checkpoint, so it stays outside of the Type
+    p.peek();
+    let checkpoint = p.checkpoint();
+    {
+        let mut p = p.start_node(SyntaxKind::Type);
+        match p.nth(0).kind() {
+            SyntaxKind::LBrace => parse_type_object(&mut *p),
+            SyntaxKind::LBracket => parse_type_array(&mut *p),
+            _ => {
+                parse_qualified_name(&mut *p);
+            }
+        }
+    }
+    // The Type node is finished before looking for the `?`, so that the whitespace that
+    // follows the type doesn't end up in the Type node
+    loop {
+        // `int??` is lexed as the `??` operator, but still denotes a (nested) optional
+        let wrap_count = match p.nth(0).kind() {
+            SyntaxKind::Question => 1,
+            SyntaxKind::QuestionQuestion => 2,
+            _ => break,
+        };
+        for i in 0..wrap_count {
+            {
+                let mut p = p.start_node_at(checkpoint.clone(), SyntaxKind::OptionalType);
+                if i == 0 {
+                    p.consume();
+                }
+            }
+            let _ = p.start_node_at(checkpoint.clone(), SyntaxKind::Type);
         }
     }
 }
 
+#[cfg_attr(test, parser_test)]
+/// ```test,GenericParameters
+/// <T>
+/// <T, Value>
+/// ```
+pub fn parse_generic_parameters(p: &mut impl Parser) {
+    let mut p = p.start_node(SyntaxKind::GenericParameters);
+    p.expect(SyntaxKind::LAngle);
+    loop {
+        {
+            let mut p = p.start_node(SyntaxKind::DeclaredIdentifier);
+            p.expect(SyntaxKind::Identifier);
+        }
+        if !p.test(SyntaxKind::Comma) {
+            break;
+        }
+    }
+    p.expect(SyntaxKind::RAngle);
+}
+
+#[cfg_attr(test, parser_test)]
+/// ```test,GenericArguments
+/// <int>
+/// <[string], {a: int}, Foo?>
+/// ```
+pub fn parse_generic_arguments(p: &mut impl Parser) {
+    let mut p = p.start_node(SyntaxKind::GenericArguments);
+    p.expect(SyntaxKind::LAngle);
+    loop {
+        parse_type(&mut *p);
+        if !p.test(SyntaxKind::Comma) {
+            break;
+        }
+    }
+    p.expect(SyntaxKind::RAngle);
+}
+
 #[cfg_attr(test, parser_test)]
 /// ```test,ObjectType
 /// {a: string, b: int}
@@ -59,11 +124,20 @@ pub fn parse_type_object(p: &mut impl Parser) {
 /// [[int]]
 /// [{a: string, b: [string]}]
 /// ```
+/// Also parses `[string: type]` as a MapType
 pub fn parse_type_array(p: &mut impl Parser) {
-    let mut p = p.start_node(SyntaxKind::ArrayType);
+    let checkpoint = p.checkpoint();
     p.expect(SyntaxKind::LBracket);
     parse_type(&mut *p);
-    p.expect(SyntaxKind::RBracket);
+    if p.peek().kind() == SyntaxKind::Colon {
+        let mut p = p.start_node_at(checkpoint, SyntaxKind::MapType);
+        p.consume();
+        parse_type(&mut *p);
+        p.expect(SyntaxKind::RBracket);
+    } else {
+        let mut p = p.start_node_at(checkpoint, SyntaxKind::ArrayType);
+        p.expect(SyntaxKind::RBracket);
+    }
 }
 
 #[cfg_attr(test, parser_test)]
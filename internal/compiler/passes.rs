@@ -7,6 +7,7 @@
 mod check_expressions;
 mod check_public_api;
 mod check_rotation;
+pub mod check_unused_code;
 mod clip;
 mod collect_custom_fonts;
 mod collect_globals;
@@ -82,6 +83,9 @@ pub async fn run_passes(
 
     let global_type_registry = type_loader.global_type_registry.clone();
     run_import_passes(doc, type_loader, diag);
+    if type_loader.compiler_config.warn_unused_code {
+        check_unused_code::check_unused_exports(type_loader, Some(doc), diag);
+    }
     check_public_api::check_public_api(doc, &type_loader.compiler_config, diag);
 
     let raw_type_loader =
@@ -320,4 +324,7 @@ pub fn run_import_passes(
     check_expressions::check_expressions(doc, diag);
     check_rotation::check_rotation(doc, diag);
     unique_id::check_unique_id(doc, diag);
+    if type_loader.compiler_config.warn_unused_code {
+        check_unused_code::check_unused_code(doc, type_loader, diag);
+    }
 }
@@ -0,0 +1,192 @@
+// Copyright © SixtyFPS GmbH <info@slint.dev>
+// SPDX-License-Identifier: GPL-3.0-only OR LicenseRef-Slint-Royalty-free-2.0 OR LicenseRef-Slint-Software-3.0
+
+//! Passes that report unused imports, exported components that are never imported,
+//! unused private properties and states that can never be active.
+//!
+//! They only run when [`crate::CompilerConfiguration::warn_unused_code`] is set, and only
+//! report warnings: `passes/remove_unused_properties.rs` is what removes the unused
+//! properties from the generated code.
+
+use crate::diagnostics::BuildDiagnostics;
+use crate::expression_tree::Expression;
+use crate::object_tree::{recurse_elem, Document, PropertyVisibility};
+use crate::parser::{normalize_identifier, syntax_nodes, SyntaxKind};
+use crate::typeloader::{ImportKind, ImportedName, TypeLoader};
+use itertools::Either;
+use smol_str::SmolStr;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+/// Report the unused imports, private properties and states of the document
+pub fn check_unused_code(doc: &Document, type_loader: &TypeLoader, diag: &mut BuildDiagnostics) {
+    let Some(node) = &doc.node else { return };
+    if is_external_file(node.source_file.path(), type_loader) {
+        return;
+    }
+    check_unused_imports(doc, node, diag);
+    for component in &doc.inner_components {
+        recurse_elem(&component.root_element, &(), &mut |elem, _| {
+            let elem = elem.borrow();
+            for (name, decl) in &elem.property_declarations {
+                let Some(node) = &decl.node else { continue };
+                if decl.visibility == PropertyVisibility::Private
+                    && decl.property_type.is_property_type()
+                    && !elem.named_references.is_referenced(name)
+                    && !elem.change_callbacks.contains_key(name)
+                {
+                    let location = node.child_node(SyntaxKind::DeclaredIdentifier);
+                    diag.push_unnecessary_code_warning(
+                        format!("Private property '{name}' is never used"),
+                        location.as_ref().unwrap_or(node),
+                    );
+                }
+            }
+
+            let Some(debug) = elem.debug.first() else { return };
+            let mut always_active_state = None;
+            for (state, state_node) in
+                elem.states.iter().zip(debug.node.States().flat_map(|s| s.State()))
+            {
+                let location = state_node.DeclaredIdentifier();
+                let message = if let Some(previous) = &always_active_state {
+                    format!("State '{}' is never active because the state '{previous}' before it is always active", state.id)
+                } else {
+                    match &state.condition {
+                        None => format!(
+                            "State '{}' is never active because it has no 'when' condition",
+                            state.id
+                        ),
+                        Some(Expression::BoolLiteral(false)) => format!(
+                            "State '{}' is never active because its condition is always false",
+                            state.id
+                        ),
+                        Some(Expression::BoolLiteral(true)) => {
+                            always_active_state = Some(state.id.clone());
+                            continue;
+                        }
+                        Some(_) => continue,
+                    }
+                };
+                diag.push_unnecessary_code_warning(message, &location);
+            }
+        });
+    }
+}
+
+fn check_unused_imports(
+    doc: &Document,
+    node: &syntax_nodes::Document,
+    diag: &mut BuildDiagnostics,
+) {
+    let mut used_identifiers = HashSet::new();
+    let mut token = node.first_token();
+    while let Some(t) = token {
+        if t.kind() == SyntaxKind::Identifier
+            && !t.parent_ancestors().any(|n| n.kind() == SyntaxKind::ImportSpecifier)
+        {
+            used_identifiers.insert(normalize_identifier(t.text()));
+        }
+        token = t.next_token();
+    }
+
+    for import in &doc.imports {
+        let ImportKind::ImportList(list) = &import.import_kind else { continue };
+        for identifier in list.ImportIdentifier() {
+            let name = ImportedName::from_node(identifier.clone()).internal_name;
+            if !used_identifiers.contains(&name) {
+                diag.push_unnecessary_code_warning(
+                    format!("'{name}' is imported but never used"),
+                    &identifier,
+                );
+            }
+        }
+    }
+}
+
+/// Report the components that are exported by a file that is imported by other files,
+/// but that none of the loaded files imports.
+///
+/// This looks at all the documents of the type loader, and at `root` that may not be in it yet.
+pub fn check_unused_exports(
+    type_loader: &TypeLoader,
+    root: Option<&Document>,
+    diag: &mut BuildDiagnostics,
+) {
+    let documents = type_loader
+        .all_files()
+        .filter_map(|path| Some((path.clone(), type_loader.get_document(path)?)))
+        .collect::<Vec<_>>();
+    let root = root.and_then(|doc| {
+        Some((crate::pathutils::clean_path(doc.node.as_ref()?.source_file.path()), doc))
+    });
+    let documents = match root {
+        Some(root) if !documents.iter().any(|(path, _)| *path == root.0) => {
+            documents.into_iter().chain(std::iter::once(root)).collect()
+        }
+        _ => documents,
+    };
+
+    let mut imported_files = HashSet::new();
+    let mut imported_names = HashSet::<(PathBuf, SmolStr)>::new();
+    for (_, doc) in &documents {
+        for import in &doc.imports {
+            let file = PathBuf::from(&import.file);
+            match &import.import_kind {
+                ImportKind::ImportList(list) => {
+                    for name in ImportedName::extract_imported_names(list) {
+                        imported_names.insert((file.clone(), name.external_name));
+                    }
+                }
+                ImportKind::ModuleReexport(exports) => {
+                    let is_star_reexport = exports
+                        .ExportModule()
+                        .is_some_and(|m| m.child_token(SyntaxKind::Star).is_some());
+                    if is_star_reexport {
+                        if let Some(reexported) = type_loader.get_document(&file) {
+                            imported_names.extend(
+                                reexported
+                                    .exports
+                                    .iter()
+                                    .map(|(n, _)| (file.clone(), n.name.clone())),
+                            );
+                        }
+                    } else {
+                        for specifier in exports.ExportSpecifier() {
+                            let (name, _) =
+                                crate::object_tree::ExportedName::from_export_specifier(&specifier);
+                            imported_names.insert((file.clone(), name));
+                        }
+                    }
+                }
+                ImportKind::FileImport => {}
+            }
+            imported_files.insert(file);
+        }
+    }
+
+    for (path, doc) in &documents {
+        if !imported_files.contains(path) || is_external_file(path, type_loader) {
+            continue;
+        }
+        for (exported_name, component) in doc.exports.iter() {
+            let Either::Left(component) = component else { continue };
+            if !imported_names.contains(&(path.clone(), exported_name.name.clone())) {
+                diag.push_unnecessary_code_warning(
+                    format!(
+                        "{} '{}' is exported but never imported",
+                        if component.is_global() { "Global" } else { "Component" },
+                        exported_name.name
+                    ),
+                    &exported_name.name_ident,
+                );
+            }
+        }
+    }
+}
+
+/// Builtin files and files of libraries are not the code of the project
+fn is_external_file(path: &Path, type_loader: &TypeLoader) -> bool {
+    path.starts_with("builtin:")
+        || type_loader.compiler_config.library_paths.values().any(|lib| path.starts_with(lib))
+}
@@ -38,10 +38,13 @@ pub fn compile_paths(
             _ => return,
         };
 
+        // Embedded textures are used for MCU builds, where the software renderer is built
+        // without std and can't rasterize paths.
         #[cfg(feature = "software-renderer")]
         if _embed_resources == EmbedResourcesKind::EmbedTextures {
             diag.push_warning(
-                "Path element is not supported with the software renderer".into(),
+                "Path element is not supported with the software renderer on targets without std"
+                    .into(),
                 &*elem_.borrow(),
             )
         }
@@ -21,6 +21,8 @@
 
 pub fn resolve_aliases(doc: &Document, diag: &mut BuildDiagnostics) {
     for component in doc.inner_components.iter() {
+        let type_register =
+            component.generic_type_register.as_deref().unwrap_or(&doc.local_registry);
         let scope = ComponentScope(vec![]);
         crate::object_tree::recurse_elem_no_borrow(
             &component.root_element,
@@ -39,7 +41,7 @@ pub fn resolve_aliases(doc: &Document, diag: &mut BuildDiagnostics) {
                 // make it deterministic
                 need_resolving.sort();
                 for n in need_resolving {
-                    resolve_alias(elem, &n, &new_scope, &doc.local_registry, diag);
+                    resolve_alias(elem, &n, &new_scope, type_register, diag);
                 }
                 new_scope
             },
@@ -410,6 +410,7 @@ fn duplicate_sub_component(
         used: component_to_duplicate.used.clone(),
         private_properties: Default::default(),
         inherits_popup_window: core::cell::Cell::new(false),
+        generic_type_register: component_to_duplicate.generic_type_register.clone(),
     };
 
     let new_component = Rc::new(new_component);
@@ -601,6 +601,7 @@ fn lower_dialog_layout(
                                         )),
                                         visibility: PropertyVisibility::InOut,
                                         pure: None,
+                                        is_async: false,
                                     });
                             }
                         }
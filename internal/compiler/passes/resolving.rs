@@ -10,13 +10,13 @@
 
 use crate::diagnostics::{BuildDiagnostics, Spanned};
 use crate::expression_tree::*;
-use crate::langtype::{ElementType, Struct, Type};
+use crate::langtype::{ElementType, EnumerationValue, Struct, Type};
 use crate::lookup::{LookupCtx, LookupObject, LookupResult, LookupResultCallable};
 use crate::object_tree::*;
 use crate::parser::{identifier_text, syntax_nodes, NodeOrToken, SyntaxKind, SyntaxNode};
 use crate::typeregister::TypeRegister;
 use core::num::IntErrorKind;
-use smol_str::{SmolStr, ToSmolStr};
+use smol_str::{format_smolstr, SmolStr, ToSmolStr};
 use std::collections::HashMap;
 use std::rc::Rc;
 
@@ -42,6 +42,7 @@ fn resolve_expression(
             component_scope: scope,
             diag,
             arguments: vec![],
+            local_variables: vec![],
             type_register,
             type_loader: Some(type_loader),
             current_token: None,
@@ -103,9 +104,11 @@ pub fn resolve_expressions(
     type_loader: &crate::typeloader::TypeLoader,
     diag: &mut BuildDiagnostics,
 ) {
-    resolve_two_way_bindings(doc, &doc.local_registry, diag);
+    resolve_two_way_bindings(doc, diag);
 
     for component in doc.inner_components.iter() {
+        let type_register =
+            component.generic_type_register.as_deref().unwrap_or(&doc.local_registry);
         recurse_elem_with_scope(
             &component.root_element,
             ComponentScope(vec![]),
@@ -133,7 +136,7 @@ pub fn resolve_expressions(
                         property_name,
                         property_type(),
                         scope,
-                        &doc.local_registry,
+                        type_register,
                         type_loader,
                         diag,
                     );
@@ -153,6 +156,28 @@ enum LookupPhase {
     ResolvingTwoWayBindings,
 }
 
+/// A pattern of a `match` expression, resolved against the type of the matched value
+enum MatchPattern {
+    /// `_`, or a struct pattern that only contains such patterns
+    Any,
+    /// The value of an enum with that index, or `false` (0) and `true` (1) for a bool,
+    /// with the condition testing it
+    Value(usize, Expression),
+    /// Any other pattern (literals and struct patterns), matching when the condition is true
+    Condition(Expression),
+}
+
+impl MatchPattern {
+    fn condition(self) -> Option<Expression> {
+        match self {
+            MatchPattern::Any => None,
+            MatchPattern::Value(_, condition) | MatchPattern::Condition(condition) => {
+                Some(condition)
+            }
+        }
+    }
+}
+
 impl Expression {
     pub fn from_binding_expression_node(node: SyntaxNode, ctx: &mut LookupCtx) -> Self {
         debug_assert_eq!(node.kind(), SyntaxKind::BindingExpression);
@@ -273,7 +298,7 @@ fn from_function(node: syntax_nodes::Function, ctx: &mut LookupCtx) -> Expressio
         )
     }
 
-    fn from_expression_node(node: syntax_nodes::Expression, ctx: &mut LookupCtx) -> Self {
+    pub fn from_expression_node(node: syntax_nodes::Expression, ctx: &mut LookupCtx) -> Self {
         node.children_with_tokens()
             .find_map(|child| match child {
                 NodeOrToken::Node(node) => match node.kind() {
@@ -292,6 +317,9 @@ fn from_expression_node(node: syntax_nodes::Expression, ctx: &mut LookupCtx) ->
                     SyntaxKind::MemberAccess => {
                         Some(Self::from_member_access_node(node.into(), ctx))
                     }
+                    SyntaxKind::OptionalMemberAccess => {
+                        Some(Self::from_optional_member_access_node(node.into(), ctx))
+                    }
                     SyntaxKind::IndexExpression => {
                         Some(Self::from_index_expression_node(node.into(), ctx))
                     }
@@ -307,6 +335,9 @@ fn from_expression_node(node: syntax_nodes::Expression, ctx: &mut LookupCtx) ->
                     SyntaxKind::ConditionalExpression => {
                         Some(Self::from_conditional_expression_node(node.into(), ctx))
                     }
+                    SyntaxKind::MatchExpression => {
+                        Some(Self::from_match_expression_node(node.into(), ctx))
+                    }
                     SyntaxKind::ObjectLiteral => {
                         Some(Self::from_object_literal_node(node.into(), ctx))
                     }
@@ -315,6 +346,14 @@ fn from_expression_node(node: syntax_nodes::Expression, ctx: &mut LookupCtx) ->
                     SyntaxKind::StringTemplate => {
                         Some(Self::from_string_template_node(node.into(), ctx))
                     }
+                    SyntaxKind::Lambda => {
+                        ctx.diag.push_error(
+                            "Lambda expressions can only be used as argument of `map` or `filter`"
+                                .into(),
+                            &node,
+                        );
+                        Some(Self::Invalid)
+                    }
                     _ => None,
                 },
                 NodeOrToken::Token(token) => match token.kind() {
@@ -854,6 +893,12 @@ fn from_function_call_node(
                 );
             }
             LookupResultCallable::MemberFunction { member, base, base_node } => {
+                if let LookupResultCallable::Macro(
+                    mac @ (BuiltinMacroFunction::ArrayMap | BuiltinMacroFunction::ArrayFilter),
+                ) = &*member
+                {
+                    return Self::from_array_lambda_call(mac.clone(), base, &node, ctx);
+                }
                 arguments.push((base, base_node));
                 adjust_arg_count = 1;
                 match *member {
@@ -909,6 +954,59 @@ fn from_function_call_node(
         Expression::FunctionCall { function, arguments, source_location: Some(source_location) }
     }
 
+    /// `array.map(item => expression)` or `array.filter(item => condition)`
+    fn from_array_lambda_call(
+        mac: BuiltinMacroFunction,
+        array: Expression,
+        node: &syntax_nodes::FunctionCallExpression,
+        ctx: &mut LookupCtx,
+    ) -> Expression {
+        let name = if matches!(mac, BuiltinMacroFunction::ArrayMap) { "map" } else { "filter" };
+        let mut args = node.Expression().skip(1);
+        let (Some(arg), None) = (args.next(), args.next()) else {
+            ctx.diag.push_error(format!("`{name}` expects 1 argument"), node);
+            return Self::Invalid;
+        };
+        let Some(lambda) = arg.Lambda() else {
+            ctx.diag.push_error(
+                format!("The argument of `{name}` must be a lambda expression, such as `item => item.name`"),
+                &arg,
+            );
+            return Self::Invalid;
+        };
+        let Type::Array(item_ty) = array.ty() else {
+            return Self::Invalid;
+        };
+        let Some(item_name) = identifier_text(&lambda.DeclaredIdentifier()) else {
+            return Self::Invalid;
+        };
+
+        let item = format_smolstr!("lambda_{item_name}");
+        ctx.local_variables.push((
+            item_name,
+            Expression::ReadLocalVariable { name: item.clone(), ty: (*item_ty).clone() },
+        ));
+        let body = Self::from_expression_node(lambda.Expression(), ctx);
+        ctx.local_variables.pop();
+
+        if matches!(mac, BuiltinMacroFunction::ArrayMap) {
+            let ty = body.ty();
+            if !ty.is_property_type() {
+                if ty != Type::Invalid {
+                    ctx.diag.push_error(
+                        format!("`map` cannot produce an array of '{ty}'"),
+                        &lambda.Expression(),
+                    );
+                }
+                return Self::Invalid;
+            }
+            Expression::ArrayMap { array: Box::new(array), item, body: Box::new(body) }
+        } else {
+            let condition = body.maybe_convert_to(Type::Bool, &lambda.Expression(), ctx.diag);
+            Expression::ArrayFilter { array: Box::new(array), item, condition: Box::new(condition) }
+        }
+    }
+
     fn from_member_access_node(
         node: syntax_nodes::MemberAccess,
         ctx: &mut LookupCtx,
@@ -922,6 +1020,109 @@ fn from_member_access_node(
         )
     }
 
+    /// `base?.field`: null if the base is null, otherwise the field of the struct as an optional
+    fn from_optional_member_access_node(
+        node: syntax_nodes::OptionalMemberAccess,
+        ctx: &mut LookupCtx,
+    ) -> Expression {
+        let base = Self::from_expression_node(node.Expression(), ctx);
+        let Some(field) = node.child_token(SyntaxKind::Identifier) else {
+            assert!(ctx.diag.has_errors());
+            return Expression::Invalid;
+        };
+        let base_ty = base.ty();
+        let struct_ty = match &base_ty {
+            Type::Invalid => return Expression::Invalid,
+            Type::Optional(inner) if matches!(**inner, Type::Struct(_)) => match &**inner {
+                Type::Struct(s) => s.clone(),
+                _ => unreachable!(),
+            },
+            _ => {
+                ctx.diag.push_error(
+                    format!("'?.' can only be used on optional structs, not on {base_ty}"),
+                    &field,
+                );
+                return Expression::Invalid;
+            }
+        };
+        let name = crate::parser::normalize_identifier(field.text());
+        let Some(field_ty) = struct_ty.fields.get(&name) else {
+            ctx.diag.push_error(
+                format!("Cannot access the field '{}' of {base_ty}", field.text()),
+                &field,
+            );
+            return Expression::Invalid;
+        };
+        let result_ty = match field_ty {
+            Type::Optional(_) => field_ty.clone(),
+            _ => Type::Optional(field_ty.clone().into()),
+        };
+
+        static COUNT: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(0);
+        let var_name = format_smolstr!(
+            "tmpobj_optional_field_{}",
+            COUNT.fetch_add(1, std::sync::atomic::Ordering::Relaxed)
+        );
+        let read = || Expression::ReadLocalVariable { name: var_name.clone(), ty: base_ty.clone() };
+        let value = Expression::StructFieldAccess {
+            base: Box::new(Expression::OptionalUnwrap(read().into())),
+            name,
+        }
+        .maybe_convert_to(result_ty.clone(), &field, ctx.diag);
+        Expression::CodeBlock(vec![
+            Expression::StoreLocalVariable { name: var_name.clone(), value: Box::new(base) },
+            Expression::Condition {
+                condition: Box::new(Expression::OptionalHasValue(read().into())),
+                true_expr: Box::new(value),
+                false_expr: Box::new(Expression::NullLiteral(result_ty)),
+            },
+        ])
+    }
+
+    /// `lhs ?? rhs`: the value of `lhs` if it is not null, otherwise `rhs`
+    fn from_null_coalescing(
+        lhs: Expression,
+        lhs_n: &syntax_nodes::Expression,
+        rhs: Expression,
+        rhs_n: &syntax_nodes::Expression,
+        ctx: &mut LookupCtx,
+    ) -> Expression {
+        let lhs_ty = lhs.ty();
+        let inner_ty = match &lhs_ty {
+            Type::Invalid => return Expression::Invalid,
+            Type::Optional(inner) => (**inner).clone(),
+            _ => {
+                ctx.diag.push_error(
+                    format!("The left side of '??' must be an optional value, not {lhs_ty}"),
+                    lhs_n,
+                );
+                return Expression::Invalid;
+            }
+        };
+        // When the right side is also optional, so is the result
+        let rhs_is_optional = matches!(rhs.ty(), Type::Optional(_));
+        let lhs_value_ty = if rhs_is_optional { lhs_ty.clone() } else { inner_ty };
+        let result_ty =
+            Self::common_target_type_for_type_list([lhs_value_ty.clone(), rhs.ty()].into_iter());
+
+        static COUNT: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(0);
+        let var_name = format_smolstr!(
+            "tmpobj_null_coalescing_{}",
+            COUNT.fetch_add(1, std::sync::atomic::Ordering::Relaxed)
+        );
+        let read = || Expression::ReadLocalVariable { name: var_name.clone(), ty: lhs_ty.clone() };
+        let value =
+            if rhs_is_optional { read() } else { Expression::OptionalUnwrap(read().into()) };
+        Expression::CodeBlock(vec![
+            Expression::StoreLocalVariable { name: var_name.clone(), value: Box::new(lhs) },
+            Expression::Condition {
+                condition: Box::new(Expression::OptionalHasValue(read().into())),
+                true_expr: Box::new(value.maybe_convert_to(result_ty.clone(), lhs_n, ctx.diag)),
+                false_expr: Box::new(rhs.maybe_convert_to(result_ty, rhs_n, ctx.diag)),
+            },
+        ])
+    }
+
     fn from_self_assignment_node(
         node: syntax_nodes::SelfAssignment,
         ctx: &mut LookupCtx,
@@ -945,6 +1146,7 @@ fn from_self_assignment_node(
         let ty = lhs.ty();
         let expected_ty = match op {
             '=' => ty,
+            '+' | '-' | '*' | '/' if ty == Type::Int64 => ty,
             '+' if ty == Type::String || ty.as_unit_product().is_some() => ty,
             '-' if ty.as_unit_product().is_some() => ty,
             '/' | '*' if ty.as_unit_product().is_some() => Type::Float32,
@@ -986,6 +1188,7 @@ fn from_binary_expression_node(
                 SyntaxKind::NotEqual => Some('!'),
                 SyntaxKind::AndAnd => Some('&'),
                 SyntaxKind::OrOr => Some('|'),
+                SyntaxKind::QuestionQuestion => Some('?'),
                 _ => None,
             })
             .unwrap_or('_');
@@ -994,9 +1197,23 @@ fn from_binary_expression_node(
         let lhs = Self::from_expression_node(lhs_n.clone(), ctx);
         let rhs = Self::from_expression_node(rhs_n.clone(), ctx);
 
+        if op == '?' {
+            return Self::from_null_coalescing(lhs, &lhs_n, rhs, &rhs_n, ctx);
+        }
+
         let expected_ty = match operator_class(op) {
             OperatorClass::ComparisonOp => {
-                Self::common_target_type_for_type_list([lhs.ty(), rhs.ty()].iter().cloned())
+                let ty = Self::long_operation_type(&lhs, &rhs).unwrap_or_else(|| {
+                    Self::common_target_type_for_type_list([lhs.ty(), rhs.ty()].iter().cloned())
+                });
+                if matches!(ty, Type::Optional(_)) && !matches!(op, '=' | '!') {
+                    ctx.diag.push_error(
+                        "Optional values can only be compared with == or !=. Use '??' to provide a value for null".into(),
+                        &node,
+                    );
+                    return Expression::Invalid;
+                }
+                ty
             }
             OperatorClass::LogicalOp => Type::Bool,
             OperatorClass::ArithmeticOp => {
@@ -1013,7 +1230,7 @@ fn from_binary_expression_node(
                     } else if matches!(rhs_ty, Type::UnitProduct(_)) {
                         rhs_ty
                     } else {
-                        Type::Float32
+                        Self::long_operation_type(&lhs, &rhs).unwrap_or(Type::Float32)
                     }
                 } else if op == '*' || op == '/' {
                     let has_unit = |ty: &Type| {
@@ -1049,7 +1266,9 @@ fn from_binary_expression_node(
                                 op,
                             }
                         }
-                        (false, false) => Type::Float32,
+                        (false, false) => {
+                            Self::long_operation_type(&lhs, &rhs).unwrap_or(Type::Float32)
+                        }
                     }
                 } else {
                     unreachable!()
@@ -1063,6 +1282,28 @@ fn from_binary_expression_node(
         }
     }
 
+    /// The type of an arithmetic operation or a comparison where one of the operands is a `long`:
+    /// `long` if the other one is an integer too, or `float` otherwise.
+    /// Returns None unless one of the operands is a `long` and the other one a number.
+    fn long_operation_type(lhs: &Expression, rhs: &Expression) -> Option<Type> {
+        fn is_integer(e: &Expression) -> bool {
+            match e {
+                Expression::NumberLiteral(value, Unit::None) => value.fract() == 0.,
+                Expression::UnaryOp { sub, op: '-' | '+' } => is_integer(sub),
+                _ => matches!(e.ty(), Type::Int32 | Type::Int64),
+            }
+        }
+        let (lhs_ty, rhs_ty) = (lhs.ty(), rhs.ty());
+        let is_number = |ty: &Type| matches!(ty, Type::Int32 | Type::Int64 | Type::Float32);
+        if (lhs_ty != Type::Int64 && rhs_ty != Type::Int64)
+            || !is_number(&lhs_ty)
+            || !is_number(&rhs_ty)
+        {
+            return None;
+        }
+        Some(if is_integer(lhs) && is_integer(rhs) { Type::Int64 } else { Type::Float32 })
+    }
+
     fn from_unaryop_expression_node(
         node: syntax_nodes::UnaryOpExpression,
         ctx: &mut LookupCtx,
@@ -1088,6 +1329,7 @@ fn from_unaryop_expression_node(
                     && !matches!(
                         ty,
                         Type::Int32
+                            | Type::Int64
                             | Type::Float32
                             | Type::Percent
                             | Type::UnitProduct(..)
@@ -1132,12 +1374,296 @@ fn from_conditional_expression_node(
         }
     }
 
+    /// A match expression is lowered to a chain of conditions on a local variable
+    /// holding the matched value.
+    fn from_match_expression_node(
+        node: syntax_nodes::MatchExpression,
+        ctx: &mut LookupCtx,
+    ) -> Expression {
+        let value = Self::from_expression_node(node.Expression(), ctx);
+        let value_ty = value.ty();
+        if value_ty == Type::Invalid {
+            return Expression::Invalid;
+        }
+
+        static COUNT: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(0);
+        let var_name = format_smolstr!(
+            "tmpobj_match_{}",
+            COUNT.fetch_add(1, std::sync::atomic::Ordering::Relaxed)
+        );
+        let read = Expression::ReadLocalVariable { name: var_name.clone(), ty: value_ty.clone() };
+
+        // The values of the enum (or bool) handled by the previous arms
+        let mut handled_values = match &value_ty {
+            Type::Enumeration(en) => vec![false; en.values.len()],
+            Type::Bool => vec![false; 2],
+            _ => vec![],
+        };
+        let mut exhaustive = false;
+        let mut arms = vec![];
+        for arm in node.MatchArm() {
+            if exhaustive {
+                ctx.diag.push_warning(
+                    "Unreachable match arm: the previous arms already handle all the values".into(),
+                    &arm,
+                );
+                // Still report the errors in the expression
+                Self::from_expression_node(arm.Expression(), ctx);
+                continue;
+            }
+            let mut conditions = Some(vec![]);
+            for pattern_node in arm.MatchPattern() {
+                if exhaustive {
+                    ctx.diag.push_warning(
+                        "Unreachable pattern: all the values are already handled".into(),
+                        &pattern_node,
+                    );
+                    continue;
+                }
+                let pattern = Self::from_match_pattern_node(&pattern_node, read.clone(), ctx);
+                if let MatchPattern::Value(index, _) = &pattern {
+                    if std::mem::replace(&mut handled_values[*index], true) {
+                        ctx.diag.push_warning(
+                            "Unreachable pattern: this value is already handled".into(),
+                            &pattern_node,
+                        );
+                    }
+                }
+                match pattern.condition() {
+                    Some(condition) => {
+                        if let Some(conditions) = &mut conditions {
+                            conditions.push(condition);
+                        }
+                    }
+                    None => conditions = None,
+                }
+                exhaustive = conditions.is_none()
+                    || (!handled_values.is_empty() && handled_values.iter().all(|h| *h));
+            }
+            let condition = conditions.map(|conditions| {
+                conditions
+                    .into_iter()
+                    .reduce(|lhs, rhs| Expression::BinaryExpression {
+                        lhs: Box::new(lhs),
+                        rhs: Box::new(rhs),
+                        op: '|',
+                    })
+                    .unwrap_or(Expression::BoolLiteral(false))
+            });
+            let expr_node = arm.Expression();
+            let expr = Self::from_expression_node(expr_node.clone(), ctx);
+            arms.push((condition, expr, expr_node));
+        }
+
+        if !exhaustive {
+            let missing = match &value_ty {
+                Type::Enumeration(en) => en.values.iter().map(|v| v.as_str()).collect(),
+                Type::Bool => vec!["false", "true"],
+                _ => vec![],
+            }
+            .into_iter()
+            .zip(&handled_values)
+            .filter(|(_, handled)| !**handled)
+            .map(|(v, _)| format!("'{v}'"))
+            .collect::<Vec<_>>();
+            let message = if missing.is_empty() {
+                format!("The match doesn't handle all the values of type '{value_ty}'. Add a '_' arm to handle the other values")
+            } else {
+                format!(
+                    "The match doesn't handle all the values of type '{value_ty}': {} {} not handled",
+                    missing.join(", "),
+                    if missing.len() == 1 { "is" } else { "are" }
+                )
+            };
+            let keyword = node.child_token(SyntaxKind::Identifier);
+            match &keyword {
+                Some(keyword) => ctx.diag.push_error(message, keyword),
+                None => ctx.diag.push_error(message, &node),
+            }
+            return Expression::Invalid;
+        }
+
+        let result_ty =
+            Self::common_target_type_for_type_list(arms.iter().map(|(_, expr, _)| expr.ty()));
+        // The last arm doesn't need its condition, since the match is exhaustive
+        let (_, last_expr, last_node) = arms.pop().unwrap();
+        let mut result = last_expr.maybe_convert_to(result_ty.clone(), &last_node, ctx.diag);
+        for (condition, expr, expr_node) in arms.into_iter().rev() {
+            result = Expression::Condition {
+                condition: Box::new(condition.unwrap()),
+                true_expr: Box::new(expr.maybe_convert_to(result_ty.clone(), &expr_node, ctx.diag)),
+                false_expr: Box::new(result),
+            };
+        }
+        Expression::CodeBlock(vec![
+            Expression::StoreLocalVariable { name: var_name, value: Box::new(value) },
+            result,
+        ])
+    }
+
+    /// Resolve a pattern of a match expression for the `value`
+    fn from_match_pattern_node(
+        node: &syntax_nodes::MatchPattern,
+        value: Expression,
+        ctx: &mut LookupCtx,
+    ) -> MatchPattern {
+        let ty = value.ty();
+        let equals = |rhs: Expression| Expression::BinaryExpression {
+            lhs: Box::new(value.clone()),
+            rhs: Box::new(rhs),
+            op: '=',
+        };
+
+        if let Some(qualified_name) = node.QualifiedName() {
+            let names = qualified_name
+                .children_with_tokens()
+                .filter(|n| n.kind() == SyntaxKind::Identifier)
+                .filter_map(|n| n.into_token())
+                .collect::<Vec<_>>();
+            if let [name] = names.as_slice() {
+                if name.text() == "_" {
+                    return MatchPattern::Any;
+                }
+            }
+            let Some(name) = names.last() else {
+                assert!(ctx.diag.has_errors());
+                return MatchPattern::Condition(Expression::Invalid);
+            };
+            let value_name = crate::parser::normalize_identifier(name.text());
+            match &ty {
+                Type::Enumeration(en)
+                    if names.len() == 1
+                        || (names.len() == 2
+                            && crate::parser::normalize_identifier(names[0].text()) == en.name) =>
+                {
+                    if let Some(index) = en.values.iter().position(|v| *v == value_name) {
+                        let enum_value = Expression::EnumerationValue(EnumerationValue {
+                            value: index,
+                            enumeration: en.clone(),
+                        });
+                        return MatchPattern::Value(index, equals(enum_value));
+                    }
+                    ctx.diag.push_error(
+                        format!("'{}' is not a value of the enum '{}'", name.text(), en.name),
+                        &qualified_name,
+                    );
+                }
+                Type::Bool
+                    if names.len() == 1 && matches!(value_name.as_str(), "true" | "false") =>
+                {
+                    return if value_name == "true" {
+                        MatchPattern::Value(1, value)
+                    } else {
+                        MatchPattern::Value(
+                            0,
+                            Expression::UnaryOp { sub: Box::new(value), op: '!' },
+                        )
+                    };
+                }
+                _ => ctx.diag.push_error(
+                    format!(
+                        "'{}' is not a valid pattern for a value of type '{ty}'",
+                        qualified_name.text().to_string().trim()
+                    ),
+                    &qualified_name,
+                ),
+            }
+            return MatchPattern::Condition(Expression::Invalid);
+        }
+
+        if node.child_token(SyntaxKind::LBrace).is_some() {
+            let Type::Struct(s) = &ty else {
+                ctx.diag.push_error(
+                    format!("A struct pattern cannot match a value of type '{ty}'"),
+                    node,
+                );
+                return MatchPattern::Condition(Expression::Invalid);
+            };
+            let mut conditions = vec![];
+            for field_node in node.MatchFieldPattern() {
+                let Some(field) = field_node.child_token(SyntaxKind::Identifier) else {
+                    assert!(ctx.diag.has_errors());
+                    conditions.push(Expression::Invalid);
+                    continue;
+                };
+                let name = crate::parser::normalize_identifier(field.text());
+                if !s.fields.contains_key(&name) {
+                    ctx.diag.push_error(
+                        format!("Cannot access the field '{}' of {ty}", field.text()),
+                        &field,
+                    );
+                    conditions.push(Expression::Invalid);
+                    continue;
+                }
+                let field_value =
+                    Expression::StructFieldAccess { base: Box::new(value.clone()), name };
+                let pattern =
+                    Self::from_match_pattern_node(&field_node.MatchPattern(), field_value, ctx);
+                conditions.extend(pattern.condition());
+            }
+            return conditions
+                .into_iter()
+                .reduce(|lhs, rhs| Expression::BinaryExpression {
+                    lhs: Box::new(lhs),
+                    rhs: Box::new(rhs),
+                    op: '&',
+                })
+                .map_or(MatchPattern::Any, MatchPattern::Condition);
+        }
+
+        if let Some(token) = node.child_token(SyntaxKind::StringLiteral) {
+            if ty != Type::String {
+                ctx.diag
+                    .push_error(format!("A string cannot match a value of type '{ty}'"), &token);
+                return MatchPattern::Condition(Expression::Invalid);
+            }
+            let Some(string) = crate::literals::unescape_string(token.text()) else {
+                ctx.diag.push_error("Cannot parse string literal".into(), &token);
+                return MatchPattern::Condition(Expression::Invalid);
+            };
+            return MatchPattern::Condition(equals(Expression::StringLiteral(string)));
+        }
+
+        if let Some(token) = node.child_token(SyntaxKind::NumberLiteral) {
+            if ty.as_unit_product().is_none() && ty != Type::Int64 {
+                ctx.diag
+                    .push_error(format!("A number cannot match a value of type '{ty}'"), &token);
+                return MatchPattern::Condition(Expression::Invalid);
+            }
+            let number = match crate::literals::parse_number_literal(token.text().into()) {
+                Ok(Expression::NumberLiteral(n, unit))
+                    if node.child_token(SyntaxKind::Minus).is_some() =>
+                {
+                    Expression::NumberLiteral(-n, unit)
+                }
+                Ok(number) => number,
+                Err(e) => {
+                    ctx.diag.push_error(e.to_string(), &token);
+                    return MatchPattern::Condition(Expression::Invalid);
+                }
+            };
+            let number = number.maybe_convert_to(ty.clone(), &token, ctx.diag);
+            return MatchPattern::Condition(equals(number));
+        }
+
+        assert!(ctx.diag.has_errors());
+        MatchPattern::Condition(Expression::Invalid)
+    }
+
     fn from_index_expression_node(
         node: syntax_nodes::IndexExpression,
         ctx: &mut LookupCtx,
     ) -> Expression {
         let (array_expr_n, index_expr_n) = node.Expression();
         let array_expr = Self::from_expression_node(array_expr_n, ctx);
+        if let Type::Map(_) = array_expr.ty() {
+            let key_expr = Self::from_expression_node(index_expr_n.clone(), ctx).maybe_convert_to(
+                Type::String,
+                &index_expr_n,
+                ctx.diag,
+            );
+            return Expression::MapIndex { map: Box::new(array_expr), key: Box::new(key_expr) };
+        }
         let index_expr = Self::from_expression_node(index_expr_n.clone(), ctx).maybe_convert_to(
             Type::Int32,
             &index_expr_n,
@@ -1267,6 +1793,31 @@ pub fn common_target_type_for_type_list(types: impl Iterator<Item = Type>) -> Ty
                         .into()
                     }),
                     (Type::Color, Type::Brush) | (Type::Brush, Type::Color) => Type::Brush,
+                    (Type::Int64, Type::Int32 | Type::Float32)
+                    | (Type::Int32 | Type::Float32, Type::Int64) => Type::Int64,
+                    (Type::Optional(lhs), Type::Optional(rhs)) => {
+                        Type::Optional(if *lhs == Type::Void {
+                            rhs
+                        } else if *rhs == Type::Void {
+                            lhs
+                        } else {
+                            Self::common_target_type_for_type_list(
+                                [(*lhs).clone(), (*rhs).clone()].into_iter(),
+                            )
+                            .into()
+                        })
+                    }
+                    // A value and `null` or another optional
+                    (Type::Optional(optional), ty) | (ty, Type::Optional(optional)) => {
+                        Type::Optional(if *optional == Type::Void {
+                            ty.into()
+                        } else {
+                            Self::common_target_type_for_type_list(
+                                [(*optional).clone(), ty].into_iter(),
+                            )
+                            .into()
+                        })
+                    }
                     (target_type, expr_ty) => {
                         if expr_ty.can_convert(&target_type) {
                             target_type
@@ -1572,6 +2123,9 @@ fn maybe_lookup_object(
                     LookupResult::Expression { expression, .. } => {
                         let ty_descr = match expression.ty() {
                             Type::Struct { .. } => String::new(),
+                            Type::Optional(inner) if matches!(*inner, Type::Struct(_)) => {
+                                format!(" of {inner}?. Use '?.' to access the fields of an optional struct")
+                            }
                             ty => format!(" of {ty}"),
                         };
                         ctx.diag.push_error(
@@ -1588,12 +2142,10 @@ fn maybe_lookup_object(
 }
 
 /// Go through all the two way binding and resolve them first
-fn resolve_two_way_bindings(
-    doc: &Document,
-    type_register: &TypeRegister,
-    diag: &mut BuildDiagnostics,
-) {
+fn resolve_two_way_bindings(doc: &Document, diag: &mut BuildDiagnostics) {
     for component in doc.inner_components.iter() {
+        let type_register =
+            component.generic_type_register.as_deref().unwrap_or(&doc.local_registry);
         recurse_elem_with_scope(
             &component.root_element,
             ComponentScope(vec![]),
@@ -1614,6 +2166,7 @@ fn resolve_two_way_bindings(
                                 component_scope: &scope.0,
                                 diag,
                                 arguments: vec![],
+                                local_variables: vec![],
                                 type_register,
                                 type_loader: None,
                                 current_token: Some(node.clone().into()),
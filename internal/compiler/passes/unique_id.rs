@@ -28,7 +28,13 @@ fn assign_unique_id_in_component(component: &Rc<Component>, count: &mut u32) {
         let old_id = if !elem_mut.id.is_empty() {
             elem_mut.id.clone()
         } else {
-            elem_mut.base_type.to_smolstr().to_ascii_lowercase().into()
+            // The name of an instance of a generic component contains its type arguments
+            elem_mut
+                .base_type
+                .to_smolstr()
+                .chars()
+                .map(|c| if c.is_alphanumeric() || c == '-' { c.to_ascii_lowercase() } else { '_' })
+                .collect()
         };
         elem_mut.id = format_smolstr!("{}-{}", old_id, count);
 
@@ -370,6 +370,7 @@ pub(crate) fn create_component(
                 private_properties: RefCell::new(component.private_properties.borrow().clone()),
                 root_constraints,
                 root_element,
+                generic_type_register: component.generic_type_register.clone(),
             }
         });
         self.keep_alive.push((component.clone(), result.clone()));
@@ -524,6 +525,7 @@ fn snapshot_element(
                     is_alias: v.is_alias.as_ref().map(|a| a.snapshot(self)),
                     visibility: v.visibility,
                     pure: v.pure,
+                    is_async: v.is_async,
                 };
                 (k.clone(), decl)
             })
@@ -719,6 +721,46 @@ fn snapshot_expression(
                 array: Box::new(self.snapshot_expression(array)),
                 index: Box::new(self.snapshot_expression(index)),
             },
+            Expression::ArrayMap { array, item, body } => Expression::ArrayMap {
+                array: Box::new(self.snapshot_expression(array)),
+                item: item.clone(),
+                body: Box::new(self.snapshot_expression(body)),
+            },
+            Expression::ArrayFilter { array, item, condition } => Expression::ArrayFilter {
+                array: Box::new(self.snapshot_expression(array)),
+                item: item.clone(),
+                condition: Box::new(self.snapshot_expression(condition)),
+            },
+            Expression::ArraySlice { array, start, end } => Expression::ArraySlice {
+                array: Box::new(self.snapshot_expression(array)),
+                start: Box::new(self.snapshot_expression(start)),
+                end: Box::new(self.snapshot_expression(end)),
+            },
+            Expression::ArrayConcat { lhs, rhs } => Expression::ArrayConcat {
+                lhs: Box::new(self.snapshot_expression(lhs)),
+                rhs: Box::new(self.snapshot_expression(rhs)),
+            },
+            Expression::ArrayIndexOf { array, value } => Expression::ArrayIndexOf {
+                array: Box::new(self.snapshot_expression(array)),
+                value: Box::new(self.snapshot_expression(value)),
+            },
+            Expression::MapIndex { map, key } => Expression::MapIndex {
+                map: Box::new(self.snapshot_expression(map)),
+                key: Box::new(self.snapshot_expression(key)),
+            },
+            Expression::MapHasKey { map, key } => Expression::MapHasKey {
+                map: Box::new(self.snapshot_expression(map)),
+                key: Box::new(self.snapshot_expression(key)),
+            },
+            Expression::MapEntries(map) => {
+                Expression::MapEntries(Box::new(self.snapshot_expression(map)))
+            }
+            Expression::OptionalHasValue(optional) => {
+                Expression::OptionalHasValue(Box::new(self.snapshot_expression(optional)))
+            }
+            Expression::OptionalUnwrap(optional) => {
+                Expression::OptionalUnwrap(Box::new(self.snapshot_expression(optional)))
+            }
             Expression::Cast { from, to } => {
                 Expression::Cast { from: Box::new(self.snapshot_expression(from)), to: to.clone() }
             }
@@ -766,6 +808,13 @@ fn snapshot_expression(
                     .map(|(k, v)| (k.clone(), self.snapshot_expression(v)))
                     .collect(),
             },
+            Expression::Map { value_ty, values } => Expression::Map {
+                value_ty: value_ty.clone(),
+                values: values
+                    .iter()
+                    .map(|(k, v)| (k.clone(), self.snapshot_expression(v)))
+                    .collect(),
+            },
             Expression::PathData(path) => Expression::PathData(match path {
                 expression_tree::Path::Elements(path_elements) => expression_tree::Path::Elements(
                     path_elements
@@ -1441,6 +1490,14 @@ fn register_imported_types(
             let imported_type = match imported_type {
                 Some(ty) => ty,
                 None => {
+                    if let Some(generic) =
+                        doc.exports.find_generic_component(&import_name.external_name)
+                    {
+                        registry_to_populate
+                            .borrow_mut()
+                            .add_generic_component_with_name(import_name.internal_name, generic);
+                        continue;
+                    }
                     build_diagnostics.push_error(
                         format!(
                             "No exported type called '{}' found in \"{}\"",
@@ -13,7 +13,7 @@
     BuiltinElement, BuiltinPropertyDefault, BuiltinPropertyInfo, ElementType, Enumeration,
     Function, PropertyLookupResult, Struct, Type,
 };
-use crate::object_tree::{Component, PropertyVisibility};
+use crate::object_tree::{Component, GenericComponent, PropertyVisibility};
 use crate::typeloader;
 
 pub const RESERVED_GEOMETRY_PROPERTIES: &[(&str, Type)] = &[
@@ -334,6 +334,8 @@ pub struct TypeRegister {
     /// Map from a context restricted type to the list of contexts (parent type) it is allowed in. This is
     /// used to construct helpful error messages, such as "Row can only be within a GridLayout element".
     context_restricted_types: HashMap<SmolStr, HashSet<SmolStr>>,
+    /// The set of components with type parameters
+    generic_components: HashMap<SmolStr, Rc<GenericComponent>>,
     parent_registry: Option<Rc<RefCell<TypeRegister>>>,
     /// If the lookup function should return types that are marked as internal
     pub(crate) expose_internal_types: bool,
@@ -353,6 +355,11 @@ pub(crate) fn snapshot(&self, snapshotter: &mut typeloader::Snapshotter) -> Self
                 .snapshot_element_type(&self.property_animation_type),
             empty_type: snapshotter.snapshot_element_type(&self.empty_type),
             context_restricted_types: self.context_restricted_types.clone(),
+            generic_components: self
+                .generic_components
+                .iter()
+                .map(|(k, v)| (k.clone(), v.snapshot()))
+                .collect(),
             parent_registry: self
                 .parent_registry
                 .as_ref()
@@ -379,6 +386,7 @@ fn builtin_internal() -> Self {
 
         register.insert_type(Type::Float32);
         register.insert_type(Type::Int32);
+        register.insert_type(Type::Int64);
         register.insert_type(Type::String);
         register.insert_type(Type::PhysicalLength);
         register.insert_type(Type::LogicalLength);
@@ -647,7 +655,67 @@ pub fn add(&mut self, comp: Rc<Component>) -> bool {
     ///
     /// Returns false if there was already an element with the same name
     pub fn add_with_name(&mut self, name: SmolStr, comp: Rc<Component>) -> bool {
-        self.elements.insert(name, ElementType::Component(comp)).is_none()
+        let replaced_generic = self.generic_components.remove(&name).is_some();
+        self.elements.insert(name, ElementType::Component(comp)).is_none() && !replaced_generic
+    }
+
+    /// Add the generic component with it's defined name
+    ///
+    /// Returns false if there was already an element with the same name
+    pub fn add_generic_component(&mut self, generic: Rc<GenericComponent>) -> bool {
+        self.add_generic_component_with_name(generic.name.clone(), generic)
+    }
+
+    /// Add the generic component with a specified name
+    ///
+    /// Returns false if there was already an element with the same name
+    pub fn add_generic_component_with_name(
+        &mut self,
+        name: SmolStr,
+        generic: Rc<GenericComponent>,
+    ) -> bool {
+        let replaced_element = self.elements.remove(&name).is_some();
+        self.generic_components.insert(name, generic).is_none() && !replaced_element
+    }
+
+    /// Return the generic component with that name, unless the name refers to an element
+    pub fn lookup_generic_component(&self, name: &str) -> Option<Rc<GenericComponent>> {
+        if let Some(generic) = self.generic_components.get(name) {
+            return Some(generic.clone());
+        }
+        if self.elements.contains_key(name) {
+            return None;
+        }
+        self.parent_registry.as_ref().and_then(|r| r.borrow().lookup_generic_component(name))
+    }
+
+    /// Take the components that were instantiated from the generic components visible in this
+    /// register and that were not yet added to a document
+    pub(crate) fn take_generic_instances(&self) -> Vec<Rc<Component>> {
+        let mut instances = self
+            .parent_registry
+            .as_ref()
+            .map(|r| r.borrow().take_generic_instances())
+            .unwrap_or_default();
+        for generic in self.generic_components.values() {
+            instances.extend(generic.take_new_instances());
+        }
+        instances
+    }
+
+    /// A copy of this register which is not affected by the types added to it afterwards
+    pub(crate) fn shallow_copy(&self) -> Self {
+        Self {
+            types: self.types.clone(),
+            elements: self.elements.clone(),
+            supported_property_animation_types: self.supported_property_animation_types.clone(),
+            property_animation_type: self.property_animation_type.clone(),
+            empty_type: self.empty_type.clone(),
+            context_restricted_types: self.context_restricted_types.clone(),
+            generic_components: self.generic_components.clone(),
+            parent_registry: self.parent_registry.clone(),
+            expose_internal_types: self.expose_internal_types,
+        }
     }
 
     pub fn add_builtin(&mut self, builtin: Rc<BuiltinElement>) {
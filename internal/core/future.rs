@@ -146,3 +146,37 @@ pub(crate) fn spawn_local_with_ctx<F: Future + 'static>(
     arc.wake_by_ref();
     Ok(JoinHandle(arc))
 }
+
+/// Runs the futures returned by the handler of an `async callback`.
+///
+/// Only the last call counts: starting a new call aborts the future of the previous one.
+#[derive(Default)]
+pub struct AsyncCallbackRunner(core::cell::Cell<Option<JoinHandle<()>>>);
+
+impl AsyncCallbackRunner {
+    /// Spawns the future in the event loop, after aborting the one of the previous call.
+    ///
+    /// `update` is called with `None` once the future is spawned, and with its output when it completes.
+    pub fn spawn<T: 'static>(
+        &self,
+        fut: impl Future<Output = T> + 'static,
+        update: impl Fn(Option<T>) + 'static,
+    ) {
+        if let Some(previous) = self.0.take() {
+            previous.abort();
+        }
+        let Some(ctx) = crate::context::GLOBAL_CONTEXT.with(|ctx| ctx.get().cloned()) else {
+            crate::debug_log!("Cannot run an async callback without a platform");
+            return;
+        };
+        let update = alloc::rc::Rc::new(update);
+        let update_on_completion = update.clone();
+        match spawn_local_with_ctx(&ctx, async move { update_on_completion(Some(fut.await)) }) {
+            Ok(handle) => {
+                update(None);
+                self.0.set(Some(handle));
+            }
+            Err(err) => crate::debug_log!("Cannot run an async callback: {err}"),
+        }
+    }
+}
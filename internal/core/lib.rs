@@ -1,7 +1,7 @@
 // Copyright © SixtyFPS GmbH <info@slint.dev>
 // SPDX-License-Identifier: GPL-3.0-only OR LicenseRef-Slint-Royalty-free-2.0 OR LicenseRef-Slint-Software-3.0
 
-// cSpell: ignore sharedvector textlayout
+// cSpell: ignore sharedmap sharedvector textlayout
 
 #![doc = include_str!("README.md")]
 #![doc(html_logo_url = "https://slint.dev/logo/slint-logo-square-light.svg")]
@@ -46,6 +46,7 @@
 pub mod renderer;
 #[cfg(feature = "rtti")]
 pub mod rtti;
+pub mod sharedmap;
 pub mod sharedvector;
 pub mod slice;
 #[cfg(feature = "software-renderer")]
@@ -60,6 +61,9 @@
 #[doc(inline)]
 pub use string::SharedString;
 
+#[doc(inline)]
+pub use sharedmap::SharedMap;
+
 #[doc(inline)]
 pub use sharedvector::SharedVector;
 
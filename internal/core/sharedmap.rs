@@ -0,0 +1,180 @@
+// Copyright © SixtyFPS GmbH <info@slint.dev>
+// SPDX-License-Identifier: GPL-3.0-only OR LicenseRef-Slint-Royalty-free-2.0 OR LicenseRef-Slint-Software-3.0
+
+//! module for the SharedMap
+#![warn(missing_docs)]
+
+use crate::{SharedString, SharedVector};
+use alloc::vec::Vec;
+use core::fmt::Debug;
+
+/// A map from string keys to values, used for the `[string: T]` type of the Slint language.
+///
+/// Like [`SharedVector`], it is cheap to clone: the entries are reference counted and only
+/// copied when a shared map is modified. The entries are sorted by key.
+#[derive(Clone, PartialEq, Eq)]
+pub struct SharedMap<T> {
+    entries: SharedVector<(SharedString, T)>,
+}
+
+impl<T> Default for SharedMap<T> {
+    fn default() -> Self {
+        Self { entries: Default::default() }
+    }
+}
+
+impl<T> SharedMap<T> {
+    /// Create an empty map
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The number of entries in the map
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns true if the map has no entries
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    fn position(&self, key: &str) -> Result<usize, usize> {
+        self.entries.binary_search_by(|(k, _)| k.as_str().cmp(key))
+    }
+
+    /// Returns the value for the key, if any
+    pub fn get(&self, key: &str) -> Option<&T> {
+        self.position(key).ok().map(|i| &self.entries[i].1)
+    }
+
+    /// Returns true if the map has an entry for the key
+    pub fn contains_key(&self, key: &str) -> bool {
+        self.position(key).is_ok()
+    }
+
+    /// Iterates over the entries, sorted by key
+    pub fn iter(&self) -> impl Iterator<Item = (&SharedString, &T)> {
+        self.entries.iter().map(|(k, v)| (k, v))
+    }
+
+    /// Iterates over the keys, in order
+    pub fn keys(&self) -> impl Iterator<Item = &SharedString> {
+        self.entries.iter().map(|(k, _)| k)
+    }
+
+    /// Iterates over the values, sorted by key
+    pub fn values(&self) -> impl Iterator<Item = &T> {
+        self.entries.iter().map(|(_, v)| v)
+    }
+}
+
+impl<T: Clone> SharedMap<T> {
+    /// Set the value for the key, and returns the previous value, if any
+    pub fn insert(&mut self, key: impl Into<SharedString>, value: T) -> Option<T> {
+        let key = key.into();
+        match self.position(&key) {
+            Ok(i) => Some(core::mem::replace(&mut self.entries.make_mut_slice()[i].1, value)),
+            Err(i) => {
+                let mut entries = self.entries.iter().cloned().collect::<Vec<_>>();
+                entries.insert(i, (key, value));
+                self.entries = entries.into_iter().collect();
+                None
+            }
+        }
+    }
+
+    /// Remove the entry for the key, and returns its value, if any
+    pub fn remove(&mut self, key: &str) -> Option<T> {
+        let i = self.position(key).ok()?;
+        let mut entries = self.entries.iter().cloned().collect::<Vec<_>>();
+        let (_, value) = entries.remove(i);
+        self.entries = entries.into_iter().collect();
+        Some(value)
+    }
+}
+
+impl<K: Into<SharedString>, T> FromIterator<(K, T)> for SharedMap<T> {
+    /// Collect the entries into a map. If a key is there several times, the last value is kept.
+    fn from_iter<I: IntoIterator<Item = (K, T)>>(iter: I) -> Self {
+        let mut entries = iter.into_iter().map(|(k, v)| (k.into(), v)).collect::<Vec<_>>();
+        // The sort is stable, so the last value of a key is the last of its run
+        entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+        let mut deduplicated: Vec<(SharedString, T)> = Vec::with_capacity(entries.len());
+        for entry in entries {
+            match deduplicated.last_mut() {
+                Some(last) if last.0 == entry.0 => *last = entry,
+                _ => deduplicated.push(entry),
+            }
+        }
+        Self { entries: deduplicated.into_iter().collect() }
+    }
+}
+
+impl<K: Into<SharedString>, T, const N: usize> From<[(K, T); N]> for SharedMap<T> {
+    fn from(entries: [(K, T); N]) -> Self {
+        entries.into_iter().collect()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a SharedMap<T> {
+    type Item = (&'a SharedString, &'a T);
+    type IntoIter = core::iter::Map<
+        core::slice::Iter<'a, (SharedString, T)>,
+        fn(&'a (SharedString, T)) -> (&'a SharedString, &'a T),
+    >;
+    fn into_iter(self) -> Self::IntoIter {
+        self.entries.iter().map(|(k, v)| (k, v))
+    }
+}
+
+impl<T: Debug> Debug for SharedMap<T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<T: serde::Serialize> serde::Serialize for SharedMap<T> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.collect_map(self.iter())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, T: serde::Deserialize<'de>> serde::Deserialize<'de> for SharedMap<T> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let entries: alloc::collections::BTreeMap<SharedString, T> =
+            serde::Deserialize::deserialize(deserializer)?;
+        Ok(entries.into_iter().collect())
+    }
+}
+
+#[test]
+fn simple_test() {
+    let mut map = SharedMap::from([("b", 2), ("a", 1), ("c", 3), ("a", 4)]);
+    assert_eq!(map.len(), 3);
+    assert_eq!(map.get("a"), Some(&4));
+    assert_eq!(map.get("d"), None);
+    assert!(map.contains_key("c"));
+    assert_eq!(map.keys().map(|k| k.as_str()).collect::<Vec<_>>(), ["a", "b", "c"]);
+
+    let copy = map.clone();
+    assert_eq!(map.insert("d", 5), None);
+    assert_eq!(map.insert("b", 6), Some(2));
+    assert_eq!(map.remove("a"), Some(4));
+    assert_eq!(map.remove("a"), None);
+    assert_eq!(
+        map.iter().map(|(k, v)| (k.as_str(), *v)).collect::<Vec<_>>(),
+        [("b", 6), ("c", 3), ("d", 5)]
+    );
+    // The copy was not modified
+    assert_eq!(copy.values().copied().collect::<Vec<_>>(), [4, 2, 3]);
+    assert_ne!(copy, map);
+}
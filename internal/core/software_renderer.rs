@@ -7,10 +7,13 @@
 
 #![warn(missing_docs)]
 
+mod box_shadow;
 mod draw_functions;
 mod fixed;
 mod fonts;
 mod minimal_software_window;
+#[cfg(feature = "std")]
+mod path;
 mod scene;
 
 use self::fonts::GlyphRenderer;
@@ -23,10 +26,10 @@
     CachedRenderingData, DirtyRegion, PartialRenderingState, RenderBorderRectangle, RenderImage,
     RenderRectangle,
 };
-use crate::items::{ItemRc, TextOverflow, TextWrap};
+use crate::items::{ImageRendering, ItemRc, TextOverflow, TextWrap};
 use crate::lengths::{
-    LogicalBorderRadius, LogicalLength, LogicalPoint, LogicalRect, LogicalSize, LogicalVector,
-    PhysicalPx, PointLengths, RectLengths, ScaleFactor, SizeLengths,
+    LogicalBorderRadius, LogicalLength, LogicalPoint, LogicalPx, LogicalRect, LogicalSize,
+    LogicalVector, PhysicalPx, PointLengths, RectLengths, ScaleFactor, SizeLengths,
 };
 use crate::renderer::RendererSealed;
 use crate::textlayout::{AbstractFont, FontMetrics, TextParagraphLayout};
@@ -142,6 +145,63 @@ fn transformed(self, info: RotationInfo) -> Self {
     }
 }
 
+impl Transform for euclid::Transform2D<f32, PhysicalPx, PhysicalPx> {
+    fn transformed(self, info: RotationInfo) -> Self {
+        // These are continuous coordinates, so unlike pixel positions, mirroring doesn't subtract 1
+        let (sx, tx) = if info.orientation.mirror_width() {
+            (-1., info.screen_size.width as f32)
+        } else {
+            (1., 0.)
+        };
+        let (sy, ty) = if info.orientation.mirror_height() {
+            (-1., info.screen_size.height as f32)
+        } else {
+            (1., 0.)
+        };
+        let rotation = if info.orientation.is_transpose() {
+            Self::new(0., sx, sy, 0., ty, tx)
+        } else {
+            Self::new(sx, 0., 0., sy, tx, ty)
+        };
+        self.then(&rotation)
+    }
+}
+
+#[test]
+fn transformed_layer_inverse_transform() {
+    let screen_size = PhysicalSize::new(10, 8);
+    // A layer of 4x3 pixels, rotated by 90° and moved to (5, 1), so that the center of each
+    // pixel of the layer maps to the center of a pixel of the screen.
+    let layer_to_screen =
+        euclid::Transform2D::<f32, PhysicalPx, PhysicalPx>::rotation(euclid::Angle::degrees(90.))
+            .then_translate(euclid::vec2(5., 1.));
+    // The same layer, rendered with half of the resolution (as done for big layers)
+    let low_res_layer_to_screen = euclid::Transform2D::scale(2., 2.).then(&layer_to_screen);
+
+    for orientation in [
+        RenderingRotation::NoRotation,
+        RenderingRotation::Rotate90,
+        RenderingRotation::Rotate180,
+        RenderingRotation::Rotate270,
+    ] {
+        let info = RotationInfo { orientation, screen_size };
+        let inverse = layer_to_screen.transformed(info).inverse().unwrap();
+        let low_res_inverse = low_res_layer_to_screen.transformed(info).inverse().unwrap();
+        for (x, y) in [(0, 0), (3, 0), (0, 2), (3, 2), (1, 1)] {
+            let layer_point = euclid::point2::<f32, PhysicalPx>(x as f32 + 0.5, y as f32 + 0.5);
+            let screen_pixel = layer_to_screen.transform_point(layer_point).floor().cast::<i16>();
+            assert_eq!(screen_pixel, euclid::point2(4 - y, x + 1));
+            // The pixel of the buffer that the renderer samples for this screen pixel
+            let buffer_pixel = screen_pixel.transformed(info);
+            let sample = euclid::point2(buffer_pixel.x as f32 + 0.5, buffer_pixel.y as f32 + 0.5);
+            let found = inverse.transform_point(sample);
+            assert!((found - layer_point).length() < 0.001, "{orientation:?}: {found:?}");
+            let found = low_res_inverse.transform_point(sample);
+            assert!((found - layer_point / 2.).length() < 0.001, "{orientation:?}: {found:?}");
+        }
+    }
+}
+
 impl<T: Copy> Transform for BorderRadius<T, PhysicalPx> {
     fn transformed(self, info: RotationInfo) -> Self {
         match info.orientation {
@@ -407,6 +467,8 @@ pub struct SoftwareRenderer {
     maybe_window_adapter: RefCell<Option<Weak<dyn crate::window::WindowAdapter>>>,
     rotation: Cell<RenderingRotation>,
     rendering_metrics_collector: Option<Rc<RenderingMetricsCollector>>,
+    box_shadow_cache: box_shadow::BoxShadowCache,
+    layer_cache: LayerCache,
 }
 
 impl Default for SoftwareRenderer {
@@ -418,6 +480,8 @@ fn default() -> Self {
             rotation: Default::default(),
             rendering_metrics_collector: RenderingMetricsCollector::new("software"),
             repaint_buffer_type: Default::default(),
+            box_shadow_cache: Default::default(),
+            layer_cache: Default::default(),
         }
     }
 }
@@ -542,8 +606,17 @@ fn render_buffer_impl(
             size,
             factor,
             window_inner,
-            RenderToBuffer { buffer, dirty_range_cache: vec![], dirty_region: Default::default() },
+            RenderToBuffer {
+                buffer,
+                dirty_range_cache: vec![],
+                dirty_region: Default::default(),
+                rounded_clips: vec![],
+                current_rounded_clip: None,
+                layer_cache: &self.layer_cache,
+            },
             rotation,
+            &self.box_shadow_cache,
+            &self.layer_cache,
         );
         let mut renderer = self.partial_rendering_state.create_partial_renderer(buffer_renderer);
         let window_adapter = renderer.window_adapter.clone();
@@ -864,6 +937,8 @@ fn default_font_size(&self) -> LogicalLength {
     fn set_window_adapter(&self, window_adapter: &Rc<dyn WindowAdapter>) {
         *self.maybe_window_adapter.borrow_mut() = Some(Rc::downgrade(window_adapter));
         self.partial_rendering_state.clear_cache();
+        self.box_shadow_cache.clear();
+        self.layer_cache.clear();
     }
 
     fn take_snapshot(&self) -> Result<SharedPixelBuffer<Rgba8Pixel>, PlatformError> {
@@ -947,16 +1022,30 @@ fn render_window_frame_by_line(
                         let range_buffer =
                             &mut line_buffer[(begin - offset) as usize..(end - offset) as usize];
 
+                        let geometry = PhysicalRect { origin: span.pos, size: span.size };
+                        let clips = &scene.vectors.rounded_clips;
+                        let line = scene.current_line;
                         match span.command {
                             SceneCommand::Rectangle { color } => {
-                                TargetPixel::blend_slice(range_buffer, color);
+                                draw_functions::draw_clipped_line(
+                                    &geometry,
+                                    line,
+                                    &color,
+                                    clips,
+                                    span.rounded_clip,
+                                    range_buffer,
+                                    extra_left_clip,
+                                    extra_right_clip,
+                                );
                             }
                             SceneCommand::Texture { texture_index } => {
                                 let texture = &scene.vectors.textures[texture_index as usize];
-                                draw_functions::draw_texture_line(
-                                    &PhysicalRect { origin: span.pos, size: span.size },
-                                    scene.current_line,
+                                draw_functions::draw_clipped_line(
+                                    &geometry,
+                                    line,
                                     texture,
+                                    clips,
+                                    span.rounded_clip,
                                     range_buffer,
                                     extra_left_clip,
                                     extra_right_clip,
@@ -966,10 +1055,12 @@ fn render_window_frame_by_line(
                                 let texture = scene.vectors.shared_buffers
                                     [shared_buffer_index as usize]
                                     .as_texture();
-                                draw_functions::draw_texture_line(
-                                    &PhysicalRect { origin: span.pos, size: span.size },
-                                    scene.current_line,
+                                draw_functions::draw_clipped_line(
+                                    &geometry,
+                                    line,
                                     &texture,
+                                    clips,
+                                    span.rounded_clip,
                                     range_buffer,
                                     extra_left_clip,
                                     extra_right_clip,
@@ -978,10 +1069,12 @@ fn render_window_frame_by_line(
                             SceneCommand::RoundedRectangle { rectangle_index } => {
                                 let rr =
                                     &scene.vectors.rounded_rectangles[rectangle_index as usize];
-                                draw_functions::draw_rounded_rectangle_line(
-                                    &PhysicalRect { origin: span.pos, size: span.size },
-                                    scene.current_line,
+                                draw_functions::draw_clipped_line(
+                                    &geometry,
+                                    line,
                                     rr,
+                                    clips,
+                                    span.rounded_clip,
                                     range_buffer,
                                     extra_left_clip,
                                     extra_right_clip,
@@ -989,13 +1082,29 @@ fn render_window_frame_by_line(
                             }
                             SceneCommand::Gradient { gradient_index } => {
                                 let g = &scene.vectors.gradients[gradient_index as usize];
-
-                                draw_functions::draw_gradient_line(
-                                    &PhysicalRect { origin: span.pos, size: span.size },
-                                    scene.current_line,
+                                draw_functions::draw_clipped_line(
+                                    &geometry,
+                                    line,
                                     g,
+                                    clips,
+                                    span.rounded_clip,
                                     range_buffer,
                                     extra_left_clip,
+                                    extra_right_clip,
+                                );
+                            }
+                            SceneCommand::TransformedTexture { texture_index } => {
+                                let texture =
+                                    &scene.vectors.transformed_textures[texture_index as usize];
+                                draw_functions::draw_clipped_line(
+                                    &geometry,
+                                    line,
+                                    texture,
+                                    clips,
+                                    span.rounded_clip,
+                                    range_buffer,
+                                    extra_left_clip,
+                                    extra_right_clip,
                                 );
                             }
                         }
@@ -1008,6 +1117,9 @@ fn render_window_frame_by_line(
             scene.next_line();
         }
     }
+    for texture in scene.vectors.transformed_textures.drain(..) {
+        renderer.layer_cache.release(texture.data);
+    }
     scene.dirty_region
 }
 
@@ -1023,6 +1135,8 @@ fn prepare_scene(
         window,
         PrepareScene::default(),
         software_renderer.rotation.get(),
+        &software_renderer.box_shadow_cache,
+        &software_renderer.layer_cache,
     );
     let mut renderer =
         software_renderer.partial_rendering_state.create_partial_renderer(prepare_scene);
@@ -1114,12 +1228,24 @@ trait ProcessScene {
     fn process_rounded_rectangle(&mut self, geometry: PhysicalRect, data: RoundedRectangle);
     fn process_shared_image_buffer(&mut self, geometry: PhysicalRect, buffer: SharedBufferCommand);
     fn process_gradient(&mut self, geometry: PhysicalRect, gradient: GradientCommand);
+    fn process_transformed_texture(
+        &mut self,
+        geometry: PhysicalRect,
+        texture: TransformedTextureCommand,
+    );
+    /// Register a rounded clip and returns its index
+    fn add_rounded_clip(&mut self, clip: RoundedClip) -> u16;
+    /// Set the rounded clip (as returned by add_rounded_clip) that applies to the next commands
+    fn set_rounded_clip(&mut self, rounded_clip: Option<u16>);
 }
 
 struct RenderToBuffer<'a, TargetPixelBuffer> {
     buffer: &'a mut TargetPixelBuffer,
     dirty_range_cache: Vec<core::ops::Range<i16>>,
     dirty_region: PhysicalRegion,
+    rounded_clips: Vec<RoundedClip>,
+    current_rounded_clip: Option<u16>,
+    layer_cache: &'a LayerCache,
 }
 
 impl<B: target_pixel_buffer::TargetPixelBuffer> RenderToBuffer<'_, B> {
@@ -1141,6 +1267,25 @@ fn foreach_ranges(
         });
     }
 
+    /// Draw the command line by line, masked by the current rounded clip
+    fn draw_lines(&mut self, geometry: &PhysicalRect, command: &impl draw_functions::DrawLine) {
+        let rounded_clip = self.current_rounded_clip;
+        let clips = core::mem::take(&mut self.rounded_clips);
+        self.foreach_ranges(geometry, |line, buffer, extra_left_clip, extra_right_clip| {
+            draw_functions::draw_clipped_line(
+                geometry,
+                PhysicalLength::new(line),
+                command,
+                &clips,
+                rounded_clip,
+                buffer,
+                extra_left_clip,
+                extra_right_clip,
+            );
+        });
+        self.rounded_clips = clips;
+    }
+
     fn foreach_region(
         &mut self,
         geometry: &PhysicalRect,
@@ -1178,7 +1323,9 @@ fn foreach_region(
     }
 
     fn process_texture_impl(&mut self, geometry: PhysicalRect, texture: SceneTexture<'_>) {
-        if !self.buffer.draw_texture(
+        if self.current_rounded_clip.is_some() {
+            self.draw_lines(&geometry, &texture);
+        } else if !self.buffer.draw_texture(
             geometry.origin.x,
             geometry.origin.y,
             geometry.size.width,
@@ -1222,7 +1369,11 @@ fn process_rectangle_impl(
         color: PremultipliedRgbaColor,
         composition_mode: CompositionMode,
     ) {
-        if !self.buffer.fill_rectangle(
+        if self.current_rounded_clip.is_some()
+            && matches!(composition_mode, CompositionMode::SourceOver)
+        {
+            self.draw_lines(&geometry, &color);
+        } else if !self.buffer.fill_rectangle(
             geometry.origin.x,
             geometry.origin.y,
             geometry.size.width,
@@ -1274,28 +1425,29 @@ fn process_rectangle(&mut self, geometry: PhysicalRect, color: PremultipliedRgba
     }
 
     fn process_rounded_rectangle(&mut self, geometry: PhysicalRect, rr: RoundedRectangle) {
-        self.foreach_ranges(&geometry, |line, buffer, extra_left_clip, extra_right_clip| {
-            draw_functions::draw_rounded_rectangle_line(
-                &geometry,
-                PhysicalLength::new(line),
-                &rr,
-                buffer,
-                extra_left_clip,
-                extra_right_clip,
-            );
-        });
+        self.draw_lines(&geometry, &rr);
     }
 
     fn process_gradient(&mut self, geometry: PhysicalRect, g: GradientCommand) {
-        self.foreach_ranges(&geometry, |line, buffer, extra_left_clip, _extra_right_clip| {
-            draw_functions::draw_gradient_line(
-                &geometry,
-                PhysicalLength::new(line),
-                &g,
-                buffer,
-                extra_left_clip,
-            );
-        });
+        self.draw_lines(&geometry, &g);
+    }
+
+    fn process_transformed_texture(
+        &mut self,
+        geometry: PhysicalRect,
+        texture: TransformedTextureCommand,
+    ) {
+        self.draw_lines(&geometry, &texture);
+        self.layer_cache.release(texture.data);
+    }
+
+    fn add_rounded_clip(&mut self, clip: RoundedClip) -> u16 {
+        self.rounded_clips.push(clip);
+        (self.rounded_clips.len() - 1) as u16
+    }
+
+    fn set_rounded_clip(&mut self, rounded_clip: Option<u16>) {
+        self.current_rounded_clip = rounded_clip;
     }
 }
 
@@ -1303,9 +1455,19 @@ fn process_gradient(&mut self, geometry: PhysicalRect, g: GradientCommand) {
 struct PrepareScene {
     items: Vec<SceneItem>,
     vectors: SceneVectors,
+    current_rounded_clip: Option<u16>,
 }
 
 impl ProcessScene for PrepareScene {
+    fn add_rounded_clip(&mut self, clip: RoundedClip) -> u16 {
+        self.vectors.rounded_clips.push(clip);
+        (self.vectors.rounded_clips.len() - 1) as u16
+    }
+
+    fn set_rounded_clip(&mut self, rounded_clip: Option<u16>) {
+        self.current_rounded_clip = rounded_clip;
+    }
+
     fn process_texture(&mut self, geometry: PhysicalRect, texture: SceneTexture<'static>) {
         let size = geometry.size;
         if !size.is_empty() {
@@ -1316,6 +1478,7 @@ fn process_texture(&mut self, geometry: PhysicalRect, texture: SceneTexture<'sta
                 size,
                 z: self.items.len() as u16,
                 command: SceneCommand::Texture { texture_index },
+                rounded_clip: self.current_rounded_clip,
             });
         }
     }
@@ -1330,6 +1493,7 @@ fn process_shared_image_buffer(&mut self, geometry: PhysicalRect, buffer: Shared
                 size,
                 z: self.items.len() as u16,
                 command: SceneCommand::SharedBuffer { shared_buffer_index },
+                rounded_clip: self.current_rounded_clip,
             });
         }
     }
@@ -1339,7 +1503,13 @@ fn process_rectangle(&mut self, geometry: PhysicalRect, color: PremultipliedRgba
         if !size.is_empty() {
             let z = self.items.len() as u16;
             let pos = geometry.origin;
-            self.items.push(SceneItem { pos, size, z, command: SceneCommand::Rectangle { color } });
+            self.items.push(SceneItem {
+                pos,
+                size,
+                z,
+                command: SceneCommand::Rectangle { color },
+                rounded_clip: self.current_rounded_clip,
+            });
         }
     }
 
@@ -1353,6 +1523,7 @@ fn process_rounded_rectangle(&mut self, geometry: PhysicalRect, data: RoundedRec
                 size,
                 z: self.items.len() as u16,
                 command: SceneCommand::RoundedRectangle { rectangle_index },
+                rounded_clip: self.current_rounded_clip,
             });
         }
     }
@@ -1367,11 +1538,71 @@ fn process_gradient(&mut self, geometry: PhysicalRect, gradient: GradientCommand
                 size,
                 z: self.items.len() as u16,
                 command: SceneCommand::Gradient { gradient_index },
+                rounded_clip: self.current_rounded_clip,
+            });
+        }
+    }
+
+    fn process_transformed_texture(
+        &mut self,
+        geometry: PhysicalRect,
+        texture: TransformedTextureCommand,
+    ) {
+        let size = geometry.size;
+        if !size.is_empty() {
+            let texture_index = self.vectors.transformed_textures.len() as u16;
+            self.vectors.transformed_textures.push(texture);
+            self.items.push(SceneItem {
+                pos: geometry.origin,
+                size,
+                z: self.items.len() as u16,
+                command: SceneCommand::TransformedTexture { texture_index },
+                rounded_clip: self.current_rounded_clip,
             });
         }
     }
 }
 
+/// The maximum amount of pixels in the layer of a transformed item (see
+/// [`SceneBuilder::draw_transformed`]). Bigger layers are rendered with a lower resolution and
+/// scaled up, so a layer never takes much more than 256 KiB.
+const MAX_LAYER_PIXELS: f32 = 256. * 256.;
+
+/// Pool of the pixel buffers of the layers of transformed items, so that they can be re-used
+/// across frames instead of being allocated for every frame.
+#[derive(Default)]
+struct LayerCache(RefCell<Vec<Vec<PremultipliedRgbaColor>>>);
+
+impl LayerCache {
+    /// The amount of buffers that are kept around
+    const MAX_ENTRIES: usize = 4;
+
+    /// Returns a buffer of `len` transparent pixels, re-using a buffer of the pool if possible.
+    fn take(&self, len: usize) -> Vec<PremultipliedRgbaColor> {
+        let mut cache = self.0.borrow_mut();
+        let mut data = match cache.iter().position(|data| data.capacity() >= len) {
+            Some(pos) => cache.swap_remove(pos),
+            None => cache.pop().unwrap_or_default(),
+        };
+        data.clear();
+        data.resize(len, PremultipliedRgbaColor::default());
+        data
+    }
+
+    /// Put back a buffer returned by [`Self::take`] into the pool
+    fn release(&self, data: Vec<PremultipliedRgbaColor>) {
+        let mut cache = self.0.borrow_mut();
+        if cache.len() < Self::MAX_ENTRIES {
+            cache.push(data);
+        }
+    }
+
+    /// Free all the buffers
+    fn clear(&self) {
+        self.0.borrow_mut().clear();
+    }
+}
+
 struct SceneBuilder<'a, T> {
     processor: T,
     state_stack: Vec<RenderState>,
@@ -1379,6 +1610,8 @@ struct SceneBuilder<'a, T> {
     scale_factor: ScaleFactor,
     window: &'a WindowInner,
     rotation: RotationInfo,
+    box_shadow_cache: &'a box_shadow::BoxShadowCache,
+    layer_cache: &'a LayerCache,
 }
 
 impl<'a, T: ProcessScene> SceneBuilder<'a, T> {
@@ -1388,21 +1621,27 @@ fn new(
         window: &'a WindowInner,
         processor: T,
         orientation: RenderingRotation,
+        box_shadow_cache: &'a box_shadow::BoxShadowCache,
+        layer_cache: &'a LayerCache,
     ) -> Self {
+        let clip =
+            LogicalRect::new(LogicalPoint::default(), (screen_size.cast() / scale_factor).cast());
         Self {
             processor,
             state_stack: vec![],
             current_state: RenderState {
                 alpha: 1.,
                 offset: LogicalPoint::default(),
-                clip: LogicalRect::new(
-                    LogicalPoint::default(),
-                    (screen_size.cast() / scale_factor).cast(),
-                ),
+                clip,
+                transform: None,
+                screen_clip: clip,
+                rounded_clip: None,
             },
             scale_factor,
             window,
             rotation: RotationInfo { orientation, screen_size },
+            box_shadow_cache,
+            layer_cache,
         }
     }
 
@@ -1748,6 +1987,137 @@ fn draw_text_paragraph<Font>(
             .ok();
     }
 
+    /// Render what `draw` draws within `rect` (in item coordinates) into a layer, and draw that layer
+    /// with the current state's transformation.
+    ///
+    /// The layer is a premultiplied RGBA buffer that stays alive until the scene is drawn, also
+    /// when rendering line by line. Its size is bounded by [`MAX_LAYER_PIXELS`], and its memory
+    /// is taken from the [`LayerCache`].
+    fn draw_transformed(
+        &mut self,
+        rect: LogicalRect,
+        bilinear: bool,
+        draw: impl FnOnce(
+            &mut SceneBuilder<'_, RenderToBuffer<'_, TargetPixelSlice<'_, PremultipliedRgbaColor>>>,
+        ),
+    ) {
+        let Some(transform) = self.current_state.transform else { return };
+        let Some(rect) = rect.intersection(&self.current_state.clip) else { return };
+        let mut layer_scale = self.scale_factor;
+        let mut layer_rect = (rect.cast::<f32>() * layer_scale).round_out();
+        if layer_rect.area() > MAX_LAYER_PIXELS {
+            layer_scale =
+                ScaleFactor::new(layer_scale.get() * (MAX_LAYER_PIXELS / layer_rect.area()).sqrt());
+            layer_rect = (rect.cast::<f32>() * layer_scale).round_out();
+        }
+        let layer_size = layer_rect.size.cast::<i16>();
+        if layer_size.is_empty() {
+            return;
+        }
+        // Item coordinate of the layer's top-left corner
+        let layer_origin = layer_rect.origin / layer_scale;
+
+        let mut data =
+            self.layer_cache.take(layer_size.width as usize * layer_size.height as usize);
+        {
+            let mut buffer =
+                TargetPixelSlice { data: &mut data, pixel_stride: layer_size.width as usize };
+            let full_layer = euclid::Box2D::from_size(layer_size);
+            let mut layer = SceneBuilder::new(
+                layer_size,
+                layer_scale,
+                self.window,
+                RenderToBuffer {
+                    buffer: &mut buffer,
+                    dirty_range_cache: vec![],
+                    dirty_region: PhysicalRegion {
+                        rectangles: core::array::from_fn(|i| {
+                            if i == 0 {
+                                full_layer
+                            } else {
+                                Default::default()
+                            }
+                        }),
+                        count: 1,
+                    },
+                    rounded_clips: vec![],
+                    current_rounded_clip: None,
+                    layer_cache: self.layer_cache,
+                },
+                RenderingRotation::NoRotation,
+                self.box_shadow_cache,
+                self.layer_cache,
+            );
+            layer.current_state.alpha = self.current_state.alpha;
+            layer.current_state.offset = (-layer_origin.to_vector()).to_point().cast();
+            layer.current_state.clip = rect;
+            draw(&mut layer);
+        }
+
+        // Maps the pixels of the layer to the physical pixels of the screen
+        let scale = self.scale_factor.get();
+        let layer_to_screen = euclid::Transform2D::<f32, PhysicalPx, LogicalPx>::scale(
+            1. / layer_scale.get(),
+            1. / layer_scale.get(),
+        )
+        .then_translate(layer_origin.to_vector() + self.current_state.offset.cast().to_vector())
+        .then(&transform)
+        .then_scale(scale, scale)
+        .with_destination::<PhysicalPx>();
+
+        let screen_clip = (self.current_state.screen_clip.cast() * self.scale_factor).round();
+        let target = layer_to_screen
+            .outer_transformed_rect(&euclid::Rect::from_size(layer_size.cast()))
+            .round_out()
+            .intersection(&screen_clip);
+        let inverse_transform = layer_to_screen.transformed(self.rotation).inverse();
+        let (Some(target), Some(inverse_transform)) = (target, inverse_transform) else {
+            self.layer_cache.release(data);
+            return;
+        };
+        self.processor.process_transformed_texture(
+            target.cast().transformed(self.rotation),
+            TransformedTextureCommand {
+                data,
+                width: layer_size.width as u16,
+                height: layer_size.height as u16,
+                inverse_transform,
+                bilinear,
+            },
+        );
+    }
+
+    /// Draw an alpha map (whose stride is the width of the geometry) with the given color
+    #[cfg(feature = "std")]
+    fn draw_alpha_map(
+        &mut self,
+        geometry: euclid::Rect<i32, PhysicalPx>,
+        alpha_map: Vec<u8>,
+        color: Color,
+    ) {
+        let geometry = geometry.cast::<i16>();
+        self.processor.process_shared_image_buffer(
+            geometry.transformed(self.rotation),
+            SharedBufferCommand {
+                buffer: SharedBufferData::AlphaMap {
+                    data: alpha_map.into(),
+                    width: geometry.width() as u16,
+                },
+                source_rect: PhysicalRect::from_size(geometry.size),
+                extra: SceneTextureExtra {
+                    colorize: color,
+                    // color already is mixed with global alpha
+                    alpha: color.alpha(),
+                    rotation: self.rotation.orientation,
+                    dx: Fixed::from_integer(1),
+                    dy: Fixed::from_integer(1),
+                    off_x: Fixed::from_integer(0),
+                    off_y: Fixed::from_integer(0),
+                },
+            },
+        );
+    }
+
     /// Returns the color, mixed with the current_state's alpha
     fn alpha_color(&self, color: Color) -> Color {
         if self.current_state.alpha < 1.0 {
@@ -1774,6 +2144,13 @@ struct RenderState {
     alpha: f32,
     offset: LogicalPoint,
     clip: LogicalRect,
+    /// Transformation from the untransformed window coordinates to the window coordinates,
+    /// if there is a rotation. In that case, `clip` is only an approximation and `screen_clip`
+    /// is the actual clip in window coordinates.
+    transform: Option<euclid::Transform2D<f32, LogicalPx, LogicalPx>>,
+    screen_clip: LogicalRect,
+    /// Index of the rounded clip registered in the processor, if any
+    rounded_clip: Option<u16>,
 }
 
 impl<T: ProcessScene> crate::item_rendering::ItemRenderer for SceneBuilder<'_, T> {
@@ -1781,11 +2158,16 @@ impl<T: ProcessScene> crate::item_rendering::ItemRenderer for SceneBuilder<'_, T
     fn draw_rectangle(
         &mut self,
         rect: Pin<&dyn RenderRectangle>,
-        _: &ItemRc,
+        self_rc: &ItemRc,
         size: LogicalSize,
-        _cache: &CachedRenderingData,
+        cache: &CachedRenderingData,
     ) {
         let geom = LogicalRect::from(size);
+        if self.current_state.transform.is_some() {
+            return self.draw_transformed(geom, true, |layer| {
+                layer.draw_rectangle(rect, self_rc, size, cache)
+            });
+        }
         if self.should_draw(&geom) {
             let clipped = match geom.intersection(&self.current_state.clip) {
                 Some(geom) => geom,
@@ -1908,11 +2290,16 @@ fn draw_rectangle(
     fn draw_border_rectangle(
         &mut self,
         rect: Pin<&dyn RenderBorderRectangle>,
-        _: &ItemRc,
+        self_rc: &ItemRc,
         size: LogicalSize,
-        _: &CachedRenderingData,
+        cache: &CachedRenderingData,
     ) {
         let geom = LogicalRect::from(size);
+        if self.current_state.transform.is_some() {
+            return self.draw_transformed(geom, true, |layer| {
+                layer.draw_border_rectangle(rect, self_rc, size, cache)
+            });
+        }
         if self.should_draw(&geom) {
             let mut border = rect.border_width();
             let radius = rect.border_radius();
@@ -2050,11 +2437,17 @@ fn draw_window_background(
     fn draw_image(
         &mut self,
         image: Pin<&dyn RenderImage>,
-        _: &ItemRc,
+        self_rc: &ItemRc,
         size: LogicalSize,
-        _: &CachedRenderingData,
+        cache: &CachedRenderingData,
     ) {
         let geom = LogicalRect::from(size);
+        if self.current_state.transform.is_some() {
+            let bilinear = image.rendering() == ImageRendering::Smooth;
+            return self.draw_transformed(geom, bilinear, |layer| {
+                layer.draw_image(image, self_rc, size, cache)
+            });
+        }
         if self.should_draw(&geom) {
             let source = image.source();
 
@@ -2099,15 +2492,19 @@ fn draw_image(
     fn draw_text(
         &mut self,
         text: Pin<&dyn crate::item_rendering::RenderText>,
-        _: &ItemRc,
+        self_rc: &ItemRc,
         size: LogicalSize,
-        _cache: &CachedRenderingData,
+        cache: &CachedRenderingData,
     ) {
         let string = text.text();
         if string.trim().is_empty() {
             return;
         }
         let geom = LogicalRect::from(size);
+        if self.current_state.transform.is_some() {
+            return self
+                .draw_transformed(geom, true, |layer| layer.draw_text(text, self_rc, size, cache));
+        }
         if !self.should_draw(&geom) {
             return;
         }
@@ -2174,10 +2571,15 @@ fn draw_text(
     fn draw_text_input(
         &mut self,
         text_input: Pin<&crate::items::TextInput>,
-        _: &ItemRc,
+        self_rc: &ItemRc,
         size: LogicalSize,
     ) {
         let geom = LogicalRect::from(size);
+        if self.current_state.transform.is_some() {
+            return self.draw_transformed(geom, true, |layer| {
+                layer.draw_text_input(text_input, self_rc, size)
+            });
+        }
         if !self.should_draw(&geom) {
             return;
         }
@@ -2281,36 +2683,169 @@ fn draw_text_input(
     }
 
     #[cfg(feature = "std")]
-    fn draw_path(&mut self, _path: Pin<&crate::items::Path>, _: &ItemRc, _size: LogicalSize) {
-        // TODO
+    fn draw_path(&mut self, path: Pin<&crate::items::Path>, self_rc: &ItemRc, size: LogicalSize) {
+        let geom = LogicalRect::from(size);
+        if self.current_state.transform.is_some() {
+            return self.draw_transformed(geom, true, |layer| layer.draw_path(path, self_rc, size));
+        }
+        if !self.should_draw(&geom) {
+            return;
+        }
+        let Some((offset, path_events)) = path.fitted_path_events(self_rc) else { return };
+
+        // Clip against the Path's geometry as well, since the partial renderer assumes the item doesn't draw outside of it.
+        let Some(logical_clip) = self.current_state.clip.intersection(&geom) else { return };
+        let physical_clip = (logical_clip.translate(self.current_state.offset.to_vector()).cast()
+            * self.scale_factor)
+            .round()
+            .cast::<i32>();
+
+        let origin = offset + self.current_state.offset.to_vector();
+        let transform = lyon_path::math::Transform::translation(origin.x as f32, origin.y as f32)
+            .then_scale(self.scale_factor.get(), self.scale_factor.get());
+        let polylines =
+            path::flatten(path_events.iter().map(|event| event.transformed(&transform)));
+        let anti_alias = path.anti_alias();
+
+        // FIXME: gradients
+        let fill_color = self.alpha_color(path.fill().color());
+        if fill_color.alpha() > 0 {
+            if let Some((geometry, alpha_map)) = path::rasterize(
+                polylines.iter().map(|p| p.points.as_slice()),
+                path.fill_rule(),
+                physical_clip,
+                anti_alias,
+            ) {
+                self.draw_alpha_map(geometry, alpha_map, fill_color);
+            }
+        }
+
+        let stroke_width = path.stroke_width().get() as f32 * self.scale_factor.get();
+        let stroke_color = self.alpha_color(path.stroke().color());
+        if stroke_color.alpha() > 0 && stroke_width > 0. {
+            let polygons = path::stroke(&polylines, stroke_width, path.stroke_line_cap());
+            if let Some((geometry, alpha_map)) = path::rasterize(
+                polygons.iter().map(Vec::as_slice),
+                crate::items::FillRule::Nonzero,
+                physical_clip,
+                anti_alias,
+            ) {
+                self.draw_alpha_map(geometry, alpha_map, stroke_color);
+            }
+        }
     }
 
     fn draw_box_shadow(
         &mut self,
-        _box_shadow: Pin<&crate::items::BoxShadow>,
-        _: &ItemRc,
-        _size: LogicalSize,
+        box_shadow: Pin<&crate::items::BoxShadow>,
+        self_rc: &ItemRc,
+        size: LogicalSize,
     ) {
-        // TODO
+        let color = self.alpha_color(box_shadow.color());
+        let blur = box_shadow.blur();
+        let offset = LogicalVector::from_lengths(box_shadow.offset_x(), box_shadow.offset_y());
+        if color.alpha() == 0 || (blur.get() <= 0 as Coord && offset == LogicalVector::zero()) {
+            return;
+        }
+        if self.current_state.transform.is_some() {
+            let shadow_rect =
+                LogicalRect::new(offset.to_point(), size).inflate(blur.get(), blur.get());
+            return self.draw_transformed(shadow_rect, true, |layer| {
+                layer.draw_box_shadow(box_shadow, self_rc, size)
+            });
+        }
+
+        let blur = (blur.cast() * self.scale_factor).get().ceil().max(0.) as i16;
+        let geometry = (LogicalRect::new(self.current_state.offset + offset, size).cast()
+            * self.scale_factor)
+            .round()
+            .cast::<i16>();
+        if geometry.is_empty() {
+            return;
+        }
+        let options = box_shadow::BoxShadowOptions {
+            width: geometry.width(),
+            height: geometry.height(),
+            blur,
+            radius: (box_shadow.border_radius().cast() * self.scale_factor).get().round() as i16,
+        };
+        let (texture_width, texture_height) = options.texture_size();
+        let target_rect = PhysicalRect::new(
+            geometry.origin - euclid::vec2(blur, blur),
+            PhysicalSize::new(texture_width, texture_height),
+        );
+
+        let physical_clip =
+            (self.current_state.clip.translate(self.current_state.offset.to_vector()).cast()
+                * self.scale_factor)
+                .round()
+                .cast::<i16>();
+        let Some(clipped_target) = physical_clip.intersection(&target_rect) else { return };
+
+        let alpha_map = self.box_shadow_cache.get_box_shadow(options);
+        self.processor.process_shared_image_buffer(
+            clipped_target.transformed(self.rotation),
+            SharedBufferCommand {
+                buffer: SharedBufferData::AlphaMap { data: alpha_map, width: texture_width as u16 },
+                source_rect: PhysicalRect::from_size(target_rect.size),
+                extra: SceneTextureExtra {
+                    colorize: color,
+                    // color already is mixed with global alpha
+                    alpha: color.alpha(),
+                    rotation: self.rotation.orientation,
+                    dx: Fixed::from_integer(1),
+                    dy: Fixed::from_integer(1),
+                    off_x: Fixed::from_integer(
+                        (clipped_target.min_x() - target_rect.min_x()) as u16,
+                    ),
+                    off_y: Fixed::from_integer(
+                        (clipped_target.min_y() - target_rect.min_y()) as u16,
+                    ),
+                },
+            },
+        );
     }
 
     fn combine_clip(
         &mut self,
         other: LogicalRect,
-        _radius: LogicalBorderRadius,
-        _border_width: LogicalLength,
+        radius: LogicalBorderRadius,
+        border_width: LogicalLength,
     ) -> bool {
+        // The children are clipped to the inside of the border
+        let border_width = border_width.max(LogicalLength::new(0 as Coord));
+        let other = other.inflate(-border_width.get(), -border_width.get());
         match self.current_state.clip.intersection(&other) {
             Some(r) => {
                 self.current_state.clip = r;
-                true
             }
             None => {
                 self.current_state.clip = LogicalRect::default();
-                false
+                return false;
             }
         }
-        // TODO: handle radius and border
+
+        let radius = radius
+            .inner(border_width)
+            .min(LogicalBorderRadius::from_length(other.width_length() / 2 as Coord))
+            .min(LogicalBorderRadius::from_length(other.height_length() / 2 as Coord));
+        // FIXME: rounded clips within a rotation are only clipped to their bounding rectangle
+        if !radius.is_zero() && self.current_state.transform.is_none() {
+            let rect = (other.translate(self.current_state.offset.to_vector()).cast()
+                * self.scale_factor)
+                .round()
+                .cast()
+                .transformed(self.rotation);
+            let radius = (radius.cast() * self.scale_factor).cast().transformed(self.rotation);
+            let index = self.processor.add_rounded_clip(RoundedClip {
+                rect,
+                radius,
+                parent: self.current_state.rounded_clip,
+            });
+            self.current_state.rounded_clip = Some(index);
+            self.processor.set_rounded_clip(Some(index));
+        }
+        true
     }
 
     fn get_current_clip(&self) -> LogicalRect {
@@ -2326,8 +2861,29 @@ fn translation(&self) -> LogicalVector {
         self.current_state.offset.to_vector()
     }
 
-    fn rotate(&mut self, _angle_in_degrees: f32) {
-        // TODO (#6068)
+    fn rotate(&mut self, angle_in_degrees: f32) {
+        if angle_in_degrees % 360. == 0. {
+            return;
+        }
+        let pivot = self.current_state.offset.cast::<f32>().to_vector();
+        let rotation = |angle: f32| {
+            euclid::Transform2D::<f32, LogicalPx, LogicalPx>::translation(-pivot.x, -pivot.y)
+                .then_rotate(euclid::Angle::degrees(angle))
+                .then_translate(pivot)
+        };
+        if self.current_state.transform.is_none() {
+            self.current_state.screen_clip =
+                self.current_state.clip.translate(self.current_state.offset.to_vector());
+        }
+        // The clip must stay aligned with the axis of the rotated coordinate system, so use the
+        // bounding box of the rotated clip. The items are then clipped in window coordinates.
+        let clip = self.current_state.clip.translate(self.current_state.offset.to_vector()).cast();
+        self.current_state.clip =
+            rotation(-angle_in_degrees).outer_transformed_rect(&clip).translate(-pivot).cast();
+        self.current_state.transform = Some(match self.current_state.transform {
+            Some(transform) => rotation(angle_in_degrees).then(&transform),
+            None => rotation(angle_in_degrees),
+        });
     }
 
     fn apply_opacity(&mut self, opacity: f32) {
@@ -2339,7 +2895,11 @@ fn save_state(&mut self) {
     }
 
     fn restore_state(&mut self) {
+        let rounded_clip = self.current_state.rounded_clip;
         self.current_state = self.state_stack.pop().unwrap();
+        if self.current_state.rounded_clip != rounded_clip {
+            self.processor.set_rounded_clip(self.current_state.rounded_clip);
+        }
     }
 
     fn scale_factor(&self) -> f32 {
@@ -2445,5 +3005,5 @@ fn as_any(&mut self) -> Option<&mut dyn core::any::Any> {
 }
 
 impl<T: ProcessScene> crate::item_rendering::ItemRendererFeatures for SceneBuilder<'_, T> {
-    const SUPPORTS_TRANSFORMATIONS: bool = false;
+    const SUPPORTS_TRANSFORMATIONS: bool = true;
 }
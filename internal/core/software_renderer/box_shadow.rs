@@ -0,0 +1,178 @@
+// Copyright © SixtyFPS GmbH <info@slint.dev>
+// SPDX-License-Identifier: GPL-3.0-only OR LicenseRef-Slint-Royalty-free-2.0 OR LicenseRef-Slint-Software-3.0
+
+//! This module contains the rendering of the `BoxShadow` element.
+//!
+//! Like the box shadow cache of the GPU renderers, the blurred shadow is rendered once into an
+//! alpha map which is kept in a cache so it can be re-used across frames. The alpha map doesn't
+//! depend on the color, which is applied when the alpha map is drawn.
+
+use alloc::rc::Rc;
+use alloc::vec::Vec;
+use core::cell::RefCell;
+#[allow(unused)]
+use num_traits::Float;
+
+/// The parameters of the shadow alpha map, in physical pixels
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct BoxShadowOptions {
+    /// Width of the rectangle casting the shadow
+    pub width: i16,
+    /// Height of the rectangle casting the shadow
+    pub height: i16,
+    /// The blur radius. The alpha map has a margin of that size around the rectangle.
+    pub blur: i16,
+    pub radius: i16,
+}
+
+impl BoxShadowOptions {
+    /// The size of the alpha map, including the blur margin
+    pub fn texture_size(&self) -> (i16, i16) {
+        (self.width + 2 * self.blur, self.height + 2 * self.blur)
+    }
+}
+
+/// Cache of the most recently used shadow alpha maps.
+#[derive(Default)]
+pub struct BoxShadowCache(RefCell<Vec<(BoxShadowOptions, Rc<[u8]>)>>);
+
+impl BoxShadowCache {
+    /// The amount of shadow alpha maps that are kept around
+    const MAX_ENTRIES: usize = 8;
+
+    /// Look up the alpha map for the given options, or render a new one if needed.
+    pub fn get_box_shadow(&self, options: BoxShadowOptions) -> Rc<[u8]> {
+        let mut cache = self.0.borrow_mut();
+        if let Some(pos) = cache.iter().position(|(o, _)| *o == options) {
+            // Move the entry to the back so the least recently used entries are at the front
+            let entry = cache.remove(pos);
+            let data = entry.1.clone();
+            cache.push(entry);
+            return data;
+        }
+        let data: Rc<[u8]> = render_box_shadow(&options).into();
+        if cache.len() >= Self::MAX_ENTRIES {
+            cache.remove(0);
+        }
+        cache.push((options, data.clone()));
+        data
+    }
+
+    /// Free all the alpha maps
+    pub fn clear(&self) {
+        self.0.borrow_mut().clear();
+    }
+}
+
+/// Render the rounded rectangle with its anti-aliased edges, and blur it.
+fn render_box_shadow(options: &BoxShadowOptions) -> Vec<u8> {
+    let (width, height) = options.texture_size();
+    let (width, height) = (width as usize, height as usize);
+    let half_w = options.width as f32 / 2.;
+    let half_h = options.height as f32 / 2.;
+    let radius = (options.radius as f32).min(half_w).min(half_h).max(0.);
+    let center_x = options.blur as f32 + half_w;
+    let center_y = options.blur as f32 + half_h;
+
+    let mut data = alloc::vec![0u8; width * height];
+    for (y, line) in data.chunks_exact_mut(width).enumerate() {
+        let qy = (y as f32 + 0.5 - center_y).abs() - (half_h - radius);
+        for (x, pixel) in line.iter_mut().enumerate() {
+            let qx = (x as f32 + 0.5 - center_x).abs() - (half_w - radius);
+            // Signed distance to the rounded rectangle
+            let outside = (qx.max(0.) * qx.max(0.) + qy.max(0.) * qy.max(0.)).sqrt();
+            let distance = outside + qx.max(qy).min(0.) - radius;
+            *pixel = ((0.5 - distance).clamp(0., 1.) * 255.) as u8;
+        }
+    }
+
+    if options.blur > 0 {
+        // Same as the GPU renderers: the blur radius is twice the standard deviation
+        gaussian_blur(&mut data, width, height, options.blur as f32 / 2.);
+    }
+    data
+}
+
+/// Approximate a gaussian blur with three successive box blurs in both directions.
+fn gaussian_blur(data: &mut [u8], width: usize, height: usize, sigma: f32) {
+    const PASSES: usize = 3;
+    // See "Fast Almost-Gaussian Filtering" by Peter Kovesi
+    let ideal_width = (12. * sigma * sigma / PASSES as f32 + 1.).sqrt();
+    let mut lower = ideal_width.floor() as i32;
+    if lower % 2 == 0 {
+        lower -= 1;
+    }
+    let lower_count = ((12. * sigma * sigma
+        - (PASSES as i32 * lower * lower + 4 * PASSES as i32 * lower + 3 * PASSES as i32) as f32)
+        / (-4 * lower - 4) as f32)
+        .round() as usize;
+
+    let mut tmp = alloc::vec![0u8; width.max(height)];
+    for pass in 0..PASSES {
+        let size = if pass < lower_count { lower } else { lower + 2 };
+        let radius = ((size - 1) / 2).max(0) as usize;
+        if radius == 0 {
+            continue;
+        }
+        for line in data.chunks_exact_mut(width) {
+            box_blur_line(line.iter_mut(), &mut tmp[..width], radius);
+        }
+        for x in 0..width {
+            box_blur_line(data[x..].iter_mut().step_by(width), &mut tmp[..height], radius);
+        }
+    }
+}
+
+/// Blur the pixels, considering that anything outside of the line is transparent.
+fn box_blur_line<'a>(line: impl Iterator<Item = &'a mut u8>, tmp: &mut [u8], radius: usize) {
+    let mut line: Vec<&mut u8> = line.collect();
+    let len = line.len();
+    for (dst, src) in tmp.iter_mut().zip(line.iter()) {
+        *dst = **src;
+    }
+    let size = (2 * radius + 1) as u32;
+    let mut sum: u32 = tmp[..radius.min(len)].iter().map(|x| *x as u32).sum();
+    for (i, pixel) in line.iter_mut().enumerate() {
+        if i + radius < len {
+            sum += tmp[i + radius] as u32;
+        }
+        **pixel = (sum / size) as u8;
+        if i >= radius {
+            sum -= tmp[i - radius] as u32;
+        }
+    }
+}
+
+#[test]
+fn box_shadow_alpha_map() {
+    let options = BoxShadowOptions { width: 10, height: 6, blur: 0, radius: 0 };
+    let data = render_box_shadow(&options);
+    assert_eq!(data.len(), 60);
+    assert!(data.iter().all(|a| *a == 255));
+
+    let options = BoxShadowOptions { width: 10, height: 6, blur: 4, radius: 2 };
+    let data = render_box_shadow(&options);
+    let (width, height) = options.texture_size();
+    assert_eq!(data.len(), width as usize * height as usize);
+    // Fully transparent in the corner, opaque in the middle and symmetric
+    assert_eq!(data[0], 0);
+    let center = (height as usize / 2) * width as usize + width as usize / 2;
+    assert!(data[center] > 200);
+    let left = (height as usize / 2) * width as usize + 2;
+    let right = (height as usize / 2) * width as usize + width as usize - 3;
+    assert!(data[left] > 0 && data[left] < data[center]);
+    assert_eq!(data[left], data[right]);
+}
+
+#[test]
+fn box_shadow_cache() {
+    let cache = BoxShadowCache::default();
+    let options = BoxShadowOptions { width: 4, height: 4, blur: 2, radius: 1 };
+    let a = cache.get_box_shadow(options);
+    let b = cache.get_box_shadow(options);
+    assert!(Rc::ptr_eq(&a, &b));
+    for i in 0..BoxShadowCache::MAX_ENTRIES as i16 {
+        cache.get_box_shadow(BoxShadowOptions { width: 5 + i, ..options });
+    }
+    assert!(!Rc::ptr_eq(&a, &cache.get_box_shadow(options)));
+}
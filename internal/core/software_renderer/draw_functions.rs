@@ -12,6 +12,8 @@
 use crate::Color;
 use derive_more::{Add, Mul, Sub};
 use integer_sqrt::IntegerSquareRoot;
+#[allow(unused)]
+use num_traits::Float;
 
 /// Draw one line of the texture in the line buffer
 ///
@@ -654,6 +656,232 @@ pub(super) fn draw_gradient_line(
     }
 }
 
+/// Draw one line of a texture with an arbitrary transformation
+pub(super) fn draw_transformed_texture_line(
+    span: &PhysicalRect,
+    line: PhysicalLength,
+    texture: &super::TransformedTextureCommand,
+    buffer: &mut [impl TargetPixel],
+    extra_left_clip: i16,
+) {
+    // Sample at the center of the pixels
+    let start =
+        euclid::point2((span.origin.x + extra_left_clip) as f32 + 0.5, line.get() as f32 + 0.5);
+    let mut pos = texture.inverse_transform.transform_point(start);
+    let delta = texture.inverse_transform.transform_vector(euclid::vec2(1., 0.));
+
+    for pix in buffer {
+        let color = if texture.bilinear {
+            let x = pos.x - 0.5;
+            let y = pos.y - 0.5;
+            let (x0, y0) = (x.floor(), y.floor());
+            let fx = ((x - x0) * 256.) as u32;
+            let fy = ((y - y0) * 256.) as u32;
+            let (x0, y0) = (x0 as i32, y0 as i32);
+            let c00 = transformed_texture_pixel(texture, x0, y0);
+            let c10 = transformed_texture_pixel(texture, x0 + 1, y0);
+            let c01 = transformed_texture_pixel(texture, x0, y0 + 1);
+            let c11 = transformed_texture_pixel(texture, x0 + 1, y0 + 1);
+            let interpolate = |a00: u8, a10: u8, a01: u8, a11: u8| {
+                let top = a00 as u32 * (256 - fx) + a10 as u32 * fx;
+                let bottom = a01 as u32 * (256 - fx) + a11 as u32 * fx;
+                ((top * (256 - fy) + bottom * fy) >> 16) as u8
+            };
+            PremultipliedRgbaColor {
+                red: interpolate(c00.red, c10.red, c01.red, c11.red),
+                green: interpolate(c00.green, c10.green, c01.green, c11.green),
+                blue: interpolate(c00.blue, c10.blue, c01.blue, c11.blue),
+                alpha: interpolate(c00.alpha, c10.alpha, c01.alpha, c11.alpha),
+            }
+        } else {
+            transformed_texture_pixel(texture, pos.x.floor() as i32, pos.y.floor() as i32)
+        };
+        if color.alpha > 0 {
+            pix.blend(color);
+        }
+        pos += delta;
+    }
+}
+
+/// Returns the pixel of the texture at the given position, or transparent if outside of the texture
+fn transformed_texture_pixel(
+    texture: &super::TransformedTextureCommand,
+    x: i32,
+    y: i32,
+) -> PremultipliedRgbaColor {
+    if x < 0 || y < 0 || x >= texture.width as i32 || y >= texture.height as i32 {
+        return PremultipliedRgbaColor::default();
+    }
+    texture.data[y as usize * texture.width as usize + x as usize]
+}
+
+/// A scene command that can be drawn line by line in any kind of pixel buffer
+pub(super) trait DrawLine {
+    /// Draw the line `line` of the command which covers `span`.
+    /// The buffer excludes `extra_left_clip` and `extra_right_clip` pixels of the span.
+    fn draw_line(
+        &self,
+        span: &PhysicalRect,
+        line: PhysicalLength,
+        buffer: &mut [impl TargetPixel],
+        extra_left_clip: i16,
+        extra_right_clip: i16,
+    );
+}
+
+impl DrawLine for PremultipliedRgbaColor {
+    fn draw_line(
+        &self,
+        _span: &PhysicalRect,
+        _line: PhysicalLength,
+        buffer: &mut [impl TargetPixel],
+        _extra_left_clip: i16,
+        _extra_right_clip: i16,
+    ) {
+        TargetPixel::blend_slice(buffer, *self);
+    }
+}
+
+impl DrawLine for super::SceneTexture<'_> {
+    fn draw_line(
+        &self,
+        span: &PhysicalRect,
+        line: PhysicalLength,
+        buffer: &mut [impl TargetPixel],
+        extra_left_clip: i16,
+        extra_right_clip: i16,
+    ) {
+        draw_texture_line(span, line, self, buffer, extra_left_clip, extra_right_clip);
+    }
+}
+
+impl DrawLine for super::RoundedRectangle {
+    fn draw_line(
+        &self,
+        span: &PhysicalRect,
+        line: PhysicalLength,
+        buffer: &mut [impl TargetPixel],
+        extra_left_clip: i16,
+        extra_right_clip: i16,
+    ) {
+        draw_rounded_rectangle_line(span, line, self, buffer, extra_left_clip, extra_right_clip);
+    }
+}
+
+impl DrawLine for super::GradientCommand {
+    fn draw_line(
+        &self,
+        span: &PhysicalRect,
+        line: PhysicalLength,
+        buffer: &mut [impl TargetPixel],
+        extra_left_clip: i16,
+        _extra_right_clip: i16,
+    ) {
+        draw_gradient_line(span, line, self, buffer, extra_left_clip);
+    }
+}
+
+impl DrawLine for super::TransformedTextureCommand {
+    fn draw_line(
+        &self,
+        span: &PhysicalRect,
+        line: PhysicalLength,
+        buffer: &mut [impl TargetPixel],
+        extra_left_clip: i16,
+        _extra_right_clip: i16,
+    ) {
+        draw_transformed_texture_line(span, line, self, buffer, extra_left_clip);
+    }
+}
+
+/// Draw one line of the command, masked by the rounded clip at index `rounded_clip` in `clips`
+/// (and its parents), if any.
+#[allow(clippy::too_many_arguments)]
+pub(super) fn draw_clipped_line(
+    span: &PhysicalRect,
+    line: PhysicalLength,
+    command: &impl DrawLine,
+    clips: &[super::RoundedClip],
+    rounded_clip: Option<u16>,
+    buffer: &mut [impl TargetPixel],
+    extra_left_clip: i16,
+    extra_right_clip: i16,
+) {
+    let Some(rounded_clip) = rounded_clip else {
+        command.draw_line(span, line, buffer, extra_left_clip, extra_right_clip);
+        return;
+    };
+    let y = line.get();
+    let begin = span.min_x() + extra_left_clip;
+    let end = span.max_x() - extra_right_clip;
+
+    // `outer` is the range of pixels that are at least partially visible, and `inner` the
+    // range of pixels that are entirely visible
+    let (mut outer, mut inner) = (begin..end, begin..end);
+    let mut clip_index = Some(rounded_clip);
+    while let Some(index) = clip_index {
+        let clip = &clips[index as usize];
+        let (o, i) = clip.line_ranges(y);
+        outer = outer.start.max(o.start)..outer.end.min(o.end);
+        inner = inner.start.max(i.start)..inner.end.min(i.end);
+        clip_index = clip.parent;
+    }
+    if outer.is_empty() {
+        return;
+    }
+    let inner_start = inner.start.clamp(outer.start, outer.end);
+    let inner = inner_start..inner.end.clamp(inner_start, outer.end);
+
+    let draw_partial = |range: core::ops::Range<i16>, buffer: &mut [_]| {
+        // Draw in a temporary buffer, then blend it with the coverage of the clip
+        const CHUNK: i16 = 32;
+        let mut x = range.start;
+        while x < range.end {
+            let chunk_end = (x + CHUNK).min(range.end);
+            let mut tmp = [PremultipliedRgbaColor::background(); CHUNK as usize];
+            let tmp = &mut tmp[..(chunk_end - x) as usize];
+            command.draw_line(span, line, tmp, x - span.min_x(), span.max_x() - chunk_end);
+            for (pixel_x, (pix, color)) in (x..chunk_end)
+                .zip(buffer[(x - begin) as usize..(chunk_end - begin) as usize].iter_mut().zip(tmp))
+            {
+                let mut coverage = 255u16;
+                let mut clip_index = Some(rounded_clip);
+                while let Some(index) = clip_index {
+                    let clip = &clips[index as usize];
+                    coverage = coverage * clip.coverage(pixel_x, y) as u16 / 255;
+                    clip_index = clip.parent;
+                }
+                if coverage == 0 || color.alpha == 0 {
+                    continue;
+                }
+                let scale = |c: u8| (c as u16 * coverage / 255) as u8;
+                TargetPixel::blend(
+                    pix,
+                    PremultipliedRgbaColor {
+                        red: scale(color.red),
+                        green: scale(color.green),
+                        blue: scale(color.blue),
+                        alpha: scale(color.alpha),
+                    },
+                );
+            }
+            x = chunk_end;
+        }
+    };
+
+    draw_partial(outer.start..inner.start, buffer);
+    if !inner.is_empty() {
+        command.draw_line(
+            span,
+            line,
+            &mut buffer[(inner.start - begin) as usize..(inner.end - begin) as usize],
+            inner.start - span.min_x(),
+            span.max_x() - inner.end,
+        );
+    }
+    draw_partial(inner.end..outer.end, buffer);
+}
+
 /// A color whose component have been pre-multiplied by alpha
 ///
 /// The renderer operates faster on pre-multiplied color since it
@@ -0,0 +1,323 @@
+// Copyright © SixtyFPS GmbH <info@slint.dev>
+// SPDX-License-Identifier: GPL-3.0-only OR LicenseRef-Slint-Royalty-free-2.0 OR LicenseRef-Slint-Software-3.0
+
+//! This module contains the anti-aliased scanline rasterizer used to render the `Path` element.
+//!
+//! The path is flattened into polylines in physical pixel coordinates. Fills and strokes are
+//! turned into polygons which are then rasterized into an alpha map that the scene can draw
+//! like a glyph.
+
+use crate::items::{FillRule, LineCap};
+use crate::lengths::PhysicalPx;
+use alloc::vec::Vec;
+use lyon_path::math::{Point, Vector};
+use lyon_path::PathEvent;
+
+/// Maximum distance, in physical pixels, between a curve and its flattened approximation
+const TOLERANCE: f32 = 0.1;
+/// Number of sub-scanlines sampled for every line of pixels when anti-aliasing
+const SUBSAMPLES: usize = 4;
+/// Same default as in SVG: above that ratio between the miter length and the stroke width, a bevel is used
+const MITER_LIMIT: f32 = 4.;
+
+/// A flattened sub-path
+#[derive(Default, Debug)]
+pub struct Polyline {
+    pub points: Vec<Point>,
+    /// Whether the sub-path was explicitly closed. (Fills are always implicitly closed)
+    pub closed: bool,
+}
+
+/// Flatten the curves of the path into polylines.
+pub fn flatten(events: impl Iterator<Item = PathEvent>) -> Vec<Polyline> {
+    use lyon_path::iterator::PathIterator;
+    let mut result = Vec::new();
+    let mut current = Polyline::default();
+    for event in events.flattened(TOLERANCE) {
+        match event {
+            PathEvent::Begin { at } => {
+                if !current.points.is_empty() {
+                    result.push(core::mem::take(&mut current));
+                }
+                current.points.push(at);
+            }
+            PathEvent::Line { to, .. } => current.points.push(to),
+            PathEvent::End { close, .. } => {
+                current.closed = close;
+                result.push(core::mem::take(&mut current));
+            }
+            // The flattened iterator only produces line segments
+            PathEvent::Quadratic { .. } | PathEvent::Cubic { .. } => unreachable!(),
+        }
+    }
+    if !current.points.is_empty() {
+        result.push(current);
+    }
+    result
+}
+
+/// Compute polygons that cover the stroke of the polylines when filled with the non-zero rule.
+///
+/// Each segment becomes a quad, joins are mitered (or beveled when above the miter limit), and
+/// the end of open polylines get the `line_cap`. All polygons have the same orientation so that
+/// overlapping areas never cancel each other.
+pub fn stroke(polylines: &[Polyline], width: f32, line_cap: LineCap) -> Vec<Vec<Point>> {
+    let half_width = width / 2.;
+    let mut result = Vec::new();
+    let normal = |dir: Vector| Vector::new(-dir.y, dir.x) * half_width;
+
+    for polyline in polylines {
+        let mut points = polyline.points.clone();
+        points.dedup_by(|a, b| (*a - *b).square_length() < 1e-6);
+        if polyline.closed
+            && points.len() > 1
+            && (points[0] - points[points.len() - 1]).square_length() < 1e-6
+        {
+            points.pop();
+        }
+        let count = points.len();
+        if count == 0 {
+            continue;
+        }
+        if count == 1 {
+            match line_cap {
+                LineCap::Round => result.push(circle(points[0], half_width)),
+                LineCap::Square => {
+                    let p = points[0];
+                    let (x, y) = (Vector::new(half_width, 0.), Vector::new(0., half_width));
+                    result.push(alloc::vec![p - x - y, p + x - y, p + x + y, p - x + y]);
+                }
+                LineCap::Butt => {}
+            }
+            continue;
+        }
+
+        let closed = polyline.closed && count > 2;
+        let segment_count = if closed { count } else { count - 1 };
+        let direction = |i: usize| (points[(i + 1) % count] - points[i]).normalize();
+
+        for i in 0..segment_count {
+            let (mut a, mut b) = (points[i], points[(i + 1) % count]);
+            let dir = direction(i);
+            if !closed && line_cap == LineCap::Square {
+                if i == 0 {
+                    a -= dir * half_width;
+                }
+                if i == segment_count - 1 {
+                    b += dir * half_width;
+                }
+            }
+            let n = normal(dir);
+            result.push(alloc::vec![a + n, b + n, b - n, a - n]);
+        }
+
+        let joins = if closed { 0..count } else { 1..count - 1 };
+        for i in joins {
+            let d1 = direction((i + count - 1) % count);
+            let d2 = direction(i);
+            let cross = d1.cross(d2);
+            if cross.abs() < 1e-6 {
+                continue;
+            }
+            // The join needs to be filled on the outer side of the turn
+            let sign = -cross.signum();
+            let (n1, n2) = (normal(d1) * sign, normal(d2) * sign);
+            let v = points[i];
+            let bisector = (n1 + n2).normalize();
+            let cos_half_angle = bisector.dot(n1) / half_width;
+            if cos_half_angle > 1. / MITER_LIMIT {
+                let miter = v + bisector * (half_width / cos_half_angle);
+                result.push(alloc::vec![v, v + n1, miter, v + n2]);
+            } else {
+                result.push(alloc::vec![v, v + n1, v + n2]);
+            }
+        }
+
+        if !closed && line_cap == LineCap::Round {
+            result.push(circle(points[0], half_width));
+            result.push(circle(points[count - 1], half_width));
+        }
+    }
+
+    for polygon in &mut result {
+        let mut area = 0.;
+        for i in 0..polygon.len() {
+            area += polygon[i].to_vector().cross(polygon[(i + 1) % polygon.len()].to_vector());
+        }
+        if area < 0. {
+            polygon.reverse();
+        }
+    }
+    result
+}
+
+fn circle(center: Point, radius: f32) -> Vec<Point> {
+    let segments = ((radius * 2.) as usize).clamp(8, 64);
+    (0..segments)
+        .map(|i| {
+            let angle = lyon_path::math::Angle::radians(
+                i as f32 * core::f32::consts::TAU / segments as f32,
+            );
+            center + Vector::from_angle_and_length(angle, radius)
+        })
+        .collect()
+}
+
+struct Edge {
+    /// x coordinate at `y0`
+    x: f32,
+    y0: f32,
+    y1: f32,
+    dxdy: f32,
+    winding: i32,
+}
+
+/// Rasterize the polygons (which are implicitly closed) with the given fill rule.
+///
+/// Returns the part of the bounding box of the polygons that intersects with `clip`, and an
+/// alpha map of that size.
+pub fn rasterize<'a>(
+    polygons: impl Iterator<Item = &'a [Point]>,
+    fill_rule: FillRule,
+    clip: euclid::Rect<i32, PhysicalPx>,
+    anti_alias: bool,
+) -> Option<(euclid::Rect<i32, PhysicalPx>, Vec<u8>)> {
+    let mut edges = Vec::new();
+    let mut bounding_box = euclid::Box2D::<f32, PhysicalPx>::new(
+        euclid::point2(f32::MAX, f32::MAX),
+        euclid::point2(f32::MIN, f32::MIN),
+    );
+    for polygon in polygons {
+        for (i, a) in polygon.iter().enumerate() {
+            bounding_box.min = bounding_box.min.min(euclid::point2(a.x, a.y));
+            bounding_box.max = bounding_box.max.max(euclid::point2(a.x, a.y));
+            let b = polygon[(i + 1) % polygon.len()];
+            if a.y == b.y {
+                continue;
+            }
+            let (top, bottom, winding) = if a.y < b.y { (*a, b, 1) } else { (b, *a, -1) };
+            edges.push(Edge {
+                x: top.x,
+                y0: top.y,
+                y1: bottom.y,
+                dxdy: (bottom.x - top.x) / (bottom.y - top.y),
+                winding,
+            });
+        }
+    }
+    let rect = bounding_box.round_out().to_rect().try_cast::<i32>()?.intersection(&clip)?;
+    let (width, height) = (rect.width() as usize, rect.height() as usize);
+    edges.sort_unstable_by(|a, b| a.y0.total_cmp(&b.y0));
+
+    let samples = if anti_alias { SUBSAMPLES } else { 1 };
+    let weight = 1. / samples as f32;
+    let is_inside = |winding: i32| match fill_rule {
+        FillRule::Nonzero => winding != 0,
+        FillRule::Evenodd => winding % 2 != 0,
+    };
+
+    let mut data = alloc::vec![0u8; width * height];
+    let mut coverage = alloc::vec![0f32; width];
+    let mut active = Vec::<usize>::new();
+    let mut crossings = Vec::<(f32, i32)>::new();
+    let mut next_edge = 0;
+    for (line, line_data) in data.chunks_exact_mut(width).enumerate() {
+        coverage.fill(0.);
+        for sample in 0..samples {
+            let y = (rect.min_y() + line as i32) as f32 + (sample as f32 + 0.5) * weight;
+            while next_edge < edges.len() && edges[next_edge].y0 <= y {
+                active.push(next_edge);
+                next_edge += 1;
+            }
+            active.retain(|e| edges[*e].y1 > y);
+
+            crossings.clear();
+            crossings.extend(active.iter().map(|e| {
+                let e = &edges[*e];
+                (e.x + (y - e.y0) * e.dxdy - rect.min_x() as f32, e.winding)
+            }));
+            crossings.sort_unstable_by(|a, b| a.0.total_cmp(&b.0));
+
+            let mut winding = 0;
+            let mut span_start = 0.;
+            for (x, w) in crossings.iter().copied() {
+                let was_inside = is_inside(winding);
+                winding += w;
+                match (was_inside, is_inside(winding)) {
+                    (false, true) => span_start = x,
+                    (true, false) => add_span(&mut coverage, span_start, x, weight),
+                    _ => {}
+                }
+            }
+        }
+        for (pixel, cov) in line_data.iter_mut().zip(coverage.iter()) {
+            *pixel = if anti_alias {
+                (cov.min(1.) * 255.).round() as u8
+            } else if *cov >= 0.5 {
+                255
+            } else {
+                0
+            };
+        }
+    }
+    Some((rect, data))
+}
+
+/// Add the coverage of the horizontal span between x0 and x1 to the line
+fn add_span(coverage: &mut [f32], x0: f32, x1: f32, weight: f32) {
+    let width = coverage.len() as f32;
+    let (x0, x1) = (x0.clamp(0., width), x1.clamp(0., width));
+    if x1 <= x0 {
+        return;
+    }
+    let (i0, i1) = (x0 as usize, x1 as usize);
+    if i0 == i1 {
+        coverage[i0] += (x1 - x0) * weight;
+        return;
+    }
+    coverage[i0] += (i0 as f32 + 1. - x0) * weight;
+    for c in &mut coverage[i0 + 1..i1] {
+        *c += weight;
+    }
+    if i1 < coverage.len() {
+        coverage[i1] += (x1 - i1 as f32) * weight;
+    }
+}
+
+#[test]
+fn rasterize_square() {
+    let square = [Point::new(1., 1.), Point::new(3., 1.), Point::new(3., 3.5), Point::new(1., 3.5)];
+    let clip = euclid::rect(0, 0, 10, 10);
+    let (rect, data) =
+        rasterize(core::iter::once(&square[..]), FillRule::Nonzero, clip, true).unwrap();
+    assert_eq!(rect, euclid::rect(1, 1, 2, 3));
+    assert_eq!(data, [255, 255, 255, 255, 128, 128]);
+}
+
+#[test]
+fn rasterize_fill_rule() {
+    let outer = [Point::new(0., 0.), Point::new(4., 0.), Point::new(4., 4.), Point::new(0., 4.)];
+    let inner = [Point::new(1., 1.), Point::new(3., 1.), Point::new(3., 3.), Point::new(1., 3.)];
+    let clip = euclid::rect(0, 0, 10, 10);
+    let polygons = || [&outer[..], &inner[..]].into_iter();
+    let (_, nonzero) = rasterize(polygons(), FillRule::Nonzero, clip, true).unwrap();
+    assert!(nonzero.iter().all(|a| *a == 255));
+    let (_, evenodd) = rasterize(polygons(), FillRule::Evenodd, clip, true).unwrap();
+    assert_eq!(evenodd[4 + 1], 0);
+    assert_eq!(evenodd[4 * 2 + 2], 0);
+    assert_eq!(evenodd[4], 255);
+}
+
+#[test]
+fn stroke_line_cap() {
+    let line =
+        [Polyline { points: alloc::vec![Point::new(2., 2.), Point::new(6., 2.)], closed: false }];
+    let clip = euclid::rect(0, 0, 10, 10);
+    let rect_for = |cap| {
+        let polygons = stroke(&line, 2., cap);
+        rasterize(polygons.iter().map(Vec::as_slice), FillRule::Nonzero, clip, true).unwrap().0
+    };
+    assert_eq!(rect_for(LineCap::Butt), euclid::rect(2, 1, 4, 2));
+    assert_eq!(rect_for(LineCap::Square), euclid::rect(1, 1, 6, 2));
+    assert_eq!(rect_for(LineCap::Round), euclid::rect(1, 1, 6, 2));
+}
@@ -8,11 +8,14 @@
     PhysicalSize, PremultipliedRgbaColor, RenderingRotation,
 };
 use crate::graphics::{SharedImageBuffer, TexturePixelFormat};
+use crate::lengths::PhysicalPx;
 use crate::lengths::{PointLengths as _, SizeLengths as _};
 use crate::Color;
 use alloc::rc::Rc;
 use alloc::vec::Vec;
 use euclid::Length;
+#[allow(unused)]
+use num_traits::Float;
 
 #[derive(Default)]
 pub struct SceneVectors {
@@ -20,6 +23,8 @@ pub struct SceneVectors {
     pub rounded_rectangles: Vec<RoundedRectangle>,
     pub shared_buffers: Vec<SharedBufferCommand>,
     pub gradients: Vec<GradientCommand>,
+    pub transformed_textures: Vec<TransformedTextureCommand>,
+    pub rounded_clips: Vec<RoundedClip>,
 }
 
 pub struct Scene {
@@ -239,6 +244,8 @@ pub struct SceneItem {
     // this is the order of the item from which it is in the item tree
     pub z: u16,
     pub command: SceneCommand,
+    /// Index in [`SceneVectors::rounded_clips`] of the rounded clip that applies to this item
+    pub rounded_clip: Option<u16>,
 }
 
 fn compare_scene_item(a: &SceneItem, b: &SceneItem) -> core::cmp::Ordering {
@@ -279,6 +286,10 @@ pub enum SceneCommand {
     Gradient {
         gradient_index: u16,
     },
+    /// texture_index is an index in the [`SceneVectors::transformed_textures`] array
+    TransformedTexture {
+        texture_index: u16,
+    },
 }
 
 pub struct SceneTexture<'a> {
@@ -429,3 +440,122 @@ pub struct GradientCommand {
     pub top_clip: PhysicalLength,
     pub bottom_clip: PhysicalLength,
 }
+
+/// A premultiplied texture drawn with an arbitrary affine transformation (eg, a rotation)
+pub struct TransformedTextureCommand {
+    /// The pixels of the texture, `width * height` in size.
+    pub data: Vec<PremultipliedRgbaColor>,
+    pub width: u16,
+    pub height: u16,
+    /// Maps a point in the target buffer to a point in the texture (in pixel, where (0,0) is
+    /// the top-left corner of the first pixel)
+    pub inverse_transform: euclid::Transform2D<f32, PhysicalPx, PhysicalPx>,
+    /// When true, use a bilinear interpolation, otherwise use the nearest pixel
+    pub bilinear: bool,
+}
+
+/// A clip with rounded corners, in the coordinate of the target buffer.
+///
+/// The rectangle itself is already part of the rectangular clip of the items, so only
+/// the corners need to be masked.
+#[derive(Debug, Clone)]
+pub struct RoundedClip {
+    pub rect: PhysicalRect,
+    pub radius: PhysicalBorderRadius,
+    /// Index in [`SceneVectors::rounded_clips`] of the enclosing rounded clip
+    pub parent: Option<u16>,
+}
+
+impl RoundedClip {
+    /// Returns the center x coordinate of the left or right corner that covers the line `y`,
+    /// the vertical distance between the center of the pixels of that line and the center
+    /// of the corner, and the radius of the corner.
+    fn corner(&self, right: bool, y: i16) -> Option<(f32, f32, f32)> {
+        let r = &self.radius;
+        let (top, bottom) =
+            if right { (r.top_right, r.bottom_right) } else { (r.top_left, r.bottom_left) };
+        let (radius, center_y) = if y < self.rect.min_y() + top {
+            (top, self.rect.min_y() + top)
+        } else if y >= self.rect.max_y() - bottom {
+            (bottom, self.rect.max_y() - bottom)
+        } else {
+            return None;
+        };
+        if radius <= 0 {
+            return None;
+        }
+        let center_x = if right { self.rect.max_x() - radius } else { self.rect.min_x() + radius };
+        Some((center_x as f32, (y as f32 + 0.5 - center_y as f32).abs(), radius as f32))
+    }
+
+    /// Returns the range of pixels of the line `y` that are at least partially inside of the
+    /// clip, and the range of pixels that are entirely inside.
+    pub fn line_ranges(&self, y: i16) -> (core::ops::Range<i16>, core::ops::Range<i16>) {
+        if y < self.rect.min_y() || y >= self.rect.max_y() {
+            return (0..0, 0..0);
+        }
+        let (mut outer, mut inner) = (self.rect.x_range(), self.rect.x_range());
+        if let Some((cx, dy, r)) = self.corner(false, y) {
+            let outer_width = ((r + 0.5) * (r + 0.5) - dy * dy).max(0.).sqrt();
+            outer.start = (cx - outer_width - 0.5).floor() as i16 + 1;
+            inner.start = if dy <= r - 0.5 {
+                let inner_width = ((r - 0.5) * (r - 0.5) - dy * dy).sqrt();
+                ((cx - inner_width - 0.5).ceil() as i16).min(cx as i16)
+            } else {
+                cx as i16
+            };
+        }
+        if let Some((cx, dy, r)) = self.corner(true, y) {
+            let outer_width = ((r + 0.5) * (r + 0.5) - dy * dy).max(0.).sqrt();
+            outer.end = (cx + outer_width - 0.5).ceil() as i16;
+            inner.end = if dy <= r - 0.5 {
+                let inner_width = ((r - 0.5) * (r - 0.5) - dy * dy).sqrt();
+                ((cx + inner_width - 0.5).floor() as i16 + 1).max(cx as i16)
+            } else {
+                cx as i16
+            };
+        }
+        (outer, inner)
+    }
+
+    /// Returns how much of the pixel is inside of the clip, between 0 and 255
+    pub fn coverage(&self, x: i16, y: i16) -> u8 {
+        if !self.rect.contains(euclid::point2(x, y)) {
+            return 0;
+        }
+        let center = x as f32 + 0.5;
+        let corner = self
+            .corner(false, y)
+            .filter(|(cx, ..)| center < *cx)
+            .or_else(|| self.corner(true, y).filter(|(cx, ..)| center > *cx));
+        match corner {
+            Some((cx, dy, r)) => {
+                let dx = center - cx;
+                let distance = (dx * dx + dy * dy).sqrt();
+                ((r - distance + 0.5).clamp(0., 1.) * 255.) as u8
+            }
+            None => 255,
+        }
+    }
+}
+
+#[test]
+fn rounded_clip_line_ranges() {
+    let clip = RoundedClip {
+        rect: euclid::rect(10, 10, 20, 30),
+        radius: PhysicalBorderRadius::new(8, 0, 4, 6),
+        parent: None,
+    };
+    for y in 5..45 {
+        let (outer, inner) = clip.line_ranges(y);
+        for x in 5..35 {
+            let coverage = clip.coverage(x, y);
+            assert_eq!(coverage > 0, outer.contains(&x), "outer at {x},{y}: {coverage}");
+            if inner.contains(&x) {
+                assert_eq!(coverage, 255, "inner at {x},{y}");
+            }
+        }
+    }
+    // No corner on that line
+    assert_eq!(clip.line_ranges(20), (10..30, 10..30));
+}
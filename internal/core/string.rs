@@ -13,6 +13,7 @@
 #[cfg(not(feature = "std"))]
 #[allow(unused)]
 use num_traits::Float;
+use unicode_segmentation::UnicodeSegmentation;
 
 /// This macro is the same as [`std::format!`], but it returns a [`SharedString`] instead.
 ///
@@ -339,6 +340,62 @@ pub fn shared_string_from_number_precision(n: f64, precision: usize) -> SharedSt
     }
 }
 
+/// Returns the byte offset of the `index`-th grapheme cluster of `s`, or the length of `s` if it has fewer.
+fn grapheme_byte_offset(s: &str, index: usize) -> usize {
+    s.grapheme_indices(true).nth(index).map_or(s.len(), |(offset, _)| offset)
+}
+
+/// Returns the part of `s` between the `start` and `end` grapheme cluster indices, similar to JavaScript's
+/// String.slice(): negative indices count from the end of the string, and out of range indices are clamped.
+pub fn shared_string_slice(s: &str, start: i32, end: i32) -> SharedString {
+    let count = s.graphemes(true).count() as i64;
+    let clamp = |i: i32| {
+        let i = i as i64;
+        (if i < 0 { count + i } else { i }).clamp(0, count) as usize
+    };
+    let (start, end) = (clamp(start), clamp(end));
+    if start >= end {
+        return SharedString::default();
+    }
+    let start_offset = grapheme_byte_offset(s, start);
+    let end_offset = start_offset + grapheme_byte_offset(&s[start_offset..], end - start);
+    SharedString::from(&s[start_offset..end_offset])
+}
+
+/// Returns the index, in grapheme clusters, of the first occurrence of `needle` in `haystack`,
+/// or -1 if `needle` is not found
+pub fn shared_string_index_of(haystack: &str, needle: &str) -> i32 {
+    haystack.find(needle).map_or(-1, |offset| haystack[..offset].graphemes(true).count() as i32)
+}
+
+/// Splits `s` at each occurrence of `separator`.
+/// If `separator` is empty, `s` is split into its grapheme clusters.
+pub fn shared_string_split(s: &str, separator: &str) -> SharedVector<SharedString> {
+    if separator.is_empty() {
+        s.graphemes(true).map(SharedString::from).collect()
+    } else {
+        s.split(separator).map(SharedString::from).collect()
+    }
+}
+
+#[test]
+fn test_string_functions() {
+    assert_eq!(shared_string_slice("hello world", 0, 5), "hello");
+    assert_eq!(shared_string_slice("hello world", 6, 100), "world");
+    assert_eq!(shared_string_slice("hello world", -5, -1), "worl");
+    assert_eq!(shared_string_slice("hello world", 5, 2), "");
+    assert_eq!(shared_string_slice("🇫🇷 tschüß", 1, 7), " tschü");
+
+    assert_eq!(shared_string_index_of("hello world", "o"), 4);
+    assert_eq!(shared_string_index_of("hello world", "xyz"), -1);
+    assert_eq!(shared_string_index_of("hello world", ""), 0);
+    assert_eq!(shared_string_index_of("🇫🇷 tschüß", "ü"), 6);
+
+    assert_eq!(shared_string_split("a,b,,c", ",").as_slice(), ["a", "b", "", "c"]);
+    assert_eq!(shared_string_split("", ",").as_slice(), [""]);
+    assert_eq!(shared_string_split("a🇫🇷b", "").as_slice(), ["a", "🇫🇷", "b"]);
+}
+
 #[test]
 fn simple_test() {
     use std::string::ToString;
@@ -667,6 +724,78 @@ fn test_slint_shared_string_to_uppercase() {
         }
         assert_eq!(out.as_str(), "HELLO");
     }
+
+    #[no_mangle]
+    pub extern "C" fn slint_shared_string_contains(
+        ss: &SharedString,
+        needle: &SharedString,
+    ) -> bool {
+        ss.contains(needle.as_str())
+    }
+
+    #[no_mangle]
+    pub extern "C" fn slint_shared_string_starts_with(
+        ss: &SharedString,
+        prefix: &SharedString,
+    ) -> bool {
+        ss.starts_with(prefix.as_str())
+    }
+
+    #[no_mangle]
+    pub extern "C" fn slint_shared_string_ends_with(
+        ss: &SharedString,
+        suffix: &SharedString,
+    ) -> bool {
+        ss.ends_with(suffix.as_str())
+    }
+
+    #[no_mangle]
+    pub extern "C" fn slint_shared_string_replace(
+        out: &mut SharedString,
+        ss: &SharedString,
+        from: &SharedString,
+        to: &SharedString,
+    ) {
+        *out = SharedString::from(ss.replace(from.as_str(), to.as_str()));
+    }
+
+    #[no_mangle]
+    pub extern "C" fn slint_shared_string_trim(out: &mut SharedString, ss: &SharedString) {
+        *out = SharedString::from(ss.trim());
+    }
+
+    #[no_mangle]
+    pub extern "C" fn slint_shared_string_slice(
+        out: &mut SharedString,
+        ss: &SharedString,
+        start: i32,
+        end: i32,
+    ) {
+        *out = shared_string_slice(ss, start, end);
+    }
+
+    #[no_mangle]
+    pub extern "C" fn slint_shared_string_index_of(
+        ss: &SharedString,
+        needle: &SharedString,
+    ) -> i32 {
+        shared_string_index_of(ss, needle)
+    }
+
+    #[no_mangle]
+    pub extern "C" fn slint_shared_string_split(
+        out: &mut SharedVector<SharedString>,
+        ss: &SharedString,
+        separator: &SharedString,
+    ) {
+        *out = shared_string_split(ss, separator);
+    }
+    #[test]
+    fn test_slint_shared_string_split() {
+        let mut out = SharedVector::default();
+        slint_shared_string_split(&mut out, &"a b c".into(), &" ".into());
+        assert_eq!(out.as_slice(), ["a", "b", "c"]);
+    }
 }
 
 #[cfg(feature = "serde")]
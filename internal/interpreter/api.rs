@@ -9,7 +9,7 @@
 use i_slint_core::model::{Model, ModelExt, ModelRc};
 #[cfg(feature = "internal")]
 use i_slint_core::window::WindowInner;
-use i_slint_core::{PathData, SharedVector};
+use i_slint_core::{PathData, SharedMap, SharedVector};
 use smol_str::{SmolStr, StrExt};
 use std::borrow::Cow;
 use std::collections::HashMap;
@@ -54,6 +54,10 @@ pub enum ValueType {
     Brush,
     /// Correspond to `image` type in .slint.
     Image,
+    /// Correspond to the `[string: T]` map type in .slint
+    Map,
+    /// Correspond to the `long` type in .slint
+    Long,
     /// The type is not a public type but something internal.
     #[doc(hidden)]
     Other = -1,
@@ -78,6 +82,10 @@ fn from(ty: LangType) -> Self {
             LangType::Struct { .. } => Self::Struct,
             LangType::Void => Self::Void,
             LangType::Image => Self::Image,
+            LangType::Map(_) => Self::Map,
+            LangType::Int64 => Self::Long,
+            // The value is a Value::Void when the optional is null
+            LangType::Optional(inner) => (*inner).clone().into(),
             _ => Self::Other,
         }
     }
@@ -99,7 +107,8 @@ fn from(ty: LangType) -> Self {
 #[repr(u8)]
 pub enum Value {
     /// There is nothing in this value. That's the default.
-    /// For example, a function that do not return a result would return a Value::Void
+    /// For example, a function that do not return a result would return a Value::Void.
+    /// This is also the value of an optional (`int?`) that is `null`.
     #[default]
     Void = 0,
     /// An `int` or a `float` (this is also used for unit based type such as `length` or `angle`)
@@ -131,6 +140,12 @@ pub enum Value {
     #[doc(hidden)]
     /// Correspond to the `component-factory` type in .slint
     ComponentFactory(ComponentFactory) = 12,
+    /// Correspond to the `[string: T]` map type in .slint
+    Map(SharedMap<Value>) = 13,
+    /// Correspond to the `long` type in .slint.
+    /// The number types can also be extracted from a `Value::Long`, and a `long` property
+    /// can also be set from a `Value::Number`.
+    Long(i64) = 14,
 }
 
 impl Value {
@@ -145,6 +160,8 @@ pub fn value_type(&self) -> ValueType {
             Value::Struct(_) => ValueType::Struct,
             Value::Brush(_) => ValueType::Brush,
             Value::Image(_) => ValueType::Image,
+            Value::Map(_) => ValueType::Map,
+            Value::Long(_) => ValueType::Long,
             _ => ValueType::Other,
         }
     }
@@ -154,7 +171,11 @@ impl PartialEq for Value {
     fn eq(&self, other: &Self) -> bool {
         match self {
             Value::Void => matches!(other, Value::Void),
-            Value::Number(lhs) => matches!(other, Value::Number(rhs) if lhs.approx_eq(rhs)),
+            Value::Number(lhs) => match other {
+                Value::Number(rhs) => lhs.approx_eq(rhs),
+                Value::Long(rhs) => lhs.approx_eq(&(*rhs as f64)),
+                _ => false,
+            },
             Value::String(lhs) => matches!(other, Value::String(rhs) if lhs == rhs),
             Value::Bool(lhs) => matches!(other, Value::Bool(rhs) if lhs == rhs),
             Value::Image(lhs) => matches!(other, Value::Image(rhs) if lhs == rhs),
@@ -176,6 +197,12 @@ fn eq(&self, other: &Self) -> bool {
             Value::ComponentFactory(lhs) => {
                 matches!(other, Value::ComponentFactory(rhs) if lhs == rhs)
             }
+            Value::Map(lhs) => matches!(other, Value::Map(rhs) if lhs == rhs),
+            Value::Long(lhs) => match other {
+                Value::Long(rhs) => lhs == rhs,
+                Value::Number(rhs) => (*lhs as f64).approx_eq(rhs),
+                _ => false,
+            },
         }
     }
 }
@@ -200,6 +227,8 @@ fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
             Value::EnumerationValue(n, v) => write!(f, "Value::EnumerationValue({n:?}, {v:?})"),
             Value::LayoutCache(v) => write!(f, "Value::LayoutCache({v:?})"),
             Value::ComponentFactory(factory) => write!(f, "Value::ComponentFactory({factory:?})"),
+            Value::Map(m) => write!(f, "Value::Map({m:?})"),
+            Value::Long(l) => write!(f, "Value::Long({l:?})"),
         }
     }
 }
@@ -232,7 +261,30 @@ fn try_from(v: Value) -> Result<$ty, Self::Error> {
         )*
     };
 }
-declare_value_conversion!(Number => [u32, u64, i32, i64, f32, f64, usize, isize] );
+/// Like [`declare_value_conversion!`] for `Value::Number`, but the numbers can also be
+/// extracted from a `Value::Long`
+macro_rules! declare_number_value_conversion {
+    ( $($ty:ty),* ) => {
+        $(
+            impl From<$ty> for Value {
+                fn from(v: $ty) -> Self {
+                    Value::Number(v as _)
+                }
+            }
+            impl TryFrom<Value> for $ty {
+                type Error = Value;
+                fn try_from(v: Value) -> Result<$ty, Self::Error> {
+                    match v {
+                        Value::Number(x) => Ok(x as _),
+                        Value::Long(x) => Ok(x as _),
+                        _ => Err(v)
+                    }
+                }
+            }
+        )*
+    };
+}
+declare_number_value_conversion!(u32, u64, i32, i64, f32, f64, usize, isize);
 declare_value_conversion!(String => [SharedString] );
 declare_value_conversion!(Bool => [bool] );
 declare_value_conversion!(Image => [Image] );
@@ -242,6 +294,7 @@ fn try_from(v: Value) -> Result<$ty, Self::Error> {
 declare_value_conversion!(EasingCurve => [i_slint_core::animations::EasingCurve]);
 declare_value_conversion!(LayoutCache => [SharedVector<f32>] );
 declare_value_conversion!(ComponentFactory => [ComponentFactory] );
+declare_value_conversion!(Map => [SharedMap<Value>] );
 
 /// Implement From / TryFrom for Value that convert a `struct` to/from `Value::Struct`
 macro_rules! declare_value_struct_conversion {
@@ -719,9 +772,12 @@ pub struct Compiler {
 
 impl Default for Compiler {
     fn default() -> Self {
-        let config = i_slint_compiler::CompilerConfiguration::new(
+        let mut config = i_slint_compiler::CompilerConfiguration::new(
             i_slint_compiler::generator::OutputFormat::Interpreter,
         );
+        // The interpreter loads the images at run-time and doesn't support embedded data, even when
+        // the environment asks to embed resources (which is the case when live-reloading from a build script)
+        config.embed_resources = i_slint_compiler::EmbedResourcesKind::Nothing;
         Self { config }
     }
 }
@@ -1234,7 +1290,7 @@ pub fn print_diagnostics(diagnostics: &[Diagnostic]) {
 /// An instance can be put on screen with the [`ComponentInstance::run`] function.
 #[repr(C)]
 pub struct ComponentInstance {
-    inner: crate::dynamic_item_tree::DynamicComponentVRc,
+    pub(crate) inner: crate::dynamic_item_tree::DynamicComponentVRc,
 }
 
 impl ComponentInstance {
@@ -1308,6 +1364,7 @@ pub fn set_property(&self, name: &str, value: Value) -> Result<(), SetPropertyEr
 
     /// Set a handler for the callback with the given name. A callback with that
     /// name must be defined in the document otherwise an error will be returned.
+    /// The handler of an `async` callback must be set with [`Self::set_async_callback`] instead.
     ///
     /// Note: Since the [`ComponentInstance`] holds the handler, the handler itself should not
     /// contain a strong reference to the instance. So if you need to capture the instance,
@@ -1345,10 +1402,73 @@ pub fn set_callback(
         name: &str,
         callback: impl Fn(&[Value]) -> Value + 'static,
     ) -> Result<(), SetCallbackError> {
+        let name = normalize_identifier(name);
+        generativity::make_guard!(guard);
+        let comp = self.inner.unerase(guard);
+        if comp.description().is_async_callback(None, &name) {
+            return Err(SetCallbackError::AsyncCallback);
+        }
+        comp.description()
+            .set_callback_handler(comp.borrow(), &name, Box::new(callback))
+            .map_err(|()| SetCallbackError::NoSuchCallback)
+    }
+
+    /// Set a handler for the `async` callback with the given name. An async callback with that
+    /// name must be defined in the document otherwise an error will be returned.
+    ///
+    /// The future returned by the handler runs in the event loop. While it runs, the
+    /// `<name>-pending` property is true, and its output is then stored in the `<name>-result`
+    /// property. A new call of the callback aborts the future of the previous call.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// # i_slint_backend_testing::init_integration_test_with_mock_time();
+    /// use slint_interpreter::{Compiler, Value, SharedString, ComponentHandle};
+    /// use core::convert::TryInto;
+    /// let code = r#"
+    ///     export component MyWin inherits Window {
+    ///         async callback load(int) -> string;
+    ///         out property <string> status: load-pending ? "loading" : load-result;
+    ///     }
+    /// "#;
+    /// let result = spin_on::spin_on(
+    ///     Compiler::default().build_from_source(code.into(), Default::default()));
+    /// assert_eq!(result.diagnostics().count(), 0, "{:?}", result.diagnostics().collect::<Vec<_>>());
+    /// let instance = result.component("MyWin").unwrap().create().unwrap();
+    /// instance.set_async_callback("load", |args: &[Value]| {
+    ///     let id: i32 = args[0].clone().try_into().unwrap();
+    ///     async move { Value::from(SharedString::from(format!("item {id}"))) }
+    /// }).unwrap();
+    ///
+    /// instance.invoke("load", &[Value::from(42)]).unwrap();
+    /// assert_eq!(instance.get_property("status").unwrap(), Value::from(SharedString::from("loading")));
+    /// slint_interpreter::spawn_local(async { slint_interpreter::quit_event_loop().unwrap() }).unwrap();
+    /// slint_interpreter::run_event_loop().unwrap();
+    /// assert_eq!(instance.get_property("status").unwrap(), Value::from(SharedString::from("item 42")));
+    /// ```
+    pub fn set_async_callback<F: Future<Output = Value> + 'static>(
+        &self,
+        name: &str,
+        callback: impl Fn(&[Value]) -> F + 'static,
+    ) -> Result<(), SetCallbackError> {
+        let name = normalize_identifier(name);
         generativity::make_guard!(guard);
         let comp = self.inner.unerase(guard);
+        if !comp.description().is_async_callback(None, &name) {
+            return Err(SetCallbackError::NoSuchCallback);
+        }
+        let self_weak = self.as_weak();
+        let handler = async_callback_handler(&name, callback, move |property, value| {
+            if let Some(instance) = self_weak.upgrade() {
+                generativity::make_guard!(guard);
+                let comp = instance.inner.unerase(guard);
+                // The properties are output properties, so don't go through set_property
+                comp.description().set_property(comp.borrow(), property, value).ok();
+            }
+        });
         comp.description()
-            .set_callback_handler(comp.borrow(), &normalize_identifier(name), Box::new(callback))
+            .set_callback_handler(comp.borrow(), &name, Box::new(handler))
             .map_err(|()| SetCallbackError::NoSuchCallback)
     }
 
@@ -1419,9 +1539,40 @@ pub fn set_global_property(
             .set_property(&normalize_identifier(property), value)
     }
 
+    /// Returns a snapshot of the state of this instance, as JSON.
+    ///
+    /// The snapshot contains the values of the public and private properties of the instance, of
+    /// the instances of its repeaters and of its globals, that changed since the instance was
+    /// created, such as the text typed in a `TextInput` or the content of a modified model.
+    /// Properties that have a binding are not part of it.
+    ///
+    /// Pass it to [`Self::restore_snapshot`] of an instance of a new version of the component
+    /// to keep the state of the UI across a reload.
+    #[doc(hidden)]
+    #[cfg(feature = "internal-json")]
+    pub fn snapshot(&self) -> serde_json::Value {
+        generativity::make_guard!(guard);
+        let comp = self.inner.unerase(guard);
+        crate::snapshot::snapshot(&comp)
+    }
+
+    /// Restore a snapshot returned by [`Self::snapshot`].
+    ///
+    /// The values of the properties that don't exist in this instance, that can't be converted
+    /// to the type of the property, or that have a binding in this instance, are ignored.
+    #[doc(hidden)]
+    #[cfg(feature = "internal-json")]
+    pub fn restore_snapshot(&self, snapshot: &serde_json::Value) {
+        generativity::make_guard!(guard);
+        let comp = self.inner.unerase(guard);
+        crate::snapshot::restore(&comp, snapshot);
+    }
+
     /// Set a handler for the callback in the exported global singleton. A callback with that
     /// name must be defined in the specified global and the global must be exported from the
     /// main document otherwise an error will be returned.
+    /// The handler of an `async` callback must be set with [`Self::set_global_async_callback`]
+    /// instead.
     ///
     /// ## Examples
     ///
@@ -1461,6 +1612,9 @@ pub fn set_global_callback(
     ) -> Result<(), SetCallbackError> {
         generativity::make_guard!(guard);
         let comp = self.inner.unerase(guard);
+        if comp.description().is_async_callback(Some(global), name) {
+            return Err(SetCallbackError::AsyncCallback);
+        }
         comp.description()
             .get_global(comp.borrow(), &normalize_identifier(global))
             .map_err(|()| SetCallbackError::NoSuchCallback)? // FIXME: should there be a NoSuchGlobal error?
@@ -1469,6 +1623,37 @@ pub fn set_global_callback(
             .map_err(|()| SetCallbackError::NoSuchCallback)
     }
 
+    /// Set a handler for the `async` callback in the exported global singleton.
+    ///
+    /// See [`Self::set_async_callback`] for how the future returned by the handler is run.
+    pub fn set_global_async_callback<F: Future<Output = Value> + 'static>(
+        &self,
+        global: &str,
+        name: &str,
+        callback: impl Fn(&[Value]) -> F + 'static,
+    ) -> Result<(), SetCallbackError> {
+        let global = normalize_identifier(global);
+        let name = normalize_identifier(name);
+        generativity::make_guard!(guard);
+        let comp = self.inner.unerase(guard);
+        if !comp.description().is_async_callback(Some(&global), &name) {
+            return Err(SetCallbackError::NoSuchCallback);
+        }
+        let self_weak = self.as_weak();
+        let global_name = global.to_string();
+        let handler = async_callback_handler(&name, callback, move |property, value| {
+            if let Some(instance) = self_weak.upgrade() {
+                instance.set_global_property(&global_name, property, value).ok();
+            }
+        });
+        comp.description()
+            .get_global(comp.borrow(), &global)
+            .map_err(|()| SetCallbackError::NoSuchCallback)?
+            .as_ref()
+            .set_callback_handler(&name, Box::new(handler))
+            .map_err(|()| SetCallbackError::NoSuchCallback)
+    }
+
     /// Call the given callback or function within a global singleton with the arguments
     ///
     /// ## Examples
@@ -1634,6 +1819,10 @@ pub enum SetCallbackError {
     /// There is no callback with the given name
     #[display("no such callback")]
     NoSuchCallback,
+    /// The callback is declared `async`: its handler must be set with
+    /// [`ComponentInstance::set_async_callback`] or [`ComponentInstance::set_global_async_callback`]
+    #[display("the callback is async, which is not supported by this API")]
+    AsyncCallback,
 }
 
 /// Error returned by [`ComponentInstance::invoke`]
@@ -1652,6 +1841,32 @@ pub fn run_event_loop() -> Result<(), PlatformError> {
     i_slint_backend_selector::with_platform(|b| b.run_event_loop())
 }
 
+/// Wraps the handler of an async callback in a handler that spawns the future it returns,
+/// and that reports its state through the `-pending` and `-result` properties.
+fn async_callback_handler<F: Future<Output = Value> + 'static>(
+    name: &str,
+    callback: impl Fn(&[Value]) -> F + 'static,
+    set_property: impl Fn(&str, Value) + 'static,
+) -> impl Fn(&[Value]) -> Value + 'static {
+    let pending: Rc<str> = format!("{name}-pending").into();
+    let result: Rc<str> = format!("{name}-result").into();
+    let set_property = Rc::new(set_property);
+    let runner = i_slint_core::future::AsyncCallbackRunner::default();
+    move |args| {
+        let (pending, result, set_property) =
+            (pending.clone(), result.clone(), set_property.clone());
+        runner.spawn(callback(args), move |value| {
+            let is_pending = value.is_none();
+            if let Some(value) = value {
+                // There is no result property when the callback doesn't return anything
+                set_property(&result, value);
+            }
+            set_property(&pending, Value::Bool(is_pending));
+        });
+        Value::Void
+    }
+}
+
 /// Spawns a [`Future`] to execute in the Slint event loop.
 ///
 /// See the documentation of `slint::spawn_local()` for more info
@@ -1980,6 +2195,56 @@ fn call_functions() {
     assert_eq!(instance.get_global_property("Gl", "q"), Ok(Value::String("Hello".into())));
 }
 
+#[test]
+fn inherited_async_callbacks() {
+    i_slint_backend_testing::init_no_event_loop();
+    let mut compiler = Compiler::default();
+    compiler.set_style("fluent".into());
+    let definition = spin_on::spin_on(
+        compiler.build_from_source(
+            r#"
+    component Base inherits Window {
+        async callback load(int) -> string;
+        callback changed();
+        out property <string> status: load-pending ? "loading" : load-result;
+        init => {
+            load(42);
+            changed();
+        }
+    }
+    export component Main inherits Base {
+        async callback save();
+    }"#
+            .into(),
+            "".into(),
+        ),
+    )
+    .component("Main")
+    .unwrap();
+    assert_eq!(definition.callbacks().collect::<Vec<_>>(), ["save"]);
+    assert_eq!(
+        definition.properties().collect::<Vec<_>>(),
+        [("save-pending".into(), ValueType::Bool)]
+    );
+    let instance = definition.create().unwrap();
+
+    assert_eq!(
+        instance.set_callback("load", |_| Value::Void),
+        Err(SetCallbackError::AsyncCallback)
+    );
+    assert_eq!(instance.set_async_callback("load", |_| async { Value::Void }), Ok(()));
+    assert_eq!(
+        instance.set_callback("save", |_| Value::Void),
+        Err(SetCallbackError::AsyncCallback)
+    );
+    assert_eq!(instance.set_async_callback("save", |_| async { Value::Void }), Ok(()));
+    assert_eq!(instance.set_callback("changed", |_| Value::Void), Ok(()));
+    assert_eq!(
+        instance.set_async_callback("changed", |_| async { Value::Void }),
+        Err(SetCallbackError::NoSuchCallback)
+    );
+}
+
 #[test]
 fn component_definition_struct_properties() {
     i_slint_backend_testing::init_no_event_loop();
@@ -2087,6 +2352,91 @@ fn check_model(val: Value, r: &[f64]) {
     check_model(instance.get_property("prop").unwrap(), &[]);
 }
 
+#[test]
+fn long_properties() {
+    use i_slint_core::model::*;
+    i_slint_backend_testing::init_no_event_loop();
+    let mut compiler = Compiler::default();
+    compiler.set_style("fluent".into());
+    let comp_def = spin_on::spin_on(
+        compiler.build_from_source(
+            r#"
+    export struct Item {
+        count: long,
+        ratio: float,
+    }
+    export component Dummy {
+        in-out property <long> counter;
+        in-out property <float> ratio;
+        out property <float> half: ratio * 0.5;
+        out property <bool> small: ratio < 3.5;
+        in-out property <Item> item;
+        out property <long> item-count: item.count;
+        out property <float> item-half: item.ratio * 0.5;
+        out property <bool> item-small: item.ratio < 3.5;
+        in-out property <[long]> values;
+        out property <long> first: values[0];
+        out property <bool> first-is-big: values[0] > 4;
+        out property <[long]> repeated: [];
+        pure callback compute() -> long;
+        out property <long> computed: compute();
+        public function identity(value: long) -> long {
+            return value;
+        }
+        out property <length> layout-height: layout.preferred-height;
+        layout := VerticalLayout {
+            for value in values: Rectangle {
+                init => { repeated = [value]; }
+            }
+        }
+    }"#
+            .into(),
+            "".into(),
+        ),
+    )
+    .component("Dummy")
+    .unwrap();
+    let instance = comp_def.create().unwrap();
+
+    assert_eq!(instance.get_property("counter"), Ok(Value::Long(0)));
+
+    // A float property set from a Value::Long
+    instance.set_property("ratio", Value::Long(3)).unwrap();
+    assert_eq!(instance.get_property("ratio").unwrap().value_type(), ValueType::Number);
+    assert_eq!(instance.get_property("half"), Ok(Value::Number(1.5)));
+    assert_eq!(instance.get_property("small"), Ok(Value::Bool(true)));
+
+    let item: Struct =
+        [("count".to_string(), Value::Number(4.)), ("ratio".to_string(), Value::Long(2))]
+            .into_iter()
+            .collect();
+    instance.set_property("item", Value::Struct(item)).unwrap();
+    let Value::Struct(item) = instance.get_property("item").unwrap() else { panic!() };
+    assert_eq!(item.get_field("count").unwrap().value_type(), ValueType::Long);
+    assert_eq!(item.get_field("ratio").unwrap().value_type(), ValueType::Number);
+    assert_eq!(instance.get_property("item-count").unwrap().value_type(), ValueType::Long);
+    assert_eq!(instance.get_property("item-half"), Ok(Value::Number(1.)));
+    assert_eq!(instance.get_property("item-small"), Ok(Value::Bool(true)));
+
+    // A `[long]` model set from Value::Number
+    instance
+        .set_property("values", Value::Model(VecModel::from_slice(&[Value::Number(5.)])))
+        .unwrap();
+    assert_eq!(instance.get_property("first").unwrap().value_type(), ValueType::Long);
+    assert_eq!(instance.get_property("first"), Ok(Value::Long(5)));
+    assert_eq!(instance.get_property("first-is-big"), Ok(Value::Bool(true)));
+    // Instantiate the rows of the repeater
+    instance.get_property("layout-height").unwrap();
+    let Value::Model(repeated) = instance.get_property("repeated").unwrap() else { panic!() };
+    assert_eq!(repeated.row_data(0).unwrap().value_type(), ValueType::Long);
+
+    // The arguments and the return value of the callbacks
+    instance.set_callback("compute", |_| Value::Number(6.)).unwrap();
+    assert_eq!(instance.get_property("computed").unwrap().value_type(), ValueType::Long);
+    let value = instance.invoke("identity", &[Value::Number(7.)]).unwrap();
+    assert_eq!(value.value_type(), ValueType::Long);
+}
+
 #[test]
 fn lang_type_to_value_type() {
     use i_slint_compiler::langtype::Struct as LangStruct;
@@ -86,7 +86,7 @@ pub fn window_adapter_ref(&self) -> Result<&WindowAdapterRc, PlatformError> {
 pub(crate) struct ItemWithinItemTree {
     offset: usize,
     pub(crate) rtti: Rc<ItemRTTI>,
-    elem: ElementRc,
+    pub(crate) elem: ElementRc,
 }
 
 impl ItemWithinItemTree {
@@ -404,7 +404,7 @@ pub struct ItemTreeDescription<'id> {
     pub(crate) popup_menu_description: PopupMenuDescription,
 
     /// The collection of compiled globals
-    compiled_globals: Option<Rc<CompiledGlobalCollection>>,
+    pub(crate) compiled_globals: Option<Rc<CompiledGlobalCollection>>,
 
     /// The type loader, which will be available only on the top-most `ItemTreeDescription`.
     /// All other `ItemTreeDescription`s have `None` here.
@@ -450,6 +450,8 @@ fn internal_properties_to_public<'a>(
                     .and_then(|n| n.child_token(parser::SyntaxKind::Identifier))
             })
             .map(|n| n.to_smolstr())
+            // The properties of an async callback are declared by the node of the callback
+            .filter(|n| crate::normalize_identifier(n) == s.as_str())
             .unwrap_or_else(|| s.to_smolstr());
         (name, v.property_type.clone(), v.visibility)
     })
@@ -519,6 +521,42 @@ impl Iterator<
             .map(|global| internal_properties_to_public(global.public_properties()))
     }
 
+    /// Whether the callback is declared `async`. `global` is the name of the exported global
+    /// that has the callback, or None for a callback of this component.
+    pub fn is_async_callback(&self, global: Option<&str>, name: &str) -> bool {
+        let name = crate::normalize_identifier(name);
+        match global {
+            None => {
+                // Like `Element::lookup_property`, also look in the base types
+                let mut element = self.original.root_element.clone();
+                loop {
+                    let base = {
+                        let e = element.borrow();
+                        if let Some(d) = e.property_declarations.get(name.as_ref()) {
+                            return d.is_async;
+                        }
+                        match &e.base_type {
+                            i_slint_compiler::langtype::ElementType::Component(c) => {
+                                c.root_element.clone()
+                            }
+                            _ => return false,
+                        }
+                    };
+                    element = base;
+                }
+            }
+            Some(global) => {
+                let g = self.compiled_globals.as_ref().expect("Root component should have globals");
+                g.exported_globals_by_name
+                    .get(crate::normalize_identifier(global).as_ref())
+                    .and_then(|global_idx| g.compiled_globals.get(*global_idx))
+                    .is_some_and(|global| {
+                        global.public_properties().any(|(n, d)| n == name.as_ref() && d.is_async)
+                    })
+            }
+        }
+    }
+
     /// Instantiate a runtime ItemTree from this ItemTreeDescription
     pub fn create(
         self: Rc<Self>,
@@ -1227,6 +1265,9 @@ fn property_info_for_type(
             }
             Type::Struct(_) => property_info::<Value>(),
             Type::Array(_) => property_info::<Value>(),
+            Type::Map(_) => property_info::<Value>(),
+            Type::Optional(_) => property_info::<Value>(),
+            Type::Int64 => property_info::<Value>(),
             Type::Easing => property_info::<i_slint_core::animations::EasingCurve>(),
             Type::Percent => animated_property_info::<f32>(),
             Type::Enumeration(e) => {
@@ -1561,7 +1602,7 @@ pub fn instantiate(
     for (prop_name, decl) in &description.original.root_element.borrow().property_declarations {
         if !matches!(
             decl.property_type,
-            Type::Struct { .. } | Type::Array(_) | Type::Enumeration(_)
+            Type::Struct { .. } | Type::Array(_) | Type::Enumeration(_) | Type::Int64
         ) || decl.is_alias.is_some()
         {
             continue;
@@ -1577,6 +1618,19 @@ pub fn instantiate(
             p.prop.set(item, eval::default_value_for_type(&decl.property_type), None).unwrap();
         }
     }
+    // Same for the model data, which is not set when the model has no data for a row
+    if let Some(p) = description.custom_properties.get(SPECIAL_PROPERTY_MODEL_DATA) {
+        let model_ty = Expression::RepeaterModelReference {
+            element: description.original.parent_element.clone(),
+        }
+        .ty();
+        if matches!(model_ty, Type::Struct { .. } | Type::Array(_) | Type::Enumeration(_)) {
+            unsafe {
+                let item = Pin::new_unchecked(&*instance_ref.as_ptr().add(p.offset));
+                p.prop.set(item, eval::default_value_for_type(&model_ty), None).unwrap();
+            }
+        }
+    }
 
     generator::handle_property_bindings_init(
         &description.original,
@@ -1853,6 +1907,19 @@ pub fn get_repeater_by_name<'a, 'id>(
     (rep_in_comp.offset.apply_pin(instance_ref.instance), rep_in_comp.item_tree_to_repeat.clone())
 }
 
+/// Same as [`get_repeater_by_name`], but make sure that the instances of the repeater are created
+#[cfg(feature = "internal-json")]
+pub(crate) fn get_updated_repeater_by_name<'a>(
+    instance_ref: InstanceRef<'a, '_>,
+    name: &str,
+) -> std::pin::Pin<&'a Repeater<ErasedItemTreeBox>> {
+    generativity::make_guard!(guard);
+    let rep_index = instance_ref.description.repeater_names[name];
+    let rep_in_comp = instance_ref.description.repeater[rep_index].unerase(guard);
+    ensure_repeater_updated(instance_ref, rep_in_comp);
+    rep_in_comp.offset.apply_pin(instance_ref.instance)
+}
+
 extern "C" fn layout_info(component: ItemTreeRefPin, orientation: Orientation) -> LayoutInfo {
     generativity::make_guard!(guard);
     // This is fine since we can only be called with a component that with our vtable which is a ItemTreeDescription
@@ -1898,18 +1965,26 @@ extern "C" fn layout_info(component: ItemTreeRefPin, orientation: Orientation) -
     }
 }
 
+/// The index in the item tree of the `ComponentContainer` that holds the dynamic tree `index`
+fn container_item_index(component: ItemTreeRefPin, index: u32) -> u32 {
+    component
+        .as_ref()
+        .get_item_tree()
+        .iter()
+        .find_map(|node| match node {
+            ItemTreeNode::DynamicTree { index: i, parent_index } if *i == index => {
+                Some(*parent_index)
+            }
+            _ => None,
+        })
+        .unwrap_or(u32::MAX)
+}
+
 extern "C" fn get_subtree_range(component: ItemTreeRefPin, index: u32) -> IndexRange {
     generativity::make_guard!(guard);
     let instance_ref = unsafe { InstanceRef::from_pin_ref(component, guard) };
     if index as usize >= instance_ref.description.repeater.len() {
-        let container_index = {
-            let tree_node = &component.as_ref().get_item_tree()[index as usize];
-            if let ItemTreeNode::DynamicTree { parent_index, .. } = tree_node {
-                *parent_index
-            } else {
-                u32::MAX
-            }
-        };
+        let container_index = container_item_index(component, index);
         let container = component.as_ref().get_item_ref(container_index);
         let container = i_slint_core::items::ItemRef::downcast_pin::<
             i_slint_core::items::ComponentContainer,
@@ -1936,14 +2011,7 @@ extern "C" fn get_subtree(
     generativity::make_guard!(guard);
     let instance_ref = unsafe { InstanceRef::from_pin_ref(component, guard) };
     if index as usize >= instance_ref.description.repeater.len() {
-        let container_index = {
-            let tree_node = &component.as_ref().get_item_tree()[index as usize];
-            if let ItemTreeNode::DynamicTree { parent_index, .. } = tree_node {
-                *parent_index
-            } else {
-                u32::MAX
-            }
-        };
+        let container_index = container_item_index(component, index);
         let container = component.as_ref().get_item_ref(container_index);
         let container = i_slint_core::items::ItemRef::downcast_pin::<
             i_slint_core::items::ComponentContainer,
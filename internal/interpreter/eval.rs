@@ -144,6 +144,7 @@ pub fn eval_expression(expression: &Expression, local_context: &mut EvalLocalCon
         Expression::StringLiteral(s) => Value::String(s.as_str().into()),
         Expression::NumberLiteral(n, unit) => Value::Number(unit.normalize(*n)),
         Expression::BoolLiteral(b) => Value::Bool(*b),
+        Expression::NullLiteral(_) => Value::Void,
         Expression::ElementReference(_) => todo!("Element references are only supported in the context of built-in function calls at the moment"),
         Expression::PropertyReference(nr) => {
             load_property_helper(&local_context.component_instance, &nr.element(), nr.name()).unwrap()
@@ -153,13 +154,14 @@ pub fn eval_expression(expression: &Expression, local_context: &mut EvalLocalCon
             crate::dynamic_item_tree::SPECIAL_PROPERTY_INDEX,
         )
         .unwrap(),
-        Expression::RepeaterModelReference { element } => load_property_helper(&local_context.component_instance,
+        Expression::RepeaterModelReference { element } => convert_numbers(load_property_helper(&local_context.component_instance,
             &element.upgrade().unwrap().borrow().base_type.as_component().root_element,
             crate::dynamic_item_tree::SPECIAL_PROPERTY_MODEL_DATA,
         )
-        .unwrap(),
-        Expression::FunctionParameterReference { index, .. } => {
-            local_context.function_arguments[*index].clone()
+        .unwrap(), &expression.ty()),
+        Expression::FunctionParameterReference { index, ty } => {
+            // The arguments can come from the native code
+            convert_numbers(local_context.function_arguments[*index].clone(), ty)
         }
         Expression::StructFieldAccess { base, name } => {
             if let Value::Struct(o) = eval_expression(base, local_context) {
@@ -173,17 +175,98 @@ pub fn eval_expression(expression: &Expression, local_context: &mut EvalLocalCon
             let index = eval_expression(index, local_context);
             match (array, index) {
                 (Value::Model(model), Value::Number(index)) => {
-                    model.row_data_tracked(index as usize).unwrap_or_else(|| default_value_for_type(&expression.ty()))
+                    model.row_data_tracked(index as usize).map_or_else(|| default_value_for_type(&expression.ty()), |v| convert_numbers(v, &expression.ty()))
                 }
                 _ => {
                     Value::Void
                 }
             }
         }
+        Expression::ArrayMap { array, item, body } => {
+            let rows = model_rows_tracked(&eval_expression(array, local_context));
+            let result = map_rows_as_local_variable(rows, item, local_context, |_, local_context| {
+                eval_expression(body, local_context)
+            });
+            Value::Model(ModelRc::new(corelib::model::SharedVectorModel::from(
+                result.into_iter().collect::<SharedVector<_>>(),
+            )))
+        }
+        Expression::ArrayFilter { array, item, condition } => {
+            let rows = model_rows_tracked(&eval_expression(array, local_context));
+            let result = map_rows_as_local_variable(rows, item, local_context, |row, local_context| {
+                (eval_expression(condition, local_context) == Value::Bool(true)).then_some(row)
+            });
+            Value::Model(ModelRc::new(corelib::model::SharedVectorModel::from(
+                result.into_iter().flatten().collect::<SharedVector<_>>(),
+            )))
+        }
+        Expression::ArraySlice { array, start, end } => {
+            let rows = model_rows_tracked(&eval_expression(array, local_context));
+            let start: i32 = eval_expression(start, local_context).try_into().unwrap();
+            let end: i32 = eval_expression(end, local_context).try_into().unwrap();
+            let len = rows.len() as i32;
+            let clamp = |i: i32| (if i < 0 { (len + i).max(0) } else { i.min(len) }) as usize;
+            let (start, end) = (clamp(start), clamp(end));
+            Value::Model(ModelRc::new(corelib::model::SharedVectorModel::from(
+                rows.get(start..end.max(start)).unwrap_or_default().iter().cloned().collect::<SharedVector<_>>(),
+            )))
+        }
+        Expression::ArrayConcat { lhs, rhs } => {
+            let mut rows = model_rows_tracked(&eval_expression(lhs, local_context));
+            rows.extend(model_rows_tracked(&eval_expression(rhs, local_context)));
+            Value::Model(ModelRc::new(corelib::model::SharedVectorModel::from(
+                rows.into_iter().collect::<SharedVector<_>>(),
+            )))
+        }
+        Expression::ArrayIndexOf { array, value } => {
+            let rows = model_rows_tracked(&eval_expression(array, local_context));
+            let value = eval_expression(value, local_context);
+            Value::Number(rows.iter().position(|v| *v == value).map_or(-1., |i| i as f64))
+        }
+        Expression::MapIndex { map, key } => {
+            let map = eval_expression(map, local_context);
+            let key = eval_expression(key, local_context);
+            match (map, key) {
+                (Value::Map(map), Value::String(key)) => map.get(&key).cloned(),
+                _ => None,
+            }
+            .unwrap_or_else(|| default_value_for_type(&expression.ty()))
+        }
+        Expression::MapHasKey { map, key } => {
+            let map = eval_expression(map, local_context);
+            let key = eval_expression(key, local_context);
+            Value::Bool(matches!((map, key), (Value::Map(map), Value::String(key)) if map.contains_key(&key)))
+        }
+        Expression::MapEntries(map) => {
+            let Value::Map(map) = eval_expression(map, local_context) else {
+                return Value::Model(Default::default());
+            };
+            Value::Model(ModelRc::new(corelib::model::SharedVectorModel::from(
+                map.iter()
+                    .map(|(key, value)| {
+                        Value::Struct(Struct::from_iter([
+                            ("key".to_string(), Value::String(key.clone())),
+                            ("value".to_string(), value.clone()),
+                        ]))
+                    })
+                    .collect::<SharedVector<_>>(),
+            )))
+        }
+        Expression::OptionalHasValue(optional) => {
+            Value::Bool(!matches!(eval_expression(optional, local_context), Value::Void))
+        }
+        Expression::OptionalUnwrap(optional) => match eval_expression(optional, local_context) {
+            Value::Void => default_value_for_type(&expression.ty()),
+            value => value,
+        },
         Expression::Cast { from, to } => {
             let v = eval_expression(from, local_context);
             match (v, to) {
                 (Value::Number(n), Type::Int32) => Value::Number(n.trunc()),
+                (Value::Number(n), Type::Int64) => Value::Long(n as i64),
+                (Value::Long(n), Type::Int32) => Value::Number((n as i32) as f64),
+                (Value::Long(n), Type::Float32) => Value::Number(n as f64),
+                (Value::Long(n), Type::String) => Value::String(corelib::format!("{n}")),
                 (Value::Number(n), Type::String) => {
                     Value::String(i_slint_core::string::shared_string_from_number(n))
                 }
@@ -209,7 +292,9 @@ pub fn eval_expression(expression: &Expression, local_context: &mut EvalLocalCon
             }
             Callable::Callback(nr) => {
                 let args = arguments.iter().map(|e| eval_expression(e, local_context)).collect::<Vec<_>>();
-                invoke_callback(&local_context.component_instance, &nr.element(), nr.name(), &args).unwrap()
+                let value = invoke_callback(&local_context.component_instance, &nr.element(), nr.name(), &args).unwrap();
+                // The handler can be set by the native code
+                convert_numbers(value, &expression.ty())
             }
             Callable::Builtin(f) => call_builtin_function(f.clone(), arguments, local_context),
         }
@@ -223,6 +308,22 @@ pub fn eval_expression(expression: &Expression, local_context: &mut EvalLocalCon
             let rhs = eval_expression(rhs, local_context);
 
             match (op, lhs, rhs) {
+                ('+' | '-' | '*' | '/', Value::Long(a), Value::Long(b)) => eval_long_operation(*op, a, b),
+                // A `long` that was set from a Value::Number
+                ('+' | '-' | '*' | '/', Value::Long(a), Value::Number(b)) => eval_long_operation(*op, a, b as i64),
+                ('+' | '-' | '*' | '/', Value::Number(a), Value::Long(b)) => eval_long_operation(*op, a as i64, b),
+                ('<', Value::Long(a), Value::Long(b)) => Value::Bool(a < b),
+                ('>', Value::Long(a), Value::Long(b)) => Value::Bool(a > b),
+                ('≤', Value::Long(a), Value::Long(b)) => Value::Bool(a <= b),
+                ('≥', Value::Long(a), Value::Long(b)) => Value::Bool(a >= b),
+                ('<', Value::Long(a), Value::Number(b)) => Value::Bool((a as f64) < b),
+                ('>', Value::Long(a), Value::Number(b)) => Value::Bool((a as f64) > b),
+                ('≤', Value::Long(a), Value::Number(b)) => Value::Bool((a as f64) <= b),
+                ('≥', Value::Long(a), Value::Number(b)) => Value::Bool((a as f64) >= b),
+                ('<', Value::Number(a), Value::Long(b)) => Value::Bool(a < (b as f64)),
+                ('>', Value::Number(a), Value::Long(b)) => Value::Bool(a > (b as f64)),
+                ('≤', Value::Number(a), Value::Long(b)) => Value::Bool(a <= (b as f64)),
+                ('≥', Value::Number(a), Value::Long(b)) => Value::Bool(a >= (b as f64)),
                 ('+', Value::String(mut a), Value::String(b)) => { a.push_str(b.as_str()); Value::String(a) },
                 ('+', Value::Number(a), Value::Number(b)) => Value::Number(a + b),
                 ('+', a @ Value::Struct(_), b @ Value::Struct(_)) => {
@@ -257,6 +358,8 @@ pub fn eval_expression(expression: &Expression, local_context: &mut EvalLocalCon
             match (sub, op) {
                 (Value::Number(a), '+') => Value::Number(a),
                 (Value::Number(a), '-') => Value::Number(-a),
+                (Value::Long(a), '+') => Value::Long(a),
+                (Value::Long(a), '-') => Value::Long(a.wrapping_neg()),
                 (Value::Bool(a), '!') => Value::Bool(!a),
                 (sub, op) => panic!("unsupported {op} {sub:?}"),
             }
@@ -315,6 +418,12 @@ pub fn eval_expression(expression: &Expression, local_context: &mut EvalLocalCon
                 .map(|(k, v)| (k.to_string(), eval_expression(v, local_context)))
                 .collect(),
         ),
+        Expression::Map { values, .. } => Value::Map(
+            values
+                .iter()
+                .map(|(k, v)| (k.as_str(), eval_expression(v, local_context)))
+                .collect(),
+        ),
         Expression::PathData(data)  => {
             Value::PathData(convert_path(data, local_context))
         }
@@ -394,6 +503,39 @@ pub fn eval_expression(expression: &Expression, local_context: &mut EvalLocalCon
     }
 }
 
+/// Returns all the rows of the model, tracking the row count and the rows
+fn model_rows_tracked(model: &Value) -> Vec<Value> {
+    match model {
+        Value::Model(model) => {
+            model.model_tracker().track_row_count_changes();
+            (0..model.row_count()).filter_map(|i| model.row_data_tracked(i)).collect()
+        }
+        _ => vec![],
+    }
+}
+
+/// Call `f` for each row, with the row stored in the local variable `item` (used by `map` and `filter`)
+fn map_rows_as_local_variable<R>(
+    rows: Vec<Value>,
+    item: &SmolStr,
+    local_context: &mut EvalLocalContext,
+    mut f: impl FnMut(Value, &mut EvalLocalContext) -> R,
+) -> Vec<R> {
+    let previous = local_context.local_variables.remove(item);
+    let result = rows
+        .into_iter()
+        .map(|row| {
+            local_context.local_variables.insert(item.clone(), row.clone());
+            f(row, local_context)
+        })
+        .collect();
+    match previous {
+        Some(previous) => local_context.local_variables.insert(item.clone(), previous),
+        None => local_context.local_variables.remove(item),
+    };
+    result
+}
+
 fn call_builtin_function(
     f: BuiltinFunction,
     arguments: &[Expression],
@@ -983,6 +1125,26 @@ fn call_builtin_function(
                 panic!("Argument not a string");
             }
         }
+        BuiltinFunction::StringIsLong => {
+            if arguments.len() != 1 {
+                panic!("internal error: incorrect argument count to StringIsLong")
+            }
+            if let Value::String(s) = eval_expression(&arguments[0], local_context) {
+                Value::Bool(<i64 as core::str::FromStr>::from_str(s.as_str()).is_ok())
+            } else {
+                panic!("Argument not a string");
+            }
+        }
+        BuiltinFunction::StringToLong => {
+            if arguments.len() != 1 {
+                panic!("internal error: incorrect argument count to StringToLong")
+            }
+            if let Value::String(s) = eval_expression(&arguments[0], local_context) {
+                Value::Long(core::str::FromStr::from_str(s.as_str()).unwrap_or(0))
+            } else {
+                panic!("Argument not a string");
+            }
+        }
         BuiltinFunction::StringIsEmpty => {
             if arguments.len() != 1 {
                 panic!("internal error: incorrect argument count to StringIsEmpty")
@@ -1026,6 +1188,55 @@ fn call_builtin_function(
                 panic!("Argument not a string");
             }
         }
+        BuiltinFunction::StringContains => {
+            let s: SharedString = eval_expression(&arguments[0], local_context).try_into().unwrap();
+            let n: SharedString = eval_expression(&arguments[1], local_context).try_into().unwrap();
+            Value::Bool(s.contains(n.as_str()))
+        }
+        BuiltinFunction::StringStartsWith => {
+            let s: SharedString = eval_expression(&arguments[0], local_context).try_into().unwrap();
+            let p: SharedString = eval_expression(&arguments[1], local_context).try_into().unwrap();
+            Value::Bool(s.starts_with(p.as_str()))
+        }
+        BuiltinFunction::StringEndsWith => {
+            let s: SharedString = eval_expression(&arguments[0], local_context).try_into().unwrap();
+            let p: SharedString = eval_expression(&arguments[1], local_context).try_into().unwrap();
+            Value::Bool(s.ends_with(p.as_str()))
+        }
+        BuiltinFunction::StringReplace => {
+            let s: SharedString = eval_expression(&arguments[0], local_context).try_into().unwrap();
+            let from: SharedString =
+                eval_expression(&arguments[1], local_context).try_into().unwrap();
+            let to: SharedString =
+                eval_expression(&arguments[2], local_context).try_into().unwrap();
+            Value::String(s.replace(from.as_str(), to.as_str()).into())
+        }
+        BuiltinFunction::StringTrim => {
+            let s: SharedString = eval_expression(&arguments[0], local_context).try_into().unwrap();
+            Value::String(s.trim().into())
+        }
+        BuiltinFunction::StringSlice => {
+            let s: SharedString = eval_expression(&arguments[0], local_context).try_into().unwrap();
+            let start: i32 = eval_expression(&arguments[1], local_context).try_into().unwrap();
+            let end: i32 = eval_expression(&arguments[2], local_context).try_into().unwrap();
+            Value::String(corelib::string::shared_string_slice(s.as_str(), start, end))
+        }
+        BuiltinFunction::StringSplit => {
+            let s: SharedString = eval_expression(&arguments[0], local_context).try_into().unwrap();
+            let sep: SharedString =
+                eval_expression(&arguments[1], local_context).try_into().unwrap();
+            Value::Model(ModelRc::new(VecModel::from(
+                corelib::string::shared_string_split(s.as_str(), sep.as_str())
+                    .into_iter()
+                    .map(Value::String)
+                    .collect::<Vec<_>>(),
+            )))
+        }
+        BuiltinFunction::StringIndexOf => {
+            let s: SharedString = eval_expression(&arguments[0], local_context).try_into().unwrap();
+            let n: SharedString = eval_expression(&arguments[1], local_context).try_into().unwrap();
+            Value::Number(corelib::string::shared_string_index_of(s.as_str(), n.as_str()) as f64)
+        }
         BuiltinFunction::ColorRgbaStruct => {
             if arguments.len() != 1 {
                 panic!("internal error: incorrect argument count to ColorRGBAComponents")
@@ -1466,6 +1677,17 @@ fn from_index(&self, index: usize) -> Option<SharedString> {
     }
 }
 
+/// The arithmetic on `long`: it wraps on overflow, and a division by 0 is 0, like in the generated code
+fn eval_long_operation(op: char, a: i64, b: i64) -> Value {
+    Value::Long(match op {
+        '+' => a.wrapping_add(b),
+        '-' => a.wrapping_sub(b),
+        '*' => a.wrapping_mul(b),
+        '/' => a.checked_div(b).unwrap_or(0),
+        _ => panic!("unsupported {a:?} {op} {b:?}"),
+    })
+}
+
 fn eval_assignment(lhs: &Expression, op: char, rhs: Value, local_context: &mut EvalLocalContext) {
     let eval = |lhs| match (lhs, &rhs, op) {
         (Value::String(ref mut a), Value::String(b), '+') => {
@@ -1476,6 +1698,9 @@ fn eval_assignment(lhs: &Expression, op: char, rhs: Value, local_context: &mut E
         (Value::Number(a), Value::Number(b), '-') => Value::Number(a - b),
         (Value::Number(a), Value::Number(b), '/') => Value::Number(a / b),
         (Value::Number(a), Value::Number(b), '*') => Value::Number(a * b),
+        (Value::Long(a), Value::Long(b), op) => eval_long_operation(op, a, *b),
+        (Value::Long(a), Value::Number(b), op) => eval_long_operation(op, a, *b as i64),
+        (Value::Number(a), Value::Long(b), op) => eval_long_operation(op, a as i64, *b),
         (lhs, rhs, op) => panic!("unsupported {lhs:?} {op} {rhs:?}"),
     };
     match lhs {
@@ -1639,7 +1864,7 @@ pub fn store_property(
     component_instance: InstanceRef,
     element: &ElementRc,
     name: &str,
-    value: Value,
+    mut value: Value,
 ) -> Result<(), SetPropertyError> {
     generativity::make_guard!(guard);
     match enclosing_component_instance_for_element(
@@ -1673,6 +1898,7 @@ pub fn store_property(
                         if !check_value_type(&value, &orig_decl.property_type) {
                             return Err(SetPropertyError::WrongType);
                         }
+                        value = convert_numbers(value, &orig_decl.property_type);
                     }
                     unsafe {
                         let p = Pin::new_unchecked(&*enclosing_component.as_ptr().add(x.offset));
@@ -1698,6 +1924,30 @@ pub fn store_property(
     Ok(())
 }
 
+/// Convert the numbers of the value to what the evaluation expects for the type: a `Value::Long`
+/// for `long`, and a `Value::Number` for the other numeric types.
+/// The native code can use both variants for any numeric type. The rows of models are converted
+/// when they are read.
+pub(crate) fn convert_numbers(value: Value, ty: &Type) -> Value {
+    match (value, ty) {
+        (Value::Number(n), Type::Int64) => Value::Long(n as i64),
+        (Value::Long(n), ty) if *ty != Type::Int64 => Value::Number(n as f64),
+        (Value::Struct(s), Type::Struct(st)) => Value::Struct(
+            s.iter()
+                .map(|(name, value)| {
+                    let value = match st.fields.get(name) {
+                        Some(ty) => convert_numbers(value.clone(), ty),
+                        None => value.clone(),
+                    };
+                    (name.to_string(), value)
+                })
+                .collect(),
+        ),
+        (value, Type::Optional(inner)) => convert_numbers(value, inner),
+        (value, _) => value,
+    }
+}
+
 /// Return true if the Value can be used for a property of the given type
 fn check_value_type(value: &Value, ty: &Type) -> bool {
     match ty {
@@ -1708,8 +1958,9 @@ fn check_value_type(value: &Value, ty: &Type) -> bool {
         | Type::Callback { .. }
         | Type::Function { .. }
         | Type::ElementReference => panic!("not valid property type"),
-        Type::Float32 => matches!(value, Value::Number(_)),
-        Type::Int32 => matches!(value, Value::Number(_)),
+        Type::Float32 => matches!(value, Value::Number(_) | Value::Long(_)),
+        Type::Int32 => matches!(value, Value::Number(_) | Value::Long(_)),
+        Type::Int64 => matches!(value, Value::Long(_) | Value::Number(_)),
         Type::String => matches!(value, Value::String(_)),
         Type::Color => matches!(value, Value::Brush(_)),
         Type::UnitProduct(_)
@@ -1718,7 +1969,7 @@ fn check_value_type(value: &Value, ty: &Type) -> bool {
         | Type::LogicalLength
         | Type::Rem
         | Type::Angle
-        | Type::Percent => matches!(value, Value::Number(_)),
+        | Type::Percent => matches!(value, Value::Number(_) | Value::Long(_)),
         Type::Image => matches!(value, Value::Image(_)),
         Type::Bool => matches!(value, Value::Bool(_)),
         Type::Model => {
@@ -1733,6 +1984,10 @@ fn check_value_type(value: &Value, ty: &Type) -> bool {
         Type::Struct(s) => {
             matches!(value, Value::Struct(str) if str.iter().all(|(k, v)| s.fields.get(k).is_some_and(|ty| check_value_type(v, ty))))
         }
+        Type::Map(inner) => {
+            matches!(value, Value::Map(m) if m.values().all(|v| check_value_type(v, inner)))
+        }
+        Type::Optional(inner) => matches!(value, Value::Void) || check_value_type(value, inner),
         Type::Enumeration(en) => {
             matches!(value, Value::EnumerationValue(name, _) if name == en.name.as_str())
         }
@@ -2006,6 +2261,7 @@ fn convert_path_element(
 pub fn default_value_for_type(ty: &Type) -> Value {
     match ty {
         Type::Float32 | Type::Int32 => Value::Number(0.),
+        Type::Int64 => Value::Long(0),
         Type::String => Value::String(Default::default()),
         Type::Color | Type::Brush => Value::Brush(Default::default()),
         Type::Duration | Type::Angle | Type::PhysicalLength | Type::LogicalLength | Type::Rem => {
@@ -2021,6 +2277,8 @@ pub fn default_value_for_type(ty: &Type) -> Value {
                 .collect::<Struct>(),
         ),
         Type::Array(_) | Type::Model => Value::Model(Default::default()),
+        Type::Map(_) => Value::Map(Default::default()),
+        Type::Optional(_) => Value::Void,
         Type::Percent => Value::Number(0.),
         Type::Enumeration(e) => Value::EnumerationValue(
             e.name.to_string(),
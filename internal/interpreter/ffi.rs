@@ -46,6 +46,12 @@ pub extern "C" fn slint_interpreter_value_eq(a: &Value, b: &Value) -> bool {
     Box::new(Value::Number(double))
 }
 
+/// Construct a new Value in the given memory location as long
+#[no_mangle]
+pub unsafe extern "C" fn slint_interpreter_value_new_long(long: i64) -> Box<Value> {
+    Box::new(Value::Long(long))
+}
+
 /// Construct a new Value in the given memory location as bool
 #[no_mangle]
 pub unsafe extern "C" fn slint_interpreter_value_new_bool(b: bool) -> Box<Value> {
@@ -112,6 +118,14 @@ pub extern "C" fn slint_interpreter_value_to_number(val: &Value) -> Option<&f64>
     }
 }
 
+#[no_mangle]
+pub extern "C" fn slint_interpreter_value_to_long(val: &Value) -> Option<&i64> {
+    match val {
+        Value::Long(v) => Some(v),
+        _ => None,
+    }
+}
+
 #[no_mangle]
 pub extern "C" fn slint_interpreter_value_to_bool(val: &Value) -> Option<&bool> {
     match val {
@@ -360,12 +374,13 @@ fn call(&self, args: &[Value]) -> Value {
 
     generativity::make_guard!(guard);
     let comp = inst.unerase(guard);
+    let name = normalize_identifier(std::str::from_utf8(&name).unwrap());
+    // The handler of an async callback returns a future, which the C++ API doesn't support
+    if comp.description().is_async_callback(None, &name) {
+        return false;
+    }
     comp.description()
-        .set_callback_handler(
-            comp.borrow(),
-            &normalize_identifier(std::str::from_utf8(&name).unwrap()),
-            Box::new(move |args| ud.call(args)),
-        )
+        .set_callback_handler(comp.borrow(), &name, Box::new(move |args| ud.call(args)))
         .is_ok()
 }
 
@@ -426,14 +441,14 @@ pub extern "C" fn slint_interpreter_component_instance_set_global_property(
 
     generativity::make_guard!(guard);
     let comp = inst.unerase(guard);
+    let global = normalize_identifier(std::str::from_utf8(&global).unwrap());
+    let name = normalize_identifier(std::str::from_utf8(&name).unwrap());
+    if comp.description().is_async_callback(Some(&global), &name) {
+        return false;
+    }
     comp.description()
-        .get_global(comp.borrow(), &normalize_identifier(std::str::from_utf8(&global).unwrap()))
-        .and_then(|g| {
-            g.as_ref().set_callback_handler(
-                &normalize_identifier(std::str::from_utf8(&name).unwrap()),
-                Box::new(move |args| ud.call(args)),
-            )
-        })
+        .get_global(comp.borrow(), &global)
+        .and_then(|g| g.as_ref().set_callback_handler(&name, Box::new(move |args| ud.call(args))))
         .is_ok()
 }
 
@@ -162,6 +162,15 @@ fn set_property(
     fn get_property_ptr(self: Pin<&Self>, prop_name: &SmolStr) -> *const ();
 
     fn eval_function(self: Pin<&Self>, fn_name: &str, args: Vec<Value>) -> Result<Value, ()>;
+
+    /// The snapshot of the state of the global, see [`crate::snapshot`]
+    #[cfg(feature = "internal-json")]
+    fn snapshot(self: Pin<&Self>) -> serde_json::Map<String, serde_json::Value> {
+        Default::default()
+    }
+
+    #[cfg(feature = "internal-json")]
+    fn restore_snapshot(self: Pin<&Self>, _snapshot: &serde_json::Value) {}
 }
 
 /// Instantiate the global singleton and store it in `globals`
@@ -288,6 +297,20 @@ fn eval_function(self: Pin<&Self>, fn_name: &str, args: Vec<Value>) -> Result<Va
         );
         Ok(result)
     }
+
+    #[cfg(feature = "internal-json")]
+    fn snapshot(self: Pin<&Self>) -> serde_json::Map<String, serde_json::Value> {
+        generativity::make_guard!(guard);
+        let comp = self.0.unerase(guard);
+        crate::snapshot::snapshot_item_tree(comp.borrow_instance())
+    }
+
+    #[cfg(feature = "internal-json")]
+    fn restore_snapshot(self: Pin<&Self>, snapshot: &serde_json::Value) {
+        generativity::make_guard!(guard);
+        let comp = self.0.unerase(guard);
+        crate::snapshot::restore_item_tree(comp.borrow_instance(), snapshot);
+    }
 }
 
 impl<T: rtti::BuiltinItem + 'static> GlobalComponent for T {
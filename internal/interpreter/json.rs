@@ -55,9 +55,17 @@ fn string_to_color(s: &str) -> Option<i_slint_core::Color> {
         i_slint_compiler::literals::parse_color_literal(s).map(Color::from_argb_encoded)
     }
 
+    if let langtype::Type::Optional(inner) = t {
+        // A null optional is a Value::Void
+        return if v.is_null() { Ok(Value::Void) } else { value_from_json(inner, v) };
+    }
+
     match v {
         serde_json::Value::Null => Ok(Value::Void),
         serde_json::Value::Bool(b) => Ok((*b).into()),
+        serde_json::Value::Number(n) if *t == langtype::Type::Int64 => {
+            n.as_i64().map(Value::Long).ok_or_else(|| format!("Could not convert {n} into a long"))
+        }
         serde_json::Value::Number(n) => Ok(Value::Number(n.as_f64().unwrap_or(f64::NAN))),
         serde_json::Value::String(s) => match t {
             langtype::Type::Enumeration(e) => {
@@ -193,6 +201,11 @@ fn parse_stops<'a>(
                     .collect::<Result<HashMap<String, Value>, _>>()?,
             )
             .into()),
+            langtype::Type::Map(value_type) => Ok(Value::Map(
+                obj.iter()
+                    .map(|(k, v)| value_from_json(value_type, v).map(|v| (k.as_str(), v)))
+                    .collect::<Result<_, String>>()?,
+            )),
             _ => Err("Got a struct where none was expected".into()),
         },
     }
@@ -249,6 +262,7 @@ fn gradient_to_string_helper<'a>(
                 Err(format!("Could not convert {n} into a number"))
             }
         }
+        Value::Long(n) => Ok(serde_json::Value::Number((*n).into())),
         Value::EnumerationValue(e, v) => Ok(serde_json::Value::String(format!("{e}.{v}"))),
         Value::String(shared_string) => Ok(serde_json::Value::String(shared_string.to_string())),
         Value::Image(image) => {
@@ -266,6 +280,11 @@ fn gradient_to_string_helper<'a>(
                 .map(|(k, v)| v.to_json().map(|v| (k.to_string(), v)))
                 .collect::<Result<serde_json::Map<_, _>, _>>()?,
         )),
+        Value::Map(m) => Ok(serde_json::Value::Object(
+            m.iter()
+                .map(|(k, v)| v.to_json().map(|v| (k.to_string(), v)))
+                .collect::<Result<serde_json::Map<_, _>, _>>()?,
+        )),
         Value::Brush(brush) => match brush {
             Brush::SolidColor(color) => Ok(serde_json::Value::String(color_to_string(color))),
             Brush::LinearGradient(lg) => Ok(gradient_to_string_helper(
@@ -288,6 +307,80 @@ pub fn value_to_json_string(value: &Value) -> Result<String, String> {
     Ok(value_to_json(value)?.to_string())
 }
 
+/// Create a JSON Schema describing the JSON values of the type `t`, as read by [`value_from_json`].
+///
+/// Named structs and enumerations are described once in `defs`, and referenced as
+/// `#/$defs/<name>`. Returns `None` for types that have no JSON representation, such as callbacks.
+pub fn type_to_json_schema(
+    t: &langtype::Type,
+    defs: &mut serde_json::Map<String, serde_json::Value>,
+) -> Option<serde_json::Value> {
+    use serde_json::json;
+    Some(match t {
+        langtype::Type::Int32 | langtype::Type::Int64 => json!({ "type": "integer" }),
+        langtype::Type::Float32
+        | langtype::Type::Duration
+        | langtype::Type::PhysicalLength
+        | langtype::Type::LogicalLength
+        | langtype::Type::Rem
+        | langtype::Type::Angle
+        | langtype::Type::Percent
+        | langtype::Type::UnitProduct(_) => json!({ "type": "number" }),
+        langtype::Type::Bool => json!({ "type": "boolean" }),
+        langtype::Type::String => json!({ "type": "string" }),
+        langtype::Type::Color => json!({
+            "type": "string",
+            "pattern": "^#([0-9a-fA-F]{3,4}|[0-9a-fA-F]{6}|[0-9a-fA-F]{8})$",
+        }),
+        langtype::Type::Brush => json!({
+            "type": "string",
+            "description": "A color such as #ff0000, or a gradient such as @linear-gradient(90deg, #ff0000 0%, #0000ff 100%)",
+        }),
+        langtype::Type::Image => {
+            json!({ "type": "string", "description": "The path of an image file" })
+        }
+        langtype::Type::Array(item) => {
+            json!({ "type": "array", "items": type_to_json_schema(item, defs)? })
+        }
+        langtype::Type::Map(value) => {
+            json!({ "type": "object", "additionalProperties": type_to_json_schema(value, defs)? })
+        }
+        langtype::Type::Optional(inner) => {
+            json!({ "anyOf": [type_to_json_schema(inner, defs)?, { "type": "null" }] })
+        }
+        langtype::Type::Struct(s) => {
+            let mut properties = serde_json::Map::new();
+            if let Some(name) = &s.name {
+                if defs.contains_key(name.as_str()) {
+                    return Some(json!({ "$ref": format!("#/$defs/{name}") }));
+                }
+                // Insert a placeholder first for recursive types
+                defs.insert(name.to_string(), serde_json::Value::Null);
+            }
+            for (field, field_type) in &s.fields {
+                if let Some(schema) = type_to_json_schema(field_type, defs) {
+                    properties.insert(field.to_string(), schema);
+                }
+            }
+            let schema = json!({ "type": "object", "properties": properties, "additionalProperties": false });
+            match &s.name {
+                Some(name) => {
+                    defs.insert(name.to_string(), schema);
+                    json!({ "$ref": format!("#/$defs/{name}") })
+                }
+                None => schema,
+            }
+        }
+        langtype::Type::Enumeration(e) => {
+            defs.entry(e.name.to_string()).or_insert_with(
+                || json!({ "enum": e.values.iter().map(|v| v.as_str()).collect::<Vec<_>>() }),
+            );
+            json!({ "$ref": format!("#/$defs/{}", e.name) })
+        }
+        _ => return None,
+    })
+}
+
 #[test]
 fn test_from_json() {
     let v = value_from_json_str(&langtype::Type::Void, "null").unwrap();
@@ -301,6 +394,9 @@ fn test_from_json() {
     let v = value_from_json_str(&langtype::Type::Int32, "23").unwrap();
     assert_eq!(v, Value::Number(23.0));
 
+    let v = value_from_json_str(&langtype::Type::Int64, "9007199254740993").unwrap();
+    assert!(matches!(v, Value::Long(9007199254740993)));
+
     let v = value_from_json_str(&langtype::Type::String, "\"a string with \\\\ escape\"").unwrap();
     assert_eq!(v, Value::String("a string with \\ escape".into()));
 
@@ -445,6 +541,9 @@ fn test_to_json() {
     let v = value_to_json_string(&Value::Number(4.2)).unwrap();
     assert_eq!(&v, "4.2");
 
+    let v = value_to_json_string(&Value::Long(-9007199254740993)).unwrap();
+    assert_eq!(&v, "-9007199254740993");
+
     let v = value_to_json_string(&Value::EnumerationValue("Foo".to_string(), "bar".to_string()))
         .unwrap();
     assert_eq!(&v, "\"Foo.bar\"");
@@ -488,6 +587,13 @@ fn test_to_json() {
     .unwrap();
     assert_eq!(&v, "{\"is-bool\":false,\"kind\":\"test.foo\",\"string-value\":\"some string\"}");
 
+    let v = value_to_json_string(&Value::Map(crate::SharedMap::from([
+        ("b", Value::Number(2.0)),
+        ("a", Value::Number(1.0)),
+    ])))
+    .unwrap();
+    assert_eq!(&v, "{\"a\":1,\"b\":2}");
+
     let v = value_to_json_string(&Value::Brush(Brush::SolidColor(Color::from_argb_u8(
         0xff, 0x0a, 0xb0, 0xcd,
     ))))
@@ -539,3 +645,54 @@ fn test_to_json() {
     .unwrap();
     assert_eq!(&v, "\"@radial-gradient(circle, #ff0000ff 0%, #00ff00ff 50%, #0000ffff 100%)\"");
 }
+
+#[test]
+fn test_json_schema() {
+    use std::rc::Rc;
+
+    let mut defs = serde_json::Map::new();
+    let enumeration = Rc::new(langtype::Enumeration {
+        name: "Mode".into(),
+        values: vec!["light".into(), "dark".into()],
+        default_value: 0,
+        node: None,
+    });
+    let point = langtype::Type::Struct(Rc::new(langtype::Struct {
+        fields: [
+            ("x".into(), langtype::Type::Int32),
+            ("mode".into(), langtype::Type::Enumeration(enumeration)),
+        ]
+        .into_iter()
+        .collect(),
+        name: Some("Point".into()),
+        node: None,
+        rust_attributes: None,
+    }));
+    let schema = type_to_json_schema(&langtype::Type::Array(Rc::new(point)), &mut defs);
+    assert_eq!(
+        schema,
+        Some(serde_json::json!({ "type": "array", "items": { "$ref": "#/$defs/Point" } }))
+    );
+    assert_eq!(
+        serde_json::Value::Object(defs),
+        serde_json::json!({
+            "Mode": { "enum": ["light", "dark"] },
+            "Point": {
+                "type": "object",
+                "properties": {
+                    "mode": { "$ref": "#/$defs/Mode" },
+                    "x": { "type": "integer" },
+                },
+                "additionalProperties": false,
+            },
+        })
+    );
+
+    let mut defs = serde_json::Map::new();
+    assert_eq!(
+        type_to_json_schema(&langtype::Type::LogicalLength, &mut defs),
+        Some(serde_json::json!({ "type": "number" }))
+    );
+    assert_eq!(type_to_json_schema(&langtype::Type::ElementReference, &mut defs), None);
+    assert!(defs.is_empty());
+}
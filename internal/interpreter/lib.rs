@@ -88,6 +88,11 @@
 pub mod highlight;
 #[cfg(feature = "internal-json")]
 pub mod json;
+#[cfg(feature = "internal-live-reload")]
+#[doc(hidden)]
+pub mod live_reload;
+#[cfg(feature = "internal-json")]
+mod snapshot;
 mod value_model;
 
 #[doc(inline)]
@@ -99,7 +104,7 @@
 
 /// (Re-export from corelib.)
 #[doc(inline)]
-pub use i_slint_core::{Brush, Color, SharedString, SharedVector};
+pub use i_slint_core::{Brush, Color, SharedMap, SharedString, SharedVector};
 
 #[cfg(test)]
 mod tests;
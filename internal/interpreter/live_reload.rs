@@ -0,0 +1,603 @@
+// Copyright © SixtyFPS GmbH <info@slint.dev>
+// SPDX-License-Identifier: GPL-3.0-only OR LicenseRef-Slint-Royalty-free-2.0 OR LicenseRef-Slint-Software-3.0
+
+//! Runtime support for the code generated by the Rust code generator in live reload mode.
+//!
+//! In that mode, the generated component is a [`LiveReloadingComponent`] that loads the `.slint`
+//! files with the interpreter, watches them, and replaces the instance when they change. The
+//! state of the old instance is restored in the new one, and the callbacks that were set from
+//! Rust are installed again.
+//! The code of a `slint!` macro is loaded from the Rust file that contains the macro.
+//!
+//! The generated code holds a `VRc<ItemTreeVTable, LiveReloadingComponent>` so that
+//! `slint::Weak` keeps pointing to the same component across reloads. This item tree is
+//! never shown: the window shows the item tree of the current instance.
+
+pub use crate::api::{Compiler, Struct, Value};
+
+use crate::api::{CompilationResult, ComponentDefinition, ComponentInstance};
+use i_slint_core::accessibility::{
+    AccessibilityAction, AccessibleStringProperty, SupportedAccessibilityAction,
+};
+use i_slint_core::api::{ComponentHandle, PlatformError, Window};
+use i_slint_core::item_tree::{
+    IndexRange, ItemTree, ItemTreeNode, ItemTreeVTable, ItemTreeWeak, ItemVisitorRefMut,
+    ItemVisitorVTable, ItemWeak, TraversalOrder, VisitChildrenResult,
+};
+use i_slint_core::items::{AccessibleRole, ItemRef, ItemVTable};
+use i_slint_core::layout::{LayoutInfo, Orientation};
+use i_slint_core::lengths::LogicalRect;
+use i_slint_core::model::{Model, ModelRc, ModelTracker};
+use i_slint_core::slice::Slice;
+use i_slint_core::window::WindowAdapterRc;
+use i_slint_core::SharedString;
+use smol_str::SmolStr;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::pin::Pin;
+use std::rc::Rc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, OnceLock};
+use vtable::{VRc, VWeak};
+
+type CallbackHandler = Rc<dyn Fn(&[Value]) -> Value>;
+type AsyncCallbackHandler = Rc<dyn Fn(&[Value]) -> Pin<Box<dyn Future<Output = Value>>>>;
+
+#[derive(Clone)]
+enum Handler {
+    Sync(CallbackHandler),
+    Async(AsyncCallbackHandler),
+}
+
+/// A component instance that is re-created when the `.slint` files it was loaded from change
+pub struct LiveReloadingComponent {
+    compiler: Compiler,
+    file_name: PathBuf,
+    /// The line of the `slint!` macro, if `file_name` is the Rust file that contains it
+    macro_line: Option<u32>,
+    component_name: String,
+    instance: RefCell<ComponentInstance>,
+    /// The handlers set from Rust, by global name (empty for the component) and callback name
+    callbacks: RefCell<HashMap<(SmolStr, SmolStr), Handler>>,
+    window_adapter: WindowAdapterRc,
+    /// A reference to this component while its window is shown, as the window only keeps
+    /// the current instance alive
+    shown: RefCell<Option<VRc<ItemTreeVTable, LiveReloadingComponent>>>,
+}
+
+impl LiveReloadingComponent {
+    /// Compile the file with the compiler and create an instance of the component
+    pub fn new(
+        compiler: Compiler,
+        file_name: PathBuf,
+        component_name: String,
+    ) -> Result<VRc<ItemTreeVTable, Self>, PlatformError> {
+        Self::new_impl(compiler, file_name, None, component_name)
+    }
+
+    /// Compile the `slint!` macro invoked at the given line of a Rust file, and create an
+    /// instance of the component. `file` is the path returned by `file!()`, which can be
+    /// relative to the directory of the workspace that contains `manifest_dir`.
+    pub fn new_from_rust_macro(
+        compiler: Compiler,
+        manifest_dir: &str,
+        file: &str,
+        line: u32,
+        component_name: String,
+    ) -> Result<VRc<ItemTreeVTable, Self>, PlatformError> {
+        let file_name = Path::new(manifest_dir)
+            .ancestors()
+            .map(|dir| dir.join(file))
+            .find(|path| path.exists())
+            .unwrap_or_else(|| file.into());
+        Self::new_impl(compiler, file_name, Some(line), component_name)
+    }
+
+    fn new_impl(
+        mut compiler: Compiler,
+        file_name: PathBuf,
+        macro_line: Option<u32>,
+        component_name: String,
+    ) -> Result<VRc<ItemTreeVTable, Self>, PlatformError> {
+        watch(&file_name);
+        compiler.set_file_loader(|path| {
+            watch(path);
+            Box::pin(async { None })
+        });
+        let definition = compile(&compiler, &file_name, macro_line, &component_name)
+            .ok_or_else(|| format!("Could not load {component_name} from {file_name:?}"))?;
+        let instance = definition.create()?;
+        let window_adapter = instance.inner.window_adapter_ref()?.clone();
+        let component = VRc::new(Self {
+            compiler,
+            file_name,
+            macro_line,
+            component_name,
+            instance: RefCell::new(instance),
+            callbacks: Default::default(),
+            window_adapter,
+            shown: Default::default(),
+        });
+        LIVE_COMPONENTS.with(|components| {
+            components.borrow_mut().push(VRc::downgrade(&component));
+        });
+        Ok(component)
+    }
+
+    /// Compile the files again and replace the instance, if there was no error
+    pub fn reload(&self) {
+        let Some(definition) =
+            compile(&self.compiler, &self.file_name, self.macro_line, &self.component_name)
+        else {
+            return;
+        };
+        let old_instance = self.instance();
+        let snapshot = old_instance.snapshot();
+        let instance = match definition.create_with_existing_window(old_instance.window()) {
+            Ok(instance) => instance,
+            Err(err) => {
+                eprintln!("Error: could not create {}: {err}", self.component_name);
+                return;
+            }
+        };
+        instance.restore_snapshot(&snapshot);
+        for ((global, name), handler) in self.callbacks.borrow().iter() {
+            install_callback(&instance, global, name, handler.clone());
+        }
+        *self.instance.borrow_mut() = instance;
+    }
+
+    /// The current instance
+    pub fn instance(&self) -> ComponentInstance {
+        self.instance.borrow().clone_strong()
+    }
+
+    /// The window of the component, which is the same for all the instances
+    pub fn window(&self) -> &Window {
+        self.window_adapter.window()
+    }
+
+    /// Show the window, and keep the component alive until it is hidden, like a compiled component
+    pub fn show(this: &VRc<ItemTreeVTable, Self>) -> Result<(), PlatformError> {
+        this.window().show()?;
+        this.shown.replace(Some(this.clone()));
+        Ok(())
+    }
+
+    /// Hide the window, and release the reference taken by [`Self::show`]
+    pub fn hide(&self) -> Result<(), PlatformError> {
+        let result = self.window().hide();
+        self.shown.take();
+        result
+    }
+
+    /// Get the value of a property, or of a property of a global if `global` is not empty
+    pub fn get_property(&self, global: &str, name: &str) -> Value {
+        let instance = self.instance();
+        let result = if global.is_empty() {
+            instance.get_property(name)
+        } else {
+            instance.get_global_property(global, name)
+        };
+        result.unwrap_or_else(|err| {
+            eprintln!("Warning: could not get the property {}: {err}", qualified(global, name));
+            Value::Void
+        })
+    }
+
+    /// Set the value of a property, or of a property of a global if `global` is not empty
+    pub fn set_property(&self, global: &str, name: &str, value: Value) {
+        let instance = self.instance();
+        let result = if global.is_empty() {
+            instance.set_property(name, value)
+        } else {
+            instance.set_global_property(global, name, value)
+        };
+        if let Err(err) = result {
+            eprintln!("Warning: could not set the property {}: {err}", qualified(global, name));
+        }
+    }
+
+    /// Set the handler of a callback, and keep it to set it again on the next instances
+    pub fn set_callback(&self, global: &str, name: &str, handler: CallbackHandler) {
+        self.set_handler(global, name, Handler::Sync(handler));
+    }
+
+    /// Set the handler of an async callback, and keep it to set it again on the next instances
+    pub fn set_async_callback(&self, global: &str, name: &str, handler: AsyncCallbackHandler) {
+        self.set_handler(global, name, Handler::Async(handler));
+    }
+
+    fn set_handler(&self, global: &str, name: &str, handler: Handler) {
+        install_callback(&self.instance(), global, name, handler.clone());
+        self.callbacks.borrow_mut().insert((global.into(), name.into()), handler);
+    }
+
+    /// Invoke a callback or a function, of a global if `global` is not empty
+    pub fn invoke(&self, global: &str, name: &str, args: &[Value]) -> Value {
+        let instance = self.instance();
+        let result = if global.is_empty() {
+            instance.invoke(name, args)
+        } else {
+            instance.invoke_global(global, name, args)
+        };
+        result.unwrap_or_else(|err| {
+            eprintln!("Warning: could not invoke {}: {err}", qualified(global, name));
+            Value::Void
+        })
+    }
+}
+
+fn qualified(global: &str, name: &str) -> String {
+    if global.is_empty() {
+        name.into()
+    } else {
+        format!("{global}.{name}")
+    }
+}
+
+fn install_callback(instance: &ComponentInstance, global: &str, name: &str, handler: Handler) {
+    let result = match (global.is_empty(), handler) {
+        (true, Handler::Sync(handler)) => instance.set_callback(name, move |args| handler(args)),
+        (false, Handler::Sync(handler)) => {
+            instance.set_global_callback(global, name, move |args| handler(args))
+        }
+        (true, Handler::Async(handler)) => {
+            instance.set_async_callback(name, move |args| handler(args))
+        }
+        (false, Handler::Async(handler)) => {
+            instance.set_global_async_callback(global, name, move |args| handler(args))
+        }
+    };
+    if let Err(err) = result {
+        eprintln!("Warning: could not set the callback {}: {err}", qualified(global, name));
+    }
+}
+
+fn compile(
+    compiler: &Compiler,
+    file_name: &Path,
+    macro_line: Option<u32>,
+    component_name: &str,
+) -> Option<ComponentDefinition> {
+    let result: CompilationResult = match macro_line {
+        None => spin_on::spin_on(compiler.build_from_path(file_name)),
+        Some(line) => {
+            let source = match std::fs::read_to_string(file_name) {
+                Ok(source) => source,
+                Err(err) => {
+                    eprintln!("Error: could not load {}: {err}", file_name.display());
+                    return None;
+                }
+            };
+            let Some(source) = rust_macro_source(&source, line) else {
+                eprintln!("Error: no slint! macro at line {line} of {}", file_name.display());
+                return None;
+            };
+            spin_on::spin_on(compiler.build_from_source(source, file_name.into()))
+        }
+    };
+    result.print_diagnostics();
+    if result.has_errors() {
+        return None;
+    }
+    let definition = result.component(component_name);
+    if definition.is_none() {
+        eprintln!("Error: {component_name} is not exported from {}", file_name.display());
+    }
+    definition
+}
+
+/// Extract the code of the `slint!` macro invoked at the given line (starting at 1) of a Rust
+/// file. Like with [`i_slint_compiler::lexer::extract_rust_macro`], all the other bytes, including
+/// the attributes at the beginning of the macro, are replaced by spaces so that the diagnostics
+/// have the right line and column.
+fn rust_macro_source(rust_source: &str, line: u32) -> Option<String> {
+    let line_start = rust_source
+        .split_inclusive('\n')
+        .take(line.saturating_sub(1) as usize)
+        .map(str::len)
+        .sum::<usize>();
+    let range = i_slint_compiler::lexer::locate_slint_macro(rust_source)
+        .find(|range| range.start >= line_start)?;
+    let code = range.start + attributes_len(&rust_source[range.clone()])..range.end;
+    let bytes = rust_source
+        .bytes()
+        .enumerate()
+        .map(|(i, c)| if c == b'\n' || code.contains(&i) { c } else { b' ' })
+        .collect::<Vec<_>>();
+    Some(String::from_utf8(bytes).expect("Only whole characters were replaced"))
+}
+
+/// The length of the attributes such as `#[include_path = "..."]` at the beginning of the code
+/// of a `slint!` macro. They are already part of the configuration of the compiler.
+fn attributes_len(code: &str) -> usize {
+    let mut len = 0;
+    loop {
+        let rest = &code[len..];
+        let Some(attribute) = rest.trim_start().strip_prefix('#') else { return len };
+        if !attribute.trim_start().starts_with('[') {
+            return len;
+        }
+        let Some(end) = attribute.find(']') else { return len };
+        len += rest.len() - attribute.len() + end + 1;
+    }
+}
+
+thread_local! {
+    /// The components to reload when a file changes
+    static LIVE_COMPONENTS: RefCell<Vec<VWeak<ItemTreeVTable, LiveReloadingComponent>>> =
+        Default::default();
+}
+
+/// Set while a reload is scheduled, so that the many events of a save only reload once
+static RELOAD_PENDING: AtomicBool = AtomicBool::new(false);
+
+fn watch(path: &Path) {
+    static WATCHER: OnceLock<Option<Mutex<notify::RecommendedWatcher>>> = OnceLock::new();
+    let watcher = WATCHER.get_or_init(|| {
+        notify::recommended_watcher(|event: notify::Result<notify::Event>| {
+            use notify::EventKind::*;
+            let Ok(event) = event else { return };
+            if matches!(event.kind, Modify(_) | Remove(_) | Create(_))
+                && !RELOAD_PENDING.swap(true, Ordering::SeqCst)
+            {
+                // Editors may write the file in several steps: wait a bit for them to be done
+                let scheduled = i_slint_core::api::invoke_from_event_loop(|| {
+                    i_slint_core::timers::Timer::single_shot(
+                        std::time::Duration::from_millis(50),
+                        reload_all,
+                    )
+                });
+                if scheduled.is_err() {
+                    RELOAD_PENDING.store(false, Ordering::SeqCst);
+                }
+            }
+        })
+        .map_err(|err| eprintln!("Warning: cannot watch the .slint files: {err}"))
+        .ok()
+        .map(Mutex::new)
+    });
+    if let Some(watcher) = watcher {
+        notify::Watcher::watch(
+            &mut *watcher.lock().unwrap(),
+            path,
+            notify::RecursiveMode::NonRecursive,
+        )
+        .unwrap_or_else(|err| eprintln!("Warning: cannot watch {}: {err}", path.display()));
+    }
+}
+
+fn reload_all() {
+    RELOAD_PENDING.store(false, Ordering::SeqCst);
+    let components = LIVE_COMPONENTS.with(|components| {
+        let mut components = components.borrow_mut();
+        components.retain(|c| c.upgrade().is_some());
+        components.iter().filter_map(|c| c.upgrade()).collect::<Vec<_>>()
+    });
+    for component in components {
+        component.reload();
+    }
+}
+
+impl ItemTree for LiveReloadingComponent {
+    fn visit_children_item(
+        self: Pin<&Self>,
+        _index: isize,
+        _order: TraversalOrder,
+        _visitor: ItemVisitorRefMut,
+    ) -> VisitChildrenResult {
+        VisitChildrenResult::CONTINUE
+    }
+
+    fn layout_info(self: Pin<&Self>, _orientation: Orientation) -> LayoutInfo {
+        LayoutInfo::default()
+    }
+
+    fn get_item_tree(self: Pin<&Self>) -> Slice<ItemTreeNode> {
+        Slice::default()
+    }
+
+    fn get_item_ref(self: Pin<&Self>, _index: u32) -> Pin<ItemRef> {
+        unreachable!("The item tree of a LiveReloadingComponent has no items")
+    }
+
+    fn get_subtree_range(self: Pin<&Self>, _index: u32) -> IndexRange {
+        IndexRange { start: 0, end: 0 }
+    }
+
+    fn get_subtree(self: Pin<&Self>, _index: u32, _subindex: usize, _result: &mut ItemTreeWeak) {}
+
+    fn parent_node(self: Pin<&Self>, _result: &mut ItemWeak) {}
+
+    fn embed_component(self: Pin<&Self>, _parent: &ItemTreeWeak, _item_tree_index: u32) -> bool {
+        false
+    }
+
+    fn subtree_index(self: Pin<&Self>) -> usize {
+        usize::MAX
+    }
+
+    fn item_geometry(self: Pin<&Self>, _index: u32) -> LogicalRect {
+        LogicalRect::default()
+    }
+
+    fn accessible_role(self: Pin<&Self>, _index: u32) -> AccessibleRole {
+        AccessibleRole::default()
+    }
+
+    fn accessible_string_property(
+        self: Pin<&Self>,
+        _index: u32,
+        _what: AccessibleStringProperty,
+        _result: &mut SharedString,
+    ) -> bool {
+        false
+    }
+
+    fn window_adapter(self: Pin<&Self>, _do_create: bool, result: &mut Option<WindowAdapterRc>) {
+        *result = Some(self.window_adapter.clone());
+    }
+
+    fn accessibility_action(self: Pin<&Self>, _index: u32, _action: &AccessibilityAction) {}
+
+    fn supported_accessibility_actions(
+        self: Pin<&Self>,
+        _index: u32,
+    ) -> SupportedAccessibilityAction {
+        SupportedAccessibilityAction::default()
+    }
+
+    fn item_element_infos(self: Pin<&Self>, _index: u32, _result: &mut SharedString) -> bool {
+        false
+    }
+}
+
+i_slint_core::ItemTreeVTable_static!(static LIVE_RELOADING_COMPONENT_VT for LiveReloadingComponent);
+
+/// A model that converts the rows of another model in both directions, so that the rows written
+/// by the `.slint` code are written to the model that was set from Rust, and conversely.
+/// The generated code uses it to convert the models of Rust types from and to models of `Value`.
+pub struct ConvertedModel<S, T> {
+    model: ModelRc<S>,
+    to: fn(S) -> T,
+    from: fn(T) -> S,
+}
+
+impl<S: 'static, T: 'static> ConvertedModel<S, T> {
+    pub fn convert(model: ModelRc<S>, to: fn(S) -> T, from: fn(T) -> S) -> ModelRc<T> {
+        // Converting back a model that was converted gives the original model
+        if let Some(converted) = model.as_any().downcast_ref::<ConvertedModel<T, S>>() {
+            return converted.model.clone();
+        }
+        ModelRc::new(Self { model, to, from })
+    }
+}
+
+impl<S: 'static, T: 'static> Model for ConvertedModel<S, T> {
+    type Data = T;
+
+    fn row_count(&self) -> usize {
+        self.model.row_count()
+    }
+
+    fn row_data(&self, row: usize) -> Option<T> {
+        self.model.row_data(row).map(self.to)
+    }
+
+    fn set_row_data(&self, row: usize, data: T) {
+        self.model.set_row_data(row, (self.from)(data))
+    }
+
+    fn model_tracker(&self) -> &dyn ModelTracker {
+        self.model.model_tracker()
+    }
+
+    fn as_any(&self) -> &dyn core::any::Any {
+        self
+    }
+}
+
+#[test]
+fn test_rust_macro_source() {
+    let rust_source = r##"
+slint::slint! {
+    export component A {}
+}
+fn foo() {}
+slint::slint!{ #[include_path = r#"../ui"#]
+    #[style = "fluent"]
+    export component B {}
+}
+"##;
+    let first = rust_macro_source(rust_source, 2).unwrap();
+    assert_eq!(first.len(), rust_source.len());
+    assert_eq!(first.trim(), "export component A {}");
+    // The line and the column of the code are the same as in the Rust file
+    assert_eq!(first.lines().nth(2), Some("    export component A {}"));
+
+    let second = rust_macro_source(rust_source, 6).unwrap();
+    assert_eq!(second.len(), rust_source.len());
+    assert_eq!(second.trim(), "export component B {}");
+    assert_eq!(second.lines().nth(7), Some("    export component B {}"));
+
+    assert_eq!(rust_macro_source(rust_source, 10), None);
+}
+
+#[test]
+fn test_reload_rust_macro() {
+    i_slint_backend_testing::init_no_event_loop();
+    let dir = std::env::temp_dir().join(format!("live_reload_macro_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(dir.join("Cargo.toml"), "").unwrap();
+    std::fs::write(dir.join("colors.slint"), "export global Colors { out property <int> n: 1; }")
+        .unwrap();
+    let main = dir.join("main.rs");
+    let rust_source = r#"
+slint::slint! { export component Other { in-out property <int> value: 1; } }
+fn main() {}
+slint::slint! {
+    import { Colors } from "colors.slint";
+    export component Main inherits Window {
+        in-out property <int> value: 10;
+        out property <int> result: value + Colors.n;
+    }
+}
+"#;
+    std::fs::write(&main, rust_source).unwrap();
+
+    let component = LiveReloadingComponent::new_from_rust_macro(
+        Compiler::default(),
+        dir.to_str().unwrap(),
+        "main.rs",
+        4,
+        "Main".into(),
+    )
+    .unwrap();
+    assert_eq!(component.get_property("", "result"), Value::Number(11.));
+    component.set_property("", "value", Value::Number(20.));
+
+    std::fs::write(&main, rust_source.replace("value + Colors.n", "value * 2 + Colors.n")).unwrap();
+    component.reload();
+    std::fs::remove_dir_all(&dir).unwrap();
+    assert_eq!(component.get_property("", "result"), Value::Number(41.));
+}
+
+#[test]
+fn test_converted_model() {
+    use i_slint_core::model::VecModel;
+    let model = Rc::new(VecModel::from(vec![1, 2, 3]));
+    let values = ConvertedModel::convert(ModelRc::from(model.clone()), Value::from, |value| {
+        i32::try_from(value).unwrap_or_default()
+    });
+    assert_eq!(values.row_data(1), Some(Value::Number(2.)));
+    values.set_row_data(2, Value::Number(42.));
+    assert_eq!(model.row_data(2), Some(42));
+
+    let back = ConvertedModel::convert(
+        values,
+        |value| i32::try_from(value).unwrap_or_default(),
+        Value::from,
+    );
+    assert_eq!(back, ModelRc::from(model));
+}
+
+#[test]
+fn test_show_keeps_component_alive() {
+    i_slint_backend_testing::init_no_event_loop();
+    let path = std::env::temp_dir().join(format!("live_reload_show_{}.slint", std::process::id()));
+    std::fs::write(&path, "export component Main inherits Window {}").unwrap();
+    let component =
+        LiveReloadingComponent::new(Compiler::default(), path.clone(), "Main".into()).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    let weak = VRc::downgrade(&component);
+    LiveReloadingComponent::show(&component).unwrap();
+    drop(component);
+    let component = weak.upgrade().unwrap();
+    assert!(component.window().is_visible());
+    component.hide().unwrap();
+    drop(component);
+    assert!(weak.upgrade().is_none());
+}
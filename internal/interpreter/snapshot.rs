@@ -0,0 +1,423 @@
+// Copyright © SixtyFPS GmbH <info@slint.dev>
+// SPDX-License-Identifier: GPL-3.0-only OR LicenseRef-Slint-Royalty-free-2.0 OR LicenseRef-Slint-Software-3.0
+
+//! This module contains the code to take a snapshot of the state of a component instance as JSON,
+//! and to restore it in another instance, possibly of a newer version of the component.
+//!
+//! The snapshot of an item tree is an object with the following optional fields:
+//!  - `properties`: the declared properties (after inlining, they are all in the root element)
+//!  - `items`: an object with the `in-out` properties of the native items, by element id (see
+//!    [`item_keys`])
+//!  - `repeaters`: an array of snapshots of the instances, by repeater id
+//!  - `globals`: the snapshot of the globals, by name (only in the snapshot of the root)
+//!
+//! Only the properties that hold a value that changed since the creation of the instance are
+//! stored. A property with a binding gets its value from that binding, so it is not stored.
+//! This is also the case of the current state of the `states`: it is computed from the `when`
+//! conditions, so it is restored with the properties that these conditions depend on.
+
+use crate::dynamic_item_tree::{InstanceRef, ItemTreeBox, ItemWithinItemTree};
+use crate::eval;
+use crate::global_component::CompiledGlobal;
+use crate::json::{value_from_json, value_to_json};
+use i_slint_compiler::expression_tree::Expression;
+use i_slint_compiler::langtype::{ElementType, NativeClass, Type};
+use i_slint_compiler::object_tree::{Element, ElementRc, PropertyVisibility};
+use serde_json::{Map, Value as JsonValue};
+use smol_str::SmolStr;
+use std::collections::HashMap;
+
+/// Take the snapshot of a component instance and of its globals
+pub(crate) fn snapshot(component: &ItemTreeBox) -> JsonValue {
+    let mut snapshot = snapshot_item_tree(component.borrow_instance());
+    let description = component.description();
+    let mut globals = Map::new();
+    for global in description.compiled_globals.iter().flat_map(|g| &g.compiled_globals) {
+        let CompiledGlobal::Component { .. } = global else { continue };
+        let Some(name) = global.names().pop() else { continue };
+        let Ok(instance) =
+            description.get_global(component.borrow(), &crate::normalize_identifier(&name))
+        else {
+            continue;
+        };
+        let global_snapshot = instance.as_ref().snapshot();
+        if !global_snapshot.is_empty() {
+            globals.insert(name.to_string(), global_snapshot.into());
+        }
+    }
+    if !globals.is_empty() {
+        snapshot.insert("globals".into(), globals.into());
+    }
+    snapshot.into()
+}
+
+/// Restore a snapshot taken with [`snapshot`]. The properties that don't exist anymore, or
+/// that can't be converted to their new type, are ignored.
+pub(crate) fn restore(component: &ItemTreeBox, snapshot: &JsonValue) {
+    restore_item_tree(component.borrow_instance(), snapshot);
+    let description = component.description();
+    for (name, global_snapshot) in
+        snapshot.get("globals").and_then(|g| g.as_object()).into_iter().flatten()
+    {
+        if let Ok(instance) =
+            description.get_global(component.borrow(), &crate::normalize_identifier(name))
+        {
+            instance.as_ref().restore_snapshot(global_snapshot);
+        }
+    }
+}
+
+pub(crate) fn snapshot_item_tree(instance: InstanceRef) -> Map<String, JsonValue> {
+    let mut snapshot = Map::new();
+
+    let root = instance.description.original.root_element.clone();
+    let declarations = root
+        .borrow()
+        .property_declarations
+        .iter()
+        .filter(|(_, decl)| decl.is_alias.is_none())
+        .map(|(name, decl)| (name.clone(), decl.property_type.clone()))
+        .collect::<Vec<_>>();
+    let properties = snapshot_properties(instance, &root, declarations);
+    if !properties.is_empty() {
+        snapshot.insert("properties".into(), properties.into());
+    }
+
+    let mut items = Map::new();
+    for (key, item) in item_keys(instance) {
+        let element = &item.elem;
+        let native_properties = item
+            .rtti
+            .properties
+            .keys()
+            .filter(|name| is_in_out_native_property(&element.borrow(), name))
+            .map(|name| (SmolStr::new(name), element.borrow().lookup_property(name).property_type))
+            .collect::<Vec<_>>();
+        let properties = snapshot_properties(instance, element, native_properties);
+        if !properties.is_empty() {
+            items.insert(key, properties.into());
+        }
+    }
+    if !items.is_empty() {
+        snapshot.insert("items".into(), items.into());
+    }
+
+    let mut repeaters = Map::new();
+    for name in instance.description.repeater_names.keys() {
+        generativity::make_guard!(guard);
+        let (repeater, _) = crate::dynamic_item_tree::get_repeater_by_name(instance, name, guard);
+        let rows = (0..repeater.range().end)
+            .map(|row| {
+                repeater.instance_at(row).map_or(JsonValue::Null, |row_instance| {
+                    generativity::make_guard!(guard);
+                    snapshot_item_tree(row_instance.unerase(guard).borrow_instance()).into()
+                })
+            })
+            .collect::<Vec<_>>();
+        if rows.iter().any(|row| row.as_object().is_some_and(|row| !row.is_empty())) {
+            repeaters.insert(name.to_string(), rows.into());
+        }
+    }
+    if !repeaters.is_empty() {
+        snapshot.insert("repeaters".into(), repeaters.into());
+    }
+
+    snapshot
+}
+
+pub(crate) fn restore_item_tree(instance: InstanceRef, snapshot: &JsonValue) {
+    let root = instance.description.original.root_element.clone();
+    for (name, value) in fields(snapshot, "properties") {
+        let ty = match root.borrow().property_declarations.get(name.as_str()) {
+            Some(decl) if decl.is_alias.is_none() => decl.property_type.clone(),
+            _ => continue,
+        };
+        restore_property(instance, &root, name, &ty, value);
+    }
+
+    let items = item_keys(instance).into_iter().collect::<HashMap<_, _>>();
+    for (key, properties) in fields(snapshot, "items") {
+        let Some(item) = items.get(key) else { continue };
+        for (name, value) in properties.as_object().into_iter().flatten() {
+            if !item.rtti.properties.contains_key(name.as_str())
+                || !is_in_out_native_property(&item.elem.borrow(), name)
+            {
+                continue;
+            }
+            let ty = item.elem.borrow().lookup_property(name).property_type;
+            restore_property(instance, &item.elem, name, &ty, value);
+        }
+    }
+
+    // The properties are restored first, as they may change the models of the repeaters
+    for (name, rows) in fields(snapshot, "repeaters") {
+        if !instance.description.repeater_names.contains_key(name.as_str()) {
+            continue;
+        }
+        let repeater = crate::dynamic_item_tree::get_updated_repeater_by_name(instance, name);
+        for (row, row_snapshot) in rows.as_array().into_iter().flatten().enumerate() {
+            if let Some(row_instance) = repeater.instance_at(row) {
+                generativity::make_guard!(guard);
+                restore_item_tree(row_instance.unerase(guard).borrow_instance(), row_snapshot);
+            }
+        }
+    }
+}
+
+/// The compiler makes the ids of the elements unique by appending a number that changes as soon
+/// as an element is added or removed. So the items are keyed by the id from the source instead,
+/// followed by `#` and the occurrence number (in the order of the item tree) when it is used by
+/// several items.
+fn item_keys<'a>(instance: InstanceRef<'a, '_>) -> Vec<(String, &'a ItemWithinItemTree)> {
+    let mut items = instance
+        .description
+        .items
+        .iter()
+        .map(|(id, item)| match id.rsplit_once('-') {
+            Some((source_id, n)) => (source_id, n.parse::<u32>().unwrap_or_default(), item),
+            None => (id.as_str(), 0, item),
+        })
+        .collect::<Vec<_>>();
+    items.sort_by_key(|(_, n, _)| *n);
+    let mut occurrences = HashMap::<&str, usize>::new();
+    items
+        .into_iter()
+        .map(|(source_id, _, item)| {
+            let occurrence = occurrences.entry(source_id).or_default();
+            *occurrence += 1;
+            let key = match *occurrence {
+                1 => source_id.to_string(),
+                n => format!("{source_id}#{n}"),
+            };
+            (key, item)
+        })
+        .collect()
+}
+
+fn fields<'a>(
+    snapshot: &'a JsonValue,
+    name: &str,
+) -> impl Iterator<Item = (&'a String, &'a JsonValue)> {
+    snapshot.get(name).and_then(|v| v.as_object()).into_iter().flatten()
+}
+
+/// The properties of the element with a value that changed since the creation of the instance
+fn snapshot_properties(
+    instance: InstanceRef,
+    element: &ElementRc,
+    properties: Vec<(SmolStr, Type)>,
+) -> Map<String, JsonValue> {
+    let mut result = Map::new();
+    for (name, ty) in properties {
+        if !ty.is_property_type() || !holds_state(&element.borrow(), &name) {
+            continue;
+        }
+        let Ok(value) = eval::load_property(instance, element, &name) else { continue };
+        let Ok(json) = value_to_json(&value) else { continue };
+        let binding = element.borrow().bindings.get(&name).map(|b| b.borrow().expression.clone());
+        let initial_value = match binding {
+            Some(expression) if !matches!(expression, Expression::Invalid) => {
+                eval::eval_expression(
+                    &expression,
+                    &mut eval::EvalLocalContext::from_component_instance(instance),
+                )
+            }
+            _ => eval::default_value_for_type(&ty),
+        };
+        if value_to_json(&initial_value).ok().as_ref() != Some(&json) {
+            result.insert(name.to_string(), json);
+        }
+    }
+    result
+}
+
+fn restore_property(
+    instance: InstanceRef,
+    element: &ElementRc,
+    name: &str,
+    ty: &Type,
+    value: &JsonValue,
+) {
+    // The new version of the component may have a binding for this property, which wins
+    if !ty.is_property_type() || !holds_state(&element.borrow(), name) {
+        return;
+    }
+    if let Ok(value) = value_from_json(ty, value) {
+        eval::store_property(instance, element, name, value).ok();
+    }
+}
+
+/// A property holds state if its value is not computed by a binding
+fn holds_state(element: &Element, name: &str) -> bool {
+    element.bindings.get(name).is_none_or(|b| is_initial_value(&b.borrow().expression))
+}
+
+/// Array literals are not constant because they become models that can change, but they
+/// are still only the initial value of the property
+fn is_initial_value(expression: &Expression) -> bool {
+    match expression {
+        Expression::Array { values, .. } => values.iter().all(is_initial_value),
+        Expression::Struct { values, .. } => values.values().all(is_initial_value),
+        Expression::Cast { from, .. } => is_initial_value(from),
+        _ => expression.is_constant(),
+    }
+}
+
+/// The `in-out` properties of native items are the ones that the item itself can change,
+/// like the text of a `TextInput` or the viewport of a `Flickable`.
+/// The sizes and the properties of the window are set by the layouts and the window.
+fn is_in_out_native_property(element: &Element, name: &str) -> bool {
+    if matches!(name, "width" | "height") {
+        return false;
+    }
+    fn visibility(class: &NativeClass, name: &str) -> Option<PropertyVisibility> {
+        match class.properties.get(name) {
+            Some(info) => Some(info.property_visibility),
+            None => visibility(class.parent.as_deref()?, name),
+        }
+    }
+    match &element.base_type {
+        ElementType::Native(class) if class.class_name != "WindowItem" => {
+            visibility(class, name) == Some(PropertyVisibility::InOut)
+        }
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+fn create_for_test(code: &str) -> crate::ComponentInstance {
+    let result = spin_on::spin_on(
+        crate::Compiler::default().build_from_source(code.into(), Default::default()),
+    );
+    assert!(!result.has_errors(), "{:?}", result.diagnostics().collect::<Vec<_>>());
+    result.component("App").unwrap().create().unwrap()
+}
+
+#[test]
+fn snapshot_repeater_rows() {
+    i_slint_backend_testing::init_no_event_loop();
+    use crate::Value;
+    let code = r#"
+        export component App {
+            in-out property <[int]> model: [1, 2, 3];
+            out property <length> layout-height: layout.preferred-height;
+            layout := VerticalLayout {
+                for data in model: Rectangle {
+                    property <int> value;
+                    height: value * 1px;
+                    init => { value = data * 10; }
+                }
+            }
+        }
+    "#;
+    let instance = create_for_test(code);
+    // Instantiate the rows of the repeater
+    instance.get_property("layout-height").unwrap();
+    let snapshot = instance.snapshot();
+    let rows = snapshot["repeaters"].as_object().unwrap().values().next().unwrap();
+    assert_eq!(rows.as_array().unwrap().len(), 3);
+    assert_eq!(rows[1]["properties"]["value"], 20);
+
+    // The rows of the new version get their values from the snapshot instead of their init callback
+    let new_instance = create_for_test(&code.replace("value = data * 10;", "value = data;"));
+    new_instance.restore_snapshot(&snapshot);
+    assert_eq!(new_instance.get_property("layout-height").unwrap(), Value::Number(60.));
+    assert_eq!(new_instance.snapshot(), snapshot);
+}
+
+#[test]
+fn snapshot_native_in_out_properties() {
+    i_slint_backend_testing::init_no_event_loop();
+    use crate::{SharedString, Value};
+    let code = r#"
+        export component App {
+            out property <string> text: input.text;
+            out property <length> input-width: input.width;
+            input := TextInput { width: 100px; }
+            init => { input.text = "hello"; }
+        }
+    "#;
+    let instance = create_for_test(code);
+    let snapshot = instance.snapshot();
+    assert_eq!(snapshot["items"]["input"]["text"], "hello");
+    // The geometry is set by the layouts, so it isn't part of the snapshot
+    assert!(snapshot["items"]["input"].get("width").is_none());
+
+    // A new version of the code with an additional element, that changes the internal ids
+    let new_code = code
+        .replace(r#"init => { input.text = "hello"; }"#, "")
+        .replace("input := TextInput", "TextInput {} input := TextInput");
+    let new_instance = create_for_test(&new_code);
+    new_instance.restore_snapshot(&snapshot);
+    assert_eq!(
+        new_instance.get_property("text").unwrap(),
+        Value::String(SharedString::from("hello"))
+    );
+    assert_eq!(new_instance.get_property("input-width").unwrap(), Value::Number(100.));
+}
+
+#[test]
+fn snapshot_type_change() {
+    i_slint_backend_testing::init_no_event_loop();
+    use crate::{SharedString, Value};
+    let code = r#"
+        export component App {
+            in-out property <int> count;
+            in-out property <int> ratio;
+            in-out property <string> name;
+            in-out property <bool> removed;
+        }
+    "#;
+    let instance = create_for_test(code);
+    instance.set_property("count", Value::Number(7.)).unwrap();
+    instance.set_property("ratio", Value::Number(3.)).unwrap();
+    instance.set_property("name", Value::String("x".into())).unwrap();
+    instance.set_property("removed", Value::Bool(true)).unwrap();
+    let snapshot = instance.snapshot();
+
+    let new_instance = create_for_test(
+        r#"
+        export component App {
+            in-out property <string> count;
+            in-out property <float> ratio;
+            in-out property <int> name;
+        }
+    "#,
+    );
+    new_instance.restore_snapshot(&snapshot);
+    // The values that can't be converted to the new type are ignored
+    assert_eq!(new_instance.get_property("count").unwrap(), Value::String(SharedString::new()));
+    assert_eq!(new_instance.get_property("name").unwrap(), Value::Number(0.));
+    assert_eq!(new_instance.get_property("ratio").unwrap(), Value::Number(3.));
+}
+
+#[test]
+fn snapshot_states() {
+    i_slint_backend_testing::init_no_event_loop();
+    use crate::Value;
+    let code = r#"
+        export component App {
+            in-out property <int> count;
+            out property <color> color: r.background;
+            r := Rectangle {
+                background: blue;
+                states [
+                    big when count > 5: { background: red; }
+                ]
+            }
+        }
+    "#;
+    let instance = create_for_test(code);
+    instance.set_property("count", Value::Number(7.)).unwrap();
+    let snapshot = instance.snapshot();
+    // The current state is computed from the `when` conditions, so restoring the properties
+    // they depend on restores the state.
+    assert_eq!(snapshot["properties"].as_object().unwrap().len(), 1);
+
+    let new_instance = create_for_test(code);
+    new_instance.restore_snapshot(&snapshot);
+    assert_eq!(
+        new_instance.get_property("color").unwrap(),
+        Value::Brush(i_slint_core::Brush::SolidColor(i_slint_core::Color::from_rgb_u8(255, 0, 0)))
+    );
+}
@@ -44,3 +44,193 @@ fn reuse_window() {
         instance
     };
 }
+
+#[cfg(feature = "internal-json")]
+#[test]
+fn snapshot_and_restore() {
+    i_slint_backend_testing::init_no_event_loop();
+    use crate::{Compiler, SharedString, Value};
+    let code = r#"
+        export global Settings {
+            in-out property <bool> dark;
+        }
+        export component App inherits Window {
+            in-out property <int> count;
+            in-out property <int> initial: 5;
+            out property <int> derived: count * 2;
+            in-out property <[string]> names: ["a", "b"];
+            in-out property <string> typed <=> input.text;
+            out property <length> layout-height: layout.preferred-height;
+            input := TextInput {}
+            layout := VerticalLayout {
+                for name in names: Rectangle {
+                    property <bool> selected;
+                    background: selected ? blue : red;
+                    init => { selected = name == "y"; }
+                }
+            }
+        }
+    "#;
+    let create = |code: &str| {
+        let result = spin_on::spin_on(
+            Compiler::default().build_from_source(code.into(), Default::default()),
+        );
+        assert!(!result.has_errors(), "{:?}", result.diagnostics().collect::<Vec<_>>());
+        result.component("App").unwrap().create().unwrap()
+    };
+
+    let instance = create(code);
+    instance.set_property("count", Value::Number(7.)).unwrap();
+    instance
+        .set_property(
+            "names",
+            Value::Model(["x", "y", "z"].map(|n| Value::String(n.into())).into()),
+        )
+        .unwrap();
+    instance.set_property("typed", Value::String("hello".into())).unwrap();
+    instance.set_global_property("Settings", "dark", Value::Bool(true)).unwrap();
+    // Instantiate the rows of the repeater
+    instance.get_property("layout-height").unwrap();
+    let snapshot = instance.snapshot();
+    assert_eq!(snapshot["properties"]["count"], 7);
+    assert!(snapshot["properties"].get("initial").is_none());
+    assert!(snapshot["properties"].get("derived").is_none());
+    assert_eq!(snapshot["globals"]["Settings"]["properties"]["dark"], true);
+
+    // A new version of the code, where the initial value changed and the rows have no init anymore
+    let new_code = code
+        .replace("initial: 5", "initial: 6")
+        .replace(r#"init => { selected = name == "y"; }"#, "");
+    let new_instance = create(&new_code);
+    new_instance.restore_snapshot(&snapshot);
+    assert_eq!(new_instance.get_property("count").unwrap(), Value::Number(7.));
+    assert_eq!(new_instance.get_property("initial").unwrap(), Value::Number(6.));
+    assert_eq!(new_instance.get_property("derived").unwrap(), Value::Number(14.));
+    assert_eq!(
+        new_instance.get_property("typed").unwrap(),
+        Value::String(SharedString::from("hello"))
+    );
+    assert_eq!(new_instance.get_global_property("Settings", "dark").unwrap(), Value::Bool(true));
+    assert_eq!(new_instance.snapshot(), snapshot);
+}
+
+#[cfg(feature = "internal-live-reload")]
+#[test]
+fn live_reload() {
+    i_slint_backend_testing::init_no_event_loop();
+    use crate::live_reload::LiveReloadingComponent;
+    use crate::{Compiler, SharedString, Value};
+    let path = std::env::temp_dir().join(format!("live_reload_{}.slint", std::process::id()));
+    let code = r#"
+        export global Settings {
+            in-out property <bool> dark;
+        }
+        export component Main inherits Window {
+            in-out property <int> counter: 1;
+            out property <string> text: "counter=" + counter;
+            callback clicked(int) -> int;
+        }
+    "#;
+    std::fs::write(&path, code).unwrap();
+    let mut compiler = Compiler::default();
+    compiler.set_style("fluent".into());
+    let component = LiveReloadingComponent::new(compiler, path.clone(), "Main".into()).unwrap();
+    component.set_property("", "counter", Value::Number(41.));
+    component.set_property("Settings", "dark", Value::Bool(true));
+    component.set_callback(
+        "",
+        "clicked",
+        std::rc::Rc::new(|args| Value::Number(f64::try_from(args[0].clone()).unwrap() + 1.)),
+    );
+
+    // An error keeps the current instance
+    std::fs::write(&path, code.replace("callback", "callbak")).unwrap();
+    component.reload();
+    assert_eq!(component.get_property("", "counter"), Value::Number(41.));
+
+    std::fs::write(&path, code.replace("\"counter=\"", "\"count: \"")).unwrap();
+    component.reload();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(component.get_property("", "text"), Value::String(SharedString::from("count: 41")));
+    assert_eq!(component.get_property("Settings", "dark"), Value::Bool(true));
+    assert_eq!(component.invoke("", "clicked", &[Value::Number(1.)]), Value::Number(2.));
+}
+
+#[test]
+fn async_callbacks() {
+    i_slint_backend_testing::init_integration_test_with_mock_time();
+    use crate::{Compiler, SetCallbackError, SharedString, Value};
+    use std::cell::Cell;
+    use std::rc::Rc;
+    let code = r#"
+        export global Logic {
+            async callback fetch(int) -> string;
+        }
+        export component App inherits Window {
+            async callback validate(string) -> bool;
+            async callback save();
+            callback sync();
+            out property <string> status: validate-pending ? "checking" : validate-result ? "ok" : "invalid";
+            public function check(value: string) { validate(value); }
+        }
+    "#;
+    let result =
+        spin_on::spin_on(Compiler::default().build_from_source(code.into(), Default::default()));
+    assert!(!result.has_errors(), "{:?}", result.diagnostics().collect::<Vec<_>>());
+    let instance = result.component("App").unwrap().create().unwrap();
+
+    let completed = Rc::new(Cell::new(0));
+    let completed_ = completed.clone();
+    instance
+        .set_async_callback("validate", move |args| {
+            let value: SharedString = args[0].clone().try_into().unwrap();
+            let completed = completed_.clone();
+            async move {
+                completed.set(completed.get() + 1);
+                Value::Bool(value.len() > 3)
+            }
+        })
+        .unwrap();
+    instance.set_async_callback("save", |_| async { Value::Void }).unwrap();
+    instance
+        .set_global_async_callback("Logic", "fetch", |args| {
+            let id: i32 = args[0].clone().try_into().unwrap();
+            async move { Value::from(SharedString::from(format!("item {id}"))) }
+        })
+        .unwrap();
+    assert_eq!(
+        instance.set_async_callback("sync", |_| async { Value::Void }),
+        Err(SetCallbackError::NoSuchCallback)
+    );
+    assert_eq!(
+        instance.set_callback("validate", |_| Value::Void),
+        Err(SetCallbackError::AsyncCallback)
+    );
+    assert_eq!(
+        instance.set_global_callback("Logic", "fetch", |_| Value::Void),
+        Err(SetCallbackError::AsyncCallback)
+    );
+
+    let status =
+        || -> SharedString { instance.get_property("status").unwrap().try_into().unwrap() };
+    assert_eq!(status(), "invalid");
+    // The second call aborts the first one
+    instance.invoke("check", &[SharedString::from("ab").into()]).unwrap();
+    instance.invoke("validate", &[SharedString::from("abcdef").into()]).unwrap();
+    assert_eq!(status(), "checking");
+    instance.invoke("save", &[]).unwrap();
+    assert_eq!(instance.get_property("save-pending").unwrap(), Value::Bool(true));
+    instance.invoke_global("Logic", "fetch", &[Value::from(42)]).unwrap();
+    assert_eq!(instance.get_global_property("Logic", "fetch-pending").unwrap(), Value::Bool(true));
+
+    crate::spawn_local(async { crate::quit_event_loop().unwrap() }).unwrap();
+    crate::run_event_loop().unwrap();
+    assert_eq!(completed.get(), 1);
+    assert_eq!(status(), "ok");
+    assert_eq!(instance.get_property("save-pending").unwrap(), Value::Bool(false));
+    assert_eq!(
+        instance.get_global_property("Logic", "fetch-result").unwrap(),
+        Value::from(SharedString::from("item 42"))
+    );
+    assert_eq!(instance.get_global_property("Logic", "fetch-pending").unwrap(), Value::Bool(false));
+}
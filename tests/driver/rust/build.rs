@@ -19,6 +19,10 @@ fn main() -> std::io::Result<()> {
         let source = std::fs::read_to_string(&testcase.absolute_path)?;
         let ignored = if testcase.is_ignored("rust") {
             "#[ignore = \"testcase ignored for rust\"]"
+        } else if cfg!(all(feature = "live-reload", not(feature = "build-time")))
+            && testcase.is_ignored("live-reload")
+        {
+            "#[ignore = \"testcase ignored for live-reload\"]"
         } else if cfg!(not(feature = "build-time")) && source.contains("//bundle-translations") {
             "#[ignore = \"translation bundle not working with the macro\"]"
         } else {
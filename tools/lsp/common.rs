@@ -16,7 +16,7 @@
 pub mod rename_component;
 #[cfg(test)]
 pub mod test;
-#[cfg(any(test, feature = "preview-engine"))]
+#[cfg_attr(not(any(test, feature = "preview-engine")), allow(unused))]
 pub mod text_edit;
 pub mod token_info;
 
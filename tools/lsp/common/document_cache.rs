@@ -3,7 +3,7 @@
 
 //! Data structures common between LSP and previewer
 
-use i_slint_compiler::diagnostics::{BuildDiagnostics, SourceFile};
+use i_slint_compiler::diagnostics::{BuildDiagnostics, SourceFile, SourceFileInner};
 use i_slint_compiler::object_tree::Document;
 use i_slint_compiler::parser::{syntax_nodes, TextSize};
 use i_slint_compiler::typeloader::TypeLoader;
@@ -27,9 +27,11 @@
 pub type SourceFileVersionMap = HashMap<PathBuf, SourceFileVersion>;
 
 fn default_cc() -> i_slint_compiler::CompilerConfiguration {
-    i_slint_compiler::CompilerConfiguration::new(
+    let mut cc = i_slint_compiler::CompilerConfiguration::new(
         i_slint_compiler::generator::OutputFormat::Interpreter,
-    )
+    );
+    cc.warn_unused_code = true;
+    cc
 }
 
 pub type OpenImportFallback = Option<
@@ -49,6 +51,8 @@ pub struct CompilerConfiguration {
     pub open_import_fallback: OpenImportFallback,
     pub resource_url_mapper:
         Option<Rc<dyn Fn(&str) -> Pin<Box<dyn Future<Output = Option<String>>>>>>,
+    /// Report unused imports, exports, private properties and states
+    pub warn_unused_code: bool,
 }
 
 impl Default for CompilerConfiguration {
@@ -61,6 +65,7 @@ fn default() -> Self {
             style: std::mem::take(&mut cc.style),
             open_import_fallback: None,
             resource_url_mapper: std::mem::take(&mut cc.resource_url_mapper),
+            warn_unused_code: cc.warn_unused_code,
         }
     }
 }
@@ -72,6 +77,7 @@ fn build(mut self) -> (i_slint_compiler::CompilerConfiguration, OpenImportFallba
         result.library_paths = std::mem::take(&mut self.library_paths);
         result.style = std::mem::take(&mut self.style);
         result.resource_url_mapper = std::mem::take(&mut self.resource_url_mapper);
+        result.warn_unused_code = self.warn_unused_code;
 
         (result, self.open_import_fallback)
     }
@@ -210,12 +216,7 @@ pub fn get_document_and_offset<'a>(
         pos: &'_ lsp_types::Position,
     ) -> Option<(&'a i_slint_compiler::object_tree::Document, TextSize)> {
         let doc = self.get_document(text_document_uri)?;
-        let o = (doc
-            .node
-            .as_ref()?
-            .source_file
-            .offset(pos.line as usize + 1, pos.character as usize + 1) as u32)
-            .into();
+        let o = crate::util::lsp_position_to_text_size(&doc.node.as_ref()?.source_file, *pos);
         doc.node.as_ref()?.text_range().contains_inclusive(o).then_some((doc, o))
     }
 
@@ -292,6 +293,58 @@ pub async fn load_url(
         Ok(())
     }
 
+    /// Report the exported components that none of the loaded documents imports
+    ///
+    /// This needs to know all the documents of the project, so it is not done while loading
+    /// a single document.
+    pub fn check_unused_exports(&self, diag: &mut BuildDiagnostics) {
+        if self.type_loader.compiler_config.warn_unused_code {
+            i_slint_compiler::passes::check_unused_code::check_unused_exports(
+                &self.type_loader,
+                None,
+                diag,
+            );
+        }
+    }
+
+    /// Apply the `changes` of a `textDocument/didChange` notification to the source code of
+    /// the document at `url` and return the new source code.
+    ///
+    /// The changes are applied in order, each range being relative to the text produced by
+    /// the previous change.
+    pub fn apply_content_changes(
+        &self,
+        url: &Url,
+        changes: &[lsp_types::TextDocumentContentChangeEvent],
+    ) -> Result<String> {
+        let path = uri_to_file(url).ok_or("Failed to convert path")?;
+        let mut contents = self
+            .type_loader
+            .all_file_documents()
+            .find(|(p, _)| **p == path)
+            .and_then(|(_, d)| d.source_file.source())
+            .map(|s| s.to_string());
+
+        for change in changes {
+            let Some(range) = change.range else {
+                contents = Some(change.text.clone());
+                continue;
+            };
+            let text = contents.take().ok_or("Document is not loaded")?;
+            let source_file = SourceFile::new(SourceFileInner::new(path.clone(), text));
+            let range = crate::util::lsp_range_to_text_range(&source_file, range);
+            let mut text = source_file.source().unwrap_or_default().to_string();
+            let (start, end) = (usize::from(range.start()), usize::from(range.end()));
+            if end > text.len() || !text.is_char_boundary(start) || !text.is_char_boundary(end) {
+                return Err("Text change range is out of bounds".into());
+            }
+            text.replace_range(start..end, &change.text);
+            contents = Some(text);
+        }
+
+        contents.ok_or_else(|| "Document is not loaded".into())
+    }
+
     pub async fn reload_cached_file(&mut self, url: &Url, diag: &mut BuildDiagnostics) {
         let Some(path) = uri_to_file(url) else { return };
         self.type_loader.reload_cached_file(&path, diag).await;
@@ -320,6 +373,7 @@ pub fn compiler_configuration(&self) -> CompilerConfiguration {
             style: self.type_loader.compiler_config.style.clone(),
             open_import_fallback: None, // We need to re-generate this anyway
             resource_url_mapper: self.type_loader.compiler_config.resource_url_mapper.clone(),
+            warn_unused_code: self.type_loader.compiler_config.warn_unused_code,
         }
     }
 
@@ -424,6 +478,57 @@ fn test_document_version() {
         assert_eq!(dc.document_version(&url), Some(42));
     }
 
+    #[test]
+    fn test_apply_content_changes() {
+        let (dc, url, _) = crate::language::test::loaded_document_cache(
+            "component Foo { property <string> s: \"🦥\"; }\n".into(),
+        );
+
+        let change = |range: Option<(u32, u32, u32, u32)>, text: &str| {
+            lsp_types::TextDocumentContentChangeEvent {
+                range: range.map(|(sl, sc, el, ec)| {
+                    lsp_types::Range::new(
+                        lsp_types::Position::new(sl, sc),
+                        lsp_types::Position::new(el, ec),
+                    )
+                }),
+                range_length: None,
+                text: text.into(),
+            }
+        };
+
+        // Each change is relative to the result of the previous one
+        let result = dc
+            .apply_content_changes(
+                &url,
+                &[
+                    change(Some((0, 10, 0, 13)), "Bar"),
+                    change(Some((0, 44, 0, 45)), "\n    in property <int> i;\n"),
+                    change(Some((1, 4, 1, 6)), "out"),
+                ],
+            )
+            .unwrap();
+        assert_eq!(
+            result,
+            "component Bar { property <string> s: \"🦥\";\n    out property <int> i;\n}\n"
+        );
+
+        // A change without range replaces everything
+        let result = dc
+            .apply_content_changes(&url, &[change(None, "foo"), change(Some((0, 3, 0, 3)), "bar")])
+            .unwrap();
+        assert_eq!(result, "foobar");
+
+        // The range must be valid
+        assert!(dc.apply_content_changes(&url, &[change(Some((0, 38, 0, 39)), "")]).is_err());
+        assert!(dc
+            .apply_content_changes(
+                &Url::parse("file:///unknown.slint").unwrap(),
+                &[change(Some((0, 0, 0, 0)), "")]
+            )
+            .is_err());
+    }
+
     #[test]
     fn test_element_at_position_no_such_document() {
         let (dc, _, _) = complex_document_cache();
@@ -576,6 +576,10 @@ fn is_same_symbol(&self, document_cache: &common::DocumentCache, token: SyntaxTo
                 common::token_info::TokenInfo::LocalProperty(s),
                 common::token_info::TokenInfo::LocalProperty(o),
             ) => Rc::ptr_eq(&s.source_file, &o.source_file) && s.text_range() == o.text_range(),
+            (
+                common::token_info::TokenInfo::LocalCallback(s),
+                common::token_info::TokenInfo::LocalCallback(o),
+            ) => Rc::ptr_eq(&s.source_file, &o.source_file) && s.text_range() == o.text_range(),
             (
                 common::token_info::TokenInfo::NamedReference(nl),
                 common::token_info::TokenInfo::NamedReference(nr),
@@ -613,6 +617,17 @@ fn is_same_symbol(&self, document_cache: &common::DocumentCache, token: SyntaxTo
                 di.node.source_file.path() == s.source_file.path()
                     && di.node.PropertyDeclaration().any(|pd| pd.text_range() == s.text_range())
             }),
+            (
+                common::token_info::TokenInfo::NamedReference(nr),
+                common::token_info::TokenInfo::LocalCallback(s),
+            )
+            | (
+                common::token_info::TokenInfo::LocalCallback(s),
+                common::token_info::TokenInfo::NamedReference(nr),
+            ) => nr.element().borrow().debug.iter().any(|di| {
+                di.node.source_file.path() == s.source_file.path()
+                    && di.node.CallbackDeclaration().any(|cd| cd.text_range() == s.text_range())
+            }),
             (
                 common::token_info::TokenInfo::LocalProperty(s),
                 common::token_info::TokenInfo::IncompleteNamedReference(nr1, nr2),
@@ -3,9 +3,10 @@
 
 use std::collections::HashMap;
 
-use i_slint_compiler::parser::TextSize;
+use i_slint_compiler::parser::{TextRange, TextSize};
 
 use crate::common;
+use crate::util;
 
 #[derive(Clone, Debug)]
 pub struct TextOffsetAdjustment {
@@ -21,16 +22,9 @@ pub fn new(
     ) -> Self {
         let new_text_length = edit.new_text.len() as u32;
         let (start_offset, end_offset) = {
-            let so = source_file.offset(
-                edit.range.start.line as usize + 1,
-                edit.range.start.character as usize + 1,
-            );
-            let eo = source_file
-                .offset(edit.range.end.line as usize + 1, edit.range.end.character as usize + 1);
-            (
-                TextSize::new(std::cmp::min(so, eo) as u32),
-                TextSize::new(std::cmp::max(so, eo) as u32),
-            )
+            let so = util::lsp_position_to_text_size(source_file, edit.range.start);
+            let eo = util::lsp_position_to_text_size(source_file, edit.range.end);
+            (std::cmp::min(so, eo), std::cmp::max(so, eo))
         };
 
         Self { start_offset, end_offset, new_text_length }
@@ -193,15 +187,11 @@ pub fn new(source_file: i_slint_compiler::diagnostics::SourceFile) -> crate::Res
 
     pub fn apply(&mut self, text_edit: &lsp_types::TextEdit) -> crate::Result<()> {
         let current_offset = {
-            let start_range = &text_edit.range.start;
-            let end_range = &text_edit.range.end;
-            let start_offset = self
-                .source_file
-                .offset(start_range.line as usize + 1, start_range.character as usize + 1);
-            let end_offset = self
-                .source_file
-                .offset(end_range.line as usize + 1, end_range.character as usize + 1);
-            (start_offset, end_offset)
+            let start_offset =
+                util::lsp_position_to_text_size(&self.source_file, text_edit.range.start);
+            let end_offset =
+                util::lsp_position_to_text_size(&self.source_file, text_edit.range.end);
+            (usize::from(start_offset), usize::from(end_offset))
         };
 
         let adjusted_offset = (
@@ -233,6 +223,24 @@ pub fn finalize(self) -> Option<(String, TextOffsetAdjustments, (usize, usize))>
     }
 }
 
+/// Create a `TextEdit` replacing `range` in `source_file` with `new_text`
+pub fn replace_text(
+    source_file: &i_slint_compiler::diagnostics::SourceFile,
+    range: TextRange,
+    new_text: impl Into<String>,
+) -> lsp_types::TextEdit {
+    lsp_types::TextEdit::new(util::text_range_to_lsp_range(source_file, range), new_text.into())
+}
+
+/// Create a `TextEdit` inserting `new_text` at `offset` in `source_file`
+pub fn insert_text(
+    source_file: &i_slint_compiler::diagnostics::SourceFile,
+    offset: TextSize,
+    new_text: impl Into<String>,
+) -> lsp_types::TextEdit {
+    replace_text(source_file, TextRange::empty(offset), new_text)
+}
+
 pub struct EditedText {
     pub url: lsp_types::Url,
     pub contents: String,
@@ -105,9 +105,14 @@ pub fn token_info(document_cache: &common::DocumentCache, token: SyntaxToken) ->
         } else if let Some(n) = syntax_nodes::ImportIdentifier::new(node.clone()) {
             let doc = document_cache.get_document_for_source_file(&node.source_file)?;
             let imp_name = i_slint_compiler::typeloader::ImportedName::from_node(n);
-            return Some(TokenInfo::ElementType(
-                doc.local_registry.lookup_element(&imp_name.internal_name).ok()?,
-            ));
+            return match doc.local_registry.lookup_element(&imp_name.internal_name) {
+                Ok(element_type) => Some(TokenInfo::ElementType(element_type)),
+                // Structs and enums are types, not elements
+                Err(_) => match doc.local_registry.lookup(&imp_name.internal_name) {
+                    Type::Invalid => None,
+                    ty => Some(TokenInfo::Type(ty)),
+                },
+            };
         } else if let Some(n) = syntax_nodes::ExportSpecifier::new(node.clone()) {
             let doc = document_cache.get_document_for_source_file(&node.source_file)?;
             let (_, exp) = i_slint_compiler::object_tree::ExportedName::from_export_specifier(&n);
@@ -193,6 +198,18 @@ pub fn token_info(document_cache: &common::DocumentCache, token: SyntaxToken) ->
                 }
                 return find_property_declaration_in_base(document_cache, element, &prop_name);
             }
+            if parent.kind() == SyntaxKind::CallbackDeclaration {
+                let prop_name = i_slint_compiler::parser::normalize_identifier(token.text());
+                let element = syntax_nodes::Element::new(parent.parent()?)?;
+                if let Some(p) = element.CallbackDeclaration().find_map(|p| {
+                    (i_slint_compiler::parser::identifier_text(&p.DeclaredIdentifier())?
+                        == prop_name)
+                        .then_some(p)
+                }) {
+                    return Some(TokenInfo::LocalCallback(p));
+                }
+                return find_property_declaration_in_base(document_cache, element, &prop_name);
+            }
             if parent.kind() == SyntaxKind::Component {
                 let doc = document_cache.get_document_for_source_file(&node.source_file)?;
                 let element_type = doc
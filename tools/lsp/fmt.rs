@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: GPL-3.0-only OR LicenseRef-Slint-Royalty-free-2.0 OR LicenseRef-Slint-Software-3.0
 
 pub mod fmt;
+pub mod options;
 #[cfg(not(target_arch = "wasm32"))]
 pub mod tool;
 pub mod writer;
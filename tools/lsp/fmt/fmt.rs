@@ -1,15 +1,31 @@
 // Copyright © SixtyFPS GmbH <info@slint.dev>
 // SPDX-License-Identifier: GPL-3.0-only OR LicenseRef-Slint-Royalty-free-2.0 OR LicenseRef-Slint-Software-3.0
 
+use super::options::FormatOptions;
 use super::writer::TokenWriter;
 use i_slint_compiler::parser::{syntax_nodes, NodeOrToken, SyntaxKind, SyntaxNode};
 
-pub fn format_document(
+pub fn format_document_with_options(
     doc: syntax_nodes::Document,
+    options: &FormatOptions,
     writer: &mut impl TokenWriter,
 ) -> Result<(), std::io::Error> {
-    let mut state = FormatState::default();
-    format_node(&doc, writer, &mut state)
+    format_node_with_options(&doc, options, 0, writer)
+}
+
+/// Format a `node` that is part of a document, such as an element or a binding.
+///
+/// `indentation_level` is the indentation of the line where the node starts. The whitespace
+/// before the node is not part of the node, so it is left untouched.
+pub fn format_node_with_options(
+    node: &SyntaxNode,
+    options: &FormatOptions,
+    indentation_level: u32,
+    writer: &mut impl TokenWriter,
+) -> Result<(), std::io::Error> {
+    let mut state =
+        FormatState { options: options.clone(), indentation_level, ..Default::default() };
+    format_node(node, writer, &mut state)
 }
 
 #[derive(Default)]
@@ -32,6 +48,12 @@ struct FormatState {
 
     /// a comment has been written followed maybe by some spacing
     after_comment: bool,
+
+    options: FormatOptions,
+
+    /// The next binary expression is too long for `options.max_line_width`, and its operators
+    /// should start new lines
+    wrap_binary_expression: bool,
 }
 
 impl FormatState {
@@ -44,11 +66,8 @@ fn new_line(&mut self) {
             x.insert(0, '\n');
             return;
         }
-        let mut new_line = String::from("\n");
-        for _ in 0..self.indentation_level {
-            new_line += "    ";
-        }
-        self.whitespace_to_add = Some(new_line);
+        self.whitespace_to_add =
+            Some(format!("\n{}", self.options.indentation(self.indentation_level)));
     }
 
     fn insert_whitespace(&mut self, arg: &str) {
@@ -159,6 +178,9 @@ fn format_node(
         SyntaxKind::MemberAccess => {
             return format_member_access(node, writer, state);
         }
+        SyntaxKind::GenericParameters | SyntaxKind::GenericArguments => {
+            return format_generic_list(node, writer, state);
+        }
 
         _ => (),
     }
@@ -292,6 +314,9 @@ fn format_component(
         let mut sub = node.children_with_tokens();
         let _ok = whitespace_to(&mut sub, SyntaxKind::Identifier, writer, state, "")?
             && whitespace_to(&mut sub, SyntaxKind::DeclaredIdentifier, writer, state, " ")?;
+        if node.child_node(SyntaxKind::GenericParameters).is_some() {
+            whitespace_to(&mut sub, SyntaxKind::GenericParameters, writer, state, "")?;
+        }
         let r = whitespace_to_one_of(
             &mut sub,
             &[SyntaxKind::Identifier, SyntaxKind::Element],
@@ -319,6 +344,8 @@ fn format_element(
 
     let ok = if node.child_node(SyntaxKind::QualifiedName).is_some() {
         whitespace_to(&mut sub, SyntaxKind::QualifiedName, writer, state, "")?
+            && (node.child_node(SyntaxKind::GenericArguments).is_none()
+                || whitespace_to(&mut sub, SyntaxKind::GenericArguments, writer, state, "")?)
             && whitespace_to(&mut sub, SyntaxKind::LBrace, writer, state, " ")?
     } else {
         whitespace_to(&mut sub, SyntaxKind::LBrace, writer, state, "")?
@@ -429,9 +456,11 @@ fn format_property_declaration(
     let need_newline = node.child_node(SyntaxKind::TwoWayBinding).is_none();
 
     state.skip_all_whitespace = true;
+    state.wrap_binary_expression = is_too_long_binding(node, state);
     for s in sub {
         fold(s, writer, state)?;
     }
+    state.wrap_binary_expression = false;
     if need_newline {
         state.new_line();
     }
@@ -444,9 +473,11 @@ fn format_binding(
     state: &mut FormatState,
 ) -> Result<(), std::io::Error> {
     let mut sub = node.children_with_tokens();
+    state.wrap_binary_expression = is_too_long_binding(node, state);
     let _ok = whitespace_to(&mut sub, SyntaxKind::Identifier, writer, state, "")?
         && whitespace_to(&mut sub, SyntaxKind::Colon, writer, state, "")?
         && whitespace_to(&mut sub, SyntaxKind::BindingExpression, writer, state, " ")?;
+    state.wrap_binary_expression = false;
     // FIXME: more formatting
     for s in sub {
         fold(s, writer, state)?;
@@ -455,6 +486,21 @@ fn format_binding(
     Ok(())
 }
 
+/// Returns true if the binding expression of `node` is a binary expression that would make
+/// the line longer than `max_line_width` when written on one line
+fn is_too_long_binding(node: &SyntaxNode, state: &FormatState) -> bool {
+    let Some(max_line_width) = state.options.max_line_width else { return false };
+    let is_binary_expression = node
+        .child_node(SyntaxKind::BindingExpression)
+        .and_then(|b| b.child_node(SyntaxKind::Expression))
+        .and_then(|e| e.child_node(SyntaxKind::BinaryExpression))
+        .is_some();
+    let one_line = node.text().to_string().split_whitespace().collect::<Vec<_>>().join(" ");
+    is_binary_expression
+        && state.options.indentation_width(state.indentation_level) + one_line.chars().count()
+            > max_line_width
+}
+
 fn format_two_way_binding(
     node: &SyntaxNode,
     writer: &mut impl TokenWriter,
@@ -476,6 +522,26 @@ fn format_two_way_binding(
     Ok(())
 }
 
+fn format_generic_list(
+    node: &SyntaxNode,
+    writer: &mut impl TokenWriter,
+    state: &mut FormatState,
+) -> Result<(), std::io::Error> {
+    for n in node.children_with_tokens() {
+        state.skip_all_whitespace = true;
+        match n.kind() {
+            SyntaxKind::Comma => {
+                fold(n, writer, state)?;
+                state.insert_whitespace(" ");
+            }
+            _ => {
+                fold(n, writer, state)?;
+            }
+        }
+    }
+    Ok(())
+}
+
 fn format_callback_declaration(
     node: &SyntaxNode,
     writer: &mut impl TokenWriter,
@@ -654,9 +720,27 @@ fn format_binary_expression(
     writer: &mut impl TokenWriter,
     state: &mut FormatState,
 ) -> Result<(), std::io::Error> {
+    let wrap = std::mem::take(&mut state.wrap_binary_expression);
     let mut sub = node.children_with_tokens();
 
-    let _ok = whitespace_to(&mut sub, SyntaxKind::Expression, writer, state, "")?
+    // In a chain such as `a && b && c`, the left operand is also a binary expression to wrap
+    state.wrap_binary_expression = wrap
+        && node
+            .child_node(SyntaxKind::Expression)
+            .and_then(|e| e.child_node(SyntaxKind::BinaryExpression))
+            .is_some();
+    let left_ok = whitespace_to(&mut sub, SyntaxKind::Expression, writer, state, "")?;
+    state.wrap_binary_expression = false;
+    let operator_prefix = if wrap {
+        state.indentation_level += 1;
+        state.new_line();
+        state.indentation_level -= 1;
+        ""
+    } else {
+        " "
+    };
+
+    let _ok = left_ok
         && whitespace_to_one_of(
             &mut sub,
             &[
@@ -680,7 +764,7 @@ fn format_binary_expression(
             ],
             writer,
             state,
-            " ",
+            operator_prefix,
         )?
         .is_found()
         && whitespace_to(&mut sub, SyntaxKind::Expression, writer, state, " ")?;
@@ -1303,6 +1387,11 @@ mod tests {
     // FIXME more descriptive errors when an assertion fails
     #[track_caller]
     fn assert_formatting(unformatted: &str, formatted: &str) {
+        assert_formatting_with_options(unformatted, formatted, &FormatOptions::default());
+    }
+
+    #[track_caller]
+    fn assert_formatting_with_options(unformatted: &str, formatted: &str, options: &FormatOptions) {
         // Parse the unformatted string
         let syntax_node = i_slint_compiler::parser::parse(
             String::from(unformatted),
@@ -1312,7 +1401,7 @@ fn assert_formatting(unformatted: &str, formatted: &str) {
         // Turn the syntax node into a document
         let doc = syntax_nodes::Document::new(syntax_node).unwrap();
         let mut file = Vec::new();
-        format_document(doc, &mut FileWriter { file: &mut file }).unwrap();
+        format_document_with_options(doc, options, &mut FileWriter { file: &mut file }).unwrap();
         assert_eq!(String::from_utf8(file).unwrap(), formatted);
     }
 
@@ -1329,6 +1418,14 @@ fn components() {
         );
     }
 
+    #[test]
+    fn generic_components() {
+        assert_formatting(
+            "component  Picker < T,U >  inherits  Base< U >{ Foo<T>{} x:=Bar < [T] ,int>{} }",
+            "component Picker<T, U> inherits Base<U> {\n    Foo<T> { }\n\n    x := Bar<[T], int> { }\n}\n",
+        );
+    }
+
     #[test]
     fn with_comments() {
         assert_formatting(
@@ -2140,4 +2237,38 @@ fn access_member() {
 "#,
         );
     }
+
+    #[test]
+    fn indentation_options() {
+        let source = "component X { Rectangle { width: 42px; } }";
+        assert_formatting_with_options(
+            source,
+            "component X {\n  Rectangle {\n    width: 42px;\n  }\n}\n",
+            &FormatOptions { indent_width: 2, ..Default::default() },
+        );
+        assert_formatting_with_options(
+            source,
+            "component X {\n\tRectangle {\n\t\twidth: 42px;\n\t}\n}\n",
+            &FormatOptions { use_tabs: true, ..Default::default() },
+        );
+    }
+
+    #[test]
+    fn wrap_long_binding_expressions() {
+        let options = FormatOptions { max_line_width: Some(40), ..Default::default() };
+        assert_formatting_with_options(
+            "component X { visible: first-condition && second-condition || (a && b); short: a && b; in property <length> w: self.width + self.padding * 2 - 1px; }",
+            r#"component X {
+    visible: first-condition
+        && second-condition
+        || (a && b);
+    short: a && b;
+    in property <length> w: self.width
+        + self.padding * 2
+        - 1px;
+}
+"#,
+            &options,
+        );
+    }
 }
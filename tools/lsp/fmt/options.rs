@@ -0,0 +1,143 @@
+// Copyright © SixtyFPS GmbH <info@slint.dev>
+// SPDX-License-Identifier: GPL-3.0-only OR LicenseRef-Slint-Royalty-free-2.0 OR LicenseRef-Slint-Software-3.0
+
+/*!
+    The options of the formatter, read from a `.slintfmt.toml` file in the directory of the
+    formatted file or in one of its parents:
+
+    ```toml
+    indent-width = 2
+    use-tabs = false
+    max-line-width = 100
+    ```
+*/
+
+use std::path::Path;
+
+pub const CONFIG_FILE_NAME: &str = ".slintfmt.toml";
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FormatOptions {
+    /// The number of spaces of one level of indentation, when not using tabs
+    pub indent_width: usize,
+    /// Indent with one tab per level instead of spaces
+    pub use_tabs: bool,
+    /// Binding expressions that make their line longer than this are wrapped before their
+    /// binary operators. There is no wrapping when `None`.
+    pub max_line_width: Option<usize>,
+}
+
+impl Default for FormatOptions {
+    fn default() -> Self {
+        Self { indent_width: 4, use_tabs: false, max_line_width: None }
+    }
+}
+
+impl FormatOptions {
+    /// The whitespace for `level` levels of indentation
+    pub fn indentation(&self, level: u32) -> String {
+        if self.use_tabs {
+            "\t".repeat(level as usize)
+        } else {
+            " ".repeat(self.indent_width * level as usize)
+        }
+    }
+
+    /// The width of `level` levels of indentation, counting a tab like `indent_width` spaces
+    pub fn indentation_width(&self, level: u32) -> usize {
+        self.indent_width * level as usize
+    }
+
+    /// Parse the content of a `.slintfmt.toml` file
+    pub fn from_toml(source: &str) -> Result<Self, String> {
+        let document = source.parse::<toml_edit::DocumentMut>().map_err(|e| e.to_string())?;
+        let mut options = Self::default();
+        let positive_integer = |key: &str, item: &toml_edit::Item| {
+            item.as_integer()
+                .filter(|i| *i > 0)
+                .map(|i| i as usize)
+                .ok_or_else(|| format!("`{key}` must be a positive integer"))
+        };
+        for (key, item) in document.iter() {
+            match key {
+                "indent-width" => options.indent_width = positive_integer(key, item)?,
+                "use-tabs" => {
+                    options.use_tabs =
+                        item.as_bool().ok_or_else(|| format!("`{key}` must be a boolean"))?
+                }
+                "max-line-width" => options.max_line_width = Some(positive_integer(key, item)?),
+                _ => return Err(format!("Unknown option `{key}`")),
+            }
+        }
+        Ok(options)
+    }
+
+    /// The options for formatting the file at `path`: the ones of the closest `.slintfmt.toml`,
+    /// or the default ones if there is none. Returns an error if that file is invalid.
+    pub fn for_file(path: &Path) -> Result<Self, String> {
+        let Some(config_file) =
+            path.ancestors().skip(1).map(|dir| dir.join(CONFIG_FILE_NAME)).find(|f| f.is_file())
+        else {
+            return Ok(Self::default());
+        };
+        std::fs::read_to_string(&config_file)
+            .map_err(|e| e.to_string())
+            .and_then(|source| Self::from_toml(&source))
+            .map_err(|e| format!("{}: {e}", config_file.display()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_toml() {
+        assert_eq!(FormatOptions::from_toml(""), Ok(FormatOptions::default()));
+        assert_eq!(
+            FormatOptions::from_toml("indent-width = 2\nuse-tabs = true\nmax-line-width = 80\n"),
+            Ok(FormatOptions { indent_width: 2, use_tabs: true, max_line_width: Some(80) })
+        );
+        assert_eq!(
+            FormatOptions::from_toml("indent-width = 0"),
+            Err("`indent-width` must be a positive integer".into())
+        );
+        assert_eq!(
+            FormatOptions::from_toml("use-tabs = 1"),
+            Err("`use-tabs` must be a boolean".into())
+        );
+        assert_eq!(FormatOptions::from_toml("tab = 2"), Err("Unknown option `tab`".into()));
+        assert!(FormatOptions::from_toml("indent-width = ").is_err());
+    }
+
+    #[test]
+    fn test_for_file() {
+        let dir = std::env::temp_dir().join(format!("slintfmt_test_{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("sub")).unwrap();
+        let file = dir.join("sub").join("test.slint");
+        let config_file = dir.join(CONFIG_FILE_NAME);
+
+        std::fs::write(&config_file, "indent-width = 2").unwrap();
+        assert_eq!(
+            FormatOptions::for_file(&file),
+            Ok(FormatOptions { indent_width: 2, ..Default::default() })
+        );
+
+        std::fs::write(&config_file, "tab = 2").unwrap();
+        assert_eq!(
+            FormatOptions::for_file(&file),
+            Err(format!("{}: Unknown option `tab`", config_file.display()))
+        );
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_indentation() {
+        let options = FormatOptions { indent_width: 2, ..Default::default() };
+        assert_eq!(options.indentation(2), "    ");
+        let options = FormatOptions { use_tabs: true, ..Default::default() };
+        assert_eq!(options.indentation(2), "\t\t");
+        assert_eq!(options.indentation_width(2), 8);
+    }
+}
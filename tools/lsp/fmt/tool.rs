@@ -20,6 +20,7 @@
 use std::io::{BufWriter, Write};
 use std::path::Path;
 
+use super::options::FormatOptions;
 use super::{fmt, writer};
 
 pub fn run(files: Vec<std::path::PathBuf>, inplace: bool) -> std::io::Result<()> {
@@ -37,7 +38,11 @@ pub fn run(files: Vec<std::path::PathBuf>, inplace: bool) -> std::io::Result<()>
 }
 
 /// FIXME! this is duplicated with the updater
-fn process_rust_file(source: String, mut file: impl Write) -> std::io::Result<()> {
+fn process_rust_file(
+    source: String,
+    options: &FormatOptions,
+    mut file: impl Write,
+) -> std::io::Result<()> {
     let mut last = 0;
     for range in i_slint_compiler::lexer::locate_slint_macro(&source) {
         file.write_all(source[last..=range.start].as_bytes())?;
@@ -47,7 +52,7 @@ fn process_rust_file(source: String, mut file: impl Write) -> std::io::Result<()
         let mut diag = BuildDiagnostics::default();
         let syntax_node = i_slint_compiler::parser::parse(code.to_owned(), None, &mut diag);
         let len = syntax_node.text_range().end().into();
-        visit_node(syntax_node, &mut file)?;
+        visit_node(syntax_node, options, &mut file)?;
         if diag.has_errors() {
             file.write_all(&code.as_bytes()[len..])?;
             diag.print();
@@ -57,7 +62,11 @@ fn process_rust_file(source: String, mut file: impl Write) -> std::io::Result<()
 }
 
 /// FIXME! this is duplicated with the updater
-fn process_markdown_file(source: String, mut file: impl Write) -> std::io::Result<()> {
+fn process_markdown_file(
+    source: String,
+    options: &FormatOptions,
+    mut file: impl Write,
+) -> std::io::Result<()> {
     let mut source_slice = &source[..];
     const CODE_FENCE_START: &str = "```slint\n";
     const CODE_FENCE_END: &str = "```\n";
@@ -77,7 +86,7 @@ fn process_markdown_file(source: String, mut file: impl Write) -> std::io::Resul
         let mut diag = BuildDiagnostics::default();
         let syntax_node = i_slint_compiler::parser::parse(code.to_owned(), None, &mut diag);
         let len = syntax_node.text_range().end().into();
-        visit_node(syntax_node, &mut file)?;
+        visit_node(syntax_node, options, &mut file)?;
         if diag.has_errors() {
             file.write_all(&code.as_bytes()[len..])?;
             diag.print();
@@ -89,12 +98,13 @@ fn process_markdown_file(source: String, mut file: impl Write) -> std::io::Resul
 fn process_slint_file(
     source: String,
     path: std::path::PathBuf,
+    options: &FormatOptions,
     mut file: impl Write,
 ) -> std::io::Result<()> {
     let mut diag = BuildDiagnostics::default();
     let syntax_node = i_slint_compiler::parser::parse(source.clone(), Some(&path), &mut diag);
     let len = syntax_node.node.text_range().end().into();
-    visit_node(syntax_node, &mut file)?;
+    visit_node(syntax_node, options, &mut file)?;
     if diag.has_errors() {
         file.write_all(&source.as_bytes()[len..])?;
         diag.print();
@@ -107,15 +117,21 @@ fn process_file(
     path: std::path::PathBuf,
     mut file: impl Write,
 ) -> std::io::Result<()> {
+    let options = FormatOptions::for_file(&path).unwrap_or_else(|e| {
+        eprintln!("{e}");
+        FormatOptions::default()
+    });
     match path.extension() {
-        Some(ext) if ext == "rs" => process_rust_file(source, file),
-        Some(ext) if ext == "md" => process_markdown_file(source, file),
+        Some(ext) if ext == "rs" => process_rust_file(source, &options, file),
+        Some(ext) if ext == "md" => process_markdown_file(source, &options, file),
         // Formatting .60 files because of backwards compatibility (project was recently renamed)
-        Some(ext) if ext == "slint" || ext == ".60" => process_slint_file(source, path, file),
+        Some(ext) if ext == "slint" || ext == ".60" => {
+            process_slint_file(source, path, &options, file)
+        }
         _ => {
             // This allows usage like `cat x.slint | slint-lsp format /dev/stdin`
             if path.as_path() == Path::new("/dev/stdin") {
-                return process_slint_file(source, path, file);
+                return process_slint_file(source, path, &options, file);
             }
             // With other file types, we just output them in their original form.
             file.write_all(source.as_bytes())
@@ -123,10 +139,14 @@ fn process_file(
     }
 }
 
-fn visit_node(node: SyntaxNode, file: &mut impl Write) -> std::io::Result<()> {
+fn visit_node(
+    node: SyntaxNode,
+    options: &FormatOptions,
+    file: &mut impl Write,
+) -> std::io::Result<()> {
     if let Some(doc) = syntax_nodes::Document::new(node) {
         let mut writer = writer::FileWriter { file };
-        fmt::format_document(doc, &mut writer)
+        fmt::format_document_with_options(doc, options, &mut writer)
     } else {
         Err(std::io::Error::new(std::io::ErrorKind::Other, "Not a Document"))
     }
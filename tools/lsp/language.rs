@@ -3,14 +3,21 @@
 
 // cSpell: ignore descr rfind unindented
 
+mod call_hierarchy;
 pub mod completion;
+mod folding_ranges;
 mod formatting;
 mod goto;
 mod hover;
+mod inlay_hints;
+mod quick_fixes;
+mod references;
+mod selection_ranges;
 mod semantic_tokens;
 mod signature_help;
 #[cfg(test)]
 pub mod test;
+mod type_hierarchy;
 
 use crate::common;
 use crate::util;
@@ -23,17 +30,23 @@
 };
 use i_slint_compiler::{diagnostics::BuildDiagnostics, langtype::Type};
 use lsp_types::request::{
+    CallHierarchyIncomingCalls, CallHierarchyOutgoingCalls, CallHierarchyPrepare,
     CodeActionRequest, CodeLensRequest, ColorPresentationRequest, Completion, DocumentColor,
-    DocumentHighlightRequest, DocumentSymbolRequest, ExecuteCommand, Formatting, GotoDefinition,
-    HoverRequest, PrepareRenameRequest, Rename, SemanticTokensFullRequest, SignatureHelpRequest,
+    DocumentHighlightRequest, DocumentSymbolRequest, ExecuteCommand, FoldingRangeRequest,
+    Formatting, GotoDefinition, HoverRequest, InlayHintRequest, OnTypeFormatting,
+    PrepareRenameRequest, RangeFormatting, References, Rename, SelectionRangeRequest,
+    SemanticTokensFullRequest, SignatureHelpRequest, TypeHierarchyPrepare, TypeHierarchySubtypes,
+    TypeHierarchySupertypes, WorkspaceSymbolRequest,
 };
 use lsp_types::{
-    ClientCapabilities, CodeActionOrCommand, CodeActionProviderCapability, CodeLens,
-    CodeLensOptions, Color, ColorInformation, ColorPresentation, Command, CompletionOptions,
-    DocumentSymbol, DocumentSymbolResponse, InitializeParams, InitializeResult, OneOf, Position,
-    PrepareRenameResponse, RenameOptions, SemanticTokensFullOptions, SemanticTokensLegend,
-    SemanticTokensOptions, ServerCapabilities, ServerInfo, TextDocumentSyncCapability, TextEdit,
-    Url, WorkDoneProgressOptions,
+    CallHierarchyServerCapability, ClientCapabilities, CodeActionOrCommand,
+    CodeActionProviderCapability, CodeLens, CodeLensOptions, Color, ColorInformation,
+    ColorPresentation, Command, CompletionOptions, DocumentOnTypeFormattingOptions, DocumentSymbol,
+    DocumentSymbolResponse, FoldingRangeProviderCapability, InitializeParams, InitializeResult,
+    OneOf, Position, PrepareRenameResponse, RenameOptions, SelectionRangeProviderCapability,
+    SemanticTokensFullOptions, SemanticTokensLegend, SemanticTokensOptions, ServerCapabilities,
+    ServerInfo, TextDocumentSyncCapability, TextEdit, Url, WorkDoneProgressOptions,
+    WorkspaceSymbol, WorkspaceSymbolResponse,
 };
 use std::cell::RefCell;
 use std::collections::HashMap;
@@ -140,6 +153,40 @@ async fn register_file_watcher(ctx: &Context) -> common::Result<()> {
     Ok(())
 }
 
+/// `ServerCapabilities` has no field for the type hierarchy provider, so register it dynamically
+async fn register_type_hierarchy(ctx: &Context) -> common::Result<()> {
+    use lsp_types::request::Request;
+
+    if ctx
+        .init_param
+        .capabilities
+        .text_document
+        .as_ref()
+        .and_then(|td| td.type_hierarchy)
+        .and_then(|th| th.dynamic_registration)
+        .unwrap_or(false)
+    {
+        ctx.server_notifier
+            .send_request::<lsp_types::request::RegisterCapability>(
+                lsp_types::RegistrationParams {
+                    registrations: vec![lsp_types::Registration {
+                        id: "slint.type_hierarchy.registration".to_string(),
+                        method: TypeHierarchyPrepare::METHOD.to_string(),
+                        register_options: Some(
+                            serde_json::to_value(
+                                lsp_types::TypeHierarchyRegistrationOptions::default(),
+                            )
+                            .unwrap(),
+                        ),
+                    }],
+                },
+            )?
+            .await?;
+    }
+
+    Ok(())
+}
+
 pub struct Context {
     pub document_cache: RefCell<common::DocumentCache>,
     pub preview_config: RefCell<common::PreviewConfig>,
@@ -224,15 +271,22 @@ pub fn register<
 }
 
 pub fn server_initialize_result(client_cap: &ClientCapabilities) -> InitializeResult {
+    // Prefer UTF-8 as this is what the compiler uses internally, UTF-16 is mandatory otherwise
+    let position_encoding = if client_cap
+        .general
+        .as_ref()
+        .and_then(|x| x.position_encodings.as_ref())
+        .is_some_and(|x| x.contains(&lsp_types::PositionEncodingKind::UTF8))
+    {
+        util::PositionEncoding::Utf8
+    } else {
+        util::PositionEncoding::Utf16
+    };
+    util::set_position_encoding(position_encoding);
+
     InitializeResult {
         capabilities: ServerCapabilities {
-            // Note: we only support UTF8 at the moment (which is a bug, as the spec says that support for utf-16 is mandatory)
-            position_encoding: client_cap
-                .general
-                .as_ref()
-                .and_then(|x| x.position_encodings.as_ref())
-                .and_then(|x| x.iter().find(|x| *x == &lsp_types::PositionEncodingKind::UTF8))
-                .cloned(),
+            position_encoding: Some(position_encoding.to_lsp()),
             hover_provider: Some(true.into()),
             signature_help_provider: Some(lsp_types::SignatureHelpOptions {
                 trigger_characters: Some(vec!["(".to_owned(), ",".to_owned()]),
@@ -247,8 +301,9 @@ pub fn server_initialize_result(client_cap: &ClientCapabilities) -> InitializeRe
                 completion_item: None,
             }),
             definition_provider: Some(OneOf::Left(true)),
+            references_provider: Some(OneOf::Left(true)),
             text_document_sync: Some(TextDocumentSyncCapability::Kind(
-                lsp_types::TextDocumentSyncKind::FULL,
+                lsp_types::TextDocumentSyncKind::INCREMENTAL,
             )),
             code_action_provider: Some(CodeActionProviderCapability::Simple(true)),
             execute_command_provider: Some(lsp_types::ExecuteCommandOptions {
@@ -256,6 +311,7 @@ pub fn server_initialize_result(client_cap: &ClientCapabilities) -> InitializeRe
                 ..Default::default()
             }),
             document_symbol_provider: Some(OneOf::Left(true)),
+            workspace_symbol_provider: Some(OneOf::Left(true)),
             color_provider: Some(true.into()),
             code_lens_provider: Some(CodeLensOptions { resolve_provider: Some(true) }),
             semantic_tokens_provider: Some(
@@ -287,13 +343,28 @@ pub fn server_initialize_result(client_cap: &ClientCapabilities) -> InitializeRe
                 },
             ),
             document_formatting_provider: Some(OneOf::Left(true)),
+            document_range_formatting_provider: Some(OneOf::Left(true)),
+            document_on_type_formatting_provider: Some(DocumentOnTypeFormattingOptions {
+                first_trigger_character: "}".into(),
+                more_trigger_character: Some(vec![";".into()]),
+            }),
+            inlay_hint_provider: Some(OneOf::Left(true)),
+            folding_range_provider: Some(FoldingRangeProviderCapability::Simple(true)),
+            selection_range_provider: Some(SelectionRangeProviderCapability::Simple(true)),
+            call_hierarchy_provider: Some(CallHierarchyServerCapability::Simple(true)),
             ..ServerCapabilities::default()
         },
         server_info: Some(ServerInfo {
             name: env!("CARGO_PKG_NAME").to_string(),
             version: Some(env!("CARGO_PKG_VERSION").to_string()),
         }),
-        offset_encoding: Some("utf-8".to_string()),
+        offset_encoding: Some(
+            match position_encoding {
+                util::PositionEncoding::Utf8 => "utf-8",
+                util::PositionEncoding::Utf16 => "utf-16",
+            }
+            .to_string(),
+        ),
     }
 }
 
@@ -355,11 +426,18 @@ pub fn register_request_handlers(rh: &mut RequestHandler) {
     rh.register::<CodeActionRequest, _>(|params, ctx| async move {
         let document_cache = &mut ctx.document_cache.borrow_mut();
 
-        let result = token_descr(document_cache, &params.text_document.uri, &params.range.start)
-            .and_then(|(token, _)| {
-                get_code_actions(document_cache, token, &ctx.init_param.capabilities)
-            });
-        Ok(result)
+        let mut result =
+            token_descr(document_cache, &params.text_document.uri, &params.range.start)
+                .and_then(|(token, _)| {
+                    get_code_actions(document_cache, token, &ctx.init_param.capabilities)
+                })
+                .unwrap_or_default();
+        result.extend(quick_fixes::get_quick_fixes(
+            document_cache,
+            &params.text_document.uri,
+            &params.context.diagnostics,
+        ));
+        Ok((!result.is_empty()).then_some(result))
     });
     rh.register::<ExecuteCommand, _>(|params, ctx| async move {
         if params.command.as_str() == SHOW_PREVIEW_COMMAND {
@@ -405,6 +483,10 @@ pub fn register_request_handlers(rh: &mut RequestHandler) {
         let document_cache = &mut ctx.document_cache.borrow_mut();
         Ok(get_document_symbols(document_cache, &params.text_document))
     });
+    rh.register::<WorkspaceSymbolRequest, _>(|params, ctx| async move {
+        let document_cache = &mut ctx.document_cache.borrow_mut();
+        Ok(get_workspace_symbols(document_cache, &params.query))
+    });
     rh.register::<CodeLensRequest, _>(|params, ctx| async move {
         let document_cache = &mut ctx.document_cache.borrow_mut();
         Ok(get_code_lenses(document_cache, &params.text_document))
@@ -480,6 +562,20 @@ pub fn register_request_handlers(rh: &mut RequestHandler) {
         );
         Ok(None)
     });
+    rh.register::<References, _>(|params, ctx| async move {
+        let document_cache = &mut ctx.document_cache.borrow_mut();
+        let uri = params.text_document_position.text_document.uri;
+        if let Some((tk, _)) =
+            token_descr(document_cache, &uri, &params.text_document_position.position)
+        {
+            return Ok(references::find_references(
+                document_cache,
+                tk,
+                params.context.include_declaration,
+            ));
+        }
+        Ok(None)
+    });
     rh.register::<Rename, _>(|params, ctx| async move {
         let mut document_cache = ctx.document_cache.borrow_mut();
         let uri = params.text_document_position.text_document.uri;
@@ -529,9 +625,75 @@ pub fn register_request_handlers(rh: &mut RequestHandler) {
         }
         Ok(None)
     });
+    rh.register::<InlayHintRequest, _>(|params, ctx| async move {
+        let document_cache = ctx.document_cache.borrow_mut();
+        Ok(inlay_hints::get_inlay_hints(&document_cache, &params.text_document, &params.range))
+    });
+    rh.register::<FoldingRangeRequest, _>(|params, ctx| async move {
+        let document_cache = ctx.document_cache.borrow_mut();
+        Ok(folding_ranges::get_folding_ranges(&document_cache, &params.text_document))
+    });
+    rh.register::<SelectionRangeRequest, _>(|params, ctx| async move {
+        let document_cache = ctx.document_cache.borrow_mut();
+        Ok(selection_ranges::get_selection_ranges(
+            &document_cache,
+            &params.text_document,
+            &params.positions,
+        ))
+    });
+    rh.register::<CallHierarchyPrepare, _>(|params, ctx| async move {
+        let document_cache = &mut ctx.document_cache.borrow_mut();
+        let result = token_descr(
+            document_cache,
+            &params.text_document_position_params.text_document.uri,
+            &params.text_document_position_params.position,
+        )
+        .and_then(|(token, _)| call_hierarchy::prepare_call_hierarchy(document_cache, token));
+        Ok(result)
+    });
+    rh.register::<CallHierarchyIncomingCalls, _>(|params, ctx| async move {
+        let document_cache = ctx.document_cache.borrow_mut();
+        Ok(call_hierarchy::incoming_calls(&document_cache, &params.item))
+    });
+    rh.register::<CallHierarchyOutgoingCalls, _>(|params, ctx| async move {
+        let document_cache = ctx.document_cache.borrow_mut();
+        Ok(call_hierarchy::outgoing_calls(&document_cache, &params.item))
+    });
+    rh.register::<TypeHierarchyPrepare, _>(|params, ctx| async move {
+        let document_cache = &mut ctx.document_cache.borrow_mut();
+        let result = token_descr(
+            document_cache,
+            &params.text_document_position_params.text_document.uri,
+            &params.text_document_position_params.position,
+        )
+        .and_then(|(token, _)| type_hierarchy::prepare_type_hierarchy(document_cache, token));
+        Ok(result)
+    });
+    rh.register::<TypeHierarchySupertypes, _>(|params, ctx| async move {
+        let document_cache = ctx.document_cache.borrow_mut();
+        Ok(type_hierarchy::supertypes(&document_cache, &params.item))
+    });
+    rh.register::<TypeHierarchySubtypes, _>(|params, ctx| async move {
+        let document_cache = ctx.document_cache.borrow_mut();
+        Ok(type_hierarchy::subtypes(&document_cache, &params.item))
+    });
     rh.register::<Formatting, _>(|params, ctx| async move {
+        let options = formatting::options_for_uri(&params.text_document.uri, &ctx.server_notifier);
+        let document_cache = ctx.document_cache.borrow_mut();
+        Ok(formatting::format_document(params, &options, &document_cache))
+    });
+    rh.register::<RangeFormatting, _>(|params, ctx| async move {
+        let options = formatting::options_for_uri(&params.text_document.uri, &ctx.server_notifier);
+        let document_cache = ctx.document_cache.borrow_mut();
+        Ok(formatting::format_range(params, &options, &document_cache))
+    });
+    rh.register::<OnTypeFormatting, _>(|params, ctx| async move {
+        let options = formatting::options_for_uri(
+            &params.text_document_position.text_document.uri,
+            &ctx.server_notifier,
+        );
         let document_cache = ctx.document_cache.borrow_mut();
-        Ok(formatting::format_document(params, &document_cache))
+        Ok(formatting::format_on_type(params, &options, &document_cache))
     });
 }
 
@@ -751,6 +913,7 @@ enum FileAction {
             }
             let dependencies = document_cache.invalidate_url(&url);
             let _ = document_cache.load_url(&url, version, content, &mut diag).await;
+            document_cache.check_unused_exports(&mut diag);
             dependencies
         }
         FileAction::IgnoreFile => return Default::default(),
@@ -808,6 +971,18 @@ pub async fn reload_document(
     Ok(())
 }
 
+/// Apply the incremental `changes` sent by the client and reload the document
+pub async fn apply_document_changes(
+    ctx: &Rc<Context>,
+    changes: Vec<lsp_types::TextDocumentContentChangeEvent>,
+    url: lsp_types::Url,
+    version: Option<i32>,
+    document_cache: &mut common::DocumentCache,
+) -> common::Result<()> {
+    let content = document_cache.apply_content_changes(&url, &changes)?;
+    reload_document(ctx, content, url, version, document_cache).await
+}
+
 pub fn convert_diagnostics(
     extra_files: &HashSet<PathBuf>,
     diag: BuildDiagnostics,
@@ -1309,6 +1484,75 @@ fn check_ranges(r: &[DocumentSymbol]) {
     Some(r.into())
 }
 
+/// Retrieve the exported components, globals and structs of all documents matching `query`
+fn get_workspace_symbols(
+    document_cache: &mut common::DocumentCache,
+    query: &str,
+) -> Option<WorkspaceSymbolResponse> {
+    let query = query.to_lowercase();
+    let mut seen = HashSet::new();
+    let mut r = vec![];
+
+    for url in document_cache.all_urls().collect::<Vec<_>>() {
+        if url.scheme() == "builtin" {
+            continue;
+        }
+        let Some(doc) = document_cache.get_document(&url) else { continue };
+
+        for (exported_name, component_or_type) in doc.exports.iter() {
+            let (node, kind) = match component_or_type {
+                itertools::Either::Left(c) => (
+                    c.node.clone(),
+                    if c.is_global() {
+                        lsp_types::SymbolKind::OBJECT
+                    } else {
+                        lsp_types::SymbolKind::CLASS
+                    },
+                ),
+                itertools::Either::Right(Type::Struct(s)) if s.name.is_some() => {
+                    (s.node.as_ref().and_then(|n| n.parent()), lsp_types::SymbolKind::STRUCT)
+                }
+                _ => continue,
+            };
+            let Some(node) = node else { continue };
+            let Some(declared_identifier) = node.child_node(SyntaxKind::DeclaredIdentifier) else {
+                continue;
+            };
+            // Re-exported symbols are reported only once, at their declaration
+            if !seen.insert((node.source_file.path().to_owned(), node.text_range())) {
+                continue;
+            }
+            let Some(name) = declared_identifier.child_text(SyntaxKind::Identifier) else {
+                continue;
+            };
+            if !name.to_lowercase().contains(&query)
+                && !exported_name.as_str().to_lowercase().contains(&query)
+            {
+                continue;
+            }
+            let Some((uri, range)) = util::node_to_url_and_lsp_range(&declared_identifier) else {
+                continue;
+            };
+
+            r.push(WorkspaceSymbol {
+                name: name.to_string(),
+                kind,
+                tags: None,
+                container_name: node
+                    .source_file
+                    .path()
+                    .file_name()
+                    .map(|f| f.to_string_lossy().to_string()),
+                location: OneOf::Left(lsp_types::Location { uri, range }),
+                data: None,
+            });
+        }
+    }
+
+    r.sort_by(|a, b| a.name.cmp(&b.name));
+    Some(WorkspaceSymbolResponse::Nested(r))
+}
+
 fn get_code_lenses(
     document_cache: &mut common::DocumentCache,
     text_document: &lsp_types::TextDocumentIdentifier,
@@ -1435,6 +1679,7 @@ fn recurse(
 
 pub async fn startup_lsp(ctx: &Context) -> common::Result<()> {
     register_file_watcher(ctx).await?;
+    register_type_hierarchy(ctx).await?;
     load_configuration(ctx).await
 }
 
@@ -1621,6 +1866,45 @@ fn test_document_symbols() {
         }
     }
 
+    #[test]
+    fn test_workspace_symbols() {
+        let (mut dc, uri, _) = loaded_document_cache(
+            r#"
+export struct Point { x: int, y: int }
+struct Internal { a: int }
+export global Settings { in-out property <bool> dark; }
+component Helper { }
+export component MainWindow inherits Window { Helper { } }
+export { Point as ReexportedPoint }
+            "#
+            .into(),
+        );
+
+        let Some(WorkspaceSymbolResponse::Nested(result)) = get_workspace_symbols(&mut dc, "")
+        else {
+            unreachable!();
+        };
+        let names = result.iter().map(|s| (s.name.as_str(), s.kind)).collect::<Vec<_>>();
+        assert_eq!(
+            names,
+            vec![
+                ("MainWindow", lsp_types::SymbolKind::CLASS),
+                ("Point", lsp_types::SymbolKind::STRUCT),
+                ("Settings", lsp_types::SymbolKind::OBJECT),
+            ]
+        );
+        let OneOf::Left(location) = &result[1].location else { unreachable!() };
+        assert_eq!(location.uri, uri);
+        assert_eq!(location.range.start, Position::new(1, 14));
+
+        let Some(WorkspaceSymbolResponse::Nested(result)) = get_workspace_symbols(&mut dc, "main")
+        else {
+            unreachable!();
+        };
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].name, "MainWindow");
+    }
+
     #[test]
     fn test_document_symbols_hello_world() {
         let (mut dc, uri, _) = loaded_document_cache(
@@ -0,0 +1,305 @@
+// Copyright © SixtyFPS GmbH <info@slint.dev>
+// SPDX-License-Identifier: GPL-3.0-only OR LicenseRef-Slint-Royalty-free-2.0 OR LicenseRef-Slint-Software-3.0
+
+use super::references::{declaration_node, is_same_node};
+use crate::common;
+use crate::util;
+use i_slint_compiler::diagnostics::SourceLocation;
+use i_slint_compiler::expression_tree::{Callable, Expression};
+use i_slint_compiler::langtype::ElementType;
+use i_slint_compiler::object_tree::{recurse_elem, ElementRc};
+use i_slint_compiler::parser::{SyntaxKind, SyntaxNode, SyntaxToken};
+use lsp_types::{
+    CallHierarchyIncomingCall, CallHierarchyItem, CallHierarchyOutgoingCall, Range, SymbolKind,
+};
+
+#[cfg(target_arch = "wasm32")]
+use crate::wasm_prelude::*;
+
+/// The kind of nodes that are call hierarchy items: the declarations of functions, callbacks and
+/// properties, and the bindings of the builtin callbacks and properties, which have no declaration.
+const ITEM_KINDS: &[SyntaxKind] = &[
+    SyntaxKind::Function,
+    SyntaxKind::CallbackDeclaration,
+    SyntaxKind::PropertyDeclaration,
+    SyntaxKind::CallbackConnection,
+    SyntaxKind::Binding,
+];
+
+/// A call found in the binding of `caller`. `range` is the range of the name of the callee at the call site.
+struct Call {
+    caller: SyntaxNode,
+    callee: SyntaxNode,
+    range: Range,
+}
+
+/// Return the function or callback at `token`
+pub fn prepare_call_hierarchy(
+    document_cache: &common::DocumentCache,
+    token: SyntaxToken,
+) -> Option<Vec<CallHierarchyItem>> {
+    let is_callable =
+        |n: &SyntaxNode| matches!(n.kind(), SyntaxKind::Function | SyntaxKind::CallbackDeclaration);
+    let node =
+        declaration_node(document_cache, token.clone()).filter(is_callable).or_else(|| {
+            let parent = token.parent();
+            match parent.kind() {
+                // A builtin callback, such as `clicked` of a `TouchArea`
+                SyntaxKind::CallbackConnection => Some(parent),
+                SyntaxKind::DeclaredIdentifier => parent.parent().filter(is_callable),
+                _ => None,
+            }
+        })?;
+    Some(vec![call_hierarchy_item(&node)?])
+}
+
+/// Return the functions, callbacks and property bindings that call the `item`
+pub fn incoming_calls(
+    document_cache: &common::DocumentCache,
+    item: &CallHierarchyItem,
+) -> Option<Vec<CallHierarchyIncomingCall>> {
+    let node = item_node(document_cache, item)?;
+    let calls = all_calls(document_cache).into_iter().filter(|c| is_same_node(&c.callee, &node));
+    Some(
+        group_by_node(calls.map(|c| (c.caller, c.range)))
+            .into_iter()
+            .filter_map(|(caller, from_ranges)| {
+                Some(CallHierarchyIncomingCall { from: call_hierarchy_item(&caller)?, from_ranges })
+            })
+            .collect(),
+    )
+}
+
+/// Return the functions and callbacks called by the `item`
+pub fn outgoing_calls(
+    document_cache: &common::DocumentCache,
+    item: &CallHierarchyItem,
+) -> Option<Vec<CallHierarchyOutgoingCall>> {
+    let node = item_node(document_cache, item)?;
+    let calls = all_calls(document_cache).into_iter().filter(|c| is_same_node(&c.caller, &node));
+    Some(
+        group_by_node(calls.map(|c| (c.callee, c.range)))
+            .into_iter()
+            .filter_map(|(callee, from_ranges)| {
+                Some(CallHierarchyOutgoingCall { to: call_hierarchy_item(&callee)?, from_ranges })
+            })
+            .collect(),
+    )
+}
+
+fn call_hierarchy_item(node: &SyntaxNode) -> Option<CallHierarchyItem> {
+    let name = name_token(node)?;
+    let kind = match node.kind() {
+        SyntaxKind::Function => SymbolKind::FUNCTION,
+        SyntaxKind::CallbackDeclaration | SyntaxKind::CallbackConnection => SymbolKind::EVENT,
+        _ => SymbolKind::PROPERTY,
+    };
+    // The name of the enclosing component
+    let detail = std::iter::successors(node.parent(), |n| n.parent())
+        .find(|n| n.kind() == SyntaxKind::Component)
+        .and_then(|c| c.child_node(SyntaxKind::DeclaredIdentifier))
+        .and_then(|id| id.child_text(SyntaxKind::Identifier))
+        .map(|n| n.to_string());
+    Some(CallHierarchyItem {
+        name: name.text().to_string(),
+        kind,
+        tags: None,
+        detail,
+        uri: lsp_types::Url::from_file_path(node.source_file.path()).ok()?,
+        range: util::text_range_to_lsp_range(
+            &node.source_file,
+            util::node_range_without_trailing_ws(node),
+        ),
+        selection_range: util::token_to_lsp_range(&name),
+        data: None,
+    })
+}
+
+fn name_token(node: &SyntaxNode) -> Option<SyntaxToken> {
+    match node.child_node(SyntaxKind::DeclaredIdentifier) {
+        Some(id) => id.child_token(SyntaxKind::Identifier),
+        None => node.child_token(SyntaxKind::Identifier),
+    }
+}
+
+/// Find the node of an item previously returned by [`call_hierarchy_item`]
+fn item_node(
+    document_cache: &common::DocumentCache,
+    item: &CallHierarchyItem,
+) -> Option<SyntaxNode> {
+    let doc_node = document_cache.get_document(&item.uri)?.node.as_ref()?;
+    doc_node.descendants().find(|n| {
+        ITEM_KINDS.contains(&n.kind())
+            && name_token(n).is_some_and(|t| util::token_to_lsp_range(&t) == item.selection_range)
+    })
+}
+
+/// Collect the calls of functions and callbacks in all the bindings of all the documents
+fn all_calls(document_cache: &common::DocumentCache) -> Vec<Call> {
+    let mut result = vec![];
+    for url in document_cache.all_urls() {
+        if url.scheme() == "builtin" {
+            continue;
+        }
+        let Some(doc) = document_cache.get_document(&url) else { continue };
+        for component in &doc.inner_components {
+            recurse_elem(&component.root_element, &(), &mut |element, _| {
+                for (name, binding) in &element.borrow().bindings {
+                    let Some(caller) = callable_node(document_cache, element, name) else {
+                        continue;
+                    };
+                    binding.borrow().expression.visit_recursive(&mut |e| {
+                        let Expression::FunctionCall {
+                            function: Callable::Callback(nr) | Callable::Function(nr),
+                            source_location: Some(location),
+                            ..
+                        } = e
+                        else {
+                            return;
+                        };
+                        let Some(callee) = callable_node(document_cache, &nr.element(), nr.name())
+                        else {
+                            return;
+                        };
+                        if let Some(token) = token_at_location(document_cache, location) {
+                            result.push(Call {
+                                caller: caller.clone(),
+                                callee,
+                                range: util::token_to_lsp_range(&token),
+                            });
+                        }
+                    });
+                }
+            });
+        }
+    }
+    result
+}
+
+/// The node of the item for the property, callback or function `name` of `element`:
+/// its declaration, or its binding in `element` if it is builtin
+fn callable_node(
+    document_cache: &common::DocumentCache,
+    element: &ElementRc,
+    name: &str,
+) -> Option<SyntaxNode> {
+    let mut current = element.clone();
+    loop {
+        if let Some(declaration) = current.borrow().property_declarations.get(name) {
+            return declaration.node.clone();
+        }
+        let base = current.borrow().base_type.clone();
+        match base {
+            ElementType::Component(c) => current = c.root_element.clone(),
+            _ => break,
+        }
+    }
+
+    let span = element.borrow().bindings.get(name)?.borrow().span.clone()?;
+    let token = token_at_location(document_cache, &span)?;
+    std::iter::successors(Some(token.parent()), |n| n.parent())
+        .find(|n| matches!(n.kind(), SyntaxKind::Binding | SyntaxKind::CallbackConnection))
+}
+
+fn token_at_location(
+    document_cache: &common::DocumentCache,
+    location: &SourceLocation,
+) -> Option<SyntaxToken> {
+    let path = location.source_file.as_ref()?.path();
+    let doc_node = document_cache.get_document_by_path(path)?.node.as_ref()?;
+    super::token_at_offset(doc_node, (location.span.offset as u32).into())
+}
+
+/// Group the ranges by node, keeping the order in which the nodes appear first
+fn group_by_node(
+    items: impl Iterator<Item = (SyntaxNode, Range)>,
+) -> Vec<(SyntaxNode, Vec<Range>)> {
+    let mut result: Vec<(SyntaxNode, Vec<Range>)> = vec![];
+    for (node, range) in items {
+        match result.iter_mut().find(|(n, _)| is_same_node(n, &node)) {
+            Some((_, ranges)) => ranges.push(range),
+            None => result.push((node, vec![range])),
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use i_slint_compiler::parser::TextSize;
+
+    #[test]
+    fn test_call_hierarchy() {
+        let source = r#"
+component Abc {
+    callback clicked;
+    function reset() {}
+    clicked => { reset(); }
+}
+export component Test {
+    callback activated;
+    function update() { activated(); abc.clicked(); }
+    abc := Abc {
+        clicked => { root.activated(); }
+    }
+    TouchArea {
+        clicked => { update(); update(); }
+    }
+    Text { text: update() ? "a" : "b"; }
+}"#;
+        let (dc, uri, _) = crate::language::test::loaded_document_cache(source.into());
+        let doc = dc.get_document(&uri).unwrap().node.clone().unwrap();
+        let token_at = |needle: &str| {
+            let offset: TextSize = (source.find(needle).unwrap() as u32).into();
+            crate::language::token_at_offset(&doc, offset).unwrap()
+        };
+        let names = |items: Vec<(CallHierarchyItem, usize)>| {
+            items.into_iter().map(|(i, count)| (i.name, count)).collect::<Vec<_>>()
+        };
+
+        let update = prepare_call_hierarchy(&dc, token_at("update() {")).unwrap().remove(0);
+        assert_eq!(update.name, "update");
+        assert_eq!(update.kind, SymbolKind::FUNCTION);
+        assert_eq!(update.detail.as_deref(), Some("Test"));
+
+        let incoming = incoming_calls(&dc, &update).unwrap();
+        assert_eq!(
+            names(incoming.into_iter().map(|c| (c.from, c.from_ranges.len())).collect()),
+            vec![("clicked".to_string(), 2), ("text".to_string(), 1)]
+        );
+
+        let outgoing = outgoing_calls(&dc, &update).unwrap();
+        assert_eq!(
+            names(outgoing.into_iter().map(|c| (c.to, c.from_ranges.len())).collect()),
+            vec![("activated".to_string(), 1), ("clicked".to_string(), 1)]
+        );
+
+        // The `clicked` callback of Abc is implemented in Abc and in the `abc` instance
+        let clicked = prepare_call_hierarchy(&dc, token_at("clicked(); }")).unwrap().remove(0);
+        assert_eq!(
+            clicked.selection_range,
+            prepare_call_hierarchy(&dc, token_at("clicked;")).unwrap()[0].selection_range
+        );
+        assert_eq!(clicked.kind, SymbolKind::EVENT);
+        assert_eq!(clicked.detail.as_deref(), Some("Abc"));
+        let outgoing = outgoing_calls(&dc, &clicked).unwrap();
+        assert_eq!(
+            names(outgoing.into_iter().map(|c| (c.to, c.from_ranges.len())).collect()),
+            vec![("reset".to_string(), 1), ("activated".to_string(), 1)]
+        );
+
+        // The builtin `clicked` callback of the TouchArea
+        let touch_area_clicked =
+            prepare_call_hierarchy(&dc, token_at("clicked => { update")).unwrap().remove(0);
+        assert_eq!(touch_area_clicked.detail.as_deref(), Some("Test"));
+        let outgoing = outgoing_calls(&dc, &touch_area_clicked).unwrap();
+        assert_eq!(
+            names(outgoing.into_iter().map(|c| (c.to, c.from_ranges.len())).collect()),
+            vec![("update".to_string(), 2)]
+        );
+
+        assert!(prepare_call_hierarchy(&dc, token_at("Text {")).is_none());
+    }
+}
@@ -0,0 +1,173 @@
+// Copyright © SixtyFPS GmbH <info@slint.dev>
+// SPDX-License-Identifier: GPL-3.0-only OR LicenseRef-Slint-Royalty-free-2.0 OR LicenseRef-Slint-Software-3.0
+
+use crate::common;
+use crate::util;
+use i_slint_compiler::parser::{syntax_nodes, NodeOrToken, SyntaxKind, SyntaxNode, SyntaxToken};
+use lsp_types::{FoldingRange, FoldingRangeKind};
+
+/// Compute the folding ranges of the document:
+///  * Every `{ ... }` and `[ ... ]` block spanning several lines (elements, code blocks,
+///    `states`, `transitions`, structs, arrays, ...)
+///  * Multi-line comments and runs of consecutive `//` comments
+///  * Runs of consecutive `import` statements
+pub fn get_folding_ranges(
+    document_cache: &common::DocumentCache,
+    text_document: &lsp_types::TextDocumentIdentifier,
+) -> Option<Vec<FoldingRange>> {
+    let doc = document_cache.get_document(&text_document.uri)?;
+    let doc_node = doc.node.as_ref()?;
+
+    let mut result = vec![];
+    for node in doc_node.descendants() {
+        block_range(&node, &mut result);
+    }
+    comment_ranges(doc_node, &mut result);
+    import_ranges(doc_node, &mut result);
+
+    result.sort_by_key(|r| (r.start_line, r.end_line));
+    Some(result)
+}
+
+fn line(token: &SyntaxToken, offset: i_slint_compiler::parser::TextSize) -> u32 {
+    util::text_size_to_lsp_position(&token.source_file, offset).line
+}
+
+/// Fold between the opening and the closing brace (or bracket) that are direct children of `node`
+fn block_range(node: &SyntaxNode, result: &mut Vec<FoldingRange>) {
+    let mut tokens = node.children_with_tokens().filter_map(NodeOrToken::into_token);
+    let Some(open) = tokens.find(|t| matches!(t.kind(), SyntaxKind::LBrace | SyntaxKind::LBracket))
+    else {
+        return;
+    };
+    let Some(close) =
+        tokens.filter(|t| matches!(t.kind(), SyntaxKind::RBrace | SyntaxKind::RBracket)).last()
+    else {
+        return;
+    };
+
+    let start_line = line(&open, open.text_range().start());
+    let mut end_line = line(&close, close.text_range().start());
+    // Keep the closing brace visible when it is on its own line
+    if close
+        .prev_token()
+        .is_some_and(|t| t.kind() == SyntaxKind::Whitespace && t.text().contains('\n'))
+    {
+        end_line -= 1;
+    }
+    if end_line > start_line {
+        result.push(FoldingRange { start_line, end_line, ..Default::default() });
+    }
+}
+
+fn comment_ranges(doc_node: &syntax_nodes::Document, result: &mut Vec<FoldingRange>) {
+    // The current run of `//` comments, as (first line, last line)
+    let mut run: Option<(u32, u32)> = None;
+    let flush = |run: &mut Option<(u32, u32)>, result: &mut Vec<FoldingRange>| {
+        push_run(run.take(), FoldingRangeKind::Comment, result)
+    };
+
+    let mut current = doc_node.first_token();
+    while let Some(token) = current {
+        current = token.next_token();
+        match token.kind() {
+            SyntaxKind::Comment if token.text().starts_with("//") => {
+                let l = line(&token, token.text_range().start());
+                match &mut run {
+                    Some((_, end)) if *end + 1 == l => *end = l,
+                    _ => {
+                        flush(&mut run, result);
+                        run = Some((l, l));
+                    }
+                }
+            }
+            SyntaxKind::Comment => {
+                flush(&mut run, result);
+                run = Some((
+                    line(&token, token.text_range().start()),
+                    line(&token, token.text_range().end()),
+                ));
+                flush(&mut run, result);
+            }
+            // A comment following code on the same line ends the run
+            SyntaxKind::Whitespace => (),
+            _ => flush(&mut run, result),
+        }
+    }
+    flush(&mut run, result);
+}
+
+fn import_ranges(doc_node: &syntax_nodes::Document, result: &mut Vec<FoldingRange>) {
+    let mut run: Option<(u32, u32)> = None;
+    for child in doc_node.children() {
+        if child.kind() != SyntaxKind::ImportSpecifier {
+            push_run(run.take(), FoldingRangeKind::Imports, result);
+            continue;
+        }
+        let range = util::node_to_lsp_range(&child);
+        run = Some(match run {
+            Some((start, _)) => (start, range.end.line),
+            None => (range.start.line, range.end.line),
+        });
+    }
+    push_run(run, FoldingRangeKind::Imports, result);
+}
+
+/// Add a folding range for a run of lines, as (first line, last line), if it spans several lines
+fn push_run(run: Option<(u32, u32)>, kind: FoldingRangeKind, result: &mut Vec<FoldingRange>) {
+    if let Some((start_line, end_line)) = run.filter(|(s, e)| e > s) {
+        result.push(FoldingRange { start_line, end_line, kind: Some(kind), ..Default::default() });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn folding_ranges(source: &str) -> Vec<(u32, u32, Option<FoldingRangeKind>)> {
+        let (dc, uri, _) = crate::language::test::loaded_document_cache(source.into());
+        get_folding_ranges(&dc, &lsp_types::TextDocumentIdentifier { uri })
+            .unwrap()
+            .into_iter()
+            .map(|r| (r.start_line, r.end_line, r.kind))
+            .collect()
+    }
+
+    #[test]
+    fn test_folding_ranges() {
+        let result = folding_ranges(
+            r#"
+import { Button } from "std-widgets.slint";
+import { LineEdit } from "std-widgets.slint";
+// A comment
+// spanning two lines
+export component Test {
+    callback clicked;
+    clicked => {
+        debug("clicked");
+    }
+    for x in [1, 2, 3] : Rectangle { }
+    states [
+        active when true: {
+            background: red;
+        }
+    ]
+    /* a
+       block comment */
+    Text { text: "single line"; }
+}"#,
+        );
+        assert_eq!(
+            result,
+            vec![
+                (1, 2, Some(FoldingRangeKind::Imports)),
+                (3, 4, Some(FoldingRangeKind::Comment)),
+                (5, 18, None),
+                (7, 8, None),
+                (11, 14, None),
+                (12, 13, None),
+                (16, 17, Some(FoldingRangeKind::Comment)),
+            ]
+        );
+    }
+}
@@ -2,11 +2,16 @@
 // SPDX-License-Identifier: GPL-3.0-only OR LicenseRef-Slint-Royalty-free-2.0 OR LicenseRef-Slint-Software-3.0
 
 use crate::common::DocumentCache;
+use crate::fmt::options::FormatOptions;
 use crate::fmt::{fmt, writer};
-use crate::util::text_range_to_lsp_range;
+use crate::util::{self, text_range_to_lsp_range};
 use dissimilar::Chunk;
-use i_slint_compiler::parser::{SyntaxToken, TextRange, TextSize};
-use lsp_types::{DocumentFormattingParams, TextEdit};
+use i_slint_compiler::diagnostics::SourceFile;
+use i_slint_compiler::parser::{SyntaxKind, SyntaxNode, SyntaxToken, TextRange, TextSize};
+use lsp_types::{
+    DocumentFormattingParams, DocumentOnTypeFormattingParams, DocumentRangeFormattingParams,
+    TextEdit, Url,
+};
 
 struct StringWriter {
     text: String,
@@ -30,20 +35,138 @@ fn insert_before(&mut self, token: SyntaxToken, contents: &str) -> std::io::Resu
     }
 }
 
+/// The syntax nodes that range and on-type formatting can format on their own
+const FORMATTABLE_KINDS: &[SyntaxKind] = &[
+    SyntaxKind::Component,
+    SyntaxKind::SubElement,
+    SyntaxKind::PropertyDeclaration,
+    SyntaxKind::Binding,
+    SyntaxKind::TwoWayBinding,
+    SyntaxKind::CallbackConnection,
+    SyntaxKind::CallbackDeclaration,
+    SyntaxKind::Function,
+];
+
+/// The formatting options for the document at `uri`. An invalid `.slintfmt.toml` is reported
+/// to the user, and the default options are used instead.
+pub fn options_for_uri(uri: &Url, server_notifier: &crate::ServerNotifier) -> FormatOptions {
+    let Some(path) = crate::common::uri_to_file(uri) else {
+        return FormatOptions::default();
+    };
+    FormatOptions::for_file(&path).unwrap_or_else(|message| {
+        let _ = server_notifier.send_notification::<lsp_types::notification::ShowMessage>(
+            lsp_types::ShowMessageParams { typ: lsp_types::MessageType::ERROR, message },
+        );
+        FormatOptions::default()
+    })
+}
+
 pub fn format_document(
     params: DocumentFormattingParams,
+    options: &FormatOptions,
     document_cache: &DocumentCache,
 ) -> Option<Vec<TextEdit>> {
     let doc = document_cache.get_document(&params.text_document.uri)?;
     let doc = doc.node.as_ref()?;
 
     let mut writer = StringWriter { text: String::new() };
-    fmt::format_document(doc.clone(), &mut writer).ok()?;
+    fmt::format_document_with_options(doc.clone(), options, &mut writer).ok()?;
 
     let original: String = doc.text().into();
-    let diff = dissimilar::diff(&original, &writer.text);
+    Some(text_edits_from_diff(&doc.source_file, &original, &writer.text, TextSize::default()))
+}
+
+/// Format the smallest syntax node that encloses the range
+pub fn format_range(
+    params: DocumentRangeFormattingParams,
+    options: &FormatOptions,
+    document_cache: &DocumentCache,
+) -> Option<Vec<TextEdit>> {
+    let uri = &params.text_document.uri;
+    let doc = document_cache.get_document(uri)?;
+    let doc = doc.node.as_ref()?;
+    let start = util::lsp_position_to_text_size(&doc.source_file, params.range.start);
+    let end = util::lsp_position_to_text_size(&doc.source_file, params.range.end);
+    let range = TextRange::new(start, end.max(start));
+
+    let token = crate::language::token_at_offset(doc, start)?;
+    let node = token
+        .parent_ancestors()
+        .find(|n| FORMATTABLE_KINDS.contains(&n.kind()) && n.text_range().contains_range(range));
+    match node {
+        Some(node) => format_node(&node, options),
+        None => format_document(
+            DocumentFormattingParams {
+                text_document: params.text_document,
+                options: params.options,
+                work_done_progress_params: params.work_done_progress_params,
+            },
+            options,
+            document_cache,
+        ),
+    }
+}
 
-    let mut pos = TextSize::default();
+/// Format the syntax node that was just closed by typing `}` or `;`
+pub fn format_on_type(
+    params: DocumentOnTypeFormattingParams,
+    options: &FormatOptions,
+    document_cache: &DocumentCache,
+) -> Option<Vec<TextEdit>> {
+    let uri = &params.text_document_position.text_document.uri;
+    let doc = document_cache.get_document(uri)?;
+    let doc = doc.node.as_ref()?;
+    let offset =
+        util::lsp_position_to_text_size(&doc.source_file, params.text_document_position.position);
+    let token = doc.token_at_offset(offset).left_biased()?;
+    let expected_kind = match params.ch.as_str() {
+        "}" => SyntaxKind::RBrace,
+        ";" => SyntaxKind::Semicolon,
+        _ => return None,
+    };
+    if token.kind() != expected_kind {
+        return None;
+    }
+    let node = token.parent_ancestors().find(|n| FORMATTABLE_KINDS.contains(&n.kind()))?;
+    format_node(&node, options)
+}
+
+fn format_node(node: &SyntaxNode, options: &FormatOptions) -> Option<Vec<TextEdit>> {
+    // Formatting code with syntax errors could move the error around
+    if node.descendants().any(|n| n.kind() == SyntaxKind::Error) {
+        return None;
+    }
+    let range = util::node_range_without_trailing_ws(node);
+    let source = node.source_file.source()?;
+
+    let line_start = source[..usize::from(range.start())].rfind('\n').map_or(0, |i| i + 1);
+    let indentation = &source[line_start..usize::from(range.start())];
+    let indentation_level = if indentation.trim().is_empty() {
+        let tabs = indentation.chars().filter(|c| *c == '\t').count();
+        let spaces = indentation.chars().filter(|c| *c == ' ').count();
+        (tabs + spaces / options.indent_width) as u32
+    } else {
+        0
+    };
+
+    let mut writer = StringWriter { text: String::new() };
+    fmt::format_node_with_options(node, options, indentation_level, &mut writer).ok()?;
+
+    let original = &source[range];
+    Some(text_edits_from_diff(&node.source_file, original, writer.text.trim_end(), range.start()))
+}
+
+/// Compute the edits that turn `original`, which starts at `offset` in the source file, into
+/// `formatted`
+fn text_edits_from_diff(
+    source_file: &SourceFile,
+    original: &str,
+    formatted: &str,
+    offset: TextSize,
+) -> Vec<TextEdit> {
+    let diff = dissimilar::diff(original, formatted);
+
+    let mut pos = offset;
     let mut last_was_deleted = false;
     let mut edits: Vec<TextEdit> = Vec::new();
 
@@ -55,8 +178,7 @@ pub fn format_document(
             }
             Chunk::Delete(text) => {
                 let len = TextSize::of(text);
-                let deleted_range =
-                    text_range_to_lsp_range(&doc.source_file, TextRange::at(pos, len));
+                let deleted_range = text_range_to_lsp_range(source_file, TextRange::at(pos, len));
                 edits.push(TextEdit { range: deleted_range, new_text: String::new() });
                 last_was_deleted = true;
                 pos += len;
@@ -70,12 +192,12 @@ pub fn format_document(
                 }
 
                 let range = TextRange::empty(pos);
-                let range = text_range_to_lsp_range(&doc.source_file, range);
+                let range = text_range_to_lsp_range(source_file, range);
                 edits.push(TextEdit { range, new_text: text.into() });
             }
         }
     }
-    Some(edits)
+    edits
 }
 
 #[cfg(test)]
@@ -92,7 +214,7 @@ fn get_formatting_edits(source: &str) -> Option<Vec<TextEdit>> {
             options: lsp_types::FormattingOptions::default(),
             work_done_progress_params: lsp_types::WorkDoneProgressParams::default(),
         };
-        format_document(params, &dc)
+        format_document(params, &FormatOptions::default(), &dc)
     }
 
     #[test]
@@ -125,4 +247,95 @@ macro_rules! text_edit {
             assert_eq!(actual, expected);
         }
     }
+
+    /// Apply the edits, which must not overlap, to the source
+    fn apply_edits(source: &str, edits: &[TextEdit]) -> String {
+        let sf = SourceFile::new(i_slint_compiler::diagnostics::SourceFileInner::new(
+            "/test.slint".into(),
+            source.into(),
+        ));
+        let mut result = source.to_string();
+        for edit in edits.iter().rev() {
+            let start = usize::from(util::lsp_position_to_text_size(&sf, edit.range.start));
+            let end = usize::from(util::lsp_position_to_text_size(&sf, edit.range.end));
+            result.replace_range(start..end, &edit.new_text);
+        }
+        result
+    }
+
+    #[test]
+    fn test_range_formatting() {
+        let source = "component Bar inherits Text {\n    nope := Rectangle {  x :  3px;   }\n    property   <string> red : \"foo\";\n}\n";
+        let (dc, uri, _) = crate::language::test::loaded_document_cache(source.into());
+        let format_range = |start: Position, end: Position| {
+            format_range(
+                lsp_types::DocumentRangeFormattingParams {
+                    text_document: lsp_types::TextDocumentIdentifier { uri: uri.clone() },
+                    range: Range { start, end },
+                    options: lsp_types::FormattingOptions::default(),
+                    work_done_progress_params: lsp_types::WorkDoneProgressParams::default(),
+                },
+                &FormatOptions::default(),
+                &dc,
+            )
+            .unwrap()
+        };
+
+        // Only the binding
+        let edits = format_range(Position::new(1, 26), Position::new(1, 30));
+        assert_eq!(
+            apply_edits(source, &edits),
+            "component Bar inherits Text {\n    nope := Rectangle {  x: 3px;   }\n    property   <string> red : \"foo\";\n}\n"
+        );
+
+        // The whole element
+        let edits = format_range(Position::new(1, 10), Position::new(1, 30));
+        assert_eq!(
+            apply_edits(source, &edits),
+            "component Bar inherits Text {\n    nope := Rectangle {\n        x: 3px;\n    }\n    property   <string> red : \"foo\";\n}\n"
+        );
+
+        // The property declaration
+        let edits = format_range(Position::new(2, 6), Position::new(2, 6));
+        assert_eq!(
+            apply_edits(source, &edits),
+            "component Bar inherits Text {\n    nope := Rectangle {  x :  3px;   }\n    property <string> red: \"foo\";\n}\n"
+        );
+    }
+
+    #[test]
+    fn test_on_type_formatting() {
+        let source =
+            "component Bar {\n    Rectangle {  x :  3px; }\n    property   <int> red : 3;\n}\n";
+        let (dc, uri, _) = crate::language::test::loaded_document_cache(source.into());
+        let format_on_type = |position: Position, ch: &str| {
+            format_on_type(
+                lsp_types::DocumentOnTypeFormattingParams {
+                    text_document_position: lsp_types::TextDocumentPositionParams {
+                        text_document: lsp_types::TextDocumentIdentifier { uri: uri.clone() },
+                        position,
+                    },
+                    ch: ch.into(),
+                    options: lsp_types::FormattingOptions::default(),
+                },
+                &FormatOptions::default(),
+                &dc,
+            )
+        };
+
+        let edits = format_on_type(Position::new(1, 28), "}").unwrap();
+        assert_eq!(
+            apply_edits(source, &edits),
+            "component Bar {\n    Rectangle {\n        x: 3px;\n    }\n    property   <int> red : 3;\n}\n"
+        );
+
+        let edits = format_on_type(Position::new(2, 29), ";").unwrap();
+        assert_eq!(
+            apply_edits(source, &edits),
+            "component Bar {\n    Rectangle {  x :  3px; }\n    property <int> red: 3;\n}\n"
+        );
+
+        // Not after the trigger character
+        assert_eq!(format_on_type(Position::new(2, 28), ";"), None);
+    }
 }
@@ -0,0 +1,311 @@
+// Copyright © SixtyFPS GmbH <info@slint.dev>
+// SPDX-License-Identifier: GPL-3.0-only OR LicenseRef-Slint-Royalty-free-2.0 OR LicenseRef-Slint-Software-3.0
+
+use crate::common::{
+    self,
+    token_info::{token_info, TokenInfo},
+};
+use crate::util;
+use i_slint_compiler::langtype::{Function, Type};
+use i_slint_compiler::namedreference::NamedReference;
+use i_slint_compiler::object_tree::ElementRc;
+use i_slint_compiler::parser::{syntax_nodes, SyntaxKind, SyntaxNode};
+use lsp_types::{InlayHint, InlayHintKind, InlayHintLabel, TextEdit};
+use std::rc::Rc;
+
+/// Maximum number of aliases that are followed to find the target of a two way binding
+const MAX_ALIAS_CHAIN: usize = 16;
+
+/// Compute the inlay hints of the document within `range`:
+///  * The inferred type of properties and callbacks declared with `<=>` but without a type
+///  * The target of a two way binding, if it is itself an alias
+///  * The name of the arguments in callback and function calls
+pub fn get_inlay_hints(
+    document_cache: &common::DocumentCache,
+    text_document: &lsp_types::TextDocumentIdentifier,
+    range: &lsp_types::Range,
+) -> Option<Vec<InlayHint>> {
+    let doc = document_cache.get_document(&text_document.uri)?;
+    let doc_node = doc.node.as_ref()?;
+
+    let mut result = vec![];
+    for component in &doc.inner_components {
+        i_slint_compiler::object_tree::recurse_elem(&component.root_element, &(), &mut |e, _| {
+            declaration_hints(e, &mut result);
+        });
+    }
+    call_site_hints(document_cache, doc_node, &mut result);
+
+    result.retain(|h| h.position >= range.start && h.position <= range.end);
+    result.sort_by_key(|h| h.position);
+    Some(result)
+}
+
+fn declaration_hints(element: &ElementRc, result: &mut Vec<InlayHint>) {
+    let e = element.borrow();
+    for (name, decl) in &e.property_declarations {
+        let Some(node) = &decl.node else { continue };
+        let Some(two_way) = node
+            .child_node(SyntaxKind::TwoWayBinding)
+            .and_then(|n| n.child_node(SyntaxKind::Expression))
+        else {
+            continue;
+        };
+        let Some(declared_identifier) = node.child_node(SyntaxKind::DeclaredIdentifier) else {
+            continue;
+        };
+
+        if let Some(p) = syntax_nodes::PropertyDeclaration::new(node.clone()) {
+            if p.Type().is_none() && decl.property_type.is_property_type() {
+                let position = util::node_to_lsp_range(&declared_identifier).start;
+                let label = format!("<{}>", decl.property_type);
+                result.push(InlayHint {
+                    position,
+                    text_edits: Some(vec![TextEdit {
+                        range: lsp_types::Range::new(position, position),
+                        new_text: format!("{label} "),
+                    }]),
+                    label: InlayHintLabel::String(label),
+                    kind: Some(InlayHintKind::TYPE),
+                    tooltip: None,
+                    padding_left: None,
+                    padding_right: Some(true),
+                    data: None,
+                });
+            }
+        } else if let Type::Callback(callback) = &decl.property_type {
+            let callback_node = syntax_nodes::CallbackDeclaration::new(node.clone());
+            if callback_node.is_some_and(|c| c.CallbackDeclarationParameter().next().is_none()) {
+                result.push(InlayHint {
+                    position: util::node_to_lsp_range(&declared_identifier).end,
+                    label: InlayHintLabel::String(signature(callback)),
+                    kind: Some(InlayHintKind::TYPE),
+                    text_edits: None,
+                    tooltip: None,
+                    padding_left: None,
+                    padding_right: Some(true),
+                    data: None,
+                });
+            }
+        }
+
+        let Some(target) =
+            e.bindings.get(name).and_then(|b| b.borrow().two_way_bindings.first().cloned())
+        else {
+            continue;
+        };
+        let resolved = resolve_alias(target.clone());
+        if resolved != target {
+            result.push(InlayHint {
+                position: util::node_to_lsp_range(&two_way).end,
+                label: InlayHintLabel::String(format!("→ {}", describe_property(&resolved))),
+                kind: None,
+                text_edits: None,
+                tooltip: None,
+                padding_left: Some(true),
+                padding_right: None,
+                data: None,
+            });
+        }
+    }
+}
+
+/// Follow the two way bindings of declared aliases until reaching a property that is not an alias
+fn resolve_alias(mut target: NamedReference) -> NamedReference {
+    for _ in 0..MAX_ALIAS_CHAIN {
+        let element = target.element();
+        let element = element.borrow();
+        let is_alias = element
+            .property_declarations
+            .get(target.name())
+            .and_then(|d| d.node.as_ref())
+            .is_some_and(|n| n.child_node(SyntaxKind::TwoWayBinding).is_some());
+        if !is_alias {
+            break;
+        }
+        let Some(next) = element
+            .bindings
+            .get(target.name())
+            .and_then(|b| b.borrow().two_way_bindings.first().cloned())
+        else {
+            break;
+        };
+        drop(element);
+        target = next;
+    }
+    target
+}
+
+fn describe_property(nr: &NamedReference) -> String {
+    let element = nr.element();
+    let element = element.borrow();
+    let owner = if let Some(component) =
+        element.enclosing_component.upgrade().filter(|c| Rc::ptr_eq(&c.root_element, &nr.element()))
+    {
+        component.id.to_string()
+    } else if !element.id.is_empty() {
+        element.id.to_string()
+    } else {
+        element.base_type.to_string()
+    };
+    format!("{owner}.{}", nr.name())
+}
+
+fn signature(function: &Function) -> String {
+    let args = function
+        .args
+        .iter()
+        .enumerate()
+        .map(|(i, ty)| match function.arg_names.get(i).filter(|n| !n.is_empty()) {
+            Some(name) => format!("{name}: {ty}"),
+            None => ty.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join(", ");
+    if function.return_type == Type::Void {
+        format!("({args})")
+    } else {
+        format!("({args}) -> {}", function.return_type)
+    }
+}
+
+fn call_site_hints(
+    document_cache: &common::DocumentCache,
+    doc_node: &syntax_nodes::Document,
+    result: &mut Vec<InlayHint>,
+) {
+    for node in doc_node.descendants() {
+        if node.kind() != SyntaxKind::FunctionCallExpression {
+            continue;
+        }
+        let mut expressions = node.children().filter(|n| n.kind() == SyntaxKind::Expression);
+        let Some(callee) = expressions.next() else { continue };
+        let Some(function) = called_function(document_cache, &callee) else { continue };
+
+        for (arg, name) in expressions.zip(function.arg_names.iter()) {
+            if name.is_empty()
+                || i_slint_compiler::parser::normalize_identifier(arg.text().to_string().trim())
+                    == *name
+            {
+                continue;
+            }
+            result.push(InlayHint {
+                position: util::node_to_lsp_range(&arg).start,
+                label: InlayHintLabel::String(format!("{name}:")),
+                kind: Some(InlayHintKind::PARAMETER),
+                text_edits: None,
+                tooltip: None,
+                padding_left: None,
+                padding_right: Some(true),
+                data: None,
+            });
+        }
+    }
+}
+
+/// Return the signature of the callback or function called by the `callee` expression
+fn called_function(
+    document_cache: &common::DocumentCache,
+    callee: &SyntaxNode,
+) -> Option<Rc<Function>> {
+    let qualified_name = callee.child_node(SyntaxKind::QualifiedName)?;
+    let token = qualified_name
+        .children_with_tokens()
+        .filter_map(|t| t.into_token())
+        .filter(|t| t.kind() == SyntaxKind::Identifier)
+        .last()?;
+    match token_info(document_cache, token)? {
+        TokenInfo::NamedReference(nr) => match nr.ty() {
+            Type::Callback(f) | Type::Function(f) => Some(f),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hints(source: &str) -> Vec<(u32, u32, String)> {
+        let (dc, uri, diag) = crate::language::test::loaded_document_cache(source.into());
+        for (u, ds) in diag {
+            assert_eq!(ds, vec![], "errors in {u}");
+        }
+        let full_range =
+            lsp_types::Range::new(lsp_types::Position::new(0, 0), lsp_types::Position::new(100, 0));
+        get_inlay_hints(&dc, &lsp_types::TextDocumentIdentifier { uri }, &full_range)
+            .unwrap()
+            .into_iter()
+            .map(|h| {
+                let InlayHintLabel::String(label) = h.label else { unreachable!() };
+                (h.position.line, h.position.character, label)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_inferred_types() {
+        let result = hints(
+            r#"
+component Abc {
+    in-out property <int> value;
+    callback activated(index: int, name: string) -> bool;
+}
+export component Test {
+    in-out property value <=> abc.value;
+    callback activated <=> abc.activated;
+    out property <int> typed <=> abc.value;
+    abc := Abc { }
+}"#,
+        );
+        assert_eq!(
+            result,
+            vec![
+                (6, 20, "<int>".to_string()),
+                (7, 22, "(index: int, name: string) -> bool".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_alias_targets() {
+        let result = hints(
+            r#"
+export component Test {
+    in-out property <string> first <=> second;
+    in-out property <string> second <=> txt.text;
+    in-out property <string> third <=> txt.text;
+    txt := Text { }
+}"#,
+        );
+        assert_eq!(result, vec![(2, 45, "→ txt.text".to_string())]);
+    }
+
+    #[test]
+    fn test_argument_names() {
+        let result = hints(
+            r#"
+export component Test {
+    callback activated(index: int, name: string);
+    function compute(factor: float, offset: float) -> float { factor + offset }
+    property <float> offset;
+    init => {
+        activated(42, "foo");
+        root.activated(1, "bar");
+        offset = compute(2, offset);
+    }
+}"#,
+        );
+        assert_eq!(
+            result,
+            vec![
+                (6, 18, "index:".to_string()),
+                (6, 22, "name:".to_string()),
+                (7, 23, "index:".to_string()),
+                (7, 26, "name:".to_string()),
+                (8, 25, "factor:".to_string()),
+            ]
+        );
+    }
+}
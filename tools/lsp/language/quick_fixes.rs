@@ -0,0 +1,519 @@
+// Copyright © SixtyFPS GmbH <info@slint.dev>
+// SPDX-License-Identifier: GPL-3.0-only OR LicenseRef-Slint-Royalty-free-2.0 OR LicenseRef-Slint-Software-3.0
+
+//! Quick fixes for the errors reported by the compiler
+
+use super::references::declaration_node;
+use crate::common::{
+    self, text_edit,
+    token_info::{token_info, TokenInfo},
+};
+use crate::util;
+use i_slint_compiler::diagnostics::SourceFile;
+use i_slint_compiler::expression_tree::Expression;
+use i_slint_compiler::langtype::{ElementType, Type};
+use i_slint_compiler::parser::{SyntaxKind, SyntaxNode, SyntaxToken, TextRange, TextSize};
+use lsp_types::{CodeAction, CodeActionKind, CodeActionOrCommand, Diagnostic, TextEdit, Url};
+
+#[cfg(target_arch = "wasm32")]
+use crate::wasm_prelude::*;
+
+struct QuickFix {
+    title: String,
+    /// The file the `edits` apply to, which is not necessarily the file of the diagnostic
+    source_file: SourceFile,
+    edits: Vec<TextEdit>,
+}
+
+impl QuickFix {
+    fn insert(title: String, token: &SyntaxToken, offset: TextSize, text: String) -> Self {
+        let source_file = token.source_file.clone();
+        let edits = vec![text_edit::insert_text(&source_file, offset, text)];
+        Self { title, source_file, edits }
+    }
+}
+
+/// Return the quick fixes for the `diagnostics` reported in the document at `uri`
+pub fn get_quick_fixes(
+    document_cache: &common::DocumentCache,
+    uri: &Url,
+    diagnostics: &[Diagnostic],
+) -> Vec<CodeActionOrCommand> {
+    let Some(doc_node) = document_cache.get_document(uri).and_then(|doc| doc.node.as_ref()) else {
+        return vec![];
+    };
+    diagnostics
+        .iter()
+        .filter_map(|diagnostic| {
+            let offset =
+                util::lsp_position_to_text_size(&doc_node.source_file, diagnostic.range.start);
+            let token = super::token_at_offset(doc_node, offset)?;
+            let fix = quick_fix(document_cache, &diagnostic.message, token)?;
+            Some(CodeActionOrCommand::CodeAction(CodeAction {
+                title: fix.title,
+                kind: Some(CodeActionKind::QUICKFIX),
+                diagnostics: Some(vec![diagnostic.clone()]),
+                edit: common::create_workspace_edit_from_path(
+                    document_cache,
+                    fix.source_file.path(),
+                    fix.edits,
+                ),
+                ..Default::default()
+            }))
+        })
+        .collect()
+}
+
+/// `token` is the token at the start of the diagnostic with `message`
+fn quick_fix(
+    document_cache: &common::DocumentCache,
+    message: &str,
+    token: SyntaxToken,
+) -> Option<QuickFix> {
+    if message.starts_with("Unknown property ") {
+        declare_bound_property(document_cache, token)
+    } else if let Some(name) =
+        message.strip_prefix("Unknown unqualified identifier '").and_then(|m| m.strip_suffix('\''))
+    {
+        declare_identifier(document_cache, token, name)
+    } else if message.starts_with('\'') && message.contains("' is not a callback in ") {
+        declare_connected_callback(token)
+    } else if message.starts_with("The property '") && message.contains("' is private.") {
+        make_property_in_out(document_cache, token)
+    } else if let Some((_, unit)) = message.split_once(". Use an unit, or multiply by 1") {
+        add_unit(token, unit.strip_suffix(" to convert explicitly")?)
+    } else if message.starts_with("Call of impure function '")
+        || message.starts_with("Call of impure callback '")
+    {
+        make_pure(document_cache, token)
+    } else if let Some(name) = message
+        .strip_prefix("The binding for the property '")
+        .and_then(|m| m.strip_suffix("' is part of a binding loop"))
+    {
+        remove_binding(token, name)
+    } else {
+        None
+    }
+}
+
+/// `foo: 42px;` where `foo` doesn't exist becomes `property <length> foo: 42px;`
+fn declare_bound_property(
+    document_cache: &common::DocumentCache,
+    token: SyntaxToken,
+) -> Option<QuickFix> {
+    let node = token.parent();
+    let ty = match node.kind() {
+        SyntaxKind::Binding => {
+            let expression = node.child_node(SyntaxKind::BindingExpression)?;
+            format!(
+                " <{}>",
+                expression_type(document_cache, expression.child_node(SyntaxKind::Expression)?)?
+            )
+        }
+        // The type of a two way binding is inferred
+        SyntaxKind::TwoWayBinding => String::new(),
+        _ => return None,
+    };
+    Some(QuickFix::insert(
+        format!("Declare `property{ty} {}`", token.text()),
+        &token,
+        token.text_range().start(),
+        format!("property{ty} "),
+    ))
+}
+
+/// Declare the unknown identifier `name` at `token` as a property or a callback of the component
+fn declare_identifier(
+    document_cache: &common::DocumentCache,
+    token: SyntaxToken,
+    name: &str,
+) -> Option<QuickFix> {
+    let expression = token.parent().parent().filter(|n| n.kind() == SyntaxKind::Expression)?;
+    let parent = expression.parent()?;
+    let declaration = if parent.kind() == SyntaxKind::FunctionCallExpression
+        && parent.first_child().is_some_and(|c| c.text_range() == expression.text_range())
+    {
+        let arguments = parent
+            .children()
+            .skip(1)
+            .map(|argument| expression_type(document_cache, argument).map(|ty| ty.to_string()))
+            .collect::<Option<Vec<_>>>()?;
+        if arguments.is_empty() {
+            format!("callback {name};")
+        } else {
+            format!("callback {name}({});", arguments.join(", "))
+        }
+    } else {
+        format!("property <{}> {name};", expected_type(document_cache, &expression)?)
+    };
+
+    let component = std::iter::successors(Some(token.parent()), |n| n.parent())
+        .find(|n| n.kind() == SyntaxKind::Component)?;
+    let lbrace = component.child_node(SyntaxKind::Element)?.child_token(SyntaxKind::LBrace)?;
+    // Use the indentation of the first line of the element, if there is one
+    let indent = lbrace
+        .next_token()
+        .filter(|t| t.kind() == SyntaxKind::Whitespace && t.text().contains('\n'))
+        .filter(|t| t.next_token().is_some_and(|n| n.kind() != SyntaxKind::RBrace))
+        .and_then(|t| t.text().rsplit('\n').next().map(|s| s.to_string()))
+        .unwrap_or_else(|| "    ".into());
+    Some(QuickFix::insert(
+        format!("Declare `{}`", declaration.trim_end_matches(';')),
+        &token,
+        lbrace.text_range().end(),
+        format!("\n{indent}{declaration}"),
+    ))
+}
+
+/// The type that the `expression` should have, given where it is used
+fn expected_type(document_cache: &common::DocumentCache, expression: &SyntaxNode) -> Option<Type> {
+    let parent = expression.parent()?;
+    let is_first_child =
+        || parent.first_child().is_some_and(|c| c.text_range() == expression.text_range());
+    match parent.kind() {
+        SyntaxKind::BindingExpression => {
+            declarable(util::with_lookup_ctx(document_cache, parent, |ctx| {
+                ctx.property_type.clone()
+            })?)
+        }
+        SyntaxKind::ConditionalElement | SyntaxKind::UnaryOpExpression => Some(Type::Bool),
+        SyntaxKind::ConditionalExpression if is_first_child() => Some(Type::Bool),
+        SyntaxKind::BinaryExpression => {
+            let operator = parent
+                .children_with_tokens()
+                .filter_map(|c| c.into_token())
+                .find(|t| !matches!(t.kind(), SyntaxKind::Whitespace | SyntaxKind::Comment))?;
+            if matches!(operator.text(), "&&" | "||") {
+                return Some(Type::Bool);
+            }
+            let other = parent.children().find(|c| c.text_range() != expression.text_range())?;
+            expression_type(document_cache, other)
+        }
+        _ => None,
+    }
+}
+
+fn expression_type(document_cache: &common::DocumentCache, expression: SyntaxNode) -> Option<Type> {
+    declarable(util::with_lookup_ctx(document_cache, expression.clone(), |ctx| {
+        Expression::from_expression_node(expression.into(), ctx).ty()
+    })?)
+}
+
+fn declarable(ty: Type) -> Option<Type> {
+    ty.is_property_type().then_some(ty)
+}
+
+/// `foo => {}` where `foo` doesn't exist gets a `callback foo;` declaration
+fn declare_connected_callback(token: SyntaxToken) -> Option<QuickFix> {
+    let connection = token.parent();
+    // The types of the arguments are unknown
+    if connection.kind() != SyntaxKind::CallbackConnection
+        || connection.child_node(SyntaxKind::DeclaredIdentifier).is_some()
+    {
+        return None;
+    }
+    let indent = connection
+        .first_token()?
+        .prev_token()
+        .filter(|t| t.kind() == SyntaxKind::Whitespace && t.text().contains('\n'))
+        .and_then(|t| t.text().rsplit('\n').next().map(|s| s.to_string()))
+        .unwrap_or_default();
+    Some(QuickFix::insert(
+        format!("Declare `callback {}`", token.text()),
+        &token,
+        connection.text_range().start(),
+        format!("callback {};\n{indent}", token.text()),
+    ))
+}
+
+/// Turn the private `property <int> foo` into `in-out property <int> foo`
+fn make_property_in_out(
+    document_cache: &common::DocumentCache,
+    token: SyntaxToken,
+) -> Option<QuickFix> {
+    let declaration = private_property_declaration(document_cache, &token)?;
+    let first = declaration.first_token()?;
+    let title = format!("Make `{}` an `in-out` property", token.text());
+    match first.text() {
+        "property" => {
+            Some(QuickFix::insert(title, &first, first.text_range().start(), "in-out ".into()))
+        }
+        "private" => Some(QuickFix {
+            title,
+            source_file: first.source_file.clone(),
+            edits: vec![text_edit::replace_text(&first.source_file, first.text_range(), "in-out")],
+        }),
+        _ => None,
+    }
+}
+
+/// The declaration of the property at `token`, in `element.property`
+///
+/// The property cannot be resolved since it is private, so look it up in the type of the element.
+fn private_property_declaration(
+    document_cache: &common::DocumentCache,
+    token: &SyntaxToken,
+) -> Option<SyntaxNode> {
+    let dot = token.prev_token().filter(|t| t.kind() == SyntaxKind::Dot)?;
+    let element = dot.prev_token().filter(|t| t.kind() == SyntaxKind::Identifier)?;
+    let TokenInfo::ElementRc(element) = token_info(document_cache, element)? else {
+        return None;
+    };
+    let name = i_slint_compiler::parser::normalize_identifier(token.text());
+    let mut element_type = element.borrow().base_type.clone();
+    while let ElementType::Component(c) = element_type {
+        if let Some(p) = c.root_element.borrow().property_declarations.get(&name) {
+            return p.node.clone().filter(|n| n.kind() == SyntaxKind::PropertyDeclaration);
+        }
+        element_type = c.root_element.borrow().base_type.clone();
+    }
+    None
+}
+
+/// `width: 10;` becomes `width: 10px;`
+fn add_unit(token: SyntaxToken, unit: &str) -> Option<QuickFix> {
+    if token.kind() != SyntaxKind::NumberLiteral
+        || !token.text().chars().all(|c| c.is_ascii_digit() || c == '.')
+    {
+        return None;
+    }
+    Some(QuickFix::insert(
+        format!("Change to `{}{unit}`", token.text()),
+        &token,
+        token.text_range().end(),
+        unit.into(),
+    ))
+}
+
+/// Add `pure` to the declaration of the function or callback called at `token`
+fn make_pure(document_cache: &common::DocumentCache, token: SyntaxToken) -> Option<QuickFix> {
+    let declaration = declaration_node(document_cache, token.clone())
+        .filter(|n| matches!(n.kind(), SyntaxKind::Function | SyntaxKind::CallbackDeclaration))?;
+    let keyword = declaration.children_with_tokens().filter_map(|c| c.into_token()).find(|t| {
+        t.kind() == SyntaxKind::Identifier && matches!(t.text(), "function" | "callback")
+    })?;
+    Some(QuickFix::insert(
+        format!("Make `{}` pure", token.text()),
+        &keyword,
+        keyword.text_range().start(),
+        "pure ".into(),
+    ))
+}
+
+/// Remove the binding of the property `name` to break a binding loop
+fn remove_binding(token: SyntaxToken, name: &str) -> Option<QuickFix> {
+    let node = std::iter::successors(Some(token.parent()), |n| n.parent())
+        .find(|n| matches!(n.kind(), SyntaxKind::Binding | SyntaxKind::PropertyDeclaration))?;
+    let range = if node.kind() == SyntaxKind::Binding {
+        // Remove the whole line
+        let end = util::node_range_without_trailing_ws(&node).end();
+        let start = node
+            .first_token()?
+            .prev_token()
+            .filter(|t| t.kind() == SyntaxKind::Whitespace)
+            .and_then(|t| {
+                t.text().rfind('\n').map(|i| t.text_range().start() + TextSize::from(i as u32))
+            })
+            .unwrap_or(node.text_range().start());
+        TextRange::new(start, end)
+    } else {
+        // Keep the declaration, but remove the `: expression` part
+        let colon = node.child_token(SyntaxKind::Colon)?;
+        let expression = node.child_node(SyntaxKind::BindingExpression)?;
+        let end = expression
+            .children()
+            .last()
+            .map(|n| n.text_range().end())
+            .unwrap_or(expression.text_range().end());
+        TextRange::new(colon.text_range().start(), end)
+    };
+    Some(QuickFix {
+        title: format!("Remove the binding of `{name}`"),
+        source_file: token.source_file.clone(),
+        edits: vec![text_edit::replace_text(&token.source_file, range, "")],
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Apply each quick fix for the diagnostics of `source`, and return their title and the resulting source
+    fn apply_quick_fixes(source: &str) -> Vec<(String, String)> {
+        let (dc, uri, diagnostics) = crate::language::test::loaded_document_cache(source.into());
+        apply_quick_fixes_for(&dc, &uri, &diagnostics[&uri])
+    }
+
+    fn apply_quick_fixes_for(
+        dc: &common::DocumentCache,
+        uri: &Url,
+        diagnostics: &[Diagnostic],
+    ) -> Vec<(String, String)> {
+        get_quick_fixes(dc, uri, diagnostics)
+            .into_iter()
+            .map(|action| {
+                let CodeActionOrCommand::CodeAction(action) = action else { unreachable!() };
+                assert_eq!(action.kind, Some(CodeActionKind::QUICKFIX));
+                let edited = text_edit::apply_workspace_edit(dc, &action.edit.unwrap()).unwrap();
+                assert_eq!(edited.len(), 1);
+                (action.title, edited[0].contents.clone())
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_declare_bound_property() {
+        let fixes = apply_quick_fixes(
+            "export component Test {\n    Rectangle {\n        foo: 42px;\n    }\n}\n",
+        );
+        assert_eq!(
+            fixes,
+            vec![(
+                "Declare `property <length> foo`".to_string(),
+                "export component Test {\n    Rectangle {\n        property <length> foo: 42px;\n    }\n}\n"
+                    .to_string()
+            )]
+        );
+    }
+
+    #[test]
+    fn test_declare_identifier() {
+        let fixes = apply_quick_fixes(
+            r#"export component Test {
+    Text {
+        text: label;
+        visible: shown && true;
+        width: 10px + margin;
+    }
+    if condition: Rectangle { }
+    TouchArea {
+        clicked => { notify(42, "hello"); reset(); }
+    }
+}
+"#,
+        );
+        let titles = fixes.iter().map(|(t, _)| t.as_str()).collect::<Vec<_>>();
+        assert_eq!(
+            titles,
+            vec![
+                "Declare `property <string> label`",
+                "Declare `property <bool> shown`",
+                "Declare `property <length> margin`",
+                "Declare `property <bool> condition`",
+                "Declare `callback notify(float, string)`",
+                "Declare `callback reset`",
+            ]
+        );
+        assert!(fixes[0].1.starts_with(
+            "export component Test {\n    property <string> label;\n    Text {\n        text: label;"
+        ));
+    }
+
+    #[test]
+    fn test_declare_connected_callback() {
+        let fixes = apply_quick_fixes(
+            "export component Test {\n    Rectangle {\n        foo => { }\n    }\n}\n",
+        );
+        assert_eq!(
+            fixes,
+            vec![(
+                "Declare `callback foo`".to_string(),
+                "export component Test {\n    Rectangle {\n        callback foo;\n        foo => { }\n    }\n}\n"
+                    .to_string()
+            )]
+        );
+    }
+
+    #[test]
+    fn test_make_property_in_out() {
+        let fixes = apply_quick_fixes(
+            r#"component Abc {
+    property <int> value: 42;
+}
+export component Test {
+    abc := Abc { }
+    out property <int> result: abc.value;
+}
+"#,
+        );
+        assert_eq!(fixes.len(), 1);
+        assert_eq!(fixes[0].0, "Make `value` an `in-out` property");
+        assert!(fixes[0].1.starts_with("component Abc {\n    in-out property <int> value: 42;"));
+    }
+
+    #[test]
+    fn test_add_unit() {
+        let fixes = apply_quick_fixes("export component Test {\n    width: 10;\n}\n");
+        assert_eq!(
+            fixes,
+            vec![(
+                "Change to `10px`".to_string(),
+                "export component Test {\n    width: 10px;\n}\n".to_string()
+            )]
+        );
+    }
+
+    #[test]
+    fn test_make_pure() {
+        let fixes = apply_quick_fixes(
+            r#"export component Test {
+    public function compute() -> int { 42 }
+    callback fetch() -> int;
+    out property <int> result: compute() + root.fetch();
+}
+"#,
+        );
+        assert_eq!(fixes.len(), 2);
+        assert_eq!(fixes[0].0, "Make `compute` pure");
+        assert!(fixes[0]
+            .1
+            .starts_with("export component Test {\n    public pure function compute()"));
+        assert_eq!(fixes[1].0, "Make `fetch` pure");
+        assert!(fixes[1].1.contains("\n    pure callback fetch() -> int;"));
+    }
+
+    #[test]
+    fn test_remove_binding() {
+        let source = r#"export component Test {
+    property <int> a: b;
+    property <int> b: a;
+    Rectangle {
+        width: self.height;
+        height: self.width;
+    }
+}
+"#;
+        let (dc, uri, _) = crate::language::test::loaded_document_cache(source.into());
+        // Binding loops are only detected by a full compilation, so the diagnostics don't come
+        // from the document cache. They are reported at the start of the binding expression.
+        let source_file = dc.get_document(&uri).unwrap().node.as_ref().unwrap().source_file.clone();
+        let diagnostics = [("a", "b;"), ("b", "a;"), ("height", "self.width")]
+            .into_iter()
+            .map(|(name, needle)| {
+                let offset = (source.find(needle).unwrap() as u32).into();
+                let position = util::text_size_to_lsp_position(&source_file, offset);
+                Diagnostic {
+                    range: lsp_types::Range::new(position, position),
+                    message: format!(
+                        "The binding for the property '{name}' is part of a binding loop"
+                    ),
+                    ..Default::default()
+                }
+            })
+            .collect::<Vec<_>>();
+
+        let fixes = apply_quick_fixes_for(&dc, &uri, &diagnostics);
+        let titles = fixes.iter().map(|(t, _)| t.as_str()).collect::<Vec<_>>();
+        assert_eq!(
+            titles,
+            vec![
+                "Remove the binding of `a`",
+                "Remove the binding of `b`",
+                "Remove the binding of `height`"
+            ]
+        );
+        assert_eq!(fixes[0].1, source.replace("a: b;", "a;"));
+        assert_eq!(fixes[1].1, source.replace("b: a;", "b;"));
+        assert_eq!(fixes[2].1, source.replace("\n        height: self.width;", ""));
+    }
+}
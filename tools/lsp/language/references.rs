@@ -0,0 +1,326 @@
+// Copyright © SixtyFPS GmbH <info@slint.dev>
+// SPDX-License-Identifier: GPL-3.0-only OR LicenseRef-Slint-Royalty-free-2.0 OR LicenseRef-Slint-Software-3.0
+
+use crate::common::{
+    self,
+    token_info::{token_info, TokenInfo},
+};
+use crate::util;
+use i_slint_compiler::langtype::{ElementType, Type};
+use i_slint_compiler::parser::{identifier_text, SyntaxKind, SyntaxNode, SyntaxToken};
+use lsp_types::{Location, Url};
+use smol_str::SmolStr;
+use std::collections::HashSet;
+
+#[cfg(target_arch = "wasm32")]
+use crate::wasm_prelude::*;
+
+/// Find all usages of the symbol at `token` in all the documents of the `document_cache`
+///
+/// The symbol can be an element id, a component, a global, a struct, an enum, a property or a callback.
+pub fn find_references(
+    document_cache: &common::DocumentCache,
+    token: SyntaxToken,
+    include_declaration: bool,
+) -> Option<Vec<Location>> {
+    if token.kind() != SyntaxKind::Identifier {
+        return None;
+    }
+
+    let parent = token.parent();
+    if let Some(ranges) = super::find_element_id_for_highlight(&token, &parent) {
+        // Element ids are local to their component
+        let uri = Url::from_file_path(token.source_file.path()).ok()?;
+        let doc_node =
+            document_cache.get_document_for_source_file(&token.source_file)?.node.clone()?;
+        return Some(
+            ranges
+                .into_iter()
+                .filter(|r| {
+                    include_declaration
+                        || doc_node
+                            .token_at_offset(r.start())
+                            .right_biased()
+                            .is_none_or(|t| t.parent().kind() != SyntaxKind::SubElement)
+                })
+                .map(|r| Location {
+                    uri: uri.clone(),
+                    range: util::text_range_to_lsp_range(&token.source_file, r),
+                })
+                .collect(),
+        );
+    }
+
+    let declaration = declaration_node(document_cache, token)?;
+    let declared_identifier = declaration.child_node(SyntaxKind::DeclaredIdentifier)?;
+    let names = symbol_names(document_cache, identifier_text(&declared_identifier)?);
+
+    let mut result = vec![];
+    for (uri, doc) in document_cache.all_url_documents() {
+        if uri.scheme() == "builtin" {
+            continue;
+        }
+
+        let mut current_token = doc.first_token();
+        while let Some(current) = current_token {
+            if current.kind() == SyntaxKind::Identifier
+                && names.contains(&i_slint_compiler::parser::normalize_identifier(current.text()))
+                && (include_declaration || !is_same_node(&current.parent(), &declared_identifier))
+                && declaration_node(document_cache, current.clone())
+                    .is_some_and(|d| is_same_node(&d, &declaration))
+            {
+                result
+                    .push(Location { uri: uri.clone(), range: util::token_to_lsp_range(&current) });
+            }
+            current_token = current.next_token();
+        }
+    }
+
+    Some(result)
+}
+
+/// Return the node declaring the symbol at `token`
+///
+/// This is the `Component`, `StructDeclaration`, `EnumDeclaration`, `PropertyDeclaration`,
+/// `CallbackDeclaration` or `Function` node.
+pub(crate) fn declaration_node(
+    document_cache: &common::DocumentCache,
+    token: SyntaxToken,
+) -> Option<SyntaxNode> {
+    fn find_in_element_type(mut element_type: ElementType, name: &str) -> Option<SyntaxNode> {
+        while let ElementType::Component(c) = element_type {
+            if let Some(p) = c.root_element.borrow().property_declarations.get(name) {
+                return p.node.clone();
+            }
+            element_type = c.root_element.borrow().base_type.clone();
+        }
+        None
+    }
+
+    match token_info(document_cache, token)? {
+        TokenInfo::Type(Type::Struct(s)) => s.node.as_ref()?.parent(),
+        TokenInfo::Type(Type::Enumeration(e)) => e.node.clone().map(Into::into),
+        TokenInfo::ElementType(ElementType::Component(c)) => c.node.clone(),
+        TokenInfo::ElementRc(e) => {
+            // Only the root element of a global or component has a name
+            let component = e.borrow().debug.first()?.node.parent()?;
+            (component.kind() == SyntaxKind::Component).then_some(component)
+        }
+        TokenInfo::NamedReference(nr) => {
+            if let Some(p) = nr.element().borrow().property_declarations.get(nr.name()) {
+                return p.node.clone();
+            }
+            let base = nr.element().borrow().base_type.clone();
+            find_in_element_type(base, nr.name())
+        }
+        TokenInfo::IncompleteNamedReference(element_type, name) => {
+            find_in_element_type(element_type, &name)
+        }
+        TokenInfo::LocalProperty(p) => Some(p.into()),
+        TokenInfo::LocalCallback(c) => Some(c.into()),
+        _ => None,
+    }
+}
+
+/// Collect the `name` and all the names it is imported or exported as with `X as Y`
+fn symbol_names(document_cache: &common::DocumentCache, name: SmolStr) -> HashSet<SmolStr> {
+    let mut names = HashSet::from([name]);
+    loop {
+        let count = names.len();
+        for (_, doc) in document_cache.all_url_documents() {
+            for import_spec in doc.ImportSpecifier() {
+                for id in
+                    import_spec.ImportIdentifierList().iter().flat_map(|l| l.ImportIdentifier())
+                {
+                    if identifier_text(&id.ExternalName()).is_some_and(|n| names.contains(&n)) {
+                        names.extend(id.InternalName().and_then(|n| identifier_text(&n)));
+                    }
+                }
+            }
+            for export in doc.ExportsList() {
+                for specifier in export.ExportSpecifier() {
+                    if identifier_text(&specifier.ExportIdentifier())
+                        .is_some_and(|n| names.contains(&n))
+                    {
+                        names.extend(specifier.ExportName().and_then(|n| identifier_text(&n)));
+                    }
+                }
+            }
+        }
+        if names.len() == count {
+            return names;
+        }
+    }
+}
+
+pub(crate) fn is_same_node(a: &SyntaxNode, b: &SyntaxNode) -> bool {
+    a.source_file.path() == b.source_file.path() && a.text_range() == b.text_range()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use i_slint_compiler::parser::TextSize;
+
+    #[track_caller]
+    fn references_at(
+        dc: &common::DocumentCache,
+        url: &Url,
+        source: &str,
+        needle: &str,
+        include_declaration: bool,
+    ) -> Vec<(Url, u32)> {
+        let doc = dc.get_document(url).unwrap().node.clone().unwrap();
+        let offset: TextSize = (source.find(needle).unwrap() as u32).into();
+        let token = crate::language::token_at_offset(&doc, offset).unwrap();
+        let mut result: Vec<_> = find_references(dc, token, include_declaration)
+            .unwrap()
+            .into_iter()
+            .map(|l| (l.uri, l.range.start.line))
+            .collect();
+        // Sorted by url: "/file2.slint" < "/file3.slint" < "/foo/bar.slint"
+        result.sort();
+        result
+    }
+
+    #[test]
+    fn test_find_references() {
+        let source = r#"
+component Abc {
+    in-out property <int> hello;
+    callback clicked;
+    clicked => { hello += 1; }
+}
+export component Test {
+    abc := Abc {
+        hello: 42;
+        changed hello => {}
+        clicked => { abc.hello = 0; }
+    }
+    Text { text: abc.hello; }
+    TouchArea { clicked => { abc.clicked(); } }
+}"#;
+        let (dc, url, _) = crate::language::test::loaded_document_cache(source.into());
+
+        let expected_hello = vec![
+            (url.clone(), 2),
+            (url.clone(), 4),
+            (url.clone(), 8),
+            (url.clone(), 9),
+            (url.clone(), 10),
+            (url.clone(), 12),
+        ];
+        assert_eq!(references_at(&dc, &url, source, "hello;", true), expected_hello);
+        assert_eq!(references_at(&dc, &url, source, "hello = 0", true), expected_hello);
+        assert_eq!(references_at(&dc, &url, source, "hello;", false), expected_hello[1..]);
+
+        // The `clicked` of the TouchArea is a different callback
+        let expected_clicked =
+            vec![(url.clone(), 3), (url.clone(), 4), (url.clone(), 10), (url.clone(), 13)];
+        assert_eq!(references_at(&dc, &url, source, "clicked;", true), expected_clicked);
+        assert_eq!(references_at(&dc, &url, source, "clicked(); }", true), expected_clicked);
+
+        let expected_abc =
+            vec![(url.clone(), 7), (url.clone(), 10), (url.clone(), 12), (url.clone(), 13)];
+        assert_eq!(references_at(&dc, &url, source, "abc.hello;", true), expected_abc);
+        assert_eq!(references_at(&dc, &url, source, "abc :=", false), expected_abc[1..]);
+
+        let expected_component = vec![(url.clone(), 1), (url.clone(), 7)];
+        assert_eq!(
+            references_at(&dc, &url, source, "Abc {\n        hello", true),
+            expected_component
+        );
+    }
+
+    #[test]
+    fn test_find_references_multi_files() {
+        let source1 = r#"
+export component Hello {
+    in-out property <int> the_prop;
+}
+export struct AStruct {
+    f: int
+}
+export global Glob {
+    in-out property <int> counter;
+}
+"#;
+        let (mut dc, url1, diags) = crate::language::test::loaded_document_cache(source1.into());
+        for (u, ds) in diags {
+            assert_eq!(ds, vec![], "errors in {u}");
+        }
+        let url2 = url1.join("../file2.slint").unwrap();
+        let source2 = format!(
+            r#"
+import {{ Hello, AStruct, Glob }} from "{url1}";
+export {{ Hello as Renamed }}
+export component Foo {{
+    in property <AStruct> s;
+    h := Hello {{ the-prop: Glob.counter; }}
+    init => {{ Glob.counter += h.the_prop; }}
+}}
+"#,
+            url1 = url1.to_file_path().unwrap().display()
+        );
+        let (extra_files, diag) = spin_on::spin_on(crate::language::reload_document_impl(
+            None,
+            source2.clone(),
+            url2.clone(),
+            Some(43),
+            &mut dc,
+        ));
+        let diag = crate::language::convert_diagnostics(&extra_files, diag);
+        for (u, ds) in diag {
+            assert_eq!(ds, vec![], "errors in {u}");
+        }
+        let url3 = url1.join("../file3.slint").unwrap();
+        let source3 = format!(
+            r#"
+import {{ Renamed, Foo }} from "{url2}";
+export component Bar {{ Renamed {{ }} Foo {{ }} }}
+"#,
+            url2 = url2.to_file_path().unwrap().display()
+        );
+        let (extra_files, diag) = spin_on::spin_on(crate::language::reload_document_impl(
+            None,
+            source3.clone(),
+            url3.clone(),
+            Some(44),
+            &mut dc,
+        ));
+        let diag = crate::language::convert_diagnostics(&extra_files, diag);
+        for (u, ds) in diag {
+            assert_eq!(ds, vec![], "errors in {u}");
+        }
+
+        assert_eq!(
+            references_at(&dc, &url1, source1, "Hello {", true),
+            vec![
+                (url2.clone(), 1),
+                (url2.clone(), 2),
+                (url2.clone(), 2),
+                (url2.clone(), 5),
+                (url3.clone(), 1),
+                (url3.clone(), 2),
+                (url1.clone(), 1),
+            ]
+        );
+        assert_eq!(
+            references_at(&dc, &url1, source1, "the_prop", true),
+            vec![(url2.clone(), 5), (url2.clone(), 6), (url1.clone(), 2)]
+        );
+        assert_eq!(
+            references_at(&dc, &url2, &source2, "counter;", true),
+            vec![(url2.clone(), 5), (url2.clone(), 6), (url1.clone(), 8)]
+        );
+        assert_eq!(
+            references_at(&dc, &url1, source1, "Glob {", false),
+            vec![(url2.clone(), 1), (url2.clone(), 5), (url2.clone(), 6)]
+        );
+        assert_eq!(
+            references_at(&dc, &url2, &source2, "AStruct> s", true),
+            vec![(url2.clone(), 1), (url2.clone(), 4), (url1.clone(), 4)]
+        );
+    }
+}
@@ -0,0 +1,108 @@
+// Copyright © SixtyFPS GmbH <info@slint.dev>
+// SPDX-License-Identifier: GPL-3.0-only OR LicenseRef-Slint-Royalty-free-2.0 OR LicenseRef-Slint-Software-3.0
+
+use crate::common;
+use crate::util;
+use i_slint_compiler::parser::{SyntaxKind, TextRange};
+use lsp_types::{Position, SelectionRange};
+
+/// Compute the selection ranges for each of the `positions`
+///
+/// The innermost range is the token at the position, and every parent range is the
+/// range of the enclosing syntax node, from the innermost to the document.
+pub fn get_selection_ranges(
+    document_cache: &common::DocumentCache,
+    text_document: &lsp_types::TextDocumentIdentifier,
+    positions: &[Position],
+) -> Option<Vec<SelectionRange>> {
+    let doc = document_cache.get_document(&text_document.uri)?;
+    let doc_node = doc.node.as_ref()?;
+    let source_file = &doc_node.source_file;
+
+    Some(
+        positions
+            .iter()
+            .map(|position| {
+                let offset = util::lsp_position_to_text_size(source_file, *position);
+                let Some(token) = super::token_at_offset(doc_node, offset) else {
+                    return SelectionRange {
+                        range: lsp_types::Range::new(*position, *position),
+                        parent: None,
+                    };
+                };
+
+                let mut ranges: Vec<TextRange> = vec![];
+                if token.kind() != SyntaxKind::Whitespace {
+                    ranges.push(token.text_range());
+                }
+                let mut node = Some(token.parent());
+                while let Some(n) = node {
+                    let range = util::node_range_without_trailing_ws(&n);
+                    if ranges
+                        .last()
+                        .is_none_or(|last| range != *last && range.contains_range(*last))
+                    {
+                        ranges.push(range);
+                    }
+                    node = n.parent();
+                }
+
+                ranges
+                    .into_iter()
+                    .rev()
+                    .fold(None, |parent, range| {
+                        Some(SelectionRange {
+                            range: util::text_range_to_lsp_range(source_file, range),
+                            parent: parent.map(Box::new),
+                        })
+                    })
+                    .unwrap_or(SelectionRange {
+                        range: lsp_types::Range::new(*position, *position),
+                        parent: None,
+                    })
+            })
+            .collect(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_selection_ranges() {
+        let source = r#"
+export component Test {
+    Text {
+        text: "hello" + "world";
+    }
+}"#;
+        let (dc, uri, _) = crate::language::test::loaded_document_cache(source.into());
+        let result = get_selection_ranges(
+            &dc,
+            &lsp_types::TextDocumentIdentifier { uri },
+            &[Position::new(3, 27), Position::new(2, 5)],
+        )
+        .unwrap();
+        assert_eq!(result.len(), 2);
+
+        let mut chain = vec![];
+        let mut current = Some(&result[0]);
+        while let Some(r) = current {
+            chain.push((r.range.start.line, r.range.start.character, r.range.end.character));
+            current = r.parent.as_deref();
+        }
+        assert_eq!(chain[0], (3, 24, 31), "the \"world\" token");
+        assert!(chain.contains(&(3, 14, 31)), "the whole expression: {chain:?}");
+        assert!(chain.contains(&(3, 8, 32)), "the binding: {chain:?}");
+        // The outermost range is the whole document
+        assert_eq!(chain.last().unwrap().0, 0);
+        // Each range contains the previous one
+        for w in chain.windows(2) {
+            assert!(w[0] != w[1]);
+        }
+
+        let text = &result[1];
+        assert_eq!(text.range, lsp_types::Range::new(Position::new(2, 4), Position::new(2, 8)));
+    }
+}
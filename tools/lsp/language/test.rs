@@ -140,7 +140,7 @@ fn accurate_diagnostics_in_dependencies() {
         None,
         &mut dc,
         &std::env::current_dir().unwrap().join("xxx/bar.slint"),
-        r#" export component Bar { property <int> hi; } "#,
+        r#" export component Bar { out property <int> hi; } "#,
     );
     assert_eq!(diag, HashMap::from_iter([(bar_url.clone(), vec![])]));
 
@@ -215,3 +215,45 @@ fn accurate_diagnostics_in_dependencies() {
     );
     assert_eq!(diag[&foo_url], vec![]);
 }
+
+#[test]
+fn unused_code_diagnostics() {
+    let mut dc = empty_document_cache();
+
+    let (lib_url, _) = load(
+        None,
+        &mut dc,
+        &std::env::current_dir().unwrap().join("xxx/lib.slint"),
+        "export component Used { }\nexport component Unused { }\n",
+    );
+    let (main_url, diag) = load(
+        None,
+        &mut dc,
+        &std::env::current_dir().unwrap().join("xxx/main.slint"),
+        "import { Used } from \"lib.slint\";\nexport component Main { property <int> value; Used { } }\n",
+    );
+
+    let unnecessary = |diagnostics: &[Diagnostic]| {
+        diagnostics
+            .iter()
+            .map(|d| {
+                assert_eq!(d.tags, Some(vec![lsp_types::DiagnosticTag::UNNECESSARY]));
+                (d.message.clone(), d.range)
+            })
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(
+        unnecessary(&diag[&main_url]),
+        vec![(
+            "Private property 'value' is never used".into(),
+            lsp_types::Range::new(lsp_types::Position::new(1, 39), lsp_types::Position::new(1, 44))
+        )]
+    );
+    assert_eq!(
+        unnecessary(&diag[&lib_url]),
+        vec![(
+            "Component 'Unused' is exported but never imported".into(),
+            lsp_types::Range::new(lsp_types::Position::new(1, 17), lsp_types::Position::new(1, 23))
+        )]
+    );
+}
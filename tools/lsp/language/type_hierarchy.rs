@@ -0,0 +1,148 @@
+// Copyright © SixtyFPS GmbH <info@slint.dev>
+// SPDX-License-Identifier: GPL-3.0-only OR LicenseRef-Slint-Royalty-free-2.0 OR LicenseRef-Slint-Software-3.0
+
+use crate::common::{
+    self,
+    token_info::{token_info, TokenInfo},
+};
+use crate::util;
+use i_slint_compiler::langtype::ElementType;
+use i_slint_compiler::object_tree::Component;
+use i_slint_compiler::parser::{SyntaxKind, SyntaxToken};
+use lsp_types::{SymbolKind, TypeHierarchyItem};
+use std::rc::Rc;
+
+#[cfg(target_arch = "wasm32")]
+use crate::wasm_prelude::*;
+
+/// Return the component at `token`
+pub fn prepare_type_hierarchy(
+    document_cache: &common::DocumentCache,
+    token: SyntaxToken,
+) -> Option<Vec<TypeHierarchyItem>> {
+    let TokenInfo::ElementType(ElementType::Component(component)) =
+        token_info(document_cache, token)?
+    else {
+        return None;
+    };
+    Some(vec![type_hierarchy_item(&component)?])
+}
+
+/// Return the component that the `item` inherits from
+///
+/// Builtin elements, such as `Rectangle`, are not part of the hierarchy since they have no location.
+pub fn supertypes(
+    document_cache: &common::DocumentCache,
+    item: &TypeHierarchyItem,
+) -> Option<Vec<TypeHierarchyItem>> {
+    let component = item_component(document_cache, item)?;
+    let base = component.root_element.borrow().base_type.clone();
+    Some(match base {
+        ElementType::Component(base) => type_hierarchy_item(&base).into_iter().collect(),
+        _ => vec![],
+    })
+}
+
+/// Return the components that inherit from the `item`, in all the loaded documents
+pub fn subtypes(
+    document_cache: &common::DocumentCache,
+    item: &TypeHierarchyItem,
+) -> Option<Vec<TypeHierarchyItem>> {
+    let component = item_component(document_cache, item)?;
+    let mut result = vec![];
+    for url in document_cache.all_urls() {
+        if url.scheme() == "builtin" {
+            continue;
+        }
+        let Some(doc) = document_cache.get_document(&url) else { continue };
+        for sub in &doc.inner_components {
+            let base = sub.root_element.borrow().base_type.clone();
+            if matches!(base, ElementType::Component(base) if Rc::ptr_eq(&base, &component)) {
+                result.extend(type_hierarchy_item(sub));
+            }
+        }
+    }
+    Some(result)
+}
+
+fn type_hierarchy_item(component: &Rc<Component>) -> Option<TypeHierarchyItem> {
+    let node = component.node.as_ref()?;
+    let declared_identifier = node.child_node(SyntaxKind::DeclaredIdentifier)?;
+    let (uri, selection_range) = util::node_to_url_and_lsp_range(&declared_identifier)?;
+    Some(TypeHierarchyItem {
+        name: component.id.to_string(),
+        kind: if component.is_global() { SymbolKind::OBJECT } else { SymbolKind::CLASS },
+        tags: None,
+        detail: node.source_file.path().file_name().map(|f| f.to_string_lossy().to_string()),
+        uri,
+        range: util::text_range_to_lsp_range(
+            &node.source_file,
+            util::node_range_without_trailing_ws(node),
+        ),
+        selection_range,
+        data: None,
+    })
+}
+
+/// Find the component of an item previously returned by [`type_hierarchy_item`]
+fn item_component(
+    document_cache: &common::DocumentCache,
+    item: &TypeHierarchyItem,
+) -> Option<Rc<Component>> {
+    let doc = document_cache.get_document(&item.uri)?;
+    doc.inner_components
+        .iter()
+        .find(|c| {
+            c.node
+                .as_ref()
+                .and_then(|n| n.child_node(SyntaxKind::DeclaredIdentifier))
+                .is_some_and(|id| util::node_to_lsp_range(&id) == item.selection_range)
+        })
+        .cloned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use i_slint_compiler::parser::TextSize;
+
+    #[test]
+    fn test_type_hierarchy() {
+        let source = r#"
+component Base inherits Rectangle { }
+component Middle inherits Base { }
+component Other inherits Base { }
+export component Test inherits Window {
+    Middle { }
+}"#;
+        let (dc, uri, _) = crate::language::test::loaded_document_cache(source.into());
+        let doc = dc.get_document(&uri).unwrap().node.clone().unwrap();
+        let token_at = |needle: &str| {
+            let offset: TextSize = (source.find(needle).unwrap() as u32).into();
+            crate::language::token_at_offset(&doc, offset).unwrap()
+        };
+        let names =
+            |items: Vec<TypeHierarchyItem>| items.into_iter().map(|i| i.name).collect::<Vec<_>>();
+
+        // From the usage of the component
+        let middle = prepare_type_hierarchy(&dc, token_at("Middle { }")).unwrap().remove(0);
+        assert_eq!(middle.name, "Middle");
+        assert_eq!(middle.kind, SymbolKind::CLASS);
+        assert_eq!(middle.selection_range.start.line, 2);
+
+        let base = supertypes(&dc, &middle).unwrap().remove(0);
+        assert_eq!(base.name, "Base");
+        assert_eq!(names(supertypes(&dc, &base).unwrap()), Vec::<String>::new());
+        assert_eq!(names(subtypes(&dc, &base).unwrap()), vec!["Middle", "Other"]);
+        assert_eq!(names(subtypes(&dc, &middle).unwrap()), Vec::<String>::new());
+
+        // From the declaration and from `inherits`
+        let base2 = prepare_type_hierarchy(&dc, token_at("Base inherits")).unwrap().remove(0);
+        assert_eq!(base2, base);
+        let base3 = prepare_type_hierarchy(&dc, token_at("Base { }")).unwrap().remove(0);
+        assert_eq!(base3, base);
+
+        assert!(prepare_type_hierarchy(&dc, token_at("Rectangle")).is_none());
+    }
+}
@@ -0,0 +1,435 @@
+// Copyright © SixtyFPS GmbH <info@slint.dev>
+// SPDX-License-Identifier: GPL-3.0-only OR LicenseRef-Slint-Royalty-free-2.0 OR LicenseRef-Slint-Software-3.0
+
+/*!
+    Report the diagnostics of .slint files without an editor, for example in CI.
+    Use like this to check all the files of a directory:
+    ```sh
+        cargo run --bin slint-lsp -- lint --format sarif ui/ > slint.sarif
+    ```
+
+    On top of the diagnostics of the compiler, some lint rules report code that compiles but
+    is probably not what was intended. They can be disabled with `--allow <rule>`.
+
+    The exit code is 0 when there are no errors, 1 when there are errors (or warnings with
+    `--deny-warnings`), and 2 when the files can't be read.
+*/
+
+use crate::common::{self, document_cache::CompilerConfiguration};
+use i_slint_compiler::diagnostics::{BuildDiagnostics, Diagnostic, DiagnosticLevel, Spanned};
+use i_slint_compiler::langtype::ElementType;
+use i_slint_compiler::object_tree::{recurse_elem, Document};
+use i_slint_compiler::parser::SyntaxKind;
+use lsp_types::Url;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, clap::ValueEnum)]
+pub enum OutputFormat {
+    /// One line per diagnostic, like a compiler
+    Human,
+    /// A JSON array of diagnostics
+    Json,
+    /// The Static Analysis Results Interchange Format, understood by code scanning services
+    Sarif,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, clap::ValueEnum)]
+pub enum Rule {
+    /// Code that is never used: imports, exported components, private properties and states
+    UnusedCode,
+    /// A `TouchArea` that is not exposed to assistive technologies
+    TouchAreaAccessibleRole,
+    /// A color literal outside of a global
+    HardCodedColor,
+}
+
+impl Rule {
+    const ALL: [Rule; 3] = [Rule::UnusedCode, Rule::TouchAreaAccessibleRole, Rule::HardCodedColor];
+
+    fn id(self) -> &'static str {
+        match self {
+            Rule::UnusedCode => "unused-code",
+            Rule::TouchAreaAccessibleRole => "touch-area-accessible-role",
+            Rule::HardCodedColor => "hard-coded-color",
+        }
+    }
+
+    fn description(self) -> &'static str {
+        match self {
+            Rule::UnusedCode => "Unused code should be removed",
+            Rule::TouchAreaAccessibleRole => {
+                "A TouchArea, or one of its parents, should have an accessible-role"
+            }
+            Rule::HardCodedColor => "Colors should be defined in a global, such as a palette",
+        }
+    }
+}
+
+/// A diagnostic of the compiler, or of a lint rule
+#[derive(Debug)]
+struct Problem {
+    rule: Option<Rule>,
+    level: DiagnosticLevel,
+    message: String,
+    path: PathBuf,
+    /// 1-based
+    line: usize,
+    /// 1-based
+    column: usize,
+}
+
+impl Problem {
+    fn from_diagnostic(diagnostic: &Diagnostic) -> Self {
+        let (line, column) = diagnostic.line_column();
+        Self {
+            // The compiler reports unused code when `warn_unused_code` is set
+            rule: diagnostic.is_unnecessary().then_some(Rule::UnusedCode),
+            level: diagnostic.level(),
+            message: diagnostic.message().into(),
+            path: diagnostic.source_file().map(Path::to_path_buf).unwrap_or_default(),
+            line,
+            column,
+        }
+    }
+
+    fn new(rule: Rule, location: &dyn Spanned, message: String) -> Option<Self> {
+        let source_file = location.source_file()?;
+        let (line, column) = source_file.line_column(location.span().offset);
+        Some(Self {
+            rule: Some(rule),
+            level: DiagnosticLevel::Warning,
+            message,
+            path: source_file.path().to_path_buf(),
+            line,
+            column,
+        })
+    }
+
+    fn level_name(&self) -> &'static str {
+        match self.level {
+            DiagnosticLevel::Error => "error",
+            DiagnosticLevel::Warning => "warning",
+            _ => "note",
+        }
+    }
+}
+
+pub fn run(
+    paths: Vec<PathBuf>,
+    config: CompilerConfiguration,
+    format: OutputFormat,
+    allowed: &[Rule],
+    deny_warnings: bool,
+) -> std::io::Result<i32> {
+    let mut files = vec![];
+    for path in paths {
+        collect_slint_files(&std::fs::canonicalize(&path)?, &mut files)?;
+    }
+
+    let mut document_cache = common::DocumentCache::new(config);
+    let mut diag = BuildDiagnostics::default();
+    for file in &files {
+        let content = std::fs::read_to_string(file)?;
+        let Ok(url) = Url::from_file_path(file) else { continue };
+        spin_on::spin_on(document_cache.load_url(&url, None, content, &mut diag))
+            .map_err(|e| std::io::Error::other(e.to_string()))?;
+    }
+
+    document_cache.check_unused_exports(&mut diag);
+
+    let rules = Rule::ALL.into_iter().filter(|r| !allowed.contains(r)).collect::<Vec<_>>();
+    let problems = collect_problems(&document_cache, &diag, &files, &rules);
+
+    let mut out = std::io::stdout().lock();
+    match format {
+        OutputFormat::Human => write_human(&problems, &mut out)?,
+        OutputFormat::Json => writeln!(out, "{:#}", to_json(&problems))?,
+        OutputFormat::Sarif => writeln!(out, "{:#}", to_sarif(&problems, &rules))?,
+    }
+
+    let fails = |p: &Problem| match p.level {
+        DiagnosticLevel::Error => true,
+        DiagnosticLevel::Warning => deny_warnings,
+        _ => false,
+    };
+    Ok(if problems.iter().any(fails) { 1 } else { 0 })
+}
+
+/// The problems of the compiler diagnostics and of the lint `rules`, sorted by location
+fn collect_problems(
+    document_cache: &common::DocumentCache,
+    diag: &BuildDiagnostics,
+    files: &[PathBuf],
+    rules: &[Rule],
+) -> Vec<Problem> {
+    let mut problems: Vec<Problem> = vec![];
+    for d in diag.iter() {
+        let problem = Problem::from_diagnostic(d);
+        if problem.rule.is_some_and(|r| !rules.contains(&r)) {
+            continue;
+        }
+        // Files imported by several files report their diagnostics several times
+        if !problems.iter().any(|p| {
+            (&p.path, p.line, p.column, &p.message)
+                == (&problem.path, problem.line, problem.column, &problem.message)
+        }) {
+            problems.push(problem);
+        }
+    }
+    for file in files {
+        if let Some(doc) = document_cache.get_document_by_path(file) {
+            problems.extend(lint_document(doc, rules));
+        }
+    }
+    problems.sort_by(|a, b| (&a.path, a.line, a.column).cmp(&(&b.path, b.line, b.column)));
+    problems
+}
+
+/// Add `path` to the `files` if it is a file, or the .slint files it contains if it is a directory
+fn collect_slint_files(path: &Path, files: &mut Vec<PathBuf>) -> std::io::Result<()> {
+    if !path.is_dir() {
+        files.push(path.to_path_buf());
+        return Ok(());
+    }
+    let mut entries =
+        std::fs::read_dir(path)?.map(|e| e.map(|e| e.path())).collect::<Result<Vec<_>, _>>()?;
+    entries.sort();
+    for entry in entries {
+        if entry.is_dir() || entry.extension().is_some_and(|e| e == "slint") {
+            collect_slint_files(&entry, files)?;
+        }
+    }
+    Ok(())
+}
+
+fn lint_document(doc: &Document, rules: &[Rule]) -> Vec<Problem> {
+    let mut problems = vec![];
+    for component in &doc.inner_components {
+        if rules.contains(&Rule::TouchAreaAccessibleRole) {
+            recurse_elem(&component.root_element, &false, &mut |element, has_role| {
+                let element = element.borrow();
+                let has_role = *has_role || element.bindings.contains_key("accessible-role");
+                let is_touch_area =
+                    matches!(&element.base_type, ElementType::Builtin(b) if b.name == "TouchArea");
+                if is_touch_area && !has_role {
+                    problems.extend(Problem::new(
+                        Rule::TouchAreaAccessibleRole,
+                        &*element,
+                        "This TouchArea has no accessible-role, and neither have its parents. Assistive technologies can't describe it".into(),
+                    ));
+                }
+                has_role
+            });
+        }
+
+        if rules.contains(&Rule::HardCodedColor) && !component.is_global() {
+            let Some(node) = &component.node else { continue };
+            let mut token = node.first_token();
+            while let Some(t) = token.filter(|t| t.text_range().end() <= node.text_range().end()) {
+                if t.kind() == SyntaxKind::ColorLiteral {
+                    problems.extend(Problem::new(
+                        Rule::HardCodedColor,
+                        &t,
+                        format!(
+                            "Hard-coded color {}. Consider using a property of a global instead",
+                            t.text()
+                        ),
+                    ));
+                }
+                token = t.next_token();
+            }
+        }
+    }
+    problems
+}
+
+fn write_human(problems: &[Problem], out: &mut impl Write) -> std::io::Result<()> {
+    for p in problems {
+        write!(
+            out,
+            "{}:{}:{}: {}: {}",
+            p.path.display(),
+            p.line,
+            p.column,
+            p.level_name(),
+            p.message
+        )?;
+        match p.rule {
+            Some(rule) => writeln!(out, " [{}]", rule.id())?,
+            None => writeln!(out)?,
+        }
+    }
+    let count = |level| problems.iter().filter(|p| p.level == level).count();
+    writeln!(
+        out,
+        "{} error(s), {} warning(s)",
+        count(DiagnosticLevel::Error),
+        count(DiagnosticLevel::Warning)
+    )
+}
+
+fn to_json(problems: &[Problem]) -> serde_json::Value {
+    problems
+        .iter()
+        .map(|p| {
+            serde_json::json!({
+                "file": p.path,
+                "line": p.line,
+                "column": p.column,
+                "level": p.level_name(),
+                "message": p.message,
+                "rule": p.rule.map(Rule::id),
+            })
+        })
+        .collect()
+}
+
+/// See https://docs.oasis-open.org/sarif/sarif/v2.1.0/sarif-v2.1.0.html
+fn to_sarif(problems: &[Problem], rules: &[Rule]) -> serde_json::Value {
+    let rules = rules
+        .iter()
+        .map(|r| {
+            serde_json::json!({
+                "id": r.id(),
+                "shortDescription": { "text": r.description() },
+            })
+        })
+        .collect::<Vec<_>>();
+    let results = problems
+        .iter()
+        .map(|p| {
+            let uri = Url::from_file_path(&p.path)
+                .map(|u| u.to_string())
+                .unwrap_or_else(|_| p.path.display().to_string());
+            let mut result = serde_json::json!({
+                "level": p.level_name(),
+                "message": { "text": p.message },
+                "locations": [{
+                    "physicalLocation": {
+                        "artifactLocation": { "uri": uri },
+                        "region": { "startLine": p.line, "startColumn": p.column },
+                    }
+                }],
+            });
+            if let Some(rule) = p.rule {
+                result["ruleId"] = rule.id().into();
+            }
+            result
+        })
+        .collect::<Vec<_>>();
+    serde_json::json!({
+        "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": "slint-lsp",
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "informationUri": "https://slint.dev",
+                    "rules": rules,
+                }
+            },
+            "results": results,
+        }],
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lint(source: &str) -> Vec<Problem> {
+        let mut dc = crate::language::test::empty_document_cache();
+        let path =
+            PathBuf::from(if cfg!(windows) { "c://foo/bar.slint" } else { "/foo/bar.slint" });
+        let mut diag = BuildDiagnostics::default();
+        let url = Url::from_file_path(&path).unwrap();
+        spin_on::spin_on(dc.load_url(&url, None, source.into(), &mut diag)).unwrap();
+        collect_problems(&dc, &diag, &[path], &Rule::ALL)
+    }
+
+    #[test]
+    fn test_lint_rules() {
+        let problems = lint(
+            r#"global Palette {
+    out property <color> accent: #3b82f6;
+}
+component Button {
+    property <int> unused;
+    property <int> used: 42;
+    accessible-role: button;
+    TouchArea { enabled: used > 0; }
+}
+export component Test {
+    in property <int> public-property;
+    property <bool> watched;
+    changed watched => {}
+    Button { }
+    Rectangle {
+        background: #ff0000;
+        TouchArea { }
+    }
+    Text { color: Palette.accent; }
+}
+"#,
+        );
+        let found =
+            problems.iter().map(|p| (p.rule.unwrap().id(), p.line, p.column)).collect::<Vec<_>>();
+        assert_eq!(
+            found,
+            vec![
+                ("unused-code", 5, 20),
+                ("hard-coded-color", 16, 21),
+                ("touch-area-accessible-role", 17, 9),
+            ]
+        );
+        assert!(problems[0].message.contains("'unused'"));
+        assert!(problems.iter().all(|p| p.level == DiagnosticLevel::Warning));
+    }
+
+    #[test]
+    fn test_output_formats() {
+        let problems = vec![
+            Problem {
+                rule: None,
+                level: DiagnosticLevel::Error,
+                message: "Unknown property foo".into(),
+                path: "/foo/bar.slint".into(),
+                line: 3,
+                column: 5,
+            },
+            Problem {
+                rule: Some(Rule::HardCodedColor),
+                level: DiagnosticLevel::Warning,
+                message: "Hard-coded color".into(),
+                path: "/foo/bar.slint".into(),
+                line: 4,
+                column: 17,
+            },
+        ];
+
+        let mut human = vec![];
+        write_human(&problems, &mut human).unwrap();
+        assert_eq!(
+            String::from_utf8(human).unwrap(),
+            "/foo/bar.slint:3:5: error: Unknown property foo\n/foo/bar.slint:4:17: warning: Hard-coded color [hard-coded-color]\n1 error(s), 1 warning(s)\n"
+        );
+
+        let json = to_json(&problems);
+        assert_eq!(json[0]["level"], "error");
+        assert_eq!(json[0]["rule"], serde_json::Value::Null);
+        assert_eq!(json[1]["rule"], "hard-coded-color");
+        assert_eq!(json[1]["line"], 4);
+
+        let sarif = to_sarif(&problems, &Rule::ALL);
+        assert_eq!(sarif["version"], "2.1.0");
+        let run = &sarif["runs"][0];
+        assert_eq!(run["tool"]["driver"]["rules"].as_array().unwrap().len(), 3);
+        assert_eq!(run["results"][0].get("ruleId"), None);
+        assert_eq!(run["results"][1]["ruleId"], "hard-coded-color");
+        let location = &run["results"][1]["locations"][0]["physicalLocation"];
+        assert_eq!(location["region"]["startColumn"], 17);
+        assert!(location["artifactLocation"]["uri"].as_str().unwrap().starts_with("file://"));
+    }
+}
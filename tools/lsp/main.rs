@@ -10,6 +10,7 @@
 mod common;
 mod fmt;
 mod language;
+mod lint;
 #[cfg(feature = "preview-engine")]
 mod preview;
 pub mod util;
@@ -82,6 +83,8 @@ pub struct Cli {
 enum Commands {
     /// Format slint files
     Format(Format),
+    /// Report the diagnostics of slint files, and of the lint rules
+    Lint(Lint),
 }
 
 #[derive(Args, Clone)]
@@ -94,6 +97,24 @@ struct Format {
     inline: bool,
 }
 
+#[derive(Args, Clone)]
+struct Lint {
+    #[arg(name = "path to .slint file(s) or directories", action)]
+    paths: Vec<std::path::PathBuf>,
+
+    /// The output format
+    #[arg(long, value_enum, default_value_t = lint::OutputFormat::Human)]
+    format: lint::OutputFormat,
+
+    /// Disable a lint rule
+    #[arg(long, value_enum, value_name = "rule", action)]
+    allow: Vec<lint::Rule>,
+
+    /// Exit with an error code when there are warnings
+    #[arg(long, action)]
+    deny_warnings: bool,
+}
+
 enum OutgoingRequest {
     Start,
     Pending(Waker),
@@ -233,6 +254,27 @@ fn main() {
         std::process::exit(0);
     }
 
+    if let Some(Commands::Lint(lint_args)) = args.command {
+        let compiler_config = CompilerConfiguration {
+            style: Some(if args.style.is_empty() { "native".into() } else { args.style }),
+            include_paths: args.include_paths,
+            library_paths: parse_library_paths(&args.library_paths),
+            ..Default::default()
+        };
+        let exit_code = lint::run(
+            lint_args.paths,
+            compiler_config,
+            lint_args.format,
+            &lint_args.allow,
+            lint_args.deny_warnings,
+        )
+        .unwrap_or_else(|e| {
+            eprintln!("{e}");
+            2
+        });
+        std::process::exit(exit_code);
+    }
+
     if let Ok(panic_log_file) = std::env::var("SLINT_LSP_PANIC_LOG") {
         let default_hook = std::panic::take_hook();
         std::panic::set_hook(Box::new(move |info| {
@@ -298,6 +340,14 @@ fn drop(&mut self) {
     }
 }
 
+/// Parse the `library=path` values of the `-L` argument
+fn parse_library_paths(library_paths: &[String]) -> HashMap<String, std::path::PathBuf> {
+    library_paths
+        .iter()
+        .filter_map(|entry| entry.split('=').collect_tuple().map(|(k, v)| (k.into(), v.into())))
+        .collect()
+}
+
 fn run_lsp_server(args: Cli) -> Result<IoThreads> {
     let (connection, io_threads) = Connection::stdio();
     let (id, params) = connection.initialize_start()?;
@@ -336,11 +386,7 @@ fn main_loop(connection: Connection, init_param: InitializeParams, cli_args: Cli
     let compiler_config = CompilerConfiguration {
         style: Some(if cli_args.style.is_empty() { "native".into() } else { cli_args.style }),
         include_paths: cli_args.include_paths,
-        library_paths: cli_args
-            .library_paths
-            .iter()
-            .filter_map(|entry| entry.split('=').collect_tuple().map(|(k, v)| (k.into(), v.into())))
-            .collect(),
+        library_paths: parse_library_paths(&cli_args.library_paths),
         open_import_fallback: Some(Rc::new(move |path| {
             let server_notifier = server_notifier_.clone();
             Box::pin(async move {
@@ -465,10 +511,10 @@ async fn handle_notification(req: lsp_server::Notification, ctx: &Rc<Context>) -
             close_document(ctx, params.text_document.uri).await
         }
         DidChangeTextDocument::METHOD => {
-            let mut params: DidChangeTextDocumentParams = serde_json::from_value(req.params)?;
-            reload_document(
+            let params: DidChangeTextDocumentParams = serde_json::from_value(req.params)?;
+            apply_document_changes(
                 ctx,
-                params.content_changes.pop().unwrap().text,
+                params.content_changes,
                 params.text_document.uri,
                 Some(params.text_document.version),
                 &mut ctx.document_cache.borrow_mut(),
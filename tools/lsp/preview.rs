@@ -1749,11 +1749,22 @@ fn update_preview_area(
         let shared_handle = preview_state.handle.clone();
         let shared_document_cache = preview_state.document_cache.clone();
 
+        // Keep the state of the previewed UI when the code changes
+        let snapshot = RefCell::new(
+            (behavior == LoadBehavior::Reload)
+                .then(|| preview_state.component_instance())
+                .flatten()
+                .map(|instance| instance.snapshot()),
+        );
+
         if let Some(compiled) = compiled {
             set_preview_factory(
                 ui,
                 compiled,
                 Box::new(move |instance| {
+                    if let Some(snapshot) = snapshot.take() {
+                        instance.restore_snapshot(&snapshot);
+                    }
                     if let Some(rtl) = instance.definition().raw_type_loader() {
                         shared_document_cache.replace(Some(Rc::new(
                             common::DocumentCache::new_from_raw_parts(
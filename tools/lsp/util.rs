@@ -9,6 +9,7 @@
 use i_slint_compiler::parser::{TextRange, TextSize};
 use i_slint_compiler::typeregister::TypeRegister;
 use smol_str::SmolStr;
+use std::sync::atomic::{AtomicBool, Ordering};
 
 use crate::common;
 
@@ -45,10 +46,64 @@ pub fn token_to_lsp_range(token: &SyntaxToken) -> lsp_types::Range {
     text_range_to_lsp_range(&token.parent().source_file, range)
 }
 
+/// The unit of the `character` field of the `lsp_types::Position`s exchanged with the client
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum PositionEncoding {
+    /// Count bytes of the UTF-8 representation
+    #[default]
+    Utf8,
+    /// Count UTF-16 code units: The default of the LSP specification
+    Utf16,
+}
+
+impl PositionEncoding {
+    pub fn to_lsp(self) -> lsp_types::PositionEncodingKind {
+        match self {
+            PositionEncoding::Utf8 => lsp_types::PositionEncodingKind::UTF8,
+            PositionEncoding::Utf16 => lsp_types::PositionEncodingKind::UTF16,
+        }
+    }
+}
+
+// The position encoding is negotiated once with the client, but positions get converted all
+// over the place (including in the preview thread), so keep it in a global.
+static POSITION_ENCODING_IS_UTF16: AtomicBool = AtomicBool::new(false);
+
+/// Set the position encoding negotiated with the client
+pub fn set_position_encoding(encoding: PositionEncoding) {
+    POSITION_ENCODING_IS_UTF16.store(encoding == PositionEncoding::Utf16, Ordering::Relaxed);
+}
+
+/// The position encoding negotiated with the client
+pub fn position_encoding() -> PositionEncoding {
+    if POSITION_ENCODING_IS_UTF16.load(Ordering::Relaxed) {
+        PositionEncoding::Utf16
+    } else {
+        PositionEncoding::Utf8
+    }
+}
+
 /// Convert a `TextSize` to a `Position` for use in the LSP
 pub fn text_size_to_lsp_position(sf: &SourceFile, pos: TextSize) -> lsp_types::Position {
-    let (line, column) = sf.line_column(pos.into());
-    lsp_types::Position::new((line as u32).saturating_sub(1), (column as u32).saturating_sub(1))
+    text_size_to_lsp_position_with_encoding(sf, pos, position_encoding())
+}
+
+/// Convert a `TextSize` to a `Position` using the given `encoding`
+pub fn text_size_to_lsp_position_with_encoding(
+    sf: &SourceFile,
+    pos: TextSize,
+    encoding: PositionEncoding,
+) -> lsp_types::Position {
+    let offset = usize::from(pos);
+    let (line, column) = sf.line_column(offset);
+    let column = column.saturating_sub(1);
+    let column = match (encoding, sf.source()) {
+        (PositionEncoding::Utf16, Some(source)) => source
+            .get(offset.saturating_sub(column)..offset)
+            .map_or(column, |line_start| line_start.encode_utf16().count()),
+        _ => column,
+    };
+    lsp_types::Position::new((line as u32).saturating_sub(1), column as u32)
 }
 
 /// Convert a `TextRange` to a `Range` for use in the LSP
@@ -61,11 +116,34 @@ pub fn text_range_to_lsp_range(sf: &SourceFile, range: TextRange) -> lsp_types::
 
 /// Convert a `Position` from the LSP into a `TextSize`
 pub fn lsp_position_to_text_size(sf: &SourceFile, position: lsp_types::Position) -> TextSize {
-    (sf.offset(
-        usize::try_from(position.line).unwrap() + 1,
-        usize::try_from(position.character).unwrap() + 1,
-    ) as u32)
-        .into()
+    lsp_position_to_text_size_with_encoding(sf, position, position_encoding())
+}
+
+/// Convert a `Position` using the given `encoding` into a `TextSize`
+pub fn lsp_position_to_text_size_with_encoding(
+    sf: &SourceFile,
+    position: lsp_types::Position,
+    encoding: PositionEncoding,
+) -> TextSize {
+    let line = usize::try_from(position.line).unwrap() + 1;
+    let character = usize::try_from(position.character).unwrap();
+    let offset = match (encoding, sf.source()) {
+        (PositionEncoding::Utf16, Some(source)) => {
+            let line_start = sf.offset(line, 1);
+            let mut offset = line_start;
+            let mut units = 0;
+            for c in source.get(line_start..).unwrap_or_default().chars() {
+                if units >= character || c == '\n' {
+                    break;
+                }
+                units += c.len_utf16();
+                offset += c.len_utf8();
+            }
+            offset
+        }
+        _ => sf.offset(line, character + 1),
+    };
+    (offset as u32).into()
 }
 
 /// Convert a `Range` from the LSP into a `TextRange`
@@ -306,14 +384,34 @@ fn lookup_expression_context(mut n: SyntaxNode) -> Option<ExpressionContextInfo>
 }
 
 pub fn to_lsp_diag(d: &i_slint_compiler::diagnostics::Diagnostic) -> lsp_types::Diagnostic {
+    let location = d.source_location();
+    let range = match &location.source_file {
+        Some(sf) if location.span.is_valid() => {
+            let offset = location.span.offset;
+            let pos = text_size_to_lsp_position(sf, (offset as u32).into());
+            let end = if d.is_unnecessary() {
+                // Cover the identifier, so that editors can fade it out
+                let source = sf.source().unwrap_or_default();
+                let len = source.get(offset..).map_or(0, |rest| {
+                    rest.find(|c: char| !c.is_alphanumeric() && c != '_' && c != '-')
+                        .unwrap_or(rest.len())
+                });
+                text_size_to_lsp_position(sf, ((offset + len) as u32).into())
+            } else {
+                pos
+            };
+            lsp_types::Range::new(pos, end)
+        }
+        _ => to_range(d.line_column()),
+    };
     lsp_types::Diagnostic::new(
-        to_range(d.line_column()),
+        range,
         Some(to_lsp_diag_level(d.level())),
         None,
         None,
         d.message().to_owned(),
         None,
-        None,
+        d.is_unnecessary().then(|| vec![lsp_types::DiagnosticTag::UNNECESSARY]),
     )
 }
 
@@ -360,6 +458,40 @@ fn test_find_element_indent() {
         assert_eq!(find_element_indent(&label.unwrap()), Some("        ".to_string()));
     }
 
+    #[test]
+    fn test_map_position_utf16() {
+        let text = "// 🔥 Test 🎆\ncomponent X { property <string> t: \"te🦥xt\"; }";
+        let (dc, url, _) = loaded_document_cache(text.to_string());
+        let doc = dc.get_document(&url).unwrap();
+        let source = doc.node.as_ref().unwrap().source_file.clone();
+        let mut offset = TextSize::new(0);
+        let mut line = 0_u32;
+        let mut pos = 0_u32;
+        for c in text.chars() {
+            let mapped =
+                text_size_to_lsp_position_with_encoding(&source, offset, PositionEncoding::Utf16);
+            assert_eq!(mapped, lsp_types::Position::new(line, pos), "at {c}");
+            let unmapped =
+                lsp_position_to_text_size_with_encoding(&source, mapped, PositionEncoding::Utf16);
+            assert_eq!(unmapped, offset);
+            offset += TextSize::of(c);
+            if c == '\n' {
+                line += 1;
+                pos = 0;
+            } else {
+                pos += c.len_utf16() as u32;
+            }
+        }
+
+        // Positions past the end of a line are clamped to the end of that line
+        let end_of_first_line = lsp_position_to_text_size_with_encoding(
+            &source,
+            lsp_types::Position::new(0, 100),
+            PositionEncoding::Utf16,
+        );
+        assert_eq!(usize::from(end_of_first_line), text.find('\n').unwrap());
+    }
+
     #[test]
     fn test_map_position() {
         let text = r#"// 🔥 Test 🎆
@@ -359,15 +359,22 @@ pub fn open_document(&self, content: String, uri: JsValue, version: i32) -> js_s
     }
 
     #[wasm_bindgen]
-    pub fn reload_document(&self, content: String, uri: JsValue, version: i32) -> js_sys::Promise {
+    pub fn apply_document_changes(
+        &self,
+        changes: JsValue,
+        uri: JsValue,
+        version: i32,
+    ) -> js_sys::Promise {
         let ctx = self.ctx.clone();
         let guard = self.reentry_guard.clone();
         wasm_bindgen_futures::future_to_promise(async move {
             let _lock = ReentryGuard::lock(guard).await;
             let uri: lsp_types::Url = serde_wasm_bindgen::from_value(uri)?;
-            language::reload_document(
+            let changes: Vec<lsp_types::TextDocumentContentChangeEvent> =
+                serde_wasm_bindgen::from_value(changes)?;
+            language::apply_document_changes(
                 &ctx,
-                content,
+                changes,
                 uri.clone(),
                 Some(version),
                 &mut ctx.document_cache.borrow_mut(),
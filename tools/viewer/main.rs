@@ -3,11 +3,16 @@
 
 #![doc = include_str!("README.md")]
 
+mod script;
+
 use clap::Parser;
+use i_slint_compiler::langtype::Type as LangType;
+use i_slint_compiler::object_tree::PropertyVisibility;
 use i_slint_compiler::ComponentSelection;
 use itertools::Itertools;
 use slint_interpreter::{
-    json::JsonExt, ComponentDefinition, ComponentHandle, ComponentInstance, Value,
+    json::{self, JsonExt},
+    CompilationResult, ComponentDefinition, ComponentHandle, ComponentInstance, Value,
 };
 use std::collections::HashMap;
 use std::io::{BufReader, BufWriter};
@@ -81,6 +86,14 @@ struct Cli {
     #[arg(long, value_name = "json file", action)]
     save_data: Option<std::path::PathBuf>,
 
+    /// Print the JSON Schema of the files of --load-data and --save-data, and exit
+    #[arg(long, action)]
+    dump_schema: bool,
+
+    /// Run a script that implements the callbacks, see the "Scripts" section of the README
+    #[arg(long, value_name = "script file", action)]
+    script: Option<std::path::PathBuf>,
+
     /// Specify callbacks handler.
     /// The first argument is the callback name, and the second argument is a string that is going
     /// to be passed to the shell to be executed. Occurrences of `$1` will be replaced by the first argument,
@@ -100,6 +113,7 @@ struct Cli {
 }
 
 thread_local! {static CURRENT_INSTANCE: std::cell::RefCell<Option<ComponentInstance>> = Default::default();}
+thread_local! {static CURRENT_SCRIPT: std::cell::RefCell<Option<script::RunningScript>> = Default::default();}
 static EXIT_CODE: std::sync::atomic::AtomicI32 = std::sync::atomic::AtomicI32::new(0);
 
 fn main() -> Result<()> {
@@ -142,12 +156,24 @@ fn main() -> Result<()> {
         std::process::exit(-1);
     };
 
+    if args.dump_schema {
+        serde_json::to_writer_pretty(std::io::stdout(), &data_schema(&r, &c))?;
+        println!();
+        return Ok(());
+    }
+
+    let script = args.script.as_deref().map(script::Script::load).transpose()?;
+
     let component = c.create()?;
     init_dialog(&component);
 
     if let Some(data_path) = args.load_data {
         load_data(&c, &component, &data_path)?;
     }
+    if let Some(script) = script {
+        let script = script.run(&c, &component);
+        CURRENT_SCRIPT.with(|current| current.replace(Some(script)));
+    }
     install_callbacks(&component, &args.on);
 
     if args.auto_reload {
@@ -157,22 +183,7 @@ fn main() -> Result<()> {
     component.run()?;
 
     if let Some(data_path) = args.save_data {
-        let mut obj = serde_json::Map::new();
-        for (name, _) in c.properties() {
-            match component.get_property(&name).unwrap().to_json() {
-                Ok(v) => {
-                    obj.insert(name, v);
-                }
-                Err(e) => {
-                    eprintln!("Failed to turn property {name} into JSON: {e}");
-                }
-            }
-        }
-        if data_path == std::path::Path::new("-") {
-            serde_json::to_writer_pretty(std::io::stdout(), &obj)?;
-        } else {
-            serde_json::to_writer_pretty(BufWriter::new(std::fs::File::create(data_path)?), &obj)?;
-        }
+        save_data(&c, &component, &data_path)?;
     }
 
     std::process::exit(EXIT_CODE.load(std::sync::atomic::Ordering::Relaxed))
@@ -304,9 +315,12 @@ async fn reload(args: Cli, fswatcher: Arc<Mutex<notify::RecommendedWatcher>>) {
         CURRENT_INSTANCE.with(|current| {
             let mut current = current.borrow_mut();
             if let Some(handle) = current.take() {
+                // Keep the state of the UI, such as the typed text or the scroll positions
+                let snapshot = handle.snapshot();
                 let window = handle.window();
                 let new_handle = c.create_with_existing_window(window).unwrap();
                 init_dialog(&new_handle);
+                new_handle.restore_snapshot(&snapshot);
                 current.replace(new_handle);
             } else {
                 let handle = c.create().unwrap();
@@ -317,6 +331,11 @@ async fn reload(args: Cli, fswatcher: Arc<Mutex<notify::RecommendedWatcher>>) {
             if let Some(data_path) = args.load_data {
                 let _ = load_data(&c, current.as_ref().unwrap(), &data_path);
             }
+            CURRENT_SCRIPT.with(|script| {
+                if let Some(script) = script.borrow().as_ref() {
+                    script.attach(&c, current.as_ref().unwrap());
+                }
+            });
             eprintln!("Successful reload of {}", args.path.display());
         });
     } else if !r.has_errors() {
@@ -329,6 +348,8 @@ async fn reload(args: Cli, fswatcher: Arc<Mutex<notify::RecommendedWatcher>>) {
     PENDING_EVENTS.fetch_sub(1, Ordering::SeqCst);
 }
 
+/// The data files contain the values of the properties of the component, and an object
+/// with the values of the properties of each exported global, under the name of the global.
 fn load_data(
     c: &ComponentDefinition,
     instance: &ComponentInstance,
@@ -341,6 +362,7 @@ fn load_data(
     };
 
     let types = c.properties_and_callbacks().collect::<HashMap<_, _>>();
+    let globals = c.globals().collect::<Vec<_>>();
     let obj = json.as_object().ok_or("The data is not a JSON object")?;
     for (name, v) in obj {
         match types.get(name.as_str()) {
@@ -353,12 +375,126 @@ fn load_data(
                 },
                 Err(e) => eprintln!("Warning: cannot set property '{name}' from data file: {e}"),
             },
+            None if globals.contains(name) => load_global_data(c, instance, name, v),
             None => eprintln!("Warning: ignoring unknown property: {name}"),
         }
     }
     Ok(())
 }
 
+fn load_global_data(
+    c: &ComponentDefinition,
+    instance: &ComponentInstance,
+    global: &str,
+    json: &serde_json::Value,
+) {
+    let Some(obj) = json.as_object() else {
+        eprintln!("Warning: the data of global '{global}' is not a JSON object");
+        return;
+    };
+    let types =
+        c.global_properties_and_callbacks(global).into_iter().flatten().collect::<HashMap<_, _>>();
+    for (name, v) in obj {
+        match types.get(name.as_str()) {
+            Some((t, _)) => match slint_interpreter::Value::from_json(t, v) {
+                Ok(v) => match instance.set_global_property(global, name, v) {
+                    Ok(()) => (),
+                    Err(e) => eprintln!(
+                        "Warning: cannot set property '{global}.{name}' from data file: {e}"
+                    ),
+                },
+                Err(e) => {
+                    eprintln!("Warning: cannot set property '{global}.{name}' from data file: {e}")
+                }
+            },
+            None => eprintln!("Warning: ignoring unknown property: {global}.{name}"),
+        }
+    }
+}
+
+fn save_data(
+    c: &ComponentDefinition,
+    instance: &ComponentInstance,
+    data_path: &std::path::Path,
+) -> Result<()> {
+    fn insert_json(obj: &mut serde_json::Map<String, serde_json::Value>, name: String, v: Value) {
+        match v.to_json() {
+            Ok(v) => {
+                obj.insert(name, v);
+            }
+            Err(e) => {
+                eprintln!("Failed to turn property {name} into JSON: {e}");
+            }
+        }
+    }
+
+    let mut obj = serde_json::Map::new();
+    for (name, _) in c.properties() {
+        let value = instance.get_property(&name).unwrap();
+        insert_json(&mut obj, name, value);
+    }
+    for global in c.globals() {
+        let mut global_obj = serde_json::Map::new();
+        for (name, _) in c.global_properties(&global).into_iter().flatten() {
+            let value = instance.get_global_property(&global, &name).unwrap();
+            insert_json(&mut global_obj, name, value);
+        }
+        obj.insert(global, global_obj.into());
+    }
+    if data_path == std::path::Path::new("-") {
+        serde_json::to_writer_pretty(std::io::stdout(), &obj)?;
+    } else {
+        serde_json::to_writer_pretty(BufWriter::new(std::fs::File::create(data_path)?), &obj)?;
+    }
+    Ok(())
+}
+
+/// The JSON Schema of the data files of `load_data` and `save_data`
+fn data_schema(r: &CompilationResult, c: &ComponentDefinition) -> serde_json::Value {
+    fn object_schema(
+        properties_and_callbacks: impl Iterator<Item = (String, (LangType, PropertyVisibility))>,
+        defs: &mut serde_json::Map<String, serde_json::Value>,
+    ) -> serde_json::Map<String, serde_json::Value> {
+        let mut properties = serde_json::Map::new();
+        for (name, (t, visibility)) in properties_and_callbacks {
+            let Some(mut schema) = json::type_to_json_schema(&t, defs) else { continue };
+            if visibility == PropertyVisibility::Output {
+                // Output properties are saved, but can't be loaded
+                schema["readOnly"] = true.into();
+            }
+            properties.insert(name, schema);
+        }
+        properties
+    }
+
+    let mut defs = serde_json::Map::new();
+    for t in r.structs_and_enums(i_slint_core::InternalToken) {
+        json::type_to_json_schema(t, &mut defs);
+    }
+    let mut properties = object_schema(c.properties_and_callbacks(), &mut defs);
+    for global in c.globals() {
+        let global_properties = object_schema(
+            c.global_properties_and_callbacks(&global).into_iter().flatten(),
+            &mut defs,
+        );
+        properties.entry(global).or_insert_with(|| {
+            serde_json::json!({
+                "type": "object",
+                "properties": global_properties,
+                "additionalProperties": false,
+            })
+        });
+    }
+    serde_json::json!({
+        "$schema": "https://json-schema.org/draft/2020-12/schema",
+        "title": c.name(),
+        "type": "object",
+        "properties": properties,
+        "additionalProperties": false,
+        "$defs": defs,
+    })
+}
+
 fn install_callbacks(instance: &ComponentInstance, callbacks: &[String]) {
     assert!(callbacks.len() % 2 == 0);
     for chunk in callbacks.chunks(2) {
@@ -0,0 +1,1439 @@
+// Copyright © SixtyFPS GmbH <info@slint.dev>
+// SPDX-License-Identifier: GPL-3.0-only OR LicenseRef-Slint-Royalty-free-2.0 OR LicenseRef-Slint-Software-3.0
+
+//! A small scripting language to implement the callbacks of the viewed component (`--script`).
+//!
+//! A script contains functions and top-level statements. The top-level statements run once
+//! when the component is created. A function that has the name of a callback of the component
+//! (or `Global.callback` for a callback of an exported global) handles that callback.
+//! The values of the scripts are JSON values. They are converted from and to Slint values like
+//! the values of the `--load-data` files.
+
+use i_slint_compiler::langtype::Type as LangType;
+use i_slint_core::model::{Model, ModelRc, VecModel};
+use i_slint_core::timers::{Timer, TimerMode};
+use itertools::Itertools;
+use serde_json::Value as JsonValue;
+use slint_interpreter::json::JsonExt;
+use slint_interpreter::{ComponentDefinition, ComponentHandle, ComponentInstance, Value, Weak};
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::rc::Rc;
+
+/// The maximum number of nested function calls, to report infinite recursions as errors
+const MAX_CALL_DEPTH: usize = 64;
+
+const KEYWORDS: &[&str] = &[
+    "fn", "let", "if", "else", "while", "for", "in", "return", "break", "continue", "true",
+    "false", "null",
+];
+
+/// The two-character punctuations must come first
+const PUNCTUATIONS: &[&str] = &[
+    "==", "!=", "<=", ">=", "&&", "||", "+=", "-=", "(", ")", "{", "}", "[", "]", ",", ";", ":",
+    ".", "=", "<", ">", "+", "-", "*", "/", "%", "!",
+];
+
+/// The binary operators, from the lowest to the highest precedence
+const BINARY_OPERATORS: &[&[&str]] =
+    &[&["||"], &["&&"], &["==", "!="], &["<", "<=", ">", ">="], &["+", "-"], &["*", "/", "%"]];
+
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+    Identifier(String),
+    Number(f64),
+    String(String),
+    Punctuation(&'static str),
+    Eof,
+}
+
+impl std::fmt::Display for Token {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Token::Identifier(i) => write!(f, "'{i}'"),
+            Token::Number(n) => write!(f, "number {n}"),
+            Token::String(s) => write!(f, "string {s:?}"),
+            Token::Punctuation(p) => write!(f, "'{p}'"),
+            Token::Eof => write!(f, "end of file"),
+        }
+    }
+}
+
+/// An error with the line where it happened
+#[derive(Debug)]
+struct ScriptError {
+    message: String,
+    line: Option<usize>,
+}
+
+impl ScriptError {
+    fn at_line(mut self, line: usize) -> Self {
+        self.line.get_or_insert(line);
+        self
+    }
+}
+
+impl From<String> for ScriptError {
+    fn from(message: String) -> Self {
+        Self { message, line: None }
+    }
+}
+
+impl From<&str> for ScriptError {
+    fn from(message: &str) -> Self {
+        message.to_string().into()
+    }
+}
+
+fn tokenize(source: &str) -> Result<Vec<(Token, usize)>, ScriptError> {
+    let chars = source.chars().collect::<Vec<_>>();
+    let mut tokens = Vec::new();
+    let mut line = 1;
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        if c == '\n' {
+            line += 1;
+            i += 1;
+        } else if c.is_whitespace() {
+            i += 1;
+        } else if c == '/' && chars.get(i + 1) == Some(&'/') {
+            while i < chars.len() && chars[i] != '\n' {
+                i += 1;
+            }
+        } else if c == '/' && chars.get(i + 1) == Some(&'*') {
+            let start_line = line;
+            i += 2;
+            loop {
+                match chars.get(i) {
+                    None => {
+                        return Err(ScriptError::from("Unterminated comment").at_line(start_line))
+                    }
+                    Some('*') if chars.get(i + 1) == Some(&'/') => break,
+                    Some('\n') => line += 1,
+                    Some(_) => {}
+                }
+                i += 1;
+            }
+            i += 2;
+        } else if c.is_ascii_digit() {
+            let start = i;
+            while i < chars.len() && chars[i].is_ascii_digit() {
+                i += 1;
+            }
+            if chars.get(i) == Some(&'.') && chars.get(i + 1).is_some_and(|c| c.is_ascii_digit()) {
+                i += 1;
+                while i < chars.len() && chars[i].is_ascii_digit() {
+                    i += 1;
+                }
+            }
+            let text = chars[start..i].iter().collect::<String>();
+            let number = text
+                .parse()
+                .map_err(|_| ScriptError::from(format!("Invalid number '{text}'")).at_line(line))?;
+            tokens.push((Token::Number(number), line));
+        } else if c.is_alphabetic() || c == '_' {
+            let start = i;
+            while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+                i += 1;
+            }
+            tokens.push((Token::Identifier(chars[start..i].iter().collect()), line));
+        } else if c == '"' || c == '\'' {
+            let start_line = line;
+            let mut string = String::new();
+            i += 1;
+            loop {
+                match chars.get(i) {
+                    None => {
+                        return Err(ScriptError::from("Unterminated string").at_line(start_line))
+                    }
+                    Some(q) if *q == c => break,
+                    Some('\\') => {
+                        i += 1;
+                        string.push(match chars.get(i) {
+                            Some('n') => '\n',
+                            Some('t') => '\t',
+                            Some(c @ ('"' | '\'' | '\\')) => *c,
+                            _ => {
+                                return Err(
+                                    ScriptError::from("Invalid escape sequence").at_line(line)
+                                )
+                            }
+                        });
+                    }
+                    Some(c) => {
+                        if *c == '\n' {
+                            line += 1;
+                        }
+                        string.push(*c);
+                    }
+                }
+                i += 1;
+            }
+            i += 1;
+            tokens.push((Token::String(string), start_line));
+        } else {
+            let Some(punctuation) = PUNCTUATIONS
+                .iter()
+                .find(|p| p.chars().enumerate().all(|(n, pc)| chars.get(i + n) == Some(&pc)))
+            else {
+                return Err(ScriptError::from(format!("Unexpected character '{c}'")).at_line(line));
+            };
+            i += punctuation.len();
+            tokens.push((Token::Punctuation(punctuation), line));
+        }
+    }
+    tokens.push((Token::Eof, line));
+    Ok(tokens)
+}
+
+#[derive(Clone, Debug)]
+enum Expr {
+    Literal(JsonValue),
+    Variable(String),
+    Array(Vec<Expr>),
+    Object(Vec<(String, Expr)>),
+    Field(Box<Expr>, String),
+    Index(Box<Expr>, Box<Expr>),
+    Call(String, Vec<Expr>),
+    Unary(&'static str, Box<Expr>),
+    Binary(&'static str, Box<Expr>, Box<Expr>),
+}
+
+#[derive(Debug)]
+enum StatementKind {
+    Let(String, Expr),
+    Assign(Expr, Expr),
+    Expression(Expr),
+    If(Expr, Vec<Statement>, Vec<Statement>),
+    While(Expr, Vec<Statement>),
+    For(String, Expr, Vec<Statement>),
+    Return(Option<Expr>),
+    Break,
+    Continue,
+}
+
+#[derive(Debug)]
+struct Statement {
+    kind: StatementKind,
+    line: usize,
+}
+
+#[derive(Debug)]
+struct Function {
+    parameters: Vec<String>,
+    body: Vec<Statement>,
+}
+
+struct Parser {
+    tokens: Vec<(Token, usize)>,
+    position: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> &Token {
+        &self.tokens[self.position].0
+    }
+
+    fn line(&self) -> usize {
+        self.tokens[self.position].1
+    }
+
+    fn next(&mut self) -> Token {
+        let token = self.peek().clone();
+        if token != Token::Eof {
+            self.position += 1;
+        }
+        token
+    }
+
+    fn error(&self, message: impl Into<String>) -> ScriptError {
+        ScriptError::from(message.into()).at_line(self.line())
+    }
+
+    fn is_punctuation(&self, punctuation: &str) -> bool {
+        matches!(self.peek(), Token::Punctuation(p) if *p == punctuation)
+    }
+
+    fn is_keyword(&self, keyword: &str) -> bool {
+        matches!(self.peek(), Token::Identifier(i) if i == keyword)
+    }
+
+    fn eat_punctuation(&mut self, punctuation: &str) -> bool {
+        let found = self.is_punctuation(punctuation);
+        if found {
+            self.next();
+        }
+        found
+    }
+
+    fn eat_keyword(&mut self, keyword: &str) -> bool {
+        let found = self.is_keyword(keyword);
+        if found {
+            self.next();
+        }
+        found
+    }
+
+    fn expect_punctuation(&mut self, punctuation: &str) -> Result<(), ScriptError> {
+        if self.eat_punctuation(punctuation) {
+            Ok(())
+        } else if punctuation == ";" && self.position > 0 {
+            // The missing ';' is at the end of the previous token, which may be on a previous line
+            let line = self.tokens[self.position - 1].1;
+            Err(ScriptError::from("Expected ';'").at_line(line))
+        } else {
+            Err(self.error(format!("Expected '{punctuation}'")))
+        }
+    }
+
+    fn expect_identifier(&mut self) -> Result<String, ScriptError> {
+        match self.peek() {
+            Token::Identifier(i) if !KEYWORDS.contains(&i.as_str()) => {
+                let i = i.clone();
+                self.next();
+                Ok(i)
+            }
+            _ => Err(self.error("Expected an identifier")),
+        }
+    }
+
+    fn parse_function(&mut self) -> Result<(String, Function), ScriptError> {
+        let mut name = self.expect_identifier()?;
+        if self.eat_punctuation(".") {
+            name = format!("{name}.{}", self.expect_identifier()?);
+        }
+        self.expect_punctuation("(")?;
+        let mut parameters = Vec::new();
+        while !self.eat_punctuation(")") {
+            if !parameters.is_empty() {
+                self.expect_punctuation(",")?;
+            }
+            parameters.push(self.expect_identifier()?);
+        }
+        let body = self.parse_block()?;
+        Ok((name, Function { parameters, body }))
+    }
+
+    fn parse_block(&mut self) -> Result<Vec<Statement>, ScriptError> {
+        self.expect_punctuation("{")?;
+        let mut statements = Vec::new();
+        while !self.eat_punctuation("}") {
+            if *self.peek() == Token::Eof {
+                return Err(self.error("Expected '}'"));
+            }
+            statements.push(self.parse_statement()?);
+        }
+        Ok(statements)
+    }
+
+    fn parse_statement(&mut self) -> Result<Statement, ScriptError> {
+        let line = self.line();
+        let kind = if self.eat_keyword("let") {
+            let name = self.expect_identifier()?;
+            self.expect_punctuation("=")?;
+            let value = self.parse_expression()?;
+            self.expect_punctuation(";")?;
+            StatementKind::Let(name, value)
+        } else if self.eat_keyword("if") {
+            let condition = self.parse_expression()?;
+            let then_branch = self.parse_block()?;
+            let else_branch = if !self.eat_keyword("else") {
+                Vec::new()
+            } else if self.is_keyword("if") {
+                vec![self.parse_statement()?]
+            } else {
+                self.parse_block()?
+            };
+            StatementKind::If(condition, then_branch, else_branch)
+        } else if self.eat_keyword("while") {
+            let condition = self.parse_expression()?;
+            StatementKind::While(condition, self.parse_block()?)
+        } else if self.eat_keyword("for") {
+            let name = self.expect_identifier()?;
+            if !self.eat_keyword("in") {
+                return Err(self.error("Expected 'in'"));
+            }
+            let iterable = self.parse_expression()?;
+            StatementKind::For(name, iterable, self.parse_block()?)
+        } else if self.eat_keyword("return") {
+            let value =
+                if self.is_punctuation(";") { None } else { Some(self.parse_expression()?) };
+            self.expect_punctuation(";")?;
+            StatementKind::Return(value)
+        } else if self.eat_keyword("break") {
+            self.expect_punctuation(";")?;
+            StatementKind::Break
+        } else if self.eat_keyword("continue") {
+            self.expect_punctuation(";")?;
+            StatementKind::Continue
+        } else {
+            let expression = self.parse_expression()?;
+            let operator = ["=", "+=", "-="].into_iter().find(|op| self.is_punctuation(op));
+            let kind = if let Some(operator) = operator {
+                if !matches!(expression, Expr::Variable(_) | Expr::Field(..) | Expr::Index(..)) {
+                    return Err(self.error("Cannot assign to this expression"));
+                }
+                self.next();
+                let value = self.parse_expression()?;
+                let value = match operator {
+                    "+=" => Expr::Binary("+", Box::new(expression.clone()), Box::new(value)),
+                    "-=" => Expr::Binary("-", Box::new(expression.clone()), Box::new(value)),
+                    _ => value,
+                };
+                StatementKind::Assign(expression, value)
+            } else {
+                StatementKind::Expression(expression)
+            };
+            self.expect_punctuation(";")?;
+            kind
+        };
+        Ok(Statement { kind, line })
+    }
+
+    fn parse_expression(&mut self) -> Result<Expr, ScriptError> {
+        self.parse_binary(0)
+    }
+
+    fn parse_binary(&mut self, level: usize) -> Result<Expr, ScriptError> {
+        let Some(operators) = BINARY_OPERATORS.get(level) else { return self.parse_unary() };
+        let mut lhs = self.parse_binary(level + 1)?;
+        loop {
+            let operator = match self.peek() {
+                Token::Punctuation(p) if operators.contains(p) => *p,
+                _ => break,
+            };
+            self.next();
+            let rhs = self.parse_binary(level + 1)?;
+            lhs = Expr::Binary(operator, Box::new(lhs), Box::new(rhs));
+        }
+        Ok(lhs)
+    }
+
+    fn parse_unary(&mut self) -> Result<Expr, ScriptError> {
+        for operator in ["!", "-"] {
+            if self.eat_punctuation(operator) {
+                return Ok(Expr::Unary(operator, Box::new(self.parse_unary()?)));
+            }
+        }
+        self.parse_postfix()
+    }
+
+    fn parse_postfix(&mut self) -> Result<Expr, ScriptError> {
+        let mut expression = self.parse_primary()?;
+        loop {
+            if self.eat_punctuation(".") {
+                expression = Expr::Field(Box::new(expression), self.expect_identifier()?);
+            } else if self.eat_punctuation("[") {
+                let index = self.parse_expression()?;
+                self.expect_punctuation("]")?;
+                expression = Expr::Index(Box::new(expression), Box::new(index));
+            } else if self.is_punctuation("(") {
+                // `Global.callback(...)` calls the function that handles a callback of a global
+                let Expr::Field(base, name) = &expression else {
+                    return Err(self.error("Only functions can be called"));
+                };
+                let Expr::Variable(global) = &**base else {
+                    return Err(self.error("Only functions can be called"));
+                };
+                let name = format!("{global}.{name}");
+                self.next();
+                expression = Expr::Call(name, self.parse_arguments()?);
+            } else {
+                return Ok(expression);
+            }
+        }
+    }
+
+    /// Parse the arguments of a call, after the '('
+    fn parse_arguments(&mut self) -> Result<Vec<Expr>, ScriptError> {
+        let mut arguments = Vec::new();
+        while !self.eat_punctuation(")") {
+            if !arguments.is_empty() {
+                self.expect_punctuation(",")?;
+            }
+            arguments.push(self.parse_expression()?);
+        }
+        Ok(arguments)
+    }
+
+    fn parse_primary(&mut self) -> Result<Expr, ScriptError> {
+        let line = self.line();
+        match self.next() {
+            Token::Number(n) => Ok(Expr::Literal(number(n))),
+            Token::String(s) => Ok(Expr::Literal(JsonValue::String(s))),
+            Token::Identifier(i) => match i.as_str() {
+                "true" => Ok(Expr::Literal(true.into())),
+                "false" => Ok(Expr::Literal(false.into())),
+                "null" => Ok(Expr::Literal(JsonValue::Null)),
+                _ if KEYWORDS.contains(&i.as_str()) => {
+                    Err(ScriptError::from(format!("Unexpected keyword '{i}'")).at_line(line))
+                }
+                _ if self.eat_punctuation("(") => Ok(Expr::Call(i, self.parse_arguments()?)),
+                _ => Ok(Expr::Variable(i)),
+            },
+            Token::Punctuation("(") => {
+                let expression = self.parse_expression()?;
+                self.expect_punctuation(")")?;
+                Ok(expression)
+            }
+            Token::Punctuation("[") => {
+                let mut items = Vec::new();
+                while !self.eat_punctuation("]") {
+                    if !items.is_empty() {
+                        self.expect_punctuation(",")?;
+                        if self.eat_punctuation("]") {
+                            break;
+                        }
+                    }
+                    items.push(self.parse_expression()?);
+                }
+                Ok(Expr::Array(items))
+            }
+            Token::Punctuation("{") => {
+                let mut fields = Vec::new();
+                while !self.eat_punctuation("}") {
+                    if !fields.is_empty() {
+                        self.expect_punctuation(",")?;
+                        if self.eat_punctuation("}") {
+                            break;
+                        }
+                    }
+                    let name = match self.next() {
+                        Token::Identifier(name) | Token::String(name) => name,
+                        _ => return Err(self.error("Expected a field name")),
+                    };
+                    self.expect_punctuation(":")?;
+                    fields.push((name, self.parse_expression()?));
+                }
+                Ok(Expr::Object(fields))
+            }
+            token => Err(ScriptError::from(format!("Unexpected {token}")).at_line(line)),
+        }
+    }
+}
+
+/// A parsed script
+pub struct Script {
+    path: String,
+    functions: HashMap<String, Rc<Function>>,
+    statements: Vec<Statement>,
+}
+
+impl Script {
+    pub fn load(path: &std::path::Path) -> Result<Self, String> {
+        let source = std::fs::read_to_string(path)
+            .map_err(|e| format!("Cannot read script {}: {e}", path.display()))?;
+        Self::parse(path.display().to_string(), &source)
+    }
+
+    fn parse(path: String, source: &str) -> Result<Self, String> {
+        let format_error =
+            |e: ScriptError| format!("{path}:{}: {}", e.line.unwrap_or(0), e.message);
+        let mut parser = Parser { tokens: tokenize(source).map_err(format_error)?, position: 0 };
+        let mut functions = HashMap::new();
+        let mut statements = Vec::new();
+        while *parser.peek() != Token::Eof {
+            if parser.eat_keyword("fn") {
+                let line = parser.line();
+                let (name, function) = parser.parse_function().map_err(format_error)?;
+                if functions.insert(name.clone(), Rc::new(function)).is_some() {
+                    return Err(format_error(
+                        ScriptError::from(format!("Duplicated function '{name}'")).at_line(line),
+                    ));
+                }
+            } else {
+                statements.push(parser.parse_statement().map_err(format_error)?);
+            }
+        }
+        Ok(Self { path, functions, statements })
+    }
+
+    /// Set the functions of the script as handlers of the callbacks that have their name,
+    /// and run the top-level statements.
+    /// The timers started by the script run as long as the returned `RunningScript` is alive.
+    pub fn run(
+        self,
+        definition: &ComponentDefinition,
+        instance: &ComponentInstance,
+    ) -> RunningScript {
+        let engine = Rc::new(Engine {
+            path: self.path,
+            functions: self.functions,
+            globals: Default::default(),
+            instance: Default::default(),
+            types: Default::default(),
+            timers: Default::default(),
+            depth: Cell::new(0),
+        });
+        let script = RunningScript(engine);
+        script.attach(definition, instance);
+
+        let mut env = Vec::new();
+        if let Err(e) = script.0.execute_statements(&self.statements, &mut env) {
+            script.0.report(e);
+        }
+        script
+    }
+}
+
+/// A script that was started with [`Script::run`]
+pub struct RunningScript(Rc<Engine>);
+
+impl RunningScript {
+    /// Set the functions of the script as handlers of the callbacks of `instance`, and use it
+    /// for the properties accessed by the script from now on. This is used to move the script
+    /// to the new instance after a reload, without running the top-level statements again.
+    pub fn attach(&self, definition: &ComponentDefinition, instance: &ComponentInstance) {
+        let engine = &self.0;
+        let mut types = definition
+            .properties_and_callbacks()
+            .map(|(name, (t, _))| (name, t))
+            .collect::<HashMap<_, _>>();
+        for global in definition.globals() {
+            for (name, (t, _)) in
+                definition.global_properties_and_callbacks(&global).into_iter().flatten()
+            {
+                types.insert(format!("{global}.{name}"), t);
+            }
+        }
+
+        for name in engine.functions.keys() {
+            let key = normalize(name);
+            let Some(LangType::Callback(callback)) = types.get(&key) else { continue };
+            let return_type = callback.return_type.clone();
+            let handler = {
+                let engine = engine.clone();
+                let name = name.clone();
+                move |args: &[Value]| engine.handle_callback(&name, &return_type, args)
+            };
+            let result = match key.split_once('.') {
+                Some((global, callback)) => instance.set_global_callback(global, callback, handler),
+                None => instance.set_callback(&key, handler),
+            };
+            if let Err(e) = result {
+                eprintln!("Warning: cannot set callback handler for '{key}': {e}");
+            }
+        }
+
+        *engine.types.borrow_mut() = types;
+        *engine.instance.borrow_mut() = Some(instance.as_weak());
+    }
+}
+
+enum Flow {
+    Next,
+    Break,
+    Continue,
+    Return(JsonValue),
+}
+
+/// The scopes of the local variables, from the outermost to the innermost
+type Env = Vec<HashMap<String, JsonValue>>;
+
+struct Engine {
+    path: String,
+    functions: HashMap<String, Rc<Function>>,
+    /// The variables declared by the top-level statements
+    globals: RefCell<HashMap<String, JsonValue>>,
+    instance: RefCell<Option<Weak<ComponentInstance>>>,
+    /// The type of the properties and callbacks, with `Global.name` for the globals
+    types: RefCell<HashMap<String, LangType>>,
+    /// The timers started with `every`. Their index is the id returned to the script.
+    timers: RefCell<Vec<Timer>>,
+    depth: Cell<usize>,
+}
+
+impl Engine {
+    fn report(&self, e: ScriptError) {
+        eprintln!("Error: {}:{}: {}", self.path, e.line.unwrap_or(0), e.message);
+    }
+
+    fn handle_callback(
+        self: &Rc<Self>,
+        name: &str,
+        return_type: &LangType,
+        args: &[Value],
+    ) -> Value {
+        let args = args
+            .iter()
+            .map(|v| {
+                v.to_json().unwrap_or_else(|e| {
+                    eprintln!("Warning: cannot pass argument of '{name}' to the script: {e}");
+                    JsonValue::Null
+                })
+            })
+            .collect();
+        let result = match self.call_function(name, args) {
+            Ok(result) => result,
+            Err(e) => {
+                self.report(e);
+                JsonValue::Null
+            }
+        };
+        if matches!(return_type, LangType::Void) || result.is_null() {
+            return slint_interpreter::default_value_for_type(return_type);
+        }
+        Value::from_json(return_type, &result).unwrap_or_else(|e| {
+            eprintln!("Warning: invalid return value for '{name}': {e}");
+            slint_interpreter::default_value_for_type(return_type)
+        })
+    }
+
+    fn call_function(
+        self: &Rc<Self>,
+        name: &str,
+        args: Vec<JsonValue>,
+    ) -> Result<JsonValue, ScriptError> {
+        let function =
+            self.functions.get(name).ok_or_else(|| format!("Unknown function '{name}'"))?;
+        if self.depth.get() >= MAX_CALL_DEPTH {
+            return Err("Too many nested function calls".into());
+        }
+        // Missing arguments are null, and extra arguments are ignored
+        let scope = function
+            .parameters
+            .iter()
+            .cloned()
+            .zip(args.into_iter().chain(std::iter::repeat(JsonValue::Null)))
+            .collect();
+        self.depth.set(self.depth.get() + 1);
+        let result = self.execute_statements(&function.body, &mut vec![scope]);
+        self.depth.set(self.depth.get() - 1);
+        match result? {
+            Flow::Return(value) => Ok(value),
+            _ => Ok(JsonValue::Null),
+        }
+    }
+
+    fn execute_block(
+        self: &Rc<Self>,
+        statements: &[Statement],
+        env: &mut Env,
+    ) -> Result<Flow, ScriptError> {
+        env.push(HashMap::new());
+        let result = self.execute_statements(statements, env);
+        env.pop();
+        result
+    }
+
+    fn execute_statements(
+        self: &Rc<Self>,
+        statements: &[Statement],
+        env: &mut Env,
+    ) -> Result<Flow, ScriptError> {
+        for statement in statements {
+            match self.execute(statement, env).map_err(|e| e.at_line(statement.line))? {
+                Flow::Next => (),
+                flow => return Ok(flow),
+            }
+        }
+        Ok(Flow::Next)
+    }
+
+    fn execute(self: &Rc<Self>, statement: &Statement, env: &mut Env) -> Result<Flow, ScriptError> {
+        match &statement.kind {
+            StatementKind::Let(name, value) => {
+                let value = self.evaluate(value, env)?;
+                match env.last_mut() {
+                    Some(scope) => scope.insert(name.clone(), value),
+                    None => self.globals.borrow_mut().insert(name.clone(), value),
+                };
+            }
+            StatementKind::Assign(target, value) => {
+                let value = self.evaluate(value, env)?;
+                self.assign(target, value, env)?;
+            }
+            StatementKind::Expression(expression) => {
+                self.evaluate(expression, env)?;
+            }
+            StatementKind::If(condition, then_branch, else_branch) => {
+                let branch = if is_truthy(&self.evaluate(condition, env)?) {
+                    then_branch
+                } else {
+                    else_branch
+                };
+                return self.execute_block(branch, env);
+            }
+            StatementKind::While(condition, body) => {
+                while is_truthy(&self.evaluate(condition, env)?) {
+                    match self.execute_block(body, env)? {
+                        Flow::Break => break,
+                        flow @ Flow::Return(_) => return Ok(flow),
+                        Flow::Next | Flow::Continue => (),
+                    }
+                }
+            }
+            StatementKind::For(name, iterable, body) => {
+                let items = match self.evaluate(iterable, env)? {
+                    JsonValue::Array(items) => items,
+                    JsonValue::Object(object) => {
+                        object.into_iter().map(|(k, _)| k.into()).collect()
+                    }
+                    JsonValue::Number(n) => {
+                        (0..n.as_f64().unwrap_or(0.) as i64).map(Into::into).collect()
+                    }
+                    v => return Err(format!("Cannot iterate over {}", type_name(&v)).into()),
+                };
+                for item in items {
+                    env.push(HashMap::from([(name.clone(), item)]));
+                    let flow = self.execute_statements(body, env);
+                    env.pop();
+                    match flow? {
+                        Flow::Break => break,
+                        flow @ Flow::Return(_) => return Ok(flow),
+                        Flow::Next | Flow::Continue => (),
+                    }
+                }
+            }
+            StatementKind::Return(value) => {
+                let value = match value {
+                    Some(value) => self.evaluate(value, env)?,
+                    None => JsonValue::Null,
+                };
+                return Ok(Flow::Return(value));
+            }
+            StatementKind::Break => return Ok(Flow::Break),
+            StatementKind::Continue => return Ok(Flow::Continue),
+        }
+        Ok(Flow::Next)
+    }
+
+    fn assign(
+        self: &Rc<Self>,
+        target: &Expr,
+        value: JsonValue,
+        env: &mut Env,
+    ) -> Result<(), ScriptError> {
+        // The fields and indexes from the variable to the assigned value, in reverse order
+        let mut path = Vec::new();
+        let mut root = target;
+        let name = loop {
+            match root {
+                Expr::Variable(name) => break name,
+                Expr::Field(base, field) => {
+                    path.push(JsonValue::String(field.clone()));
+                    root = base;
+                }
+                Expr::Index(base, index) => {
+                    path.push(self.evaluate(index, env)?);
+                    root = base;
+                }
+                _ => return Err("Cannot assign to this expression".into()),
+            }
+        };
+
+        let mut globals;
+        let mut slot = match env.iter_mut().rev().find_map(|scope| scope.get_mut(name)) {
+            Some(slot) => slot,
+            None => {
+                globals = self.globals.borrow_mut();
+                globals.get_mut(name).ok_or_else(|| format!("Unknown variable '{name}'"))?
+            }
+        };
+        for key in path.iter().rev() {
+            slot = match (slot, key) {
+                (JsonValue::Array(array), JsonValue::Number(index)) => {
+                    let index = to_index(index.as_f64().unwrap_or(f64::NAN), array.len())?;
+                    &mut array[index]
+                }
+                (JsonValue::Object(object), JsonValue::String(field)) => {
+                    object.entry(field.clone()).or_insert(JsonValue::Null)
+                }
+                (slot, key) => {
+                    return Err(
+                        format!("Cannot assign '{}' of {}", display(key), type_name(slot)).into()
+                    )
+                }
+            };
+        }
+        *slot = value;
+        Ok(())
+    }
+
+    fn evaluate(
+        self: &Rc<Self>,
+        expression: &Expr,
+        env: &mut Env,
+    ) -> Result<JsonValue, ScriptError> {
+        Ok(match expression {
+            Expr::Literal(value) => value.clone(),
+            Expr::Variable(name) => match env.iter().rev().find_map(|scope| scope.get(name)) {
+                Some(value) => value.clone(),
+                None => self
+                    .globals
+                    .borrow()
+                    .get(name)
+                    .cloned()
+                    .ok_or_else(|| format!("Unknown variable '{name}'"))?,
+            },
+            Expr::Array(items) => JsonValue::Array(
+                items.iter().map(|item| self.evaluate(item, env)).collect::<Result<_, _>>()?,
+            ),
+            Expr::Object(fields) => JsonValue::Object(
+                fields
+                    .iter()
+                    .map(|(name, value)| Ok((name.clone(), self.evaluate(value, env)?)))
+                    .collect::<Result<_, ScriptError>>()?,
+            ),
+            Expr::Field(base, field) => match self.evaluate(base, env)? {
+                JsonValue::Object(mut object) => object.remove(field).unwrap_or(JsonValue::Null),
+                v => {
+                    return Err(format!("Cannot access field '{field}' of {}", type_name(&v)).into())
+                }
+            },
+            Expr::Index(base, index) => {
+                match (self.evaluate(base, env)?, self.evaluate(index, env)?) {
+                    (JsonValue::Array(mut array), JsonValue::Number(index)) => {
+                        let index = to_index(index.as_f64().unwrap_or(f64::NAN), array.len())?;
+                        array.swap_remove(index)
+                    }
+                    (JsonValue::Object(mut object), JsonValue::String(field)) => {
+                        object.remove(&field).unwrap_or(JsonValue::Null)
+                    }
+                    (JsonValue::String(string), JsonValue::Number(index)) => {
+                        let chars = string.chars().collect::<Vec<_>>();
+                        let index = to_index(index.as_f64().unwrap_or(f64::NAN), chars.len())?;
+                        chars[index].to_string().into()
+                    }
+                    (base, index) => {
+                        return Err(format!(
+                            "Cannot index {} with {}",
+                            type_name(&base),
+                            type_name(&index)
+                        )
+                        .into())
+                    }
+                }
+            }
+            Expr::Call(name, arguments) => {
+                let arguments = arguments
+                    .iter()
+                    .map(|argument| self.evaluate(argument, env))
+                    .collect::<Result<Vec<_>, _>>()?;
+                self.call(name, arguments)?
+            }
+            Expr::Unary(operator, operand) => {
+                let operand = self.evaluate(operand, env)?;
+                match *operator {
+                    "!" => (!is_truthy(&operand)).into(),
+                    _ => number(-to_number(&operand)?),
+                }
+            }
+            Expr::Binary("&&", lhs, rhs) => (is_truthy(&self.evaluate(lhs, env)?)
+                && is_truthy(&self.evaluate(rhs, env)?))
+            .into(),
+            Expr::Binary("||", lhs, rhs) => (is_truthy(&self.evaluate(lhs, env)?)
+                || is_truthy(&self.evaluate(rhs, env)?))
+            .into(),
+            Expr::Binary(operator, lhs, rhs) => {
+                binary_operation(operator, self.evaluate(lhs, env)?, self.evaluate(rhs, env)?)?
+            }
+        })
+    }
+
+    /// Call a function of the script, or a builtin function
+    fn call(self: &Rc<Self>, name: &str, args: Vec<JsonValue>) -> Result<JsonValue, ScriptError> {
+        if self.functions.contains_key(name) {
+            return self.call_function(name, args);
+        }
+        Ok(match (name, args.as_slice()) {
+            ("print", _) => {
+                println!("{}", args.iter().map(display).join(" "));
+                JsonValue::Null
+            }
+            ("get", [property]) => self.get_property(to_str(property)?)?.to_json()?,
+            ("set", [property, value]) => {
+                let property = normalize(to_str(property)?);
+                let t = self
+                    .types
+                    .borrow()
+                    .get(&property)
+                    .cloned()
+                    .ok_or_else(|| format!("Unknown property '{property}'"))?;
+                let value = Value::from_json(&t, value)
+                    .map_err(|e| format!("Cannot set property '{property}': {e}"))?;
+                self.set_property(&property, value)?;
+                JsonValue::Null
+            }
+            ("invoke", [callback, arguments @ ..]) => {
+                let callback = normalize(to_str(callback)?);
+                let (LangType::Callback(function) | LangType::Function(function)) = self
+                    .types
+                    .borrow()
+                    .get(&callback)
+                    .cloned()
+                    .ok_or_else(|| format!("Unknown callback '{callback}'"))?
+                else {
+                    return Err(format!("'{callback}' is not a callback or a function").into());
+                };
+                let arguments = function
+                    .args
+                    .iter()
+                    .zip(arguments.iter().chain(std::iter::repeat(&JsonValue::Null)))
+                    .map(|(t, v)| match v {
+                        JsonValue::Null => Ok(slint_interpreter::default_value_for_type(t)),
+                        v => Value::from_json(t, v),
+                    })
+                    .collect::<Result<Vec<_>, _>>()
+                    .map_err(|e| format!("Invalid argument for '{callback}': {e}"))?;
+                let instance = self.instance()?;
+                let result = match callback.split_once('.') {
+                    Some((global, callback)) => {
+                        instance.invoke_global(global, callback, &arguments)
+                    }
+                    None => instance.invoke(&callback, &arguments),
+                }
+                .map_err(|e| format!("Cannot invoke '{callback}': {e}"))?;
+                match result {
+                    Value::Void => JsonValue::Null,
+                    result => result.to_json()?,
+                }
+            }
+            ("push", [model, value]) => self.with_model(to_str(model)?, |model, t| {
+                model.push(Value::from_json(t, value)?);
+                Ok(JsonValue::Null)
+            })?,
+            ("insert", [model, index, value]) => self.with_model(to_str(model)?, |model, t| {
+                let index = to_index(to_number(index)?, model.row_count() + 1)?;
+                model.insert(index, Value::from_json(t, value)?);
+                Ok(JsonValue::Null)
+            })?,
+            ("remove", [model, index]) => self.with_model(to_str(model)?, |model, _| {
+                let index = to_index(to_number(index)?, model.row_count())?;
+                let removed = model.row_data(index).unwrap_or_default().to_json()?;
+                model.remove(index);
+                Ok(removed)
+            })?,
+            ("set_row", [model, index, value]) => self.with_model(to_str(model)?, |model, t| {
+                let index = to_index(to_number(index)?, model.row_count())?;
+                model.set_row_data(index, Value::from_json(t, value)?);
+                Ok(JsonValue::Null)
+            })?,
+            ("after", [interval, function, arguments @ ..]) => {
+                let callback = self.timer_callback(to_str(function)?, arguments)?;
+                Timer::single_shot(to_duration(interval)?, callback);
+                JsonValue::Null
+            }
+            ("every", [interval, function, arguments @ ..]) => {
+                let callback = self.timer_callback(to_str(function)?, arguments)?;
+                let timer = Timer::default();
+                timer.start(TimerMode::Repeated, to_duration(interval)?, callback);
+                let mut timers = self.timers.borrow_mut();
+                timers.push(timer);
+                number((timers.len() - 1) as f64)
+            }
+            ("stop", [timer]) => {
+                let timers = self.timers.borrow();
+                let index = to_index(to_number(timer)?, timers.len())
+                    .map_err(|_| format!("Unknown timer {}", display(timer)))?;
+                timers[index].stop();
+                JsonValue::Null
+            }
+            ("quit", []) => {
+                i_slint_core::api::quit_event_loop().map_err(|e| e.to_string())?;
+                JsonValue::Null
+            }
+            ("len", [value]) => number(match value {
+                JsonValue::Array(array) => array.len(),
+                JsonValue::Object(object) => object.len(),
+                JsonValue::String(string) => string.chars().count(),
+                v => return Err(format!("Cannot get the length of {}", type_name(v)).into()),
+            } as f64),
+            ("str", [value]) => display(value).into(),
+            ("floor", [value]) => number(to_number(value)?.floor()),
+            ("round", [value]) => number(to_number(value)?.round()),
+            ("abs", [value]) => number(to_number(value)?.abs()),
+            ("min", [a, b]) => number(to_number(a)?.min(to_number(b)?)),
+            ("max", [a, b]) => number(to_number(a)?.max(to_number(b)?)),
+            (
+                "get" | "set" | "invoke" | "push" | "insert" | "remove" | "set_row" | "after"
+                | "every" | "stop" | "quit" | "len" | "str" | "floor" | "round" | "abs" | "min"
+                | "max",
+                _,
+            ) => return Err(format!("Wrong number of arguments for '{name}'").into()),
+            _ => return Err(format!("Unknown function '{name}'").into()),
+        })
+    }
+
+    fn timer_callback(
+        self: &Rc<Self>,
+        function: &str,
+        arguments: &[JsonValue],
+    ) -> Result<impl FnMut() + 'static, ScriptError> {
+        if !self.functions.contains_key(function) {
+            return Err(format!("Unknown function '{function}'").into());
+        }
+        let engine = Rc::downgrade(self);
+        let function = function.to_string();
+        let arguments = arguments.to_vec();
+        Ok(move || {
+            if let Some(engine) = engine.upgrade() {
+                if let Err(e) = engine.call_function(&function, arguments.clone()) {
+                    engine.report(e);
+                }
+            }
+        })
+    }
+
+    fn instance(&self) -> Result<ComponentInstance, ScriptError> {
+        self.instance
+            .borrow()
+            .as_ref()
+            .and_then(|instance| instance.upgrade())
+            .ok_or_else(|| "The component is not available".into())
+    }
+
+    fn get_property(&self, property: &str) -> Result<Value, ScriptError> {
+        let instance = self.instance()?;
+        let property = normalize(property);
+        match property.split_once('.') {
+            Some((global, name)) => instance.get_global_property(global, name),
+            None => instance.get_property(&property),
+        }
+        .map_err(|e| format!("Cannot get property '{property}': {e}").into())
+    }
+
+    fn set_property(&self, property: &str, value: Value) -> Result<(), ScriptError> {
+        let instance = self.instance()?;
+        match property.split_once('.') {
+            Some((global, name)) => instance.set_global_property(global, name, value),
+            None => instance.set_property(property, value),
+        }
+        .map_err(|e| format!("Cannot set property '{property}': {e}").into())
+    }
+
+    /// Call `f` with the model of the property and the type of its rows.
+    /// The model is replaced by a `VecModel` with the same rows if it isn't one already.
+    fn with_model(
+        &self,
+        property: &str,
+        f: impl FnOnce(&VecModel<Value>, &LangType) -> Result<JsonValue, String>,
+    ) -> Result<JsonValue, ScriptError> {
+        let property = normalize(property);
+        let Some(LangType::Array(row_type)) = self.types.borrow().get(&property).cloned() else {
+            return Err(format!("'{property}' is not a model property").into());
+        };
+        let model = match self.get_property(&property)? {
+            Value::Model(model) => model,
+            _ => ModelRc::default(),
+        };
+        if let Some(model) = model.as_any().downcast_ref::<VecModel<Value>>() {
+            return Ok(f(model, &row_type)?);
+        }
+        let model = Rc::new(VecModel::from(model.iter().collect::<Vec<_>>()));
+        self.set_property(&property, Value::Model(ModelRc::from(model.clone())))?;
+        Ok(f(&model, &row_type)?)
+    }
+}
+
+/// Script identifiers can't contain '-', so `_` is used instead in the names of the
+/// functions and properties
+fn normalize(name: &str) -> String {
+    name.replace('_', "-")
+}
+
+/// Integers are stored as JSON integers, so that they are displayed without decimals
+fn number(n: f64) -> JsonValue {
+    if n.fract() == 0. && n.abs() < 1e15 {
+        (n as i64).into()
+    } else {
+        serde_json::Number::from_f64(n).map_or(JsonValue::Null, JsonValue::Number)
+    }
+}
+
+fn to_number(value: &JsonValue) -> Result<f64, String> {
+    match value {
+        JsonValue::Number(n) => Ok(n.as_f64().unwrap_or(f64::NAN)),
+        v => Err(format!("Expected a number, got {}", type_name(v))),
+    }
+}
+
+fn to_index(index: f64, len: usize) -> Result<usize, String> {
+    if index.fract() == 0. && index >= 0. && (index as usize) < len {
+        Ok(index as usize)
+    } else {
+        Err(format!("Index {index} out of bounds"))
+    }
+}
+
+fn to_str(value: &JsonValue) -> Result<&str, String> {
+    match value {
+        JsonValue::String(s) => Ok(s),
+        v => Err(format!("Expected a string, got {}", type_name(v))),
+    }
+}
+
+fn to_duration(value: &JsonValue) -> Result<std::time::Duration, String> {
+    Ok(std::time::Duration::from_millis(to_number(value)?.max(0.) as u64))
+}
+
+fn is_truthy(value: &JsonValue) -> bool {
+    match value {
+        JsonValue::Null => false,
+        JsonValue::Bool(b) => *b,
+        JsonValue::Number(n) => n.as_f64().is_some_and(|n| n != 0.),
+        JsonValue::String(s) => !s.is_empty(),
+        JsonValue::Array(_) | JsonValue::Object(_) => true,
+    }
+}
+
+fn type_name(value: &JsonValue) -> &'static str {
+    match value {
+        JsonValue::Null => "null",
+        JsonValue::Bool(_) => "a boolean",
+        JsonValue::Number(_) => "a number",
+        JsonValue::String(_) => "a string",
+        JsonValue::Array(_) => "an array",
+        JsonValue::Object(_) => "an object",
+    }
+}
+
+/// The text of a value for `print`, `str` and string concatenation
+fn display(value: &JsonValue) -> String {
+    match value {
+        JsonValue::String(s) => s.clone(),
+        JsonValue::Number(n) => match n.as_f64() {
+            Some(n) if n.fract() == 0. && n.abs() < 1e15 => (n as i64).to_string(),
+            _ => n.to_string(),
+        },
+        v => v.to_string(),
+    }
+}
+
+fn equals(lhs: &JsonValue, rhs: &JsonValue) -> bool {
+    match (lhs, rhs) {
+        (JsonValue::Number(a), JsonValue::Number(b)) => a.as_f64() == b.as_f64(),
+        _ => lhs == rhs,
+    }
+}
+
+fn binary_operation(
+    operator: &str,
+    lhs: JsonValue,
+    rhs: JsonValue,
+) -> Result<JsonValue, ScriptError> {
+    Ok(match (operator, &lhs, &rhs) {
+        ("==", ..) => equals(&lhs, &rhs).into(),
+        ("!=", ..) => (!equals(&lhs, &rhs)).into(),
+        ("+", JsonValue::String(_), _) | ("+", _, JsonValue::String(_)) => {
+            format!("{}{}", display(&lhs), display(&rhs)).into()
+        }
+        ("+", JsonValue::Array(a), JsonValue::Array(b)) => {
+            JsonValue::Array(a.iter().chain(b).cloned().collect())
+        }
+        (_, JsonValue::Number(_), JsonValue::Number(_)) => {
+            let (a, b) = (to_number(&lhs)?, to_number(&rhs)?);
+            match operator {
+                "+" => number(a + b),
+                "-" => number(a - b),
+                "*" => number(a * b),
+                "/" => number(a / b),
+                "%" => number(a % b),
+                "<" => (a < b).into(),
+                "<=" => (a <= b).into(),
+                ">" => (a > b).into(),
+                _ => (a >= b).into(),
+            }
+        }
+        ("<", JsonValue::String(a), JsonValue::String(b)) => (a < b).into(),
+        ("<=", JsonValue::String(a), JsonValue::String(b)) => (a <= b).into(),
+        (">", JsonValue::String(a), JsonValue::String(b)) => (a > b).into(),
+        (">=", JsonValue::String(a), JsonValue::String(b)) => (a >= b).into(),
+        _ => {
+            return Err(format!(
+                "Cannot apply '{operator}' to {} and {}",
+                type_name(&lhs),
+                type_name(&rhs)
+            )
+            .into())
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Run the top-level statements of the script without component, and call `function`
+    fn call(source: &str, function: &str, args: Vec<JsonValue>) -> Result<JsonValue, String> {
+        let script = Script::parse("test.js".into(), source)?;
+        let engine = Rc::new(Engine {
+            path: script.path,
+            functions: script.functions,
+            globals: Default::default(),
+            instance: Default::default(),
+            types: Default::default(),
+            timers: Default::default(),
+            depth: Cell::new(0),
+        });
+        let error = |e: ScriptError| format!("{}: {}", e.line.unwrap_or(0), e.message);
+        engine.execute_statements(&script.statements, &mut Vec::new()).map_err(error)?;
+        engine.call_function(function, args).map_err(error)
+    }
+
+    #[test]
+    fn test_evaluation() {
+        let source = r#"
+            let counter = 0;
+            /* globals keep their values between calls */
+            fn increment(step) {
+                counter += step;
+                return counter;
+            }
+            fn fib(n) {
+                if n < 2 { return n; } else { return fib(n - 1) + fib(n - 2); }
+            }
+            fn describe(item) {
+                let text = item.name + ": ";
+                for i in item.values {
+                    if i == 2 { continue; }
+                    text += str(i) + ",";
+                }
+                return text;
+            }
+            fn update(data) {
+                data.list[1] = {x: data.list[0].x * 2};
+                data.extra = len(data.list) > 1 && !false;
+                return data;
+            }
+        "#;
+        assert_eq!(call(source, "increment", vec![5.into()]), Ok(5.into()));
+        assert_eq!(call(source, "fib", vec![10.into()]), Ok(55.into()));
+        assert_eq!(call(source, "fib", vec![1.5.into()]), Ok(1.5.into()));
+        assert_eq!(
+            call(source, "describe", vec![serde_json::json!({"name": "n", "values": [1, 2, 3.5]})]),
+            Ok("n: 1,3.5,".into())
+        );
+        assert_eq!(
+            call(source, "update", vec![serde_json::json!({"list": [{"x": 21}, null]})]),
+            Ok(serde_json::json!({"list": [{"x": 21}, {"x": 42}], "extra": true}))
+        );
+    }
+
+    fn compile(source: &str) -> ComponentDefinition {
+        i_slint_backend_testing::init_no_event_loop();
+        let compiler = slint_interpreter::Compiler::new();
+        let result =
+            spin_on::spin_on(compiler.build_from_source(source.into(), Default::default()));
+        assert!(!result.has_errors(), "{:?}", result.diagnostics().collect::<Vec<_>>());
+        let definition = result.components().next().unwrap();
+        definition
+    }
+
+    #[test]
+    fn test_component() {
+        let definition = compile(
+            r#"
+            export global Logic {
+                in-out property <string> status;
+                callback format(int) -> string;
+            }
+            export component App inherits Window {
+                in-out property <int> count;
+                in-out property <[{text: string, done: bool}]> items: [{text: "a"}];
+                callback add-item(string) -> int;
+            }
+            "#,
+        );
+        let instance = definition.create().unwrap();
+
+        let script = Script::parse(
+            "test.js".into(),
+            r#"
+            set("count", 10);
+            fn add_item(text) {
+                push("items", {text: text, done: false});
+                set_row("items", 0, {text: "first", done: true});
+                set("Logic.status", Logic.format(len(get("items"))));
+                return get("count") + len(get("items"));
+            }
+            fn Logic.format(n) {
+                return str(n) + " items";
+            }
+            "#,
+        )
+        .unwrap();
+        script.run(&definition, &instance);
+
+        assert_eq!(instance.get_property("count").unwrap(), Value::Number(10.));
+        let result = instance.invoke("add-item", &[Value::String("b".into())]).unwrap();
+        assert_eq!(result, Value::Number(12.));
+        assert_eq!(
+            instance.get_global_property("Logic", "status").unwrap(),
+            Value::String("2 items".into())
+        );
+        assert_eq!(
+            instance.get_property("items").unwrap().to_json().unwrap(),
+            serde_json::json!([{"text": "first", "done": true}, {"text": "b", "done": false}])
+        );
+    }
+
+    #[test]
+    fn test_timers() {
+        let definition = compile(
+            r#"
+            export component App inherits Window {
+                in-out property <int> ticks;
+                in-out property <bool> done;
+            }
+            "#,
+        );
+        let instance = definition.create().unwrap();
+
+        // A script without callback handlers, that only uses timers
+        let script = Script::parse(
+            "test.js".into(),
+            r#"
+            every(100, "tick", 2);
+            after(150, "finish");
+            fn tick(step) { set("ticks", get("ticks") + step); }
+            fn finish() { set("done", true); }
+            "#,
+        )
+        .unwrap()
+        .run(&definition, &instance);
+
+        i_slint_core::tests::slint_mock_elapsed_time(100);
+        assert_eq!(instance.get_property("ticks").unwrap(), Value::Number(2.));
+        assert_eq!(instance.get_property("done").unwrap(), Value::Bool(false));
+        i_slint_core::tests::slint_mock_elapsed_time(100);
+        assert_eq!(instance.get_property("ticks").unwrap(), Value::Number(4.));
+        assert_eq!(instance.get_property("done").unwrap(), Value::Bool(true));
+
+        // After a reload, the timers keep running and change the new instance
+        let new_instance = definition.create().unwrap();
+        script.attach(&definition, &new_instance);
+        i_slint_core::tests::slint_mock_elapsed_time(100);
+        assert_eq!(new_instance.get_property("ticks").unwrap(), Value::Number(2.));
+        assert_eq!(instance.get_property("ticks").unwrap(), Value::Number(4.));
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!(
+            call("fn f() {\n  return 1 +;\n}", "f", vec![]).map(|_| ()),
+            Err("test.js:2: Unexpected ';'".into())
+        );
+        assert_eq!(
+            call("let x = \"abc;", "f", vec![]),
+            Err("test.js:1: Unterminated string".into())
+        );
+        assert_eq!(
+            call("fn f(a) {\n  let b = 1;\n  return a - \"x\";\n}", "f", vec![1.into()]),
+            Err("3: Cannot apply '-' to a number and a string".into())
+        );
+        assert_eq!(
+            call("fn f() {\n  return g();\n}\nfn g() {\n  return [1][3];\n}", "f", vec![]),
+            Err("5: Index 3 out of bounds".into())
+        );
+        assert_eq!(
+            call("fn f() { return f(); }", "f", vec![]),
+            Err("1: Too many nested function calls".into())
+        );
+        assert_eq!(
+            call("fn f() { get(\"prop\"); }", "f", vec![]),
+            Err("1: The component is not available".into())
+        );
+    }
+}